@@ -27,6 +27,8 @@ struct NormalizedRpcConfig {
 struct NormalizedBlockProducerConfig {
     endpoint: Endpoint,
     verify_tx_proofs: bool,
+    #[serde(default)]
+    track_nullifier_origins: bool,
 }
 
 impl Default for NormalizedRpcConfig {
@@ -44,9 +46,17 @@ impl Default for NormalizedRpcConfig {
 impl Default for NormalizedBlockProducerConfig {
     fn default() -> Self {
         // Ensure we stay in sync with the original defaults.
-        let BlockProducerConfig { endpoint, store_url: _, verify_tx_proofs } =
-            BlockProducerConfig::default();
-        Self { endpoint, verify_tx_proofs }
+        let BlockProducerConfig {
+            endpoint,
+            store_url: _,
+            verify_tx_proofs,
+            track_nullifier_origins,
+        } = BlockProducerConfig::default();
+        Self {
+            endpoint,
+            verify_tx_proofs,
+            track_nullifier_origins,
+        }
     }
 }
 
@@ -58,6 +68,7 @@ impl NodeConfig {
             endpoint: block_producer.endpoint,
             store_url: store.endpoint_url(),
             verify_tx_proofs: block_producer.verify_tx_proofs,
+            track_nullifier_origins: block_producer.track_nullifier_origins,
         };
 
         let rpc = RpcConfig {
@@ -113,7 +124,8 @@ mod tests {
                             host: "127.0.0.1".to_string(),
                             port: 8080,
                         },
-                        verify_tx_proofs: true
+                        verify_tx_proofs: true,
+                        track_nullifier_origins: false,
                     },
                     rpc: NormalizedRpcConfig {
                         endpoint: Endpoint {
@@ -0,0 +1,102 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use miden_node_block_producer::block::{BlockInputs, LazyBlockInputs};
+use miden_node_proto::{
+    domain::notes::NoteAuthenticationInfo, generated::responses::GetBlockInputsResponse,
+    AccountInputRecord,
+};
+use miden_objects::{
+    accounts::{AccountId, AccountStorageMode, AccountType},
+    crypto::merkle::SimpleSmt,
+    BlockHeader, Digest, Felt, ACCOUNT_TREE_DEPTH,
+};
+
+const NUM_ACCOUNTS: u64 = 1_000;
+
+/// Builds a response with the witnesses of [NUM_ACCOUNTS] accounts against the genesis block.
+fn block_inputs_response() -> GetBlockInputsResponse {
+    let accounts: Vec<_> = (0..NUM_ACCOUNTS)
+        .map(|i| {
+            // Setting the low byte ensures the ID has enough ones to be valid.
+            let account_id = AccountId::new_with_type_and_mode(
+                (i << 8 | 0xff).to_be_bytes(),
+                AccountType::RegularAccountUpdatableCode,
+                AccountStorageMode::Private,
+            );
+            (
+                account_id,
+                Digest::from([Felt::new(i), Felt::new(i), Felt::new(i), Felt::new(i)]),
+            )
+        })
+        .collect();
+
+    let account_tree = SimpleSmt::<ACCOUNT_TREE_DEPTH>::with_leaves(
+        accounts.iter().map(|(account_id, hash)| ((*account_id).into(), (*hash).into())),
+    )
+    .unwrap();
+
+    let block_header = BlockHeader::new(
+        0,
+        Digest::default(),
+        0,
+        Digest::default(),
+        account_tree.root(),
+        Digest::default(),
+        Digest::default(),
+        Digest::default(),
+        Digest::default(),
+        Digest::default(),
+        1,
+    );
+
+    GetBlockInputsResponse {
+        block_header: Some(block_header.into()),
+        mmr_peaks: Vec::new(),
+        account_states: accounts
+            .iter()
+            .map(|(account_id, account_hash)| {
+                AccountInputRecord {
+                    account_id: *account_id,
+                    account_hash: *account_hash,
+                    proof: account_tree.open(&(*account_id).into()).path,
+                }
+                .into()
+            })
+            .collect(),
+        nullifiers: Vec::new(),
+        found_unauthenticated_notes: Some(NoteAuthenticationInfo::default().into()),
+    }
+}
+
+fn block_inputs_conversion(c: &mut Criterion) {
+    let response = block_inputs_response();
+    let account_id = response.account_states[0].account_id.unwrap().try_into().unwrap();
+
+    let mut group = c.benchmark_group("block_inputs_conversion");
+
+    group.bench_function("eager", |b| {
+        b.iter_batched(
+            || response.clone(),
+            |response| {
+                let inputs = BlockInputs::try_from(response).unwrap();
+                black_box(inputs.accounts[&account_id].clone())
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("lazy", |b| {
+        b.iter_batched(
+            || response.clone(),
+            |response| {
+                let inputs = LazyBlockInputs::try_from(response).unwrap();
+                black_box(inputs.account_witness(&account_id).unwrap())
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, block_inputs_conversion);
+criterion_main!(benches);
@@ -3,8 +3,8 @@ use std::collections::BTreeMap;
 use miden_node_proto::{
     domain::notes::NoteAuthenticationInfo,
     errors::{ConversionError, MissingFieldHelper},
-    generated::responses::GetBlockInputsResponse,
-    AccountInputRecord, NullifierWitness,
+    generated::responses::{AccountBlockInputRecord, GetBlockInputsResponse},
+    NullifierWitness,
 };
 use miden_objects::{
     accounts::AccountId,
@@ -13,7 +13,7 @@ use miden_objects::{
     BlockHeader, Digest,
 };
 
-use crate::store::BlockInputsError;
+use crate::{errors::BlockRoot, store::BlockInputsError};
 
 // BLOCK INPUTS
 // ================================================================================================
@@ -37,15 +37,139 @@ pub struct BlockInputs {
     pub found_unauthenticated_notes: NoteAuthenticationInfo,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct AccountWitness {
     pub hash: Digest,
     pub proof: MerklePath,
 }
 
+impl BlockInputs {
+    /// Checks that all witnesses open against the state committed to by the block header.
+    ///
+    /// The witness of each account must open against the account root of the header, the proof
+    /// of each nullifier must open the leaf of the nullifier against the nullifier root, and the
+    /// chain peaks must hash to the chain root. This catches stores returning witnesses of
+    /// different states, e.g. when a block was applied while the witnesses were collected.
+    ///
+    /// # Errors
+    /// Returns [BlockInputsError::InconsistentWitness] for the first witness which opens against
+    /// another root, and [BlockInputsError::MisplacedNullifierWitness] for the first nullifier
+    /// proof which opens another leaf.
+    pub fn verify_internal_consistency(&self) -> Result<(), BlockInputsError> {
+        let account_root = self.block_header.account_root();
+        for (account_id, witness) in &self.accounts {
+            let got = witness.proof.compute_root(u64::from(*account_id), witness.hash)?;
+            if got != account_root {
+                return Err(BlockInputsError::InconsistentWitness {
+                    which: BlockRoot::Account,
+                    subject: account_id.to_string(),
+                    expected: account_root,
+                    got,
+                });
+            }
+        }
+
+        let nullifier_root = self.block_header.nullifier_root();
+        for (nullifier, proof) in &self.nullifiers {
+            if proof.get(&nullifier.inner()).is_none() {
+                return Err(BlockInputsError::MisplacedNullifierWitness(*nullifier));
+            }
+            let got = proof.compute_root();
+            if got != nullifier_root {
+                return Err(BlockInputsError::InconsistentWitness {
+                    which: BlockRoot::Nullifier,
+                    subject: nullifier.to_string(),
+                    expected: nullifier_root,
+                    got,
+                });
+            }
+        }
+
+        let chain_root = self.block_header.chain_root();
+        let got = self.chain_peaks.hash_peaks();
+        if got != chain_root {
+            return Err(BlockInputsError::InconsistentWitness {
+                which: BlockRoot::Chain,
+                subject: format!("the peaks of {} blocks", self.chain_peaks.num_leaves()),
+                expected: chain_root,
+                got,
+            });
+        }
+
+        Ok(())
+    }
+}
+
 impl TryFrom<GetBlockInputsResponse> for BlockInputs {
     type Error = BlockInputsError;
 
+    fn try_from(response: GetBlockInputsResponse) -> Result<Self, Self::Error> {
+        LazyBlockInputs::try_from(response)?.into_block_inputs()
+    }
+}
+
+// LAZY BLOCK INPUTS
+// ================================================================================================
+
+/// [BlockInputs] which decode the account witnesses on access.
+///
+/// Only the account IDs are decoded when converting from [GetBlockInputsResponse]. The account
+/// hashes and authentication paths are kept in their protobuf form until requested, which avoids
+/// allocating the decoded witnesses of all accounts at once for large responses.
+#[derive(Clone, Debug)]
+pub struct LazyBlockInputs {
+    /// Previous block header
+    pub block_header: BlockHeader,
+
+    /// MMR peaks for the current chain state
+    pub chain_peaks: MmrPeaks,
+
+    /// The undecoded hashes and authentication paths of the requested accounts
+    accounts: BTreeMap<AccountId, AccountBlockInputRecord>,
+
+    /// The requested nullifiers and their authentication paths
+    pub nullifiers: BTreeMap<Nullifier, SmtProof>,
+
+    /// List of unauthenticated notes found in the store
+    pub found_unauthenticated_notes: NoteAuthenticationInfo,
+}
+
+impl LazyBlockInputs {
+    /// Returns the IDs of the requested accounts.
+    pub fn account_ids(&self) -> impl Iterator<Item = &AccountId> {
+        self.accounts.keys()
+    }
+
+    /// Decodes the witness of the given account, or returns `None` if the account was not part
+    /// of the response.
+    pub fn account_witness(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<AccountWitness>, ConversionError> {
+        self.accounts.get(account_id).map(decode_account_witness).transpose()
+    }
+
+    /// Decodes the witnesses of all accounts, returning the equivalent [BlockInputs].
+    pub fn into_block_inputs(self) -> Result<BlockInputs, BlockInputsError> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|(account_id, record)| Ok((*account_id, decode_account_witness(record)?)))
+            .collect::<Result<BTreeMap<_, _>, ConversionError>>()?;
+
+        Ok(BlockInputs {
+            block_header: self.block_header,
+            chain_peaks: self.chain_peaks,
+            accounts,
+            nullifiers: self.nullifiers,
+            found_unauthenticated_notes: self.found_unauthenticated_notes,
+        })
+    }
+}
+
+impl TryFrom<GetBlockInputsResponse> for LazyBlockInputs {
+    type Error = BlockInputsError;
+
     fn try_from(response: GetBlockInputsResponse) -> Result<Self, Self::Error> {
         let block_header: BlockHeader = response
             .block_header
@@ -72,13 +196,12 @@ impl TryFrom<GetBlockInputsResponse> for BlockInputs {
         let accounts = response
             .account_states
             .into_iter()
-            .map(|entry| {
-                let domain: AccountInputRecord = entry.try_into()?;
-                let witness = AccountWitness {
-                    hash: domain.account_hash,
-                    proof: domain.proof,
-                };
-                Ok((domain.account_id, witness))
+            .map(|record| {
+                let account_id = record
+                    .account_id
+                    .ok_or(AccountBlockInputRecord::missing_field(stringify!(account_id)))?
+                    .try_into()?;
+                Ok((account_id, record))
             })
             .collect::<Result<BTreeMap<_, _>, ConversionError>>()?;
 
@@ -105,3 +228,169 @@ impl TryFrom<GetBlockInputsResponse> for BlockInputs {
         })
     }
 }
+
+// HELPERS
+// ================================================================================================
+
+fn decode_account_witness(
+    record: &AccountBlockInputRecord,
+) -> Result<AccountWitness, ConversionError> {
+    let hash = record
+        .account_hash
+        .as_ref()
+        .ok_or(AccountBlockInputRecord::missing_field(stringify!(account_hash)))?
+        .try_into()?;
+    let proof = record
+        .proof
+        .as_ref()
+        .ok_or(AccountBlockInputRecord::missing_field(stringify!(proof)))?
+        .try_into()?;
+
+    Ok(AccountWitness { hash, proof })
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_node_proto::AccountInputRecord;
+    use miden_objects::{
+        crypto::merkle::{SimpleSmt, Smt},
+        Felt, ACCOUNT_TREE_DEPTH, ZERO,
+    };
+
+    use super::*;
+    use crate::test_utils::mock_account_id;
+
+    fn mock_response() -> GetBlockInputsResponse {
+        let accounts: Vec<_> = (0..5)
+            .map(|i| (mock_account_id(i), Digest::from([Felt::from(i), ZERO, ZERO, ZERO])))
+            .collect();
+        let nullifier = Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(7)]));
+
+        let account_tree = SimpleSmt::<ACCOUNT_TREE_DEPTH>::with_leaves(
+            accounts.iter().map(|(account_id, hash)| ((*account_id).into(), (*hash).into())),
+        )
+        .unwrap();
+        let nullifier_tree = Smt::new();
+        let chain_peaks = MmrPeaks::new(0, Vec::new()).unwrap();
+
+        let block_header = BlockHeader::new(
+            0,
+            Digest::default(),
+            0,
+            chain_peaks.hash_peaks(),
+            account_tree.root(),
+            nullifier_tree.root(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            1,
+        );
+
+        GetBlockInputsResponse {
+            block_header: Some(block_header.into()),
+            mmr_peaks: Vec::new(),
+            account_states: accounts
+                .iter()
+                .map(|(account_id, account_hash)| {
+                    AccountInputRecord {
+                        account_id: *account_id,
+                        account_hash: *account_hash,
+                        proof: account_tree.open(&(*account_id).into()).path,
+                    }
+                    .into()
+                })
+                .collect(),
+            nullifiers: vec![NullifierWitness {
+                nullifier,
+                proof: nullifier_tree.open(&nullifier.inner()),
+            }
+            .into()],
+            found_unauthenticated_notes: Some(NoteAuthenticationInfo::default().into()),
+        }
+    }
+
+    #[test]
+    fn lazy_block_inputs_decode_same_data_as_eager() {
+        let response = mock_response();
+
+        let eager = BlockInputs::try_from(response.clone()).unwrap();
+        let lazy = LazyBlockInputs::try_from(response).unwrap();
+
+        assert_eq!(lazy.block_header, eager.block_header);
+        assert_eq!(lazy.chain_peaks, eager.chain_peaks);
+        assert_eq!(lazy.nullifiers, eager.nullifiers);
+        assert!(lazy.account_ids().eq(eager.accounts.keys()));
+        for (account_id, witness) in &eager.accounts {
+            assert_eq!(lazy.account_witness(account_id).unwrap().as_ref(), Some(witness));
+        }
+        assert_eq!(lazy.account_witness(&mock_account_id(42)).unwrap(), None);
+
+        let decoded = lazy.into_block_inputs().unwrap();
+        assert_eq!(decoded.accounts, eager.accounts);
+    }
+
+    #[test]
+    fn lazy_block_inputs_defer_account_witness_errors() {
+        let mut response = mock_response();
+        let account_id = response.account_states[0].account_id.unwrap().try_into().unwrap();
+        response.account_states[0].proof = None;
+
+        // The eager conversion fails up front, while the lazy one only fails when the broken
+        // witness is accessed.
+        assert!(BlockInputs::try_from(response.clone()).is_err());
+
+        let lazy = LazyBlockInputs::try_from(response).unwrap();
+        assert!(lazy.account_witness(&account_id).is_err());
+        assert!(lazy.account_witness(&mock_account_id(1)).unwrap().is_some());
+        assert!(lazy.into_block_inputs().is_err());
+    }
+
+    #[test]
+    fn verify_internal_consistency_detects_mismatched_witnesses() {
+        let inputs = BlockInputs::try_from(mock_response()).unwrap();
+        inputs.verify_internal_consistency().unwrap();
+
+        // An account witness of another state of the account tree
+        let mut mismatched = inputs.clone();
+        let account_id = mock_account_id(1);
+        mismatched.accounts.get_mut(&account_id).unwrap().hash = Digest::default();
+        assert!(matches!(
+            mismatched.verify_internal_consistency(),
+            Err(BlockInputsError::InconsistentWitness { which: BlockRoot::Account, .. })
+        ));
+
+        // A nullifier proof of another state of the nullifier tree
+        let mut mismatched = inputs.clone();
+        let (nullifier, proof) = mismatched.nullifiers.iter_mut().next().unwrap();
+        let other_key = Digest::from([ZERO, ZERO, ZERO, Felt::new(8)]);
+        let other_tree =
+            Smt::with_entries([(other_key, [Felt::new(1), ZERO, ZERO, ZERO])]).unwrap();
+        *proof = other_tree.open(&nullifier.inner());
+        assert!(matches!(
+            mismatched.verify_internal_consistency(),
+            Err(BlockInputsError::InconsistentWitness { which: BlockRoot::Nullifier, .. })
+        ));
+
+        // A nullifier proof opening the leaf of another nullifier
+        let mut mismatched = inputs.clone();
+        let (_, proof) = mismatched.nullifiers.iter_mut().next().unwrap();
+        *proof = Smt::new().open(&other_key);
+        assert!(matches!(
+            mismatched.verify_internal_consistency(),
+            Err(BlockInputsError::MisplacedNullifierWitness(_))
+        ));
+
+        // Chain peaks of a longer chain
+        let mut mismatched = inputs;
+        mismatched.chain_peaks =
+            MmrPeaks::new(1, vec![Digest::from([ZERO, ZERO, ZERO, Felt::new(1)])]).unwrap();
+        assert!(matches!(
+            mismatched.verify_internal_consistency(),
+            Err(BlockInputsError::InconsistentWitness { which: BlockRoot::Chain, .. })
+        ));
+    }
+}
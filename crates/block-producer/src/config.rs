@@ -22,6 +22,12 @@ pub struct BlockProducerConfig {
     /// verification may take ~15ms/proof. This is OK when all transactions are forwarded to the
     /// block producer from the RPC component as transaction proofs are also verified there.
     pub verify_tx_proofs: bool,
+
+    /// Enable or disable recording the transaction which produced each nullifier in the store.
+    ///
+    /// Nullifier origins are only used for forensic analysis and are not needed to run the node.
+    #[serde(default)]
+    pub track_nullifier_origins: bool,
 }
 
 impl BlockProducerConfig {
@@ -45,6 +51,7 @@ impl Default for BlockProducerConfig {
             endpoint: Endpoint::localhost(DEFAULT_BLOCK_PRODUCER_PORT),
             store_url: Endpoint::localhost(DEFAULT_STORE_PORT).to_string(),
             verify_tx_proofs: true,
+            track_nullifier_origins: false,
         }
     }
 }
@@ -1,4 +1,12 @@
-use miden_node_proto::errors::ConversionError;
+use std::fmt::{self, Display, Formatter};
+
+use miden_node_proto::{
+    domain::blocks::BlockNumber,
+    errors::{
+        AccountVersionConflictDetail, ConversionError, StaleBlockDetail,
+        ACCOUNT_VERSION_CONFLICT_DETAIL_TYPE_URL, STALE_BLOCK_DETAIL_TYPE_URL,
+    },
+};
 use miden_node_utils::formatting::format_opt;
 use miden_objects::{
     accounts::AccountId,
@@ -9,7 +17,9 @@ use miden_objects::{
     MAX_BATCHES_PER_BLOCK, MAX_INPUT_NOTES_PER_BATCH, MAX_OUTPUT_NOTES_PER_BATCH,
 };
 use miden_processor::ExecutionError;
+use prost::Message;
 use thiserror::Error;
+use tonic::{Code, Status};
 
 // Transaction verification errors
 // =================================================================================================
@@ -158,8 +168,54 @@ pub enum BlockInputsError {
     ConversionError(#[from] ConversionError),
     #[error("MmrPeaks error: {0}")]
     MmrPeaksError(#[from] MmrError),
+    #[error("gRPC client failed with {code:?}: {message}")]
+    GrpcClientError { code: Code, message: String },
     #[error("gRPC client failed with error: {0}")]
-    GrpcClientError(String),
+    GrpcErrorWithDetails(GrpcErrorDetails),
+    #[error("store is temporarily unavailable ({code:?}): {message}")]
+    StoreUnavailable { code: Code, message: String },
+    #[error("store returned an invalid witness: {0}")]
+    InvalidWitness(#[from] MerkleError),
+    #[error("invalid unauthenticated notes: {0}")]
+    InvalidNotes(#[from] NotePathsError),
+    #[error("chunks of the block inputs request were served at different chain tips")]
+    InconsistentChunks,
+    #[error(
+        "{which} witness of {subject} opens against root {got}, but the block header commits to {expected}"
+    )]
+    InconsistentWitness {
+        which: BlockRoot,
+        subject: String,
+        expected: Digest,
+        got: Digest,
+    },
+    #[error("witness of nullifier {0} opens the leaf of another nullifier")]
+    MisplacedNullifierWitness(Nullifier),
+}
+
+impl From<Status> for BlockInputsError {
+    fn from(status: Status) -> Self {
+        if is_unavailable(&status) {
+            return Self::StoreUnavailable {
+                code: status.code(),
+                message: status.message().to_string(),
+            };
+        }
+        match GrpcErrorDetails::from_status(&status) {
+            Some(details) => Self::GrpcErrorWithDetails(details),
+            None => Self::GrpcClientError {
+                code: status.code(),
+                message: status.message().to_string(),
+            },
+        }
+    }
+}
+
+impl BlockInputsError {
+    /// Returns true if the request may succeed when retried later.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::StoreUnavailable { .. } | Self::InconsistentChunks)
+    }
 }
 
 // Note paths errors
@@ -170,8 +226,43 @@ pub enum BlockInputsError {
 pub enum NotePathsError {
     #[error("failed to parse protobuf message: {0}")]
     ConversionError(#[from] ConversionError),
+    #[error("gRPC client failed with {code:?}: {message}")]
+    GrpcClientError { code: Code, message: String },
     #[error("gRPC client failed with error: {0}")]
-    GrpcClientError(String),
+    GrpcErrorWithDetails(GrpcErrorDetails),
+    #[error("store is temporarily unavailable ({code:?}): {message}")]
+    StoreUnavailable { code: Code, message: String },
+    #[error(
+        "inclusion proof of block {block_num} does not open against the chain root {expected}"
+    )]
+    RootMismatch { expected: Digest, block_num: u32 },
+    #[error("note id {0} is the zero digest, which is not the hash of any note")]
+    InvalidNoteId(NoteId),
+}
+
+impl From<Status> for NotePathsError {
+    fn from(status: Status) -> Self {
+        if is_unavailable(&status) {
+            return Self::StoreUnavailable {
+                code: status.code(),
+                message: status.message().to_string(),
+            };
+        }
+        match GrpcErrorDetails::from_status(&status) {
+            Some(details) => Self::GrpcErrorWithDetails(details),
+            None => Self::GrpcClientError {
+                code: status.code(),
+                message: status.message().to_string(),
+            },
+        }
+    }
+}
+
+impl NotePathsError {
+    /// Returns true if the request may succeed when retried later.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::StoreUnavailable { .. })
+    }
 }
 
 // Block applying errors
@@ -179,8 +270,90 @@ pub enum NotePathsError {
 
 #[derive(Debug, PartialEq, Eq, Error)]
 pub enum ApplyBlockError {
+    #[error("gRPC client failed with {code:?}: {message}")]
+    GrpcClientError { code: Code, message: String },
     #[error("gRPC client failed with error: {0}")]
-    GrpcClientError(String),
+    GrpcErrorWithDetails(GrpcErrorDetails),
+    #[error("store is temporarily unavailable ({code:?}): {message}")]
+    StoreUnavailable { code: Code, message: String },
+    #[error("failed to persist block in the apply queue: {0}")]
+    QueuePersistenceFailed(String),
+    #[error("store expected block {expected}, but got block {got}")]
+    StaleBlock { expected: u32, got: u32 },
+    #[error("account {0} changed since it was read by the transactions of the block")]
+    AccountVersionConflict(AccountId),
+    #[error("store rejected the block following block {last_applied_block_num}: {reason}")]
+    BlockRejected {
+        last_applied_block_num: BlockNumber,
+        reason: String,
+    },
+    #[error("failed to parse protobuf message: {0}")]
+    ConversionError(#[from] ConversionError),
+    #[error("failed to get the block inputs to check the block against: {0}")]
+    BlockInputsFailed(#[from] BlockInputsError),
+    #[error("store returned invalid witnesses for the block: {0}")]
+    InvalidStoreWitness(String),
+    #[error(
+        "recomputed {which} root {got} does not match the root {expected} declared by the block"
+    )]
+    RootMismatch {
+        which: BlockRoot,
+        expected: Digest,
+        got: Digest,
+    },
+}
+
+/// A root of the state committed to by a block header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockRoot {
+    Account,
+    Nullifier,
+    Chain,
+}
+
+impl Display for BlockRoot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Account => f.write_str("account"),
+            Self::Nullifier => f.write_str("nullifier"),
+            Self::Chain => f.write_str("chain"),
+        }
+    }
+}
+
+impl From<Status> for ApplyBlockError {
+    fn from(status: Status) -> Self {
+        // An overloaded store may accept the block later, like a store which is unavailable.
+        if is_unavailable(&status) || status.code() == Code::ResourceExhausted {
+            return Self::StoreUnavailable {
+                code: status.code(),
+                message: status.message().to_string(),
+            };
+        }
+        match GrpcErrorDetails::from_status(&status) {
+            Some(details) if status.code() == Code::FailedPrecondition => {
+                if let Some(StaleBlockDetail { expected, got }) = details.stale_block() {
+                    Self::StaleBlock { expected, got }
+                } else if let Some(account_id) = details.account_version_conflict() {
+                    Self::AccountVersionConflict(account_id)
+                } else {
+                    Self::GrpcErrorWithDetails(details)
+                }
+            },
+            Some(details) => Self::GrpcErrorWithDetails(details),
+            None => Self::GrpcClientError {
+                code: status.code(),
+                message: status.message().to_string(),
+            },
+        }
+    }
+}
+
+impl ApplyBlockError {
+    /// Returns true if applying the block may succeed when retried later.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::StoreUnavailable { .. })
+    }
 }
 
 // Block building errors
@@ -218,12 +391,151 @@ pub enum BuildBlockError {
 
 #[derive(Debug, PartialEq, Eq, Error)]
 pub enum TxInputsError {
+    #[error("gRPC client failed with {code:?}: {message}")]
+    GrpcClientError { code: Code, message: String },
     #[error("gRPC client failed with error: {0}")]
-    GrpcClientError(String),
+    GrpcErrorWithDetails(GrpcErrorDetails),
+    #[error("store is temporarily unavailable ({code:?}): {message}")]
+    StoreUnavailable { code: Code, message: String },
     #[error("malformed response from store: {0}")]
     MalformedResponse(String),
     #[error("failed to parse protobuf message: {0}")]
     ConversionError(#[from] ConversionError),
+    #[error(
+        "transaction expects initial account hash {expected}, but the store has {}",
+        format_opt(.got.as_ref())
+    )]
+    AccountStateMismatch { expected: Digest, got: Option<Digest> },
+    #[error("block header hash {actual} does not match the trusted root {expected}")]
+    BlockHeaderMismatch { expected: Digest, actual: Digest },
+    #[error("account proof for {0} does not verify against the block's account root")]
+    InvalidAccountProof(AccountId),
+    #[error(
+        "proof for nullifier {0} is missing or does not verify against the block's nullifier root"
+    )]
+    InvalidNullifierProof(Nullifier),
+    #[error("nullifier {0} was already consumed")]
+    NullifierAlreadySpent(Nullifier),
+    #[error("store did not return whether nullifier {0} was consumed")]
+    MissingNullifierInfo(Nullifier),
+    #[error("store returned an invalid spend block for nullifier {nullifier}: {reason}")]
+    NullifierSpendError { nullifier: Nullifier, reason: String },
+    #[error("store did not return the requested nullifiers {0:?}")]
+    IncompleteNullifierResponse(Vec<Nullifier>),
+    #[error("chain tip #{0} has the largest block number, so no block can follow it")]
+    BlockNumberOverflow(BlockNumber),
     #[error("dummy")]
     Dummy,
 }
+
+impl From<Status> for TxInputsError {
+    fn from(status: Status) -> Self {
+        if is_unavailable(&status) {
+            return Self::StoreUnavailable {
+                code: status.code(),
+                message: status.message().to_string(),
+            };
+        }
+        match GrpcErrorDetails::from_status(&status) {
+            Some(details) => Self::GrpcErrorWithDetails(details),
+            None => Self::GrpcClientError {
+                code: status.code(),
+                message: status.message().to_string(),
+            },
+        }
+    }
+}
+
+impl TxInputsError {
+    /// Returns true if the request may succeed when retried later.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::StoreUnavailable { .. })
+    }
+}
+
+/// Returns true if `status` indicates the store could not be reached, or didn't respond in time.
+fn is_unavailable(status: &Status) -> bool {
+    matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded)
+}
+
+// gRPC error details
+// =================================================================================================
+
+/// A store error carrying structured details in the `grpc-status-details-bin` trailer of the
+/// response, in addition to its message.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{code:?}: {message} (details: {})", format_details(.details))]
+pub struct GrpcErrorDetails {
+    /// The status code of the error
+    pub code: Code,
+    pub message: String,
+    /// The details of the error, in the order sent by the store
+    pub details: Vec<GrpcErrorDetail>,
+}
+
+/// One structured detail of a store error, e.g. an encoded `google.rpc.ErrorInfo`.
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct GrpcErrorDetail {
+    /// The type URL of the detail, e.g. `type.googleapis.com/google.rpc.ErrorInfo`
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    /// The encoded detail message
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+/// The `google.rpc.Status` message encoded in the `grpc-status-details-bin` trailer.
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<GrpcErrorDetail>,
+}
+
+impl GrpcErrorDetails {
+    /// Extracts the structured details of `status`, or returns `None` if the status carries no
+    /// details or they are malformed.
+    pub fn from_status(status: &Status) -> Option<Self> {
+        let details = RpcStatus::decode(status.details()).ok()?.details;
+        if details.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            code: status.code(),
+            message: status.message().to_string(),
+            details,
+        })
+    }
+
+    /// Returns the first stale block detail, or `None` if there is none or it is malformed.
+    fn stale_block(&self) -> Option<StaleBlockDetail> {
+        let detail = self
+            .details
+            .iter()
+            .find(|detail| detail.type_url == STALE_BLOCK_DETAIL_TYPE_URL)?;
+        StaleBlockDetail::decode(detail.value.as_slice()).ok()
+    }
+
+    /// Returns the account of the first account version conflict detail, or `None` if there is
+    /// none or it is malformed.
+    fn account_version_conflict(&self) -> Option<AccountId> {
+        let detail = self
+            .details
+            .iter()
+            .find(|detail| detail.type_url == ACCOUNT_VERSION_CONFLICT_DETAIL_TYPE_URL)?;
+        let detail = AccountVersionConflictDetail::decode(detail.value.as_slice()).ok()?;
+        AccountId::try_from(detail.account_id).ok()
+    }
+}
+
+fn format_details(details: &[GrpcErrorDetail]) -> String {
+    details
+        .iter()
+        .map(|detail| detail.type_url.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -11,12 +11,12 @@ mod batch_builder;
 mod block_builder;
 mod errors;
 mod state_view;
-mod store;
 mod txqueue;
 
 pub mod block;
 pub mod config;
 pub mod server;
+pub mod store;
 
 // TYPE ALIASES
 // =================================================================================================
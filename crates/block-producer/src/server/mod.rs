@@ -1,6 +1,6 @@
 use std::{net::ToSocketAddrs, sync::Arc};
 
-use miden_node_proto::generated::{block_producer::api_server, store::api_client as store_client};
+use miden_node_proto::generated::block_producer::api_server;
 use miden_node_utils::errors::ApiError;
 use tokio::net::TcpListener;
 use tokio_stream::wrappers::TcpListenerStream;
@@ -45,11 +45,17 @@ impl BlockProducer {
     pub async fn init(config: BlockProducerConfig) -> Result<Self, ApiError> {
         info!(target: COMPONENT, %config, "Initializing server");
 
-        let store = Arc::new(DefaultStore::new(
-            store_client::ApiClient::connect(config.store_url.to_string())
-                .await
-                .map_err(|err| ApiError::DatabaseConnectionFailed(err.to_string()))?,
-        ));
+        let mut store = DefaultStore::connect(config.store_url.to_string())
+            .await
+            .map_err(|err| ApiError::DatabaseConnectionFailed(err.to_string()))?;
+        if config.track_nullifier_origins {
+            store = store.with_nullifier_origins();
+        }
+        #[cfg(feature = "otel")]
+        {
+            store = store.with_store_address(config.store_url.clone());
+        }
+        let store = Arc::new(store);
         let state_view =
             Arc::new(DefaultStateView::new(Arc::clone(&store), config.verify_tx_proofs));
 
@@ -92,7 +92,7 @@ where
         // of one of the inflight transactions)
         let mut tx_inputs = self.store.get_tx_inputs(candidate_tx).await?;
 
-        let current_block_height = tx_inputs.current_block_height;
+        let current_block_height = u32::from(tx_inputs.current_block_height);
 
         // The latest inflight account state takes precedence since this is the current block being
         // constructed.
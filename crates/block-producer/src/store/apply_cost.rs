@@ -0,0 +1,81 @@
+use miden_objects::{block::Block, utils::Serializable, Digest, EMPTY_WORD};
+
+use crate::{block::BlockInputs, errors::ApplyBlockError};
+
+/// Estimated number of bytes the store adds for a nullifier: its database row with the
+/// nullifier, its prefix and the block number, and its leaf in the nullifier tree.
+pub const NULLIFIER_ENTRY_BYTES: u64 = 32 + 4 + 4 + 64;
+
+/// Estimated number of bytes the store adds for a new account: its database row with the
+/// account ID, hash and block number, and its leaf in the account tree.
+pub const ACCOUNT_ENTRY_BYTES: u64 = 8 + 32 + 4 + 40;
+
+// APPLY COST ESTIMATE
+// ================================================================================================
+
+/// How much applying a block would grow the state of the store.
+///
+/// See [Store::estimate_apply_cost](super::Store::estimate_apply_cost).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ApplyCostEstimate {
+    /// Number of nullifiers of the block which were not consumed yet
+    pub new_nullifiers: usize,
+    /// Number of accounts updated by the block, including the new ones
+    pub updated_accounts: usize,
+    /// Number of accounts updated by the block which are unknown to the store
+    pub new_accounts: usize,
+    /// Estimated number of bytes added to the store: the serialized block, which the store
+    /// keeps, and the entries of the new nullifiers and accounts
+    pub estimated_bytes: u64,
+}
+
+/// Estimates the cost of applying `block` from the witnesses of its accounts and nullifiers in
+/// the store state before it.
+///
+/// # Errors
+/// Returns [ApplyBlockError::InvalidStoreWitness] if `inputs` lack a witness required by the
+/// block, or a nullifier witness opens another leaf than the nullifier's.
+pub(super) fn estimate_apply_cost(
+    block: &Block,
+    inputs: &BlockInputs,
+) -> Result<ApplyCostEstimate, ApplyBlockError> {
+    let mut new_accounts = 0;
+    for update in block.updated_accounts() {
+        let witness = inputs.accounts.get(&update.account_id()).ok_or_else(|| {
+            ApplyBlockError::InvalidStoreWitness(format!(
+                "missing witness of account {}",
+                update.account_id()
+            ))
+        })?;
+        if witness.hash == Digest::default() {
+            new_accounts += 1;
+        }
+    }
+
+    let mut new_nullifiers = 0;
+    for nullifier in block.nullifiers() {
+        let value = inputs
+            .nullifiers
+            .get(nullifier)
+            .and_then(|proof| proof.get(&nullifier.inner()))
+            .ok_or_else(|| {
+                ApplyBlockError::InvalidStoreWitness(format!(
+                    "missing witness of nullifier {nullifier}"
+                ))
+            })?;
+        if value == EMPTY_WORD {
+            new_nullifiers += 1;
+        }
+    }
+
+    let estimated_bytes = block.to_bytes().len() as u64
+        + new_nullifiers as u64 * NULLIFIER_ENTRY_BYTES
+        + new_accounts as u64 * ACCOUNT_ENTRY_BYTES;
+
+    Ok(ApplyCostEstimate {
+        new_nullifiers,
+        updated_accounts: block.updated_accounts().len(),
+        new_accounts,
+        estimated_bytes,
+    })
+}
@@ -0,0 +1,351 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use miden_objects::{
+    block::Block,
+    utils::{Deserializable, Serializable},
+};
+use tokio::sync::{Mutex, Notify};
+use tonic::metadata::AsciiMetadataValue;
+use tracing::{info, warn};
+
+use super::{
+    tenant::{current_tenant, scoped_to_tenant},
+    ApplyBlock, ApplyBlockError, Backoff,
+};
+use crate::COMPONENT;
+
+// CONSTANTS
+// ================================================================================================
+
+/// The extension of the files holding the queued blocks.
+const BLOCK_FILE_EXTENSION: &str = "block";
+
+/// The extension of the files holding the tenants of the queued blocks which were applied for a
+/// tenant overriding the default tenant of the store.
+const TENANT_FILE_EXTENSION: &str = "tenant";
+
+// APPLY QUEUE
+// ================================================================================================
+
+/// Durable queue of blocks which could not be applied to the store because it was transiently
+/// unavailable.
+///
+/// Each queued block is persisted to its own file in the queue directory, so blocks survive a
+/// restart of the block producer. Blocks are applied in order of their block numbers, and are
+/// removed from the queue once they are applied or permanently rejected by the store.
+///
+/// Blocks applied within [scoped_to_tenant] are persisted together with their tenant, and are
+/// retried for it.
+#[derive(Debug)]
+pub struct ApplyQueue {
+    dir: PathBuf,
+    /// Maps block number -> block, for all queued blocks
+    pending: Mutex<BTreeMap<u32, QueuedBlock>>,
+    /// Wakes up the retry task when a block is queued
+    queued: Notify,
+}
+
+impl ApplyQueue {
+    /// Opens the queue persisted in `dir`, creating the directory if it does not exist.
+    ///
+    /// Blocks queued by a previous run are loaded, and are retried before any new block.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut pending = BTreeMap::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == BLOCK_FILE_EXTENSION) {
+                let block = Block::read_from_bytes(&fs::read(&path)?)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+                let tenant = match fs::read(path.with_extension(TENANT_FILE_EXTENSION)) {
+                    Ok(tenant) => {
+                        Some(AsciiMetadataValue::try_from(tenant.as_slice()).map_err(|err| {
+                            io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+                        })?)
+                    },
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                    Err(err) => return Err(err),
+                };
+                pending.insert(block.header().block_num(), QueuedBlock { block, tenant });
+            }
+        }
+
+        Ok(Self {
+            dir,
+            pending: Mutex::new(pending),
+            queued: Notify::new(),
+        })
+    }
+
+    /// Returns the number of queued blocks.
+    pub async fn len(&self) -> usize {
+        self.pending.lock().await.len()
+    }
+
+    /// Returns true if no blocks are queued.
+    pub async fn is_empty(&self) -> bool {
+        self.pending.lock().await.is_empty()
+    }
+
+    /// Applies `block` to `store`, queueing it if the store is transiently unavailable.
+    ///
+    /// Blocks must be applied in order, so `block` is queued without contacting the store if
+    /// other blocks are already queued. Once queued, the block is guaranteed to be applied
+    /// eventually, unless the store rejects it. It is retried for the tenant of the enclosing
+    /// [scoped_to_tenant] call, if any.
+    pub async fn apply(
+        &self,
+        store: &impl ApplyBlock,
+        block: &Block,
+    ) -> Result<(), ApplyBlockError> {
+        let mut pending = self.pending.lock().await;
+
+        if pending.is_empty() {
+            match store.apply_block(block).await {
+                Err(err) if err.is_transient() => {
+                    warn!(target: COMPONENT, %err, "Store unavailable, queueing block");
+                },
+                result => return result,
+            }
+        }
+
+        let queued = QueuedBlock {
+            block: block.clone(),
+            tenant: current_tenant(None),
+        };
+        self.persist(&queued)
+            .map_err(|err| ApplyBlockError::QueuePersistenceFailed(err.to_string()))?;
+        pending.insert(block.header().block_num(), queued);
+        self.queued.notify_one();
+
+        Ok(())
+    }
+
+    /// Applies all queued blocks to `store`, in order.
+    ///
+    /// Blocks rejected by the store are dropped from the queue. Returns the error of the first
+    /// transient failure, in which case the remaining blocks stay queued.
+    pub async fn flush(&self, store: &impl ApplyBlock) -> Result<(), ApplyBlockError> {
+        let mut pending = self.pending.lock().await;
+
+        while let Some(entry) = pending.first_entry() {
+            let block_num = *entry.key();
+            match entry.get().apply(store).await {
+                Ok(()) => info!(target: COMPONENT, block_num, "Applied queued block"),
+                Err(err) if err.is_transient() => return Err(err),
+                Err(err) => {
+                    warn!(target: COMPONENT, block_num, %err, "Dropping rejected block");
+                },
+            }
+
+            entry.remove();
+            self.remove(block_num)
+                .map_err(|err| ApplyBlockError::QueuePersistenceFailed(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Retries the queued blocks until all of them are applied, and waits for new blocks to be
+    /// queued afterwards.
+    ///
+    /// Consecutive failed retries are spaced out as scheduled by `backoff`. This never returns,
+    /// and is meant to be spawned as a background task.
+    pub async fn run_retry_task(self: Arc<Self>, store: impl ApplyBlock, backoff: Backoff) {
+        let mut retry = 0;
+        loop {
+            if self.is_empty().await {
+                self.queued.notified().await;
+            }
+
+            match self.flush(&store).await {
+                Ok(()) => retry = 0,
+                Err(err) => {
+                    let delay = backoff.delay(retry);
+                    warn!(target: COMPONENT, %err, ?delay, "Failed to apply queued blocks, retrying");
+                    backoff.sleep(delay).await;
+                    retry = retry.saturating_add(1);
+                },
+            }
+        }
+    }
+
+    // HELPERS
+    // --------------------------------------------------------------------------------------------
+
+    fn block_path(&self, block_num: u32) -> PathBuf {
+        self.dir.join(format!("{block_num:010}.{BLOCK_FILE_EXTENSION}"))
+    }
+
+    /// Writes the block and its tenant, if any, to their files in the queue directory.
+    ///
+    /// The tenant is written before the block, and each file is written to a temporary file
+    /// first, so a crash never leaves a partially written block, or a block without its tenant,
+    /// in the queue.
+    fn persist(&self, queued: &QueuedBlock) -> io::Result<()> {
+        let path = self.block_path(queued.block.header().block_num());
+
+        let tenant_path = path.with_extension(TENANT_FILE_EXTENSION);
+        match &queued.tenant {
+            Some(tenant) => write_atomically(&tenant_path, tenant.as_encoded_bytes())?,
+            // A tenant left by a crash must not be picked up for this block.
+            None => remove_file_if_exists(&tenant_path)?,
+        }
+
+        write_atomically(&path, &queued.block.to_bytes())
+    }
+
+    /// Removes the files of an applied or rejected block from the queue directory.
+    fn remove(&self, block_num: u32) -> io::Result<()> {
+        let path = self.block_path(block_num);
+        fs::remove_file(&path)?;
+
+        remove_file_if_exists(&path.with_extension(TENANT_FILE_EXTENSION))
+    }
+}
+
+/// A block waiting in the [ApplyQueue], together with the tenant it is applied for.
+#[derive(Debug)]
+struct QueuedBlock {
+    block: Block,
+    /// Tenant overriding the default tenant of the store, if any
+    tenant: Option<AsciiMetadataValue>,
+}
+
+impl QueuedBlock {
+    /// Applies the block to `store`, scoped to its tenant if any.
+    async fn apply(&self, store: &impl ApplyBlock) -> Result<(), ApplyBlockError> {
+        match &self.tenant {
+            Some(tenant) => scoped_to_tenant(tenant.clone(), store.apply_block(&self.block)).await,
+            None => store.apply_block(&self.block).await,
+        }
+    }
+}
+
+/// Writes `bytes` to a temporary file which is then renamed to `path`.
+fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+
+    fs::rename(tmp_path, path)
+}
+
+fn remove_file_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use tonic::Code;
+
+    use super::*;
+    use crate::test_utils::{block::MockBlockBuilder, MockStoreSuccess, MockStoreSuccessBuilder};
+
+    /// Store which is unavailable for the first `failures` calls to `apply_block()`, and records
+    /// the tenant overriding the default tenant of each call.
+    struct FlakyStore {
+        store: Arc<MockStoreSuccess>,
+        failures: AtomicUsize,
+        tenants: std::sync::Mutex<Vec<Option<AsciiMetadataValue>>>,
+    }
+
+    #[async_trait]
+    impl ApplyBlock for FlakyStore {
+        async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+            self.tenants.lock().unwrap().push(current_tenant(None));
+            let remaining_failures = self.failures.load(Ordering::Relaxed);
+            if remaining_failures > 0 {
+                self.failures.store(remaining_failures - 1, Ordering::Relaxed);
+                return Err(ApplyBlockError::StoreUnavailable {
+                    code: Code::Unavailable,
+                    message: String::new(),
+                });
+            }
+
+            self.store.apply_block(block).await
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transiently_failed_block_is_eventually_applied() {
+        let dir = std::env::temp_dir()
+            .join(format!("miden-apply-queue-{}-transient-failure", std::process::id()));
+        let store = Arc::new(MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build());
+        let block = MockBlockBuilder::new(&store).await.build();
+
+        let flaky_store = FlakyStore {
+            store: Arc::clone(&store),
+            failures: AtomicUsize::new(2),
+            tenants: Default::default(),
+        };
+        let queue = Arc::new(ApplyQueue::open(&dir).unwrap());
+
+        // The store is unavailable, so the block is queued and persisted.
+        queue.apply(&flaky_store, &block).await.unwrap();
+        assert_eq!(queue.len().await, 1);
+        assert_eq!(ApplyQueue::open(&dir).unwrap().len().await, 1);
+        assert_eq!(*store.num_apply_block_called.read().await, 0);
+
+        // The first retry fails as well, the second one applies the block.
+        let backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(1));
+        tokio::spawn(Arc::clone(&queue).run_retry_task(flaky_store, backoff));
+        while !queue.is_empty().await {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        assert_eq!(*store.num_apply_block_called.read().await, 1);
+        assert!(ApplyQueue::open(&dir).unwrap().is_empty().await);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn queued_block_is_retried_for_its_tenant() {
+        let dir =
+            std::env::temp_dir().join(format!("miden-apply-queue-{}-tenant", std::process::id()));
+        let store = Arc::new(MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build());
+        let block = MockBlockBuilder::new(&store).await.build();
+        let tenant: AsciiMetadataValue = "acme".parse().unwrap();
+
+        let flaky_store = FlakyStore {
+            store: Arc::clone(&store),
+            failures: AtomicUsize::new(1),
+            tenants: Default::default(),
+        };
+        let queue = ApplyQueue::open(&dir).unwrap();
+        scoped_to_tenant(tenant.clone(), queue.apply(&flaky_store, &block))
+            .await
+            .unwrap();
+
+        // The tenant is persisted with the block, and the block is retried for it.
+        ApplyQueue::open(&dir).unwrap().flush(&flaky_store).await.unwrap();
+        assert_eq!(*flaky_store.tenants.lock().unwrap(), [Some(tenant.clone()), Some(tenant)]);
+        assert_eq!(*store.num_apply_block_called.read().await, 1);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
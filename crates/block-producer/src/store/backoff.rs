@@ -0,0 +1,73 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{Clock, SystemClock};
+
+// BACKOFF
+// ================================================================================================
+
+/// Schedule of the delays between the attempts of a failed store request.
+///
+/// The delay before a retry is drawn uniformly between zero and a bound, which doubles after each
+/// retry up to a maximum. The jitter keeps clients which failed at the same time from retrying at
+/// the same time. Clones of a backoff share its source of randomness and its clock.
+#[derive(Clone)]
+pub struct Backoff {
+    /// Bound of the delay before the first retry
+    base_delay: Duration,
+    /// Upper bound of the delay between two attempts
+    max_delay: Duration,
+    rng: Arc<Mutex<StdRng>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Backoff {
+    /// Returns a backoff whose first delay is at most `base_delay`, and whose delays are at most
+    /// `max_delay`.
+    ///
+    /// The backoff is seeded from entropy and waits on the system clock.
+    pub fn new(base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            base_delay,
+            max_delay,
+            rng: Arc::new(Mutex::new(StdRng::from_entropy())),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Seeds the randomness of the delays, making them deterministic.
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng = Arc::new(Mutex::new(StdRng::seed_from_u64(seed)));
+        self
+    }
+
+    /// Waits for the delays on `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the bound of the delay before the given retry, where retry `0` follows the first
+    /// attempt.
+    pub fn bound(&self, retry: u32) -> Duration {
+        self.base_delay.saturating_mul(2u32.saturating_pow(retry)).min(self.max_delay)
+    }
+
+    /// Returns the delay before the given retry, drawn in whole milliseconds between zero and its
+    /// [bound](Self::bound).
+    pub fn delay(&self, retry: u32) -> Duration {
+        let bound = u64::try_from(self.bound(retry).as_millis()).unwrap_or(u64::MAX);
+        let mut rng = self.rng.lock().expect("rng lock poisoned");
+
+        Duration::from_millis(rng.gen_range(0..=bound))
+    }
+
+    /// Waits for `delay` on the clock of the backoff.
+    pub async fn sleep(&self, delay: Duration) {
+        self.clock.sleep(delay).await
+    }
+}
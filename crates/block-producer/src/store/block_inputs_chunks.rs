@@ -0,0 +1,108 @@
+use std::{collections::BTreeSet, num::NonZeroUsize};
+
+use miden_node_proto::generated::{
+    requests::GetBlockInputsRequest, responses::GetBlockInputsResponse,
+};
+
+use crate::errors::BlockInputsError;
+
+// BLOCK INPUTS CHUNKS
+// ================================================================================================
+
+/// Splits `message` into requests of at most `max_items` account IDs, nullifiers and notes in
+/// total, or returns it unchanged if it is small enough.
+///
+/// The items keep their order across the chunks: accounts first, then nullifiers, then notes.
+pub(super) fn split_block_inputs_request(
+    message: GetBlockInputsRequest,
+    max_items: NonZeroUsize,
+) -> Vec<GetBlockInputsRequest> {
+    let num_items =
+        message.account_ids.len() + message.nullifiers.len() + message.unauthenticated_notes.len();
+    if num_items <= max_items.get() {
+        return vec![message];
+    }
+
+    let mut chunks = Chunks {
+        chunks: Vec::new(),
+        num_items: 0,
+        max_items,
+    };
+    for account_id in message.account_ids {
+        chunks.next().account_ids.push(account_id);
+    }
+    for nullifier in message.nullifiers {
+        chunks.next().nullifiers.push(nullifier);
+    }
+    for note in message.unauthenticated_notes {
+        chunks.next().unauthenticated_notes.push(note);
+    }
+
+    chunks.chunks
+}
+
+/// Merges the responses to the chunks of a block inputs request into the response to the whole
+/// request.
+///
+/// The witnesses of all chunks must be taken at the same chain tip, which is checked by
+/// comparing their block headers and chain peaks. Block inclusion proofs of notes are
+/// deduplicated, as notes of one block may be spread across chunks.
+///
+/// # Errors
+/// Returns [BlockInputsError::InconsistentChunks] if the chunks were served at different chain
+/// tips, e.g. because a block was applied between them.
+pub(super) fn merge_block_inputs_responses(
+    responses: Vec<GetBlockInputsResponse>,
+) -> Result<GetBlockInputsResponse, BlockInputsError> {
+    let mut responses = responses.into_iter();
+    let mut merged = responses.next().unwrap_or_default();
+    let mut proven_blocks: BTreeSet<_> = merged
+        .found_unauthenticated_notes
+        .iter()
+        .flat_map(|notes| &notes.block_proofs)
+        .map(|proof| proof.block_header.as_ref().map(|header| header.block_num))
+        .collect();
+
+    for response in responses {
+        if response.block_header != merged.block_header || response.mmr_peaks != merged.mmr_peaks {
+            return Err(BlockInputsError::InconsistentChunks);
+        }
+
+        merged.account_states.extend(response.account_states);
+        merged.nullifiers.extend(response.nullifiers);
+
+        let Some(notes) = response.found_unauthenticated_notes else {
+            continue;
+        };
+        let merged_notes = merged.found_unauthenticated_notes.get_or_insert_with(Default::default);
+        merged_notes.note_proofs.extend(notes.note_proofs);
+        for proof in notes.block_proofs {
+            if proven_blocks.insert(proof.block_header.as_ref().map(|header| header.block_num)) {
+                merged_notes.block_proofs.push(proof);
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Chunks of a block inputs request being filled with items.
+struct Chunks {
+    chunks: Vec<GetBlockInputsRequest>,
+    /// Number of items in the last chunk
+    num_items: usize,
+    max_items: NonZeroUsize,
+}
+
+impl Chunks {
+    /// Returns the chunk receiving the next item, starting a new chunk if the last one is full.
+    fn next(&mut self) -> &mut GetBlockInputsRequest {
+        if self.chunks.is_empty() || self.num_items == self.max_items.get() {
+            self.chunks.push(GetBlockInputsRequest::default());
+            self.num_items = 0;
+        }
+        self.num_items += 1;
+
+        self.chunks.last_mut().expect("a chunk was pushed above")
+    }
+}
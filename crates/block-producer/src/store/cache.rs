@@ -0,0 +1,371 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    mem::size_of,
+};
+
+use miden_node_proto::domain::{blocks::BlockInclusionProof, notes::NoteAuthenticationInfo};
+use miden_objects::{
+    notes::{NoteId, NoteInclusionProof},
+    Digest,
+};
+use tonic::metadata::AsciiMetadataValue;
+
+// CONSTANTS
+// ================================================================================================
+
+/// The default maximum number of notes kept in the [NoteAuthCache].
+pub const DEFAULT_MAX_CACHE_ENTRIES: usize = 1024;
+
+// NOTE AUTHENTICATION CACHE
+// ================================================================================================
+
+/// Cache of note authentication info returned by the store.
+///
+/// The cache is bounded both by number of notes and by the approximate number of bytes used by
+/// the cached proofs. When either bound is exceeded, the oldest notes are evicted first.
+///
+/// The block inclusion proofs are only valid for the chain length they were created at, so the
+/// cache must be cleared whenever a new block is applied.
+#[derive(Debug)]
+pub struct NoteAuthCache {
+    entries: BTreeMap<NoteId, CacheEntry>,
+    /// Cached note IDs, from the oldest to the newest
+    insertion_order: VecDeque<NoteId>,
+    /// Approximate number of bytes used by all entries
+    total_bytes: usize,
+    max_entries: usize,
+    max_cache_bytes: Option<usize>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    note_proof: NoteInclusionProof,
+    block_proof: BlockInclusionProof,
+    /// Approximate number of bytes used by the entry
+    size: usize,
+}
+
+impl NoteAuthCache {
+    /// Returns a builder of the cache with the default bounds.
+    pub fn builder() -> NoteAuthCacheBuilder {
+        NoteAuthCacheBuilder::default()
+    }
+
+    /// Returns the number of cached notes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no notes are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the approximate number of bytes used by the cached notes.
+    pub fn total_bytes(&self) -> usize {
+        self.total_bytes
+    }
+
+    /// Returns true if the authentication info of the note is cached.
+    pub fn contains(&self, note_id: &NoteId) -> bool {
+        self.entries.contains_key(note_id)
+    }
+
+    /// Returns the authentication info of the given notes, or `None` if any of them is not cached.
+    pub fn get<'a>(
+        &self,
+        notes: impl IntoIterator<Item = &'a NoteId>,
+    ) -> Option<NoteAuthenticationInfo> {
+        let mut note_proofs = BTreeMap::new();
+        let mut block_proofs = BTreeMap::new();
+        for note_id in notes {
+            let entry = self.entries.get(note_id)?;
+            note_proofs.insert(*note_id, entry.note_proof.clone());
+            block_proofs
+                .entry(entry.block_proof.block_header.block_num())
+                .or_insert_with(|| entry.block_proof.clone());
+        }
+
+        Some(NoteAuthenticationInfo {
+            block_proofs: block_proofs.into_values().collect(),
+            note_proofs,
+        })
+    }
+
+    /// Caches the authentication info of all notes in `info`, evicting the oldest notes if the
+    /// bounds of the cache are exceeded.
+    ///
+    /// Notes which alone exceed the byte bound of the cache are not cached.
+    pub fn insert(&mut self, info: &NoteAuthenticationInfo) {
+        for (note_id, note_proof) in &info.note_proofs {
+            let block_num = note_proof.location().block_num();
+            let Some(block_proof) = info
+                .block_proofs
+                .iter()
+                .find(|proof| proof.block_header.block_num() == block_num)
+            else {
+                continue;
+            };
+
+            let size = entry_size(note_proof, block_proof);
+            if self.max_cache_bytes.is_some_and(|max_bytes| size > max_bytes) {
+                continue;
+            }
+
+            self.remove(note_id);
+            self.entries.insert(
+                *note_id,
+                CacheEntry {
+                    note_proof: note_proof.clone(),
+                    block_proof: block_proof.clone(),
+                    size,
+                },
+            );
+            self.insertion_order.push_back(*note_id);
+            self.total_bytes += size;
+
+            self.evict();
+        }
+    }
+
+    /// Removes all notes from the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.insertion_order.clear();
+        self.total_bytes = 0;
+    }
+
+    // HELPERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an empty cache with the bounds of this cache.
+    fn with_same_bounds(&self) -> Self {
+        NoteAuthCacheBuilder {
+            max_entries: self.max_entries,
+            max_cache_bytes: self.max_cache_bytes,
+        }
+        .build()
+    }
+
+    fn remove(&mut self, note_id: &NoteId) {
+        if let Some(entry) = self.entries.remove(note_id) {
+            self.insertion_order.retain(|id| id != note_id);
+            self.total_bytes -= entry.size;
+        }
+    }
+
+    /// Evicts the oldest notes until the cache is within its bounds.
+    fn evict(&mut self) {
+        while self.entries.len() > self.max_entries
+            || self.max_cache_bytes.is_some_and(|max_bytes| self.total_bytes > max_bytes)
+        {
+            let Some(note_id) = self.insertion_order.pop_front() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&note_id) {
+                self.total_bytes -= entry.size;
+            }
+        }
+    }
+}
+
+/// Returns the approximate number of bytes used by a cache entry.
+fn entry_size(note_proof: &NoteInclusionProof, block_proof: &BlockInclusionProof) -> usize {
+    let paths_len = note_proof.note_path().len() + block_proof.mmr_path.len();
+
+    size_of::<NoteId>() + size_of::<CacheEntry>() + paths_len * size_of::<Digest>()
+}
+
+// TENANT NOTE AUTHENTICATION CACHES
+// ================================================================================================
+
+/// Note authentication caches of a [DefaultStore](super::DefaultStore), one for each tenant it
+/// sent requests for, so the info returned for one tenant is never served to another.
+///
+/// The cache of each tenant has the bounds of the cache the store was configured with.
+#[derive(Debug)]
+pub(super) struct TenantNoteAuthCaches {
+    /// Empty cache with the bounds of the cache of each tenant
+    bounds: NoteAuthCache,
+    caches: BTreeMap<Option<AsciiMetadataValue>, NoteAuthCache>,
+}
+
+impl TenantNoteAuthCaches {
+    /// Returns caches with the bounds of `cache`.
+    pub fn new(cache: &NoteAuthCache) -> Self {
+        Self {
+            bounds: cache.with_same_bounds(),
+            caches: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the authentication info of the given notes cached for `tenant`, or `None` if any of
+    /// them is not cached.
+    pub fn get<'a>(
+        &self,
+        tenant: &Option<AsciiMetadataValue>,
+        notes: impl IntoIterator<Item = &'a NoteId>,
+    ) -> Option<NoteAuthenticationInfo> {
+        self.caches.get(tenant)?.get(notes)
+    }
+
+    /// Caches the authentication info of all notes in `info` for `tenant`.
+    pub fn insert(&mut self, tenant: Option<AsciiMetadataValue>, info: &NoteAuthenticationInfo) {
+        self.caches
+            .entry(tenant)
+            .or_insert_with(|| self.bounds.with_same_bounds())
+            .insert(info);
+    }
+
+    /// Removes the notes of all tenants.
+    pub fn clear(&mut self) {
+        self.caches.clear();
+    }
+}
+
+// NOTE AUTHENTICATION CACHE BUILDER
+// ================================================================================================
+
+/// Builds a [NoteAuthCache]
+#[derive(Debug)]
+pub struct NoteAuthCacheBuilder {
+    max_entries: usize,
+    max_cache_bytes: Option<usize>,
+}
+
+impl Default for NoteAuthCacheBuilder {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_CACHE_ENTRIES,
+            max_cache_bytes: None,
+        }
+    }
+}
+
+impl NoteAuthCacheBuilder {
+    /// Sets the maximum number of cached notes.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Sets the maximum approximate number of bytes used by the cached proofs.
+    ///
+    /// By default, the cache is only bounded by the number of notes.
+    pub fn max_cache_bytes(mut self, max_cache_bytes: usize) -> Self {
+        self.max_cache_bytes = Some(max_cache_bytes);
+        self
+    }
+
+    pub fn build(self) -> NoteAuthCache {
+        NoteAuthCache {
+            entries: BTreeMap::new(),
+            insertion_order: VecDeque::new(),
+            total_bytes: 0,
+            max_entries: self.max_entries,
+            max_cache_bytes: self.max_cache_bytes,
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{crypto::merkle::MerklePath, BlockHeader, Felt, ZERO};
+
+    use super::*;
+
+    /// Returns the authentication info of a note created in block 1 with a note path of the given
+    /// length.
+    fn note_auth_info(note_num: u64, path_len: usize) -> (NoteId, NoteAuthenticationInfo) {
+        let note_id = NoteId::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(note_num)]));
+        let note_proof =
+            NoteInclusionProof::new(1, 0, MerklePath::new(vec![Digest::default(); path_len]))
+                .unwrap();
+        let block_header = BlockHeader::new(
+            0,
+            Digest::default(),
+            1,
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            1,
+        );
+        let block_proof = BlockInclusionProof {
+            block_header,
+            mmr_path: MerklePath::default(),
+            chain_length: 2,
+        };
+
+        let info = NoteAuthenticationInfo {
+            block_proofs: vec![block_proof],
+            note_proofs: BTreeMap::from_iter([(note_id, note_proof)]),
+        };
+
+        (note_id, info)
+    }
+
+    #[test]
+    fn cached_notes_are_returned() {
+        let mut cache = NoteAuthCache::builder().build();
+        let (note_a, info_a) = note_auth_info(1, 16);
+        let (note_b, info_b) = note_auth_info(2, 16);
+
+        cache.insert(&info_a);
+        assert!(cache.get([&note_a, &note_b]).is_none());
+
+        cache.insert(&info_b);
+        let info = cache.get([&note_a, &note_b]).unwrap();
+        assert_eq!(info.note_ids(), [note_a, note_b].into());
+        assert_eq!(info.block_proofs.len(), 1);
+    }
+
+    #[test]
+    fn entry_count_limit_evicts_oldest_notes() {
+        let mut cache = NoteAuthCache::builder().max_entries(1).build();
+        let (note_a, info_a) = note_auth_info(1, 16);
+        let (note_b, info_b) = note_auth_info(2, 16);
+
+        cache.insert(&info_a);
+        cache.insert(&info_b);
+
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get([&note_a]).is_none());
+        assert!(cache.get([&note_b]).is_some());
+    }
+
+    #[test]
+    fn oversized_proofs_trigger_byte_based_eviction() {
+        let (note_small, info_small) = note_auth_info(1, 16);
+        let (note_large, info_large) = note_auth_info(2, 64);
+        let (note_oversized, info_oversized) = note_auth_info(3, 128);
+
+        let small_size =
+            entry_size(&info_small.note_proofs[&note_small], &info_small.block_proofs[0]);
+        let large_size =
+            entry_size(&info_large.note_proofs[&note_large], &info_large.block_proofs[0]);
+        let mut cache = NoteAuthCache::builder().max_cache_bytes(large_size).build();
+
+        // The large proof does not fit next to the small one, which is evicted even though the
+        // entry count limit is not reached.
+        cache.insert(&info_small);
+        assert_eq!(cache.total_bytes(), small_size);
+        cache.insert(&info_large);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.total_bytes(), large_size);
+        assert!(cache.get([&note_small]).is_none());
+        assert!(cache.get([&note_large]).is_some());
+
+        // A proof exceeding the whole budget is not cached, and does not evict other notes.
+        cache.insert(&info_oversized);
+        assert!(cache.get([&note_oversized]).is_none());
+        assert!(cache.get([&note_large]).is_some());
+        assert!(cache.total_bytes() <= large_size);
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use miden_node_proto::{
+    domain::{blocks::BlockNumber, notes::NoteAuthenticationInfo},
+    AccountState,
+};
+use miden_objects::{
+    accounts::AccountId,
+    block::Block,
+    notes::{NoteId, Nullifier},
+    transaction::TransactionId,
+    Digest,
+};
+use miden_processor::crypto::RpoDigest;
+use tokio_stream::Stream;
+
+use super::{ApplyBlock, ChainStats, NoteAuthCache, Store, StoreStatus, TransactionInputs};
+use crate::{
+    block::BlockInputs,
+    errors::{ApplyBlockError, BlockInputsError, NotePathsError, TxInputsError},
+    ProvenTransaction,
+};
+
+// CACHING STORE
+// ================================================================================================
+
+/// A [Store] which memoizes the note authentication info returned by the wrapped store, e.g.
+/// for the notes queried repeatedly while building one block.
+///
+/// Only the notes which are not cached yet are requested from the wrapped store, so requests of
+/// cached notes don't reach it at all. Notes without authentication info are not cached.
+///
+/// The block inclusion proofs of the cached info are only valid for the chain they were created
+/// at, so the cache must be invalidated at block boundaries with [CachingStore::invalidate].
+/// Blocks applied through the wrapper invalidate it, and info created at a shorter chain than the
+/// last applied block, e.g. by a request which was in flight while the block was applied, is
+/// neither cached nor combined with info created at another chain length. All other requests are
+/// forwarded to the wrapped store as is.
+pub struct CachingStore<S> {
+    inner: S,
+    /// Authentication info of the notes returned by the wrapped store since the last
+    /// invalidation
+    cache: Mutex<CacheState>,
+}
+
+struct CacheState {
+    notes: NoteAuthCache,
+    /// Chain length which the block inclusion proofs of the cached notes were created at, if any
+    /// notes were cached since the last invalidation
+    chain_length: Option<u32>,
+    /// Chain length after the last block applied through the wrapper
+    min_chain_length: u32,
+}
+
+impl<S: Store> CachingStore<S> {
+    /// Returns a store memoizing the note authentication info returned by `inner`, in a cache
+    /// with the default bounds.
+    pub fn new(inner: S) -> Self {
+        Self::with_cache(inner, NoteAuthCache::builder().build())
+    }
+
+    /// Returns a store memoizing the note authentication info returned by `inner` in `cache`.
+    pub fn with_cache(inner: S, cache: NoteAuthCache) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(CacheState {
+                notes: cache,
+                chain_length: None,
+                min_chain_length: 0,
+            }),
+        }
+    }
+
+    /// Returns the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Drops all cached note authentication info, e.g. once a new block was applied.
+    pub fn invalidate(&self) {
+        self.cache().clear();
+    }
+
+    /// Drops all cached note authentication info, and rejects info created before the block
+    /// `block_num` was applied from now on.
+    fn invalidate_applied(&self, block_num: BlockNumber) {
+        // The chain holds all blocks from the genesis block up to and including `block_num`.
+        let chain_length = block_num.as_u32().saturating_add(1);
+
+        let mut cache = self.cache();
+        cache.clear();
+        cache.min_chain_length = cache.min_chain_length.max(chain_length);
+    }
+
+    fn cache(&self) -> MutexGuard<'_, CacheState> {
+        self.cache.lock().expect("note auth cache lock poisoned")
+    }
+}
+
+impl CacheState {
+    fn clear(&mut self) {
+        self.notes.clear();
+        self.chain_length = None;
+    }
+
+    /// Caches the notes of `info`, unless it was created at a shorter chain than the cached notes
+    /// or the last applied block. Notes cached at a shorter chain are dropped.
+    fn insert(&mut self, info: &NoteAuthenticationInfo) {
+        let Some(chain_length) = chain_length(info) else {
+            return;
+        };
+        if chain_length < self.min_chain_length
+            || self.chain_length.is_some_and(|cached| cached > chain_length)
+        {
+            return;
+        }
+        if self.chain_length.is_some_and(|cached| cached < chain_length) {
+            self.notes.clear();
+        }
+
+        self.chain_length = Some(chain_length);
+        self.notes.insert(info);
+    }
+}
+
+/// Returns the chain length which the block inclusion proofs of `info` were created at, or `None`
+/// if it contains no proofs.
+fn chain_length(info: &NoteAuthenticationInfo) -> Option<u32> {
+    info.block_proofs.first().map(|proof| proof.chain_length)
+}
+
+/// Adds the notes of `other` to `info`, keeping one block inclusion proof per block.
+///
+/// Both must be created at the same chain length, otherwise the kept proofs are inconsistent.
+fn merge_note_authentication_info(
+    info: &mut NoteAuthenticationInfo,
+    other: NoteAuthenticationInfo,
+) {
+    let proven_blocks: BTreeSet<_> =
+        info.block_proofs.iter().map(|proof| proof.block_header.block_num()).collect();
+
+    info.note_proofs.extend(other.note_proofs);
+    info.block_proofs.extend(
+        other
+            .block_proofs
+            .into_iter()
+            .filter(|proof| !proven_blocks.contains(&proof.block_header.block_num())),
+    );
+}
+
+#[async_trait]
+impl<S: Store> ApplyBlock for CachingStore<S> {
+    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        let result = self.inner.apply_block(block).await;
+        match &result {
+            Ok(()) => self.invalidate_applied(block.header().block_num().into()),
+            Err(_) => self.invalidate(),
+        }
+
+        result
+    }
+}
+
+#[async_trait]
+impl<S: Store> Store for CachingStore<S> {
+    async fn get_tx_inputs(
+        &self,
+        proven_tx: &ProvenTransaction,
+    ) -> Result<TransactionInputs, TxInputsError> {
+        self.inner.get_tx_inputs(proven_tx).await
+    }
+
+    async fn get_tx_inputs_batch(
+        &self,
+        txs: &[ProvenTransaction],
+    ) -> Result<Vec<TransactionInputs>, TxInputsError> {
+        self.inner.get_tx_inputs_batch(txs).await
+    }
+
+    async fn get_block_inputs(
+        &self,
+        updated_accounts: impl Iterator<Item = AccountId> + Send,
+        produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<BlockInputs, BlockInputsError> {
+        self.inner.get_block_inputs(updated_accounts, produced_nullifiers, notes).await
+    }
+
+    async fn get_note_authentication_info(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        let notes: BTreeSet<NoteId> = notes.copied().collect();
+        let (mut info, uncached) = {
+            let cache = self.cache();
+            let (cached, uncached): (Vec<_>, Vec<_>) =
+                notes.iter().partition(|note| cache.notes.contains(note));
+            let info = cache.notes.get(cached).expect("cached notes are in the cache");
+
+            (info, uncached)
+        };
+        if uncached.is_empty() {
+            return Ok(info);
+        }
+
+        let fetched = self.inner.get_note_authentication_info(uncached.into_iter()).await?;
+        self.cache().insert(&fetched);
+
+        // The chain grew in between, so the proofs of the cached and of the fetched notes can't be
+        // combined.
+        if let (Some(cached), Some(fetched_at)) = (chain_length(&info), chain_length(&fetched)) {
+            if cached != fetched_at {
+                let fetched = self.inner.get_note_authentication_info(notes.iter()).await?;
+                self.cache().insert(&fetched);
+
+                return Ok(fetched);
+            }
+        }
+        merge_note_authentication_info(&mut info, fetched);
+
+        Ok(info)
+    }
+
+    async fn notes_authenticated_since(
+        &self,
+        notes: &[NoteId],
+        since: BlockNumber,
+    ) -> Result<Vec<NoteId>, NotePathsError> {
+        self.inner.notes_authenticated_since(notes, since).await
+    }
+
+    async fn nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, TxInputsError> {
+        self.inner.nullifier_origin(nullifier).await
+    }
+
+    async fn get_note_spend_block(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.inner.get_note_spend_block(nullifier).await
+    }
+
+    async fn get_pending_nullifiers(&self) -> Result<Vec<Nullifier>, TxInputsError> {
+        self.inner.get_pending_nullifiers().await
+    }
+
+    async fn get_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountState, TxInputsError> {
+        self.inner.get_account_state(account_id).await
+    }
+
+    async fn get_account_state_forks(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(RpoDigest, RpoDigest)>, TxInputsError> {
+        self.inner.get_account_state_forks(account_id).await
+    }
+
+    async fn get_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.inner.get_account_creation_block(account_id).await
+    }
+
+    async fn get_nullifier_root_history(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, RpoDigest)>, TxInputsError> {
+        self.inner.get_nullifier_root_history(from, to).await
+    }
+
+    async fn get_chain_stats(&self) -> Result<ChainStats, TxInputsError> {
+        self.inner.get_chain_stats().await
+    }
+
+    async fn check_health(&self) -> Result<StoreStatus, BlockInputsError> {
+        self.inner.check_health().await
+    }
+
+    async fn next_block_number(&self) -> Result<BlockNumber, TxInputsError> {
+        self.inner.next_block_number().await
+    }
+
+    async fn apply_block_with_diff(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError> {
+        let result = self.inner.apply_block_with_diff(block).await;
+        match &result {
+            Ok(_) => self.invalidate_applied(block.header().block_num().into()),
+            Err(_) => self.invalidate(),
+        }
+
+        result
+    }
+
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError> {
+        // Blocks before a rejected block are applied nevertheless.
+        let result = self.inner.apply_blocks(blocks).await;
+        match &result {
+            Ok(last_applied_block_num)
+            | Err(ApplyBlockError::BlockRejected { last_applied_block_num, .. }) => {
+                self.invalidate_applied(*last_applied_block_num)
+            },
+            Err(_) => self.invalidate(),
+        }
+
+        result
+    }
+
+    fn sync_blocks_from(
+        &self,
+        from: BlockNumber,
+    ) -> impl Stream<Item = Result<Block, TxInputsError>> + Send + 'static {
+        self.inner.sync_blocks_from(from)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use miden_node_proto::domain::blocks::BlockInclusionProof;
+    use miden_objects::{
+        crypto::merkle::MerklePath, notes::NoteInclusionProof, BlockHeader, Felt, ZERO,
+    };
+
+    use super::*;
+
+    /// Returns the authentication info of a note created in block 1, proven against a chain of
+    /// the given length.
+    fn note_auth_info(note_num: u64, chain_length: u32) -> (NoteId, NoteAuthenticationInfo) {
+        let note_id = NoteId::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(note_num)]));
+        let note_proof = NoteInclusionProof::new(1, 0, MerklePath::default()).unwrap();
+        let block_header = BlockHeader::new(
+            0,
+            Digest::default(),
+            1,
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            1,
+        );
+        let block_proof = BlockInclusionProof {
+            block_header,
+            mmr_path: MerklePath::default(),
+            chain_length,
+        };
+
+        let info = NoteAuthenticationInfo {
+            block_proofs: vec![block_proof],
+            note_proofs: BTreeMap::from_iter([(note_id, note_proof)]),
+        };
+
+        (note_id, info)
+    }
+
+    fn cache_state() -> CacheState {
+        CacheState {
+            notes: NoteAuthCache::builder().build(),
+            chain_length: None,
+            min_chain_length: 0,
+        }
+    }
+
+    #[test]
+    fn info_fetched_before_the_last_applied_block_is_not_cached() {
+        let mut cache = cache_state();
+        let (note, info) = note_auth_info(1, 2);
+
+        // The request was in flight while block 2 was applied.
+        cache.clear();
+        cache.min_chain_length = 3;
+        cache.insert(&info);
+
+        assert!(!cache.notes.contains(&note));
+        assert_eq!(cache.chain_length, None);
+    }
+
+    #[test]
+    fn info_of_a_longer_chain_replaces_cached_notes() {
+        let mut cache = cache_state();
+        let (note_a, info_a) = note_auth_info(1, 2);
+        let (note_b, info_b) = note_auth_info(2, 3);
+
+        cache.insert(&info_a);
+        cache.insert(&info_b);
+        assert!(!cache.notes.contains(&note_a));
+        assert!(cache.notes.contains(&note_b));
+        assert_eq!(cache.chain_length, Some(3));
+
+        // Info of a shorter chain does not replace it in turn.
+        cache.insert(&info_a);
+        assert!(!cache.notes.contains(&note_a));
+        assert_eq!(cache.chain_length, Some(3));
+    }
+}
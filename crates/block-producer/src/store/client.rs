@@ -0,0 +1,235 @@
+use async_trait::async_trait;
+use miden_node_proto::generated::{
+    requests::{
+        ApplyBlockRequest, ApplyBlocksRequest, CheckNullifiersRequest,
+        GetAccountCreationBlockRequest, GetAccountStateForksRequest, GetAccountStateRequest,
+        GetBlockInputsRequest, GetChainStatsRequest, GetNoteAuthenticationInfoRequest,
+        GetNotesAuthenticatedSinceRequest, GetNullifierOriginRequest,
+        GetNullifierRootHistoryRequest, GetPendingNullifiersRequest,
+        GetTransactionInputsBatchRequest, GetTransactionInputsRequest, SyncBlocksRequest,
+    },
+    responses::{
+        ApplyBlockResponse, ApplyBlocksResponse, CheckNullifiersResponse,
+        GetAccountCreationBlockResponse, GetAccountStateForksResponse, GetAccountStateResponse,
+        GetBlockInputsResponse, GetChainStatsResponse, GetNoteAuthenticationInfoResponse,
+        GetNotesAuthenticatedSinceResponse, GetNullifierOriginResponse,
+        GetNullifierRootHistoryResponse, GetPendingNullifiersResponse,
+        GetTransactionInputsBatchResponse, GetTransactionInputsResponse, SyncBlocksResponse,
+    },
+    store::api_client::ApiClient,
+};
+use tokio_stream::Stream;
+use tonic::{codec::Streaming, transport::Channel, Request, Response, Status};
+
+// STORE CLIENT
+// ================================================================================================
+
+/// Sends the requests of a [DefaultStore](super::DefaultStore) to the store.
+///
+/// The methods mirror the ones of the store's gRPC API which are used by the block producer.
+#[async_trait]
+pub trait StoreClient: Clone + Send + Sync + 'static {
+    /// Stream of the blocks returned by [StoreClient::sync_blocks].
+    type BlockStream: Stream<Item = Result<SyncBlocksResponse, Status>> + Send + 'static;
+
+    async fn apply_block(
+        &mut self,
+        request: Request<ApplyBlockRequest>,
+    ) -> Result<Response<ApplyBlockResponse>, Status>;
+
+    async fn apply_blocks(
+        &mut self,
+        request: Request<ApplyBlocksRequest>,
+    ) -> Result<Response<ApplyBlocksResponse>, Status>;
+
+    async fn get_transaction_inputs(
+        &mut self,
+        request: Request<GetTransactionInputsRequest>,
+    ) -> Result<Response<GetTransactionInputsResponse>, Status>;
+
+    async fn get_transaction_inputs_batch(
+        &mut self,
+        request: Request<GetTransactionInputsBatchRequest>,
+    ) -> Result<Response<GetTransactionInputsBatchResponse>, Status>;
+
+    async fn get_block_inputs(
+        &mut self,
+        request: Request<GetBlockInputsRequest>,
+    ) -> Result<Response<GetBlockInputsResponse>, Status>;
+
+    async fn get_note_authentication_info(
+        &mut self,
+        request: Request<GetNoteAuthenticationInfoRequest>,
+    ) -> Result<Response<GetNoteAuthenticationInfoResponse>, Status>;
+
+    async fn get_notes_authenticated_since(
+        &mut self,
+        request: Request<GetNotesAuthenticatedSinceRequest>,
+    ) -> Result<Response<GetNotesAuthenticatedSinceResponse>, Status>;
+
+    async fn get_nullifier_origin(
+        &mut self,
+        request: Request<GetNullifierOriginRequest>,
+    ) -> Result<Response<GetNullifierOriginResponse>, Status>;
+
+    async fn check_nullifiers(
+        &mut self,
+        request: Request<CheckNullifiersRequest>,
+    ) -> Result<Response<CheckNullifiersResponse>, Status>;
+
+    async fn get_pending_nullifiers(
+        &mut self,
+        request: Request<GetPendingNullifiersRequest>,
+    ) -> Result<Response<GetPendingNullifiersResponse>, Status>;
+
+    async fn get_account_state(
+        &mut self,
+        request: Request<GetAccountStateRequest>,
+    ) -> Result<Response<GetAccountStateResponse>, Status>;
+
+    async fn get_account_state_forks(
+        &mut self,
+        request: Request<GetAccountStateForksRequest>,
+    ) -> Result<Response<GetAccountStateForksResponse>, Status>;
+
+    async fn get_account_creation_block(
+        &mut self,
+        request: Request<GetAccountCreationBlockRequest>,
+    ) -> Result<Response<GetAccountCreationBlockResponse>, Status>;
+
+    async fn get_nullifier_root_history(
+        &mut self,
+        request: Request<GetNullifierRootHistoryRequest>,
+    ) -> Result<Response<GetNullifierRootHistoryResponse>, Status>;
+
+    async fn get_chain_stats(
+        &mut self,
+        request: Request<GetChainStatsRequest>,
+    ) -> Result<Response<GetChainStatsResponse>, Status>;
+
+    async fn sync_blocks(
+        &mut self,
+        request: Request<SyncBlocksRequest>,
+    ) -> Result<Response<Self::BlockStream>, Status>;
+}
+
+// GRPC CLIENT
+// ================================================================================================
+
+/// Sends the requests to a store over gRPC.
+#[async_trait]
+impl StoreClient for ApiClient<Channel> {
+    type BlockStream = Streaming<SyncBlocksResponse>;
+
+    async fn apply_block(
+        &mut self,
+        request: Request<ApplyBlockRequest>,
+    ) -> Result<Response<ApplyBlockResponse>, Status> {
+        ApiClient::apply_block(self, request).await
+    }
+
+    async fn apply_blocks(
+        &mut self,
+        request: Request<ApplyBlocksRequest>,
+    ) -> Result<Response<ApplyBlocksResponse>, Status> {
+        ApiClient::apply_blocks(self, request).await
+    }
+
+    async fn get_transaction_inputs(
+        &mut self,
+        request: Request<GetTransactionInputsRequest>,
+    ) -> Result<Response<GetTransactionInputsResponse>, Status> {
+        ApiClient::get_transaction_inputs(self, request).await
+    }
+
+    async fn get_transaction_inputs_batch(
+        &mut self,
+        request: Request<GetTransactionInputsBatchRequest>,
+    ) -> Result<Response<GetTransactionInputsBatchResponse>, Status> {
+        ApiClient::get_transaction_inputs_batch(self, request).await
+    }
+
+    async fn get_block_inputs(
+        &mut self,
+        request: Request<GetBlockInputsRequest>,
+    ) -> Result<Response<GetBlockInputsResponse>, Status> {
+        ApiClient::get_block_inputs(self, request).await
+    }
+
+    async fn get_note_authentication_info(
+        &mut self,
+        request: Request<GetNoteAuthenticationInfoRequest>,
+    ) -> Result<Response<GetNoteAuthenticationInfoResponse>, Status> {
+        ApiClient::get_note_authentication_info(self, request).await
+    }
+
+    async fn get_notes_authenticated_since(
+        &mut self,
+        request: Request<GetNotesAuthenticatedSinceRequest>,
+    ) -> Result<Response<GetNotesAuthenticatedSinceResponse>, Status> {
+        ApiClient::get_notes_authenticated_since(self, request).await
+    }
+
+    async fn get_nullifier_origin(
+        &mut self,
+        request: Request<GetNullifierOriginRequest>,
+    ) -> Result<Response<GetNullifierOriginResponse>, Status> {
+        ApiClient::get_nullifier_origin(self, request).await
+    }
+
+    async fn check_nullifiers(
+        &mut self,
+        request: Request<CheckNullifiersRequest>,
+    ) -> Result<Response<CheckNullifiersResponse>, Status> {
+        ApiClient::check_nullifiers(self, request).await
+    }
+
+    async fn get_pending_nullifiers(
+        &mut self,
+        request: Request<GetPendingNullifiersRequest>,
+    ) -> Result<Response<GetPendingNullifiersResponse>, Status> {
+        ApiClient::get_pending_nullifiers(self, request).await
+    }
+
+    async fn get_account_state(
+        &mut self,
+        request: Request<GetAccountStateRequest>,
+    ) -> Result<Response<GetAccountStateResponse>, Status> {
+        ApiClient::get_account_state(self, request).await
+    }
+
+    async fn get_account_state_forks(
+        &mut self,
+        request: Request<GetAccountStateForksRequest>,
+    ) -> Result<Response<GetAccountStateForksResponse>, Status> {
+        ApiClient::get_account_state_forks(self, request).await
+    }
+
+    async fn get_account_creation_block(
+        &mut self,
+        request: Request<GetAccountCreationBlockRequest>,
+    ) -> Result<Response<GetAccountCreationBlockResponse>, Status> {
+        ApiClient::get_account_creation_block(self, request).await
+    }
+
+    async fn get_nullifier_root_history(
+        &mut self,
+        request: Request<GetNullifierRootHistoryRequest>,
+    ) -> Result<Response<GetNullifierRootHistoryResponse>, Status> {
+        ApiClient::get_nullifier_root_history(self, request).await
+    }
+
+    async fn get_chain_stats(
+        &mut self,
+        request: Request<GetChainStatsRequest>,
+    ) -> Result<Response<GetChainStatsResponse>, Status> {
+        ApiClient::get_chain_stats(self, request).await
+    }
+
+    async fn sync_blocks(
+        &mut self,
+        request: Request<SyncBlocksRequest>,
+    ) -> Result<Response<Self::BlockStream>, Status> {
+        ApiClient::sync_blocks(self, request).await
+    }
+}
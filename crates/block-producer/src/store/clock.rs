@@ -0,0 +1,33 @@
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+// CLOCK
+// ================================================================================================
+
+/// Source of the current time for all time-dependent behavior of the store.
+///
+/// This allows tests to control the passage of time instead of waiting for it.
+#[async_trait]
+pub trait Clock: Send + Sync + 'static {
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+
+    /// Waits until `duration` has passed on this clock.
+    async fn sleep(&self, duration: Duration);
+}
+
+/// [Clock] reading the system's monotonic clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+}
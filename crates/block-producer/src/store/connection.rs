@@ -0,0 +1,161 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use tonic::{Code, Status};
+use tracing::info;
+
+use super::{Clock, SystemClock};
+use crate::COMPONENT;
+
+/// The number of consecutive requests which must fail because the store is unavailable before
+/// the client is rebuilt, if reconnecting is enabled.
+const RECONNECT_AFTER_FAILURES: u32 = 3;
+
+// CONNECTION STATE
+// ================================================================================================
+
+/// State of the connection of a [DefaultStore](super::DefaultStore) to the store, inferred from
+/// the outcomes of the recent requests sent to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No request completed yet, or a request is being sent after a transient failure
+    #[default]
+    Connecting,
+    /// The last request reached the store, whether the store accepted or rejected it
+    Connected,
+    /// The last request failed because the store was unavailable
+    TransientFailure,
+}
+
+/// Holds the client sending requests to the store, and tracks the [ConnectionState] of the
+/// requests sent through it.
+///
+/// If reconnecting is enabled, the client is rebuilt after repeated requests failed because the
+/// store was unavailable, at most once per cooldown.
+///
+/// The monitor is shared between a [DefaultStore](super::DefaultStore) and its block applier.
+/// Requests sent to replicas or shards of the store are not tracked.
+#[derive(Clone)]
+pub(super) struct ConnectionMonitor<C> {
+    inner: Arc<Mutex<MonitorState<C>>>,
+}
+
+struct MonitorState<C> {
+    client: C,
+    state: ConnectionState,
+    /// Number of requests which failed in a row because the store was unavailable
+    consecutive_failures: u32,
+    reconnect: Option<Reconnect<C>>,
+    clock: Arc<dyn Clock>,
+}
+
+/// Rebuilds the client of the store.
+struct Reconnect<C> {
+    connect: Box<dyn Fn() -> C + Send + Sync>,
+    /// Minimum time between two rebuilds of the client
+    cooldown: Duration,
+    last_reconnect: Option<Instant>,
+}
+
+impl<C: Clone> ConnectionMonitor<C> {
+    /// Returns a monitor of the requests sent through `client`.
+    pub fn new(client: C) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(MonitorState {
+                client,
+                state: ConnectionState::default(),
+                consecutive_failures: 0,
+                reconnect: None,
+                clock: Arc::new(SystemClock),
+            })),
+        }
+    }
+
+    /// Enables rebuilding the client with `connect` after repeated transport failures, at most
+    /// once per `cooldown`.
+    pub fn set_reconnect(&self, connect: Box<dyn Fn() -> C + Send + Sync>, cooldown: Duration) {
+        self.lock().reconnect = Some(Reconnect { connect, cooldown, last_reconnect: None });
+    }
+
+    /// Sets the source of the current time, which the cooldown of reconnecting is measured with.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.lock().clock = clock;
+    }
+
+    /// Returns the client sending requests to the store.
+    pub fn client(&self) -> C {
+        self.lock().client.clone()
+    }
+
+    /// Returns the current state of the connection.
+    pub fn state(&self) -> ConnectionState {
+        self.lock().state
+    }
+
+    /// Awaits `call`, which sends a request to the store, and updates the state of the connection
+    /// with its outcome.
+    ///
+    /// A call sent after a transient failure moves the connection back to
+    /// [ConnectionState::Connecting] until it completes.
+    pub async fn track<R>(
+        &self,
+        call: impl Future<Output = Result<R, Status>>,
+    ) -> Result<R, Status> {
+        {
+            let mut monitor = self.lock();
+            if monitor.state == ConnectionState::TransientFailure {
+                monitor.state = ConnectionState::Connecting;
+            }
+        }
+
+        let result = call.await;
+        let mut monitor = self.lock();
+        match &result {
+            Err(status) if status.code() == Code::Unavailable => {
+                monitor.state = ConnectionState::TransientFailure;
+                monitor.consecutive_failures += 1;
+                monitor.reconnect_if_due();
+            },
+            _ => {
+                monitor.state = ConnectionState::Connected;
+                monitor.consecutive_failures = 0;
+            },
+        }
+
+        result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MonitorState<C>> {
+        self.inner.lock().expect("connection monitor lock poisoned")
+    }
+}
+
+impl<C> MonitorState<C> {
+    /// Rebuilds the client if enough requests failed in a row and the cooldown since the last
+    /// rebuild elapsed, and resets the state of the connection.
+    fn reconnect_if_due(&mut self) {
+        if self.consecutive_failures < RECONNECT_AFTER_FAILURES {
+            return;
+        }
+        let now = self.clock.now();
+        let Some(reconnect) = &mut self.reconnect else {
+            return;
+        };
+        if reconnect.last_reconnect.is_some_and(|last| now < last + reconnect.cooldown) {
+            return;
+        }
+
+        info!(
+            target: COMPONENT,
+            failures = self.consecutive_failures,
+            "Store is unavailable, rebuilding the connection"
+        );
+        self.client = (reconnect.connect)();
+        reconnect.last_reconnect = Some(now);
+        self.consecutive_failures = 0;
+        self.state = ConnectionState::Connecting;
+    }
+}
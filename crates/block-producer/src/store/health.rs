@@ -0,0 +1,14 @@
+use miden_node_proto::domain::blocks::BlockNumber;
+
+// STORE STATUS
+// ================================================================================================
+
+/// Status of a store, as reported by [Store::check_health](super::Store::check_health).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StoreStatus {
+    /// Number of the latest block of the store
+    pub chain_tip: BlockNumber,
+    /// Whether blocks sent to the store are applied right away, instead of waiting behind
+    /// blocks which could not be applied yet, or `None` if the store can't tell
+    pub accepting_writes: Option<bool>,
+}
@@ -0,0 +1,604 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    num::NonZeroU32,
+    ops::Not,
+    sync::{Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use miden_node_proto::{
+    domain::{
+        blocks::{BlockInclusionProof, BlockNumber},
+        notes::NoteAuthenticationInfo,
+        nullifiers::{block_num_to_leaf_value, leaf_value_to_block_num},
+    },
+    AccountState,
+};
+use miden_objects::{
+    accounts::AccountId,
+    block::Block,
+    crypto::merkle::{Mmr, SimpleSmt, Smt, ValuePath},
+    notes::{NoteId, NoteInclusionProof, Nullifier},
+    transaction::TransactionId,
+    BlockHeader, Digest, ACCOUNT_TREE_DEPTH, EMPTY_WORD,
+};
+use miden_processor::crypto::RpoDigest;
+use tokio_stream::Stream;
+use tonic::Code;
+
+use super::{ApplyBlock, ChainStats, Recorder, Store, TransactionInputs};
+use crate::{
+    block::{AccountWitness, BlockInputs},
+    errors::{ApplyBlockError, BlockInputsError, NotePathsError, TxInputsError},
+    ProvenTransaction,
+};
+
+// IN-MEMORY STORE
+// ================================================================================================
+
+/// A [Store] keeping the chain state in memory, for tests which need a store without running
+/// one.
+///
+/// The state is kept in the same structures as by the store: the account and nullifier trees,
+/// the chain MMR and the headers of the chain. Tests seed it with [InMemoryStore::with_accounts],
+/// [InMemoryStore::with_nullifiers] and [InMemoryStore::with_note_authentication_info], and
+/// inspect the blocks applied to it with [InMemoryStore::applied_blocks].
+///
+/// Applied blocks are checked like by the store: a block must follow the chain tip, must not
+/// consume nullifiers which were consumed before, and must commit to the updated account tree.
+/// Rejected blocks fail with the same [ApplyBlockError]s as blocks rejected by the store.
+///
+/// The origins of nullifiers are tagged when the transaction inputs are requested, and recorded
+/// once the block consuming the nullifiers is applied.
+///
+/// Only available with the `testing` feature.
+#[derive(Debug)]
+pub struct InMemoryStore {
+    state: Mutex<InMemoryState>,
+    /// Transaction whose inputs were requested for each nullifier, until its block is applied
+    requested_origins: Recorder<Nullifier, TransactionId>,
+}
+
+#[derive(Debug)]
+struct InMemoryState {
+    /// Map account id -> account hash
+    accounts: SimpleSmt<ACCOUNT_TREE_DEPTH>,
+    /// Map nullifier -> number of the block which consumed it
+    nullifiers: Smt,
+    chain_mmr: Mmr,
+    /// Headers of the chain, starting at the initial block
+    block_headers: BTreeMap<u32, BlockHeader>,
+    /// Map note id -> note inclusion proof, for all seeded and created notes
+    notes: BTreeMap<NoteId, NoteInclusionProof>,
+    /// Inclusion proofs of the blocks of the seeded notes which are not part of the chain, by
+    /// block number
+    block_proofs: BTreeMap<u32, BlockInclusionProof>,
+    /// Map block number -> block, for all applied blocks
+    blocks: BTreeMap<u32, Block>,
+    /// Number of the block which created each account created by an applied block
+    account_creations: BTreeMap<AccountId, BlockNumber>,
+    /// Transaction which produced each nullifier consumed by an applied block, if known
+    nullifier_origins: BTreeMap<Nullifier, TransactionId>,
+    /// Nullifiers requested for the block being built, until a block is applied
+    pending_nullifiers: BTreeSet<Nullifier>,
+}
+
+impl InMemoryStore {
+    /// Returns a store without accounts, nullifiers and notes, at the genesis block.
+    pub fn new() -> Self {
+        let mut state = InMemoryState {
+            accounts: SimpleSmt::new().expect("empty account tree is valid"),
+            nullifiers: Smt::new(),
+            chain_mmr: Mmr::new(),
+            block_headers: BTreeMap::new(),
+            notes: BTreeMap::new(),
+            block_proofs: BTreeMap::new(),
+            blocks: BTreeMap::new(),
+            account_creations: BTreeMap::new(),
+            nullifier_origins: BTreeMap::new(),
+            pending_nullifiers: BTreeSet::new(),
+        };
+        state.seal_genesis();
+
+        Self::from_state(state)
+    }
+
+    /// Returns the store with `accounts` set to their hashes in the genesis state.
+    pub fn with_accounts(self, accounts: impl IntoIterator<Item = (AccountId, Digest)>) -> Self {
+        let mut state = self.state();
+        for (account_id, hash) in accounts {
+            state.accounts.insert(account_id.into(), hash.into());
+        }
+        state.seal_genesis();
+        drop(state);
+
+        self
+    }
+
+    /// Returns the store with `nullifiers` consumed in their blocks in the genesis state.
+    ///
+    /// # Panics
+    /// Panics if a nullifier is consumed in the genesis block, which has no nullifiers.
+    pub fn with_nullifiers(
+        self,
+        nullifiers: impl IntoIterator<Item = (Nullifier, BlockNumber)>,
+    ) -> Self {
+        let mut state = self.state();
+        for (nullifier, block_num) in nullifiers {
+            assert!(!block_num.is_genesis(), "nullifier {nullifier} consumed in the genesis block");
+            state.nullifiers.insert(nullifier.inner(), block_num_to_leaf_value(block_num));
+        }
+        state.seal_genesis();
+        drop(state);
+
+        self
+    }
+
+    /// Returns the store with the notes of `info` authenticated by its proofs.
+    pub fn with_note_authentication_info(self, info: NoteAuthenticationInfo) -> Self {
+        let mut state = self.state();
+        state.notes.extend(info.note_proofs);
+        state.block_proofs.extend(
+            info.block_proofs
+                .into_iter()
+                .map(|proof| (proof.block_header.block_num(), proof)),
+        );
+        drop(state);
+
+        self
+    }
+
+    /// Returns the blocks applied to the store, in the order they were applied.
+    pub fn applied_blocks(&self) -> Vec<Block> {
+        self.state().blocks.values().cloned().collect()
+    }
+
+    /// Returns the header of the chain tip.
+    pub fn chain_tip(&self) -> BlockHeader {
+        self.state().chain_tip()
+    }
+
+    /// Returns the account tree of the chain tip.
+    pub fn account_tree(&self) -> SimpleSmt<ACCOUNT_TREE_DEPTH> {
+        self.state().accounts.clone()
+    }
+
+    /// Returns the root of the account tree of the chain tip.
+    pub fn account_root(&self) -> Digest {
+        self.state().accounts.root()
+    }
+
+    /// Returns the chain MMR.
+    pub fn chain_mmr(&self) -> Mmr {
+        self.state().chain_mmr.clone()
+    }
+
+    fn from_state(state: InMemoryState) -> Self {
+        Self {
+            state: Mutex::new(state),
+            requested_origins: Recorder::default(),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, InMemoryState> {
+        self.state.lock().expect("in-memory store lock poisoned")
+    }
+
+    /// Checks `block` against the chain tip and applies it, recording the origins of its
+    /// nullifiers.
+    fn apply(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        let mut state = self.state();
+        state.check_block(block)?;
+        state.apply_block(block);
+
+        let origins = block.nullifiers().iter().filter_map(|nullifier| {
+            self.requested_origins
+                .get(nullifier)
+                .map(|transaction_id| (*nullifier, transaction_id))
+        });
+        state.nullifier_origins.extend(origins);
+        self.requested_origins.forget(block.nullifiers().iter().copied());
+
+        Ok(())
+    }
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryState {
+    /// Replaces the chain with a genesis block committing to the current state.
+    ///
+    /// # Panics
+    /// Panics if blocks were applied already.
+    fn seal_genesis(&mut self) {
+        assert!(self.blocks.is_empty(), "genesis state is changed after blocks were applied");
+
+        let mut chain_mmr = Mmr::new();
+        let genesis = BlockHeader::new(
+            0,
+            Digest::default(),
+            0,
+            chain_mmr.peaks().hash_peaks(),
+            self.accounts.root(),
+            self.nullifiers.root(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            0,
+        );
+        chain_mmr.add(genesis.hash());
+
+        self.chain_mmr = chain_mmr;
+        self.block_headers = BTreeMap::from([(0, genesis)]);
+    }
+
+    /// Returns the header of the last block of the chain.
+    fn chain_tip(&self) -> BlockHeader {
+        *self.block_headers.last_key_value().expect("initial header is always set").1
+    }
+
+    /// Returns the block number of `nullifier` if it was consumed, decoded like by the store.
+    fn spend_block(&self, nullifier: &Nullifier) -> Option<Result<BlockNumber, String>> {
+        let value = self.nullifiers.get_value(&nullifier.inner());
+        if value == EMPTY_WORD {
+            return None;
+        }
+
+        Some(leaf_value_to_block_num(value).map_err(|err| err.to_string()))
+    }
+
+    /// Returns the authentication info of the given notes, with the inclusion proofs of their
+    /// blocks opened against the current chain MMR.
+    fn note_authentication_info<'a>(
+        &self,
+        notes: impl Iterator<Item = &'a NoteId>,
+    ) -> NoteAuthenticationInfo {
+        let note_proofs: BTreeMap<_, _> = notes
+            .filter_map(|id| self.notes.get(id).map(|proof| (*id, proof.clone())))
+            .collect();
+
+        let chain_length = self.chain_mmr.forest() as u32;
+        let block_nums: BTreeSet<_> =
+            note_proofs.values().map(|proof| proof.location().block_num()).collect();
+        let block_proofs = block_nums
+            .into_iter()
+            .map(|block_num| {
+                if let Some(proof) = self.block_proofs.get(&block_num) {
+                    return proof.clone();
+                }
+
+                let block_header = self.block_headers[&block_num];
+                let mmr_path = self
+                    .chain_mmr
+                    .open(block_num as usize)
+                    .expect("blocks of the chain are in the chain MMR")
+                    .merkle_path;
+
+                BlockInclusionProof { block_header, mmr_path, chain_length }
+            })
+            .collect();
+
+        NoteAuthenticationInfo { block_proofs, note_proofs }
+    }
+
+    /// Checks that `block` follows the chain tip, consumes no nullifiers which were consumed
+    /// before, and commits to the account tree updated by it.
+    ///
+    /// Blocks are rejected with the errors returned by the store.
+    fn check_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        let header = block.header();
+        let chain_tip = self.chain_tip();
+
+        let block_num = BlockNumber::from(header.block_num());
+        let chain_tip_num = BlockNumber::from(chain_tip.block_num());
+        if block_num.prev() != Some(chain_tip_num) {
+            return Err(ApplyBlockError::StaleBlock {
+                expected: chain_tip_num.next().unwrap_or(BlockNumber::MAX).as_u32(),
+                got: block_num.as_u32(),
+            });
+        }
+        if header.prev_hash() != chain_tip.hash() {
+            return Err(invalid_block("new block `prev_hash` must match the chain's tip"));
+        }
+
+        let duplicate_nullifiers: Vec<_> = block
+            .nullifiers()
+            .iter()
+            .filter(|nullifier| self.nullifiers.get_value(&nullifier.inner()) != EMPTY_WORD)
+            .copied()
+            .collect();
+        if !duplicate_nullifiers.is_empty() {
+            return Err(invalid_block(format!("duplicated nullifiers {duplicate_nullifiers:?}")));
+        }
+
+        let mut accounts = self.accounts.clone();
+        for update in block.updated_accounts() {
+            accounts.insert(update.account_id().into(), update.new_state_hash().into());
+        }
+        if accounts.root() != header.account_root() {
+            return Err(invalid_block("received invalid account tree root"));
+        }
+
+        Ok(())
+    }
+
+    fn apply_block(&mut self, block: &Block) {
+        let header = block.header();
+        for update in block.updated_accounts() {
+            let old_hash =
+                self.accounts.insert(update.account_id().into(), update.new_state_hash().into());
+            if old_hash == EMPTY_WORD {
+                self.account_creations.insert(update.account_id(), header.block_num().into());
+            }
+        }
+        for nullifier in block.nullifiers() {
+            self.nullifiers
+                .insert(nullifier.inner(), block_num_to_leaf_value(header.block_num().into()));
+        }
+
+        self.chain_mmr.add(block.hash());
+
+        let note_tree = block.build_note_tree();
+        for (note_index, note) in block.notes() {
+            self.notes.insert(
+                note.id(),
+                NoteInclusionProof::new(
+                    header.block_num(),
+                    note_index.leaf_index_value(),
+                    note_tree.get_note_path(note_index),
+                )
+                .expect("notes of a block have valid inclusion proofs"),
+            );
+        }
+
+        self.block_headers.insert(header.block_num(), header);
+        self.blocks.insert(header.block_num(), block.clone());
+
+        // The block being built was applied, so its nullifiers are not pending anymore.
+        self.pending_nullifiers.clear();
+    }
+}
+
+/// Returns the error of a block rejected as invalid, as returned by the store.
+fn invalid_block(reason: impl Display) -> ApplyBlockError {
+    ApplyBlockError::GrpcClientError {
+        code: Code::InvalidArgument,
+        message: format!("invalid block: {reason}"),
+    }
+}
+
+#[async_trait]
+impl ApplyBlock for InMemoryStore {
+    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        self.apply(block)
+    }
+}
+
+#[async_trait]
+impl Store for InMemoryStore {
+    async fn get_tx_inputs(
+        &self,
+        proven_tx: &ProvenTransaction,
+    ) -> Result<TransactionInputs, TxInputsError> {
+        let state = self.state();
+
+        let account_hash = state.accounts.get_leaf(&proven_tx.account_id().into());
+        let account_hash = (account_hash != EMPTY_WORD).then(|| account_hash.into());
+
+        let nullifiers = proven_tx
+            .get_nullifiers()
+            .map(|nullifier| {
+                let value = state.nullifiers.get_value(&nullifier.inner());
+                (nullifier, NonZeroU32::new(value[0].as_int() as u32))
+            })
+            .collect();
+        let missing_unauthenticated_notes = proven_tx
+            .get_unauthenticated_notes()
+            .map(|header| header.id())
+            .filter(|id| state.notes.contains_key(id).not())
+            .collect();
+
+        self.requested_origins
+            .record(proven_tx.get_nullifiers().map(|nullifier| (nullifier, proven_tx.id())));
+
+        Ok(TransactionInputs {
+            account_id: proven_tx.account_id(),
+            account_hash,
+            nullifiers,
+            missing_unauthenticated_notes,
+            current_block_height: state.chain_tip().block_num().into(),
+            proofs: None,
+            account_version: None,
+        })
+    }
+
+    async fn get_block_inputs(
+        &self,
+        updated_accounts: impl Iterator<Item = AccountId> + Send,
+        produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<BlockInputs, BlockInputsError> {
+        let mut state = self.state();
+
+        let accounts = updated_accounts
+            .map(|account_id| {
+                let ValuePath { value: hash, path: proof } =
+                    state.accounts.open(&account_id.into());
+
+                (account_id, AccountWitness { hash, proof })
+            })
+            .collect();
+
+        let nullifiers: BTreeMap<_, _> = produced_nullifiers
+            .map(|nullifier| (*nullifier, state.nullifiers.open(&nullifier.inner())))
+            .collect();
+        state.pending_nullifiers.extend(nullifiers.keys().copied());
+
+        Ok(BlockInputs {
+            block_header: state.chain_tip(),
+            chain_peaks: state.chain_mmr.peaks(),
+            accounts,
+            nullifiers,
+            found_unauthenticated_notes: state.note_authentication_info(notes),
+        })
+    }
+
+    async fn get_note_authentication_info(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        Ok(self.state().note_authentication_info(notes))
+    }
+
+    async fn notes_authenticated_since(
+        &self,
+        notes: &[NoteId],
+        since: BlockNumber,
+    ) -> Result<Vec<NoteId>, NotePathsError> {
+        let state = self.state();
+
+        Ok(notes
+            .iter()
+            .filter(|id| {
+                state
+                    .notes
+                    .get(id)
+                    .is_some_and(|proof| BlockNumber::from(proof.location().block_num()) > since)
+            })
+            .copied()
+            .collect())
+    }
+
+    async fn nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, TxInputsError> {
+        Ok(self.state().nullifier_origins.get(&nullifier).copied())
+    }
+
+    async fn get_note_spend_block(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.state()
+            .spend_block(&nullifier)
+            .transpose()
+            .map_err(|reason| TxInputsError::NullifierSpendError { nullifier, reason })
+    }
+
+    async fn get_pending_nullifiers(&self) -> Result<Vec<Nullifier>, TxInputsError> {
+        Ok(self.state().pending_nullifiers.iter().copied().collect())
+    }
+
+    async fn get_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountState, TxInputsError> {
+        let account_hash = self.state().accounts.get_leaf(&account_id.into());
+        let account_hash = (account_hash != EMPTY_WORD).then(|| account_hash.into());
+
+        Ok(AccountState { account_id, account_hash })
+    }
+
+    async fn get_account_state_forks(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(RpoDigest, RpoDigest)>, TxInputsError> {
+        let state = self.state();
+
+        let account_hash = state.accounts.get_leaf(&account_id.into());
+        if account_hash == EMPTY_WORD {
+            return Ok(Vec::new());
+        }
+
+        Ok(vec![(account_hash.into(), state.chain_tip().hash())])
+    }
+
+    async fn get_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        let state = self.state();
+        if let Some(block_num) = state.account_creations.get(&account_id) {
+            return Ok(Some(*block_num));
+        }
+
+        // Accounts which were not created by an applied block are part of the initial state.
+        if state.accounts.get_leaf(&account_id.into()) == EMPTY_WORD {
+            return Ok(None);
+        }
+        let (initial_block_num, _) =
+            state.block_headers.first_key_value().expect("initial header is always set");
+
+        Ok(Some((*initial_block_num).into()))
+    }
+
+    async fn get_nullifier_root_history(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, RpoDigest)>, TxInputsError> {
+        Ok(self
+            .state()
+            .block_headers
+            .range(from.as_u32()..=to.as_u32())
+            .map(|(block_num, header)| ((*block_num).into(), header.nullifier_root()))
+            .collect())
+    }
+
+    async fn get_chain_stats(&self) -> Result<ChainStats, TxInputsError> {
+        let state = self.state();
+
+        Ok(ChainStats {
+            num_blocks: state.chain_mmr.forest() as u32,
+            num_accounts: state.accounts.leaves().count() as u64,
+            num_nullifiers: state.nullifiers.entries().count() as u64,
+            account_root: state.accounts.root(),
+            nullifier_root: state.nullifiers.root(),
+            chain_root: state.chain_mmr.peaks().hash_peaks(),
+        })
+    }
+
+    async fn apply_block_with_diff(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError> {
+        self.apply(block)?;
+
+        Ok(block
+            .updated_accounts()
+            .iter()
+            .map(|update| (update.account_id(), update.new_state_hash()))
+            .collect())
+    }
+
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError> {
+        for block in blocks {
+            self.apply(block)?;
+        }
+
+        Ok(self.chain_tip().block_num().into())
+    }
+
+    fn sync_blocks_from(
+        &self,
+        from: BlockNumber,
+    ) -> impl Stream<Item = Result<Block, TxInputsError>> + Send + 'static {
+        // The blocks are collected before sending any of them, fixing the chain tip at the start
+        // of the stream.
+        let blocks: Vec<_> = self
+            .state()
+            .blocks
+            .range(from.as_u32()..)
+            .map(|(_, block)| block.clone())
+            .collect();
+
+        tokio_stream::iter(blocks.into_iter().map(Ok))
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use miden_node_proto::generated::{
+    requests::{
+        ApplyBlockRequest, ApplyBlocksRequest, CheckNullifiersRequest,
+        GetAccountCreationBlockRequest, GetAccountStateForksRequest, GetAccountStateRequest,
+        GetBlockInputsRequest, GetChainStatsRequest, GetNoteAuthenticationInfoRequest,
+        GetNotesAuthenticatedSinceRequest, GetNullifierOriginRequest,
+        GetNullifierRootHistoryRequest, GetPendingNullifiersRequest,
+        GetTransactionInputsBatchRequest, GetTransactionInputsRequest, SyncBlocksRequest,
+    },
+    responses::{
+        ApplyBlockResponse, ApplyBlocksResponse, CheckNullifiersResponse,
+        GetAccountCreationBlockResponse, GetAccountStateForksResponse, GetAccountStateResponse,
+        GetBlockInputsResponse, GetChainStatsResponse, GetNoteAuthenticationInfoResponse,
+        GetNotesAuthenticatedSinceResponse, GetNullifierOriginResponse,
+        GetNullifierRootHistoryResponse, GetPendingNullifiersResponse,
+        GetTransactionInputsBatchResponse, GetTransactionInputsResponse, SyncBlocksResponse,
+    },
+    store::api_server::Api,
+};
+use miden_node_store::{config::StoreConfig, server::StoreApi};
+use miden_node_utils::errors::ApiError;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use super::{DefaultStore, StoreClient};
+
+// IN-PROCESS CLIENT
+// ================================================================================================
+
+/// A [DefaultStore] which calls the API of a store running in the same process directly.
+///
+/// Requests are handled exactly like by a store served over gRPC, but are passed to the store's
+/// API without being encoded or sent over the network.
+pub type InProcessStore = DefaultStore<InProcessClient>;
+
+/// Sends the requests to the API of a store running in the same process.
+#[derive(Clone)]
+pub struct InProcessClient {
+    api: Arc<StoreApi>,
+}
+
+impl InProcessStore {
+    /// Returns a store calling `api` directly.
+    pub fn in_process(api: Arc<StoreApi>) -> Self {
+        Self::from_client(InProcessClient { api })
+    }
+
+    /// Loads the state of the store given by `config`, and returns a store calling its API
+    /// directly.
+    pub async fn load_in_process(config: &StoreConfig) -> Result<Self, ApiError> {
+        Ok(Self::in_process(Arc::new(StoreApi::load(config).await?)))
+    }
+}
+
+#[async_trait]
+impl StoreClient for InProcessClient {
+    type BlockStream = ReceiverStream<Result<SyncBlocksResponse, Status>>;
+
+    async fn apply_block(
+        &mut self,
+        request: Request<ApplyBlockRequest>,
+    ) -> Result<Response<ApplyBlockResponse>, Status> {
+        self.api.apply_block(request).await
+    }
+
+    async fn apply_blocks(
+        &mut self,
+        request: Request<ApplyBlocksRequest>,
+    ) -> Result<Response<ApplyBlocksResponse>, Status> {
+        self.api.apply_blocks(request).await
+    }
+
+    async fn get_transaction_inputs(
+        &mut self,
+        request: Request<GetTransactionInputsRequest>,
+    ) -> Result<Response<GetTransactionInputsResponse>, Status> {
+        self.api.get_transaction_inputs(request).await
+    }
+
+    async fn get_transaction_inputs_batch(
+        &mut self,
+        request: Request<GetTransactionInputsBatchRequest>,
+    ) -> Result<Response<GetTransactionInputsBatchResponse>, Status> {
+        self.api.get_transaction_inputs_batch(request).await
+    }
+
+    async fn get_block_inputs(
+        &mut self,
+        request: Request<GetBlockInputsRequest>,
+    ) -> Result<Response<GetBlockInputsResponse>, Status> {
+        self.api.get_block_inputs(request).await
+    }
+
+    async fn get_note_authentication_info(
+        &mut self,
+        request: Request<GetNoteAuthenticationInfoRequest>,
+    ) -> Result<Response<GetNoteAuthenticationInfoResponse>, Status> {
+        self.api.get_note_authentication_info(request).await
+    }
+
+    async fn get_notes_authenticated_since(
+        &mut self,
+        request: Request<GetNotesAuthenticatedSinceRequest>,
+    ) -> Result<Response<GetNotesAuthenticatedSinceResponse>, Status> {
+        self.api.get_notes_authenticated_since(request).await
+    }
+
+    async fn get_nullifier_origin(
+        &mut self,
+        request: Request<GetNullifierOriginRequest>,
+    ) -> Result<Response<GetNullifierOriginResponse>, Status> {
+        self.api.get_nullifier_origin(request).await
+    }
+
+    async fn check_nullifiers(
+        &mut self,
+        request: Request<CheckNullifiersRequest>,
+    ) -> Result<Response<CheckNullifiersResponse>, Status> {
+        self.api.check_nullifiers(request).await
+    }
+
+    async fn get_pending_nullifiers(
+        &mut self,
+        request: Request<GetPendingNullifiersRequest>,
+    ) -> Result<Response<GetPendingNullifiersResponse>, Status> {
+        self.api.get_pending_nullifiers(request).await
+    }
+
+    async fn get_account_state(
+        &mut self,
+        request: Request<GetAccountStateRequest>,
+    ) -> Result<Response<GetAccountStateResponse>, Status> {
+        self.api.get_account_state(request).await
+    }
+
+    async fn get_account_state_forks(
+        &mut self,
+        request: Request<GetAccountStateForksRequest>,
+    ) -> Result<Response<GetAccountStateForksResponse>, Status> {
+        self.api.get_account_state_forks(request).await
+    }
+
+    async fn get_account_creation_block(
+        &mut self,
+        request: Request<GetAccountCreationBlockRequest>,
+    ) -> Result<Response<GetAccountCreationBlockResponse>, Status> {
+        self.api.get_account_creation_block(request).await
+    }
+
+    async fn get_nullifier_root_history(
+        &mut self,
+        request: Request<GetNullifierRootHistoryRequest>,
+    ) -> Result<Response<GetNullifierRootHistoryResponse>, Status> {
+        self.api.get_nullifier_root_history(request).await
+    }
+
+    async fn get_chain_stats(
+        &mut self,
+        request: Request<GetChainStatsRequest>,
+    ) -> Result<Response<GetChainStatsResponse>, Status> {
+        self.api.get_chain_stats(request).await
+    }
+
+    async fn sync_blocks(
+        &mut self,
+        request: Request<SyncBlocksRequest>,
+    ) -> Result<Response<Self::BlockStream>, Status> {
+        self.api.sync_blocks(request).await
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fmt::{Display, Formatter},
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::oneshot;
+
+// PRIORITY
+// ================================================================================================
+
+/// Priority of a request to the store.
+///
+/// Higher priority requests are sent first when the number of concurrent requests is limited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Background requests, e.g. analytics
+    Low,
+    #[default]
+    Normal,
+    /// Requests on the critical path, e.g. transaction admission
+    High,
+}
+
+impl Priority {
+    /// The gRPC metadata key under which the priority is sent to the store.
+    pub const METADATA_KEY: &'static str = "x-priority";
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+        }
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// PRIORITY LIMITER
+// ================================================================================================
+
+/// Limits the number of concurrent requests, handing free slots to the waiting requests with the
+/// highest priority first.
+///
+/// Requests of the same priority get slots in the order they started waiting.
+#[derive(Clone, Debug)]
+pub struct PriorityLimiter {
+    inner: Arc<Mutex<LimiterState>>,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    available: usize,
+    waiters: BinaryHeap<Waiter>,
+    /// Sequence number of the next waiter, used to order waiters of the same priority
+    next_seq: u64,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    priority: Priority,
+    seq: u64,
+    sender: oneshot::Sender<Permit>,
+}
+
+impl Waiter {
+    fn key(&self) -> (Priority, Reverse<u64>) {
+        (self.priority, Reverse(self.seq))
+    }
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PriorityLimiter {
+    /// Returns a limiter allowing at most `max_concurrent` requests at the same time.
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LimiterState {
+                available: max_concurrent,
+                waiters: BinaryHeap::new(),
+                next_seq: 0,
+            })),
+        }
+    }
+
+    /// Waits for a free slot, and returns a permit which holds it until dropped.
+    pub async fn acquire(&self, priority: Priority) -> Permit {
+        let receiver = {
+            let mut state = self.inner.lock().expect("limiter lock poisoned");
+            if state.available > 0 && state.waiters.is_empty() {
+                state.available -= 1;
+                return Permit { limiter: Some(Arc::clone(&self.inner)) };
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.waiters.push(Waiter { priority, seq, sender });
+
+            receiver
+        };
+
+        receiver.await.expect("waiters are never dropped without a permit")
+    }
+}
+
+/// A slot of a [PriorityLimiter], which is handed to the next waiter when dropped.
+#[derive(Debug)]
+pub struct Permit {
+    limiter: Option<Arc<Mutex<LimiterState>>>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let Some(limiter) = self.limiter.take() else {
+            return;
+        };
+        let mut state = limiter.lock().expect("limiter lock poisoned");
+
+        while let Some(waiter) = state.waiters.pop() {
+            let permit = Permit { limiter: Some(Arc::clone(&limiter)) };
+            match waiter.sender.send(permit) {
+                Ok(()) => return,
+                // The waiter was cancelled, so the slot goes to the next one.
+                Err(mut permit) => permit.limiter = None,
+            }
+        }
+
+        state.available += 1;
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lets the spawned tasks run until they wait for a slot.
+    async fn yield_to_tasks() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn high_priority_request_jumps_ahead_of_queued_requests() {
+        let limiter = PriorityLimiter::new(1);
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let permit = limiter.acquire(Priority::Normal).await;
+
+        let mut tasks = Vec::new();
+        for (name, priority) in
+            [("low 1", Priority::Low), ("low 2", Priority::Low), ("high", Priority::High)]
+        {
+            let limiter = limiter.clone();
+            let order = Arc::clone(&order);
+            tasks.push(tokio::spawn(async move {
+                let _permit = limiter.acquire(priority).await;
+                order.lock().unwrap().push(name);
+            }));
+            yield_to_tasks().await;
+        }
+
+        drop(permit);
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), ["high", "low 1", "low 2"]);
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiter_does_not_leak_slot() {
+        let limiter = PriorityLimiter::new(1);
+        let permit = limiter.acquire(Priority::Normal).await;
+
+        let cancelled = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(Priority::High).await }
+        });
+        yield_to_tasks().await;
+        cancelled.abort();
+        let _ = cancelled.await;
+
+        drop(permit);
+        let _permit = limiter.acquire(Priority::Low).await;
+    }
+}
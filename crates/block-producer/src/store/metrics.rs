@@ -0,0 +1,191 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use tonic::{Code, Status};
+
+/// Name of the counter of completed store requests exported with the `metrics` feature, labelled
+/// by gRPC `method` and `outcome`, which is either `success` or `error`.
+#[cfg(feature = "metrics")]
+pub const STORE_RPC_CALLS_METRIC: &str = "store_rpc_calls_total";
+
+/// Name of the histogram of the latencies of completed store requests in seconds, exported with
+/// the `metrics` feature and labelled by gRPC `method`.
+#[cfg(feature = "metrics")]
+pub const STORE_RPC_LATENCY_METRIC: &str = "store_rpc_latency_seconds";
+
+// STORE METRICS
+// ================================================================================================
+
+/// Snapshot of the metrics of the requests sent to the store by a
+/// [DefaultStore](super::DefaultStore), see
+/// [DefaultStore::metrics_snapshot](super::DefaultStore::metrics_snapshot).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StoreMetrics {
+    /// Metrics of the requests of each gRPC method, e.g. `GetBlockInputs`
+    pub methods: BTreeMap<&'static str, MethodMetrics>,
+    /// Number of failed requests by status code
+    pub errors: HashMap<Code, u64>,
+    /// Number of requests which were sent, but didn't complete and weren't cancelled yet
+    pub in_flight: u64,
+    /// Reads answered from the response cache, if enabled
+    pub response_cache: CacheMetrics,
+    /// Lookups of the note authentication cache, if enabled
+    pub note_auth_cache: CacheMetrics,
+}
+
+/// Metrics of the requests of one gRPC method.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MethodMetrics {
+    /// Number of requests sent
+    pub calls: u64,
+    /// Number of requests which completed, successfully or not
+    pub completed: u64,
+    /// Number of requests which failed
+    pub errors: u64,
+    /// Sum of the latencies of the completed requests
+    pub total_latency: Duration,
+}
+
+impl MethodMetrics {
+    /// Returns the average latency of the completed requests, or `None` if none completed.
+    pub fn average_latency(&self) -> Option<Duration> {
+        let completed = u32::try_from(self.completed).ok().filter(|completed| *completed > 0)?;
+
+        Some(self.total_latency / completed)
+    }
+}
+
+/// Hits and misses of a cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheMetrics {
+    /// Returns the fraction of lookups which hit the cache, or `None` if there were none.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+// METRICS RECORDER
+// ================================================================================================
+
+/// Records the metrics of a [DefaultStore](super::DefaultStore), shared between the store and its
+/// block applier.
+///
+/// All metrics are kept behind one lock, so snapshots are consistent and cost one copy.
+///
+/// With the `metrics` feature, the outcomes and latencies of completed requests are also exported
+/// through the [`metrics`](::metrics) facade, e.g. to a Prometheus exporter installed by the
+/// node. Without an installed recorder, exporting them does nothing.
+#[derive(Clone, Debug, Default)]
+pub(super) struct MetricsRecorder(Arc<Mutex<StoreMetrics>>);
+
+impl MetricsRecorder {
+    /// Returns a copy of the current metrics.
+    pub fn snapshot(&self) -> StoreMetrics {
+        self.metrics().clone()
+    }
+
+    /// Awaits `call`, which sends a request to the gRPC method `method`, and records its outcome
+    /// and latency.
+    ///
+    /// Calls which are dropped before they complete, e.g. the losing requests of hedged reads,
+    /// only count as sent.
+    pub async fn record<R>(
+        &self,
+        method: &'static str,
+        call: impl Future<Output = Result<R, Status>>,
+    ) -> Result<R, Status> {
+        let in_flight = self.start(method);
+        let result = call.await;
+        in_flight.finish(result.as_ref().err().map(Status::code));
+
+        result
+    }
+
+    /// Records a lookup of the response cache.
+    pub fn record_response_cache(&self, hit: bool) {
+        record_lookup(&mut self.metrics().response_cache, hit);
+    }
+
+    /// Records a lookup of the note authentication cache.
+    pub fn record_note_auth_cache(&self, hit: bool) {
+        record_lookup(&mut self.metrics().note_auth_cache, hit);
+    }
+
+    fn start(&self, method: &'static str) -> InFlightCall<'_> {
+        let mut metrics = self.metrics();
+        metrics.methods.entry(method).or_default().calls += 1;
+        metrics.in_flight += 1;
+
+        InFlightCall {
+            recorder: self,
+            method,
+            started_at: Instant::now(),
+        }
+    }
+
+    fn metrics(&self) -> MutexGuard<'_, StoreMetrics> {
+        self.0.lock().expect("store metrics lock poisoned")
+    }
+}
+
+fn record_lookup(cache: &mut CacheMetrics, hit: bool) {
+    if hit {
+        cache.hits += 1;
+    } else {
+        cache.misses += 1;
+    }
+}
+
+/// Exports the outcome and latency of a completed request to the gRPC method `method`.
+#[cfg(feature = "metrics")]
+fn export_call(method: &'static str, success: bool, latency: Duration) {
+    let outcome = if success { "success" } else { "error" };
+    ::metrics::counter!(STORE_RPC_CALLS_METRIC, "method" => method, "outcome" => outcome)
+        .increment(1);
+    ::metrics::histogram!(STORE_RPC_LATENCY_METRIC, "method" => method).record(latency);
+}
+
+/// A request which was sent, and is counted as in flight until dropped.
+struct InFlightCall<'a> {
+    recorder: &'a MetricsRecorder,
+    method: &'static str,
+    started_at: Instant,
+}
+
+impl InFlightCall<'_> {
+    /// Records the completion of the request, which failed with `error` if any.
+    fn finish(self, error: Option<Code>) {
+        let latency = self.started_at.elapsed();
+        // The lock is released before the call is dropped, which takes it again.
+        {
+            let mut metrics = self.recorder.metrics();
+            let method = metrics.methods.entry(self.method).or_default();
+            method.completed += 1;
+            method.total_latency += latency;
+            if let Some(code) = error {
+                method.errors += 1;
+                *metrics.errors.entry(code).or_default() += 1;
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        export_call(self.method, error.is_none(), latency);
+    }
+}
+
+impl Drop for InFlightCall<'_> {
+    fn drop(&mut self) {
+        self.recorder.metrics().in_flight -= 1;
+    }
+}
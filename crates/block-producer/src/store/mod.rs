@@ -1,21 +1,41 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Display, Formatter},
-    num::NonZeroU32,
+    future::Future,
+    io,
+    num::{NonZeroU32, NonZeroUsize},
+    path::PathBuf,
+    pin::pin,
+    sync::{Arc, Mutex, Once},
+    time::Duration,
 };
 
 use async_trait::async_trait;
+use futures::TryStreamExt;
 use itertools::Itertools;
 use miden_node_proto::{
-    domain::notes::NoteAuthenticationInfo,
+    domain::{
+        blocks::BlockNumber, notes::NoteAuthenticationInfo, nullifiers::leaf_value_to_block_num,
+    },
     errors::{ConversionError, MissingFieldHelper},
     generated::{
+        account::AccountSummary,
         digest,
         requests::{
-            ApplyBlockRequest, GetBlockInputsRequest, GetNoteAuthenticationInfoRequest,
-            GetTransactionInputsRequest,
+            AccountVersion, ApplyBlockRequest, ApplyBlocksRequest, CheckNullifiersRequest,
+            GetAccountCreationBlockRequest, GetAccountStateForksRequest, GetAccountStateRequest,
+            GetBlockInputsRequest, GetChainStatsRequest, GetNoteAuthenticationInfoRequest,
+            GetNotesAuthenticatedSinceRequest, GetNullifierOriginRequest,
+            GetNullifierRootHistoryRequest, GetPendingNullifiersRequest,
+            GetTransactionInputsBatchRequest, GetTransactionInputsRequest, NullifierOrigin,
+            SyncBlocksRequest,
+        },
+        responses::{
+            AccountStateCandidate, AccountTransactionInputRecord, GetAccountStateResponse,
+            GetBlockInputsResponse, GetChainStatsResponse, GetNoteAuthenticationInfoResponse,
+            GetTransactionInputsResponse, NullifierBlockInputRecord, NullifierRootRecord,
+            NullifierTransactionInputRecord,
         },
-        responses::{GetTransactionInputsResponse, NullifierTransactionInputRecord},
         store::api_client as store_client,
     },
     AccountState,
@@ -24,16 +44,135 @@ use miden_node_utils::formatting::format_opt;
 use miden_objects::{
     accounts::AccountId,
     block::Block,
+    crypto::merkle::{MerklePath, MmrPeaks, SmtProof},
     notes::{NoteId, Nullifier},
-    utils::Serializable,
-    Digest,
+    transaction::TransactionId,
+    utils::{Deserializable, Serializable},
+    BlockHeader, Digest, Felt, EMPTY_WORD, ZERO,
 };
 use miden_processor::crypto::RpoDigest;
-use tonic::transport::Channel;
-use tracing::{debug, info, instrument};
+use serde::{Serialize, Serializer};
+use tokio::{sync::mpsc, task::JoinSet};
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
+#[cfg(feature = "tls")]
+use tonic::transport::ClientTlsConfig;
+use tonic::{
+    metadata::{AsciiMetadataValue, MetadataValue},
+    transport::{Channel, Endpoint},
+    Status,
+};
+use tracing::{debug, info, instrument, warn};
+
+pub use crate::errors::{
+    ApplyBlockError, BlockInputsError, GrpcErrorDetail, GrpcErrorDetails, NotePathsError,
+    TxInputsError,
+};
+use crate::{block::BlockInputs, ProvenTransaction, COMPONENT};
+
+mod apply_cost;
+use apply_cost::estimate_apply_cost;
+pub use apply_cost::{ApplyCostEstimate, ACCOUNT_ENTRY_BYTES, NULLIFIER_ENTRY_BYTES};
+
+mod apply_queue;
+pub use apply_queue::ApplyQueue;
+
+mod backoff;
+pub use backoff::Backoff;
+
+mod block_inputs_chunks;
+use block_inputs_chunks::{merge_block_inputs_responses, split_block_inputs_request};
+
+mod cache;
+use cache::TenantNoteAuthCaches;
+pub use cache::{NoteAuthCache, NoteAuthCacheBuilder, DEFAULT_MAX_CACHE_ENTRIES};
+
+mod caching;
+pub use caching::CachingStore;
+
+mod client;
+pub use client::StoreClient;
+
+mod clock;
+pub use clock::{Clock, SystemClock};
+
+mod connection;
+use connection::ConnectionMonitor;
+pub use connection::ConnectionState;
+
+mod health;
+pub use health::StoreStatus;
+
+#[cfg(any(test, feature = "testing"))]
+mod in_memory;
+#[cfg(any(test, feature = "testing"))]
+pub use in_memory::InMemoryStore;
+
+#[cfg(feature = "in-process")]
+mod in_process;
+#[cfg(feature = "in-process")]
+pub use in_process::{InProcessClient, InProcessStore};
+
+mod limiter;
+pub use limiter::{Permit, Priority, PriorityLimiter};
+
+mod metrics;
+pub use metrics::{CacheMetrics, MethodMetrics, StoreMetrics};
+#[cfg(feature = "metrics")]
+pub use metrics::{STORE_RPC_CALLS_METRIC, STORE_RPC_LATENCY_METRIC};
+
+#[cfg(feature = "persistent-cache")]
+mod persistent_cache;
+#[cfg(feature = "persistent-cache")]
+pub use persistent_cache::PersistentCache;
+
+mod precheck;
+use precheck::check_block_roots;
+
+mod proof_set;
+pub use proof_set::MinimalProofSet;
+
+mod response_cache;
+use response_cache::ResponseCache;
+
+mod read_your_writes;
+use read_your_writes::AppliedBlockNum;
+pub use read_your_writes::MIN_BLOCK_NUM_METADATA_KEY;
+
+mod recorder;
+use recorder::Recorder;
+pub use recorder::DEFAULT_MAX_RECORDED_ENTRIES;
+
+mod resumable_sync;
+pub use resumable_sync::{ResumableSync, DEFAULT_MAX_RECONNECTS, DEFAULT_RECONNECT_DELAY};
+
+mod retry_policy;
+pub use retry_policy::{RetryDecision, RetryPolicy};
+
+mod retrying;
+pub use retrying::RetryingStore;
+
+mod root_consistency;
+use root_consistency::mismatched_block;
+pub use root_consistency::RootConsistencyPolicy;
+
+mod round_trips;
+pub use round_trips::{RoundGuard, RoundTripCounter};
+
+mod shard;
+pub use shard::ShardRouter;
+
+mod tenant;
+use tenant::{current_tenant, insert_tenant};
+pub use tenant::{scoped_to_tenant, TENANT_METADATA_KEY};
 
-pub use crate::errors::{ApplyBlockError, BlockInputsError, TxInputsError};
-use crate::{block::BlockInputs, errors::NotePathsError, ProvenTransaction, COMPONENT};
+mod timeouts;
+pub use timeouts::StoreTimeouts;
+
+mod tracer;
+use tracer::CallTracer;
+
+#[cfg(test)]
+mod tests;
 
 // STORE TRAIT
 // ================================================================================================
@@ -46,6 +185,22 @@ pub trait Store: ApplyBlock {
         proven_tx: &ProvenTransaction,
     ) -> Result<TransactionInputs, TxInputsError>;
 
+    /// Returns information needed from the store to verify each of the given proven
+    /// transactions, in the order of `txs`.
+    ///
+    /// The default implementation calls [Store::get_tx_inputs] for each transaction.
+    async fn get_tx_inputs_batch(
+        &self,
+        txs: &[ProvenTransaction],
+    ) -> Result<Vec<TransactionInputs>, TxInputsError> {
+        let mut tx_inputs = Vec::with_capacity(txs.len());
+        for proven_tx in txs {
+            tx_inputs.push(self.get_tx_inputs(proven_tx).await?);
+        }
+
+        Ok(tx_inputs)
+    }
+
     /// Returns information needed from the store to build a block.
     async fn get_block_inputs(
         &self,
@@ -57,11 +212,217 @@ pub trait Store: ApplyBlock {
     /// Returns note authentication information for the set of specified notes.
     ///
     /// If authentication info for a note does not exist in the store, the note is omitted
-    /// from the returned set of notes.
+    /// from the returned set of notes. Use [Store::get_note_authentication_info_and_missing] to
+    /// learn which notes were omitted.
     async fn get_note_authentication_info(
         &self,
         notes: impl Iterator<Item = &NoteId> + Send,
     ) -> Result<NoteAuthenticationInfo, NotePathsError>;
+
+    /// Returns which of the given notes were included in a block after block `since`.
+    ///
+    /// Notes which were missing from the store at block `since` and are returned became
+    /// authenticated since, so transactions waiting for them can be retried. Notes which are
+    /// still missing are not returned.
+    async fn notes_authenticated_since(
+        &self,
+        notes: &[NoteId],
+        since: BlockNumber,
+    ) -> Result<Vec<NoteId>, NotePathsError>;
+
+    /// Returns the ID of the transaction which produced the given nullifier.
+    ///
+    /// Returns `None` if the nullifier is unknown, or if its origin was not recorded when the
+    /// block containing it was applied.
+    async fn nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, TxInputsError>;
+
+    /// Returns the number of the block in which the note with the given nullifier was consumed,
+    /// or `None` if the note was not consumed yet.
+    ///
+    /// # Errors
+    /// Returns [TxInputsError::NullifierSpendError] if the store holds a value for the nullifier
+    /// which doesn't encode a block number, instead of reporting the note as unspent.
+    async fn get_note_spend_block(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<BlockNumber>, TxInputsError>;
+
+    /// Returns the nullifiers reserved by blocks which are being built, but were not applied yet.
+    ///
+    /// Transactions consuming any of these nullifiers would conflict with a pending block.
+    async fn get_pending_nullifiers(&self) -> Result<Vec<Nullifier>, TxInputsError>;
+
+    /// Returns the ID and current hash of an account, where the hash is `None` if the account is
+    /// unknown.
+    ///
+    /// Unlike [Store::get_tx_inputs], this doesn't require a transaction, e.g. for checks of the
+    /// account state before a transaction is built.
+    async fn get_account_state(&self, account_id: AccountId)
+        -> Result<AccountState, TxInputsError>;
+
+    /// Returns the candidate states of an account as `(account hash, tip)` pairs, where `tip` is
+    /// the hash of the block header at which the account has the given hash.
+    ///
+    /// During a fork, the store may know the account at several competing tips, and the caller
+    /// is responsible for picking the canonical one. Unknown accounts have no candidates.
+    async fn get_account_state_forks(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(RpoDigest, RpoDigest)>, TxInputsError>;
+
+    /// Returns the number of the block in which the account was created, or `None` if the account
+    /// is unknown.
+    async fn get_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, TxInputsError>;
+
+    /// Returns the nullifier root of each block in the range `from..=to`, in ascending block
+    /// order.
+    ///
+    /// Fails if the range is invalid, or if the history of any block in the range was pruned.
+    async fn get_nullifier_root_history(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, RpoDigest)>, TxInputsError>;
+
+    /// Returns aggregate statistics of the chain, computed by the store.
+    async fn get_chain_stats(&self) -> Result<ChainStats, TxInputsError>;
+
+    /// Applies the block to the store, and returns the accounts updated by it together with
+    /// their new hashes.
+    async fn apply_block_with_diff(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError>;
+
+    /// Applies a contiguous sequence of blocks to the store in one request, and returns the
+    /// number of the last applied block.
+    ///
+    /// The store stops at the first block it rejects, and keeps the blocks before it applied. The
+    /// returned [ApplyBlockError::BlockRejected] reports the last applied block in that case.
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError>;
+
+    /// Returns a stream of all blocks from block `from` up to the chain tip, in ascending order.
+    ///
+    /// The chain tip is fixed when the stream starts, so blocks applied afterwards are not
+    /// included. The stream ends after the first error.
+    ///
+    /// Each block carries its number in its header, so a client can record the last block it
+    /// received. A stream which was cancelled or failed is resumed without gaps or duplicates by
+    /// calling `sync_blocks_from` with the number following the last delivered block.
+    /// [ResumableSync] does this automatically.
+    fn sync_blocks_from(
+        &self,
+        from: BlockNumber,
+    ) -> impl Stream<Item = Result<Block, TxInputsError>> + Send + 'static;
+
+    /// Checks the account and nullifier roots declared in the header of the block against the
+    /// roots recomputed from the contents of the block and the current state of the store.
+    ///
+    /// This catches blocks which were built incorrectly before they are applied. The block must
+    /// follow the chain tip of the store, as the roots are recomputed from the witnesses of the
+    /// accounts and nullifiers of the block at the tip.
+    async fn precheck_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        let inputs = self
+            .get_block_inputs(
+                block.updated_accounts().iter().map(|update| update.account_id()),
+                block.nullifiers().iter(),
+                std::iter::empty(),
+            )
+            .await?;
+
+        check_block_roots(block, &inputs)
+    }
+
+    /// Estimates how much applying the block would grow the state of the store, from the
+    /// contents of the block and the current state of the store.
+    ///
+    /// This lets operators reject blocks exceeding their resource limits before they are
+    /// applied. The estimate counts the nullifiers of the block which were not consumed yet and
+    /// the accounts it updates, and approximates the number of bytes they add to the store.
+    async fn estimate_apply_cost(
+        &self,
+        block: &Block,
+    ) -> Result<ApplyCostEstimate, ApplyBlockError> {
+        let inputs = self
+            .get_block_inputs(
+                block.updated_accounts().iter().map(|update| update.account_id()),
+                block.nullifiers().iter(),
+                std::iter::empty(),
+            )
+            .await?;
+
+        estimate_apply_cost(block, &inputs)
+    }
+
+    /// Checks that the store is reachable, and returns its chain tip and, if known, whether it
+    /// accepts blocks.
+    ///
+    /// This is meant for readiness probes, e.g. before starting to build blocks. The default
+    /// implementation requests the block inputs of no accounts, nullifiers and notes, which only
+    /// hold the header of the chain tip, and doesn't report whether the store accepts blocks.
+    async fn check_health(&self) -> Result<StoreStatus, BlockInputsError> {
+        let inputs = self
+            .get_block_inputs(std::iter::empty(), std::iter::empty(), std::iter::empty())
+            .await?;
+
+        Ok(StoreStatus {
+            chain_tip: inputs.block_header.block_num().into(),
+            accepting_writes: None,
+        })
+    }
+
+    /// Returns the number of the block following the chain tip of the store, i.e. the number of
+    /// the next block to be built.
+    ///
+    /// The default implementation derives the chain tip from [Store::get_chain_stats].
+    ///
+    /// # Errors
+    /// Returns [TxInputsError::BlockNumberOverflow] if the chain tip is the largest block
+    /// number.
+    async fn next_block_number(&self) -> Result<BlockNumber, TxInputsError> {
+        let stats = self.get_chain_stats().await?;
+
+        // The number of blocks includes the genesis block.
+        next_block_number_after(stats.num_blocks.saturating_sub(1).into())
+    }
+
+    /// Returns note authentication information for the specified notes, together with the
+    /// notes which the store couldn't authenticate, e.g. to treat them as unauthenticated inputs.
+    ///
+    /// The missing notes are the requested notes without a proof in the authentication info, in
+    /// ascending order and each listed once.
+    async fn get_note_authentication_info_and_missing(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<(NoteAuthenticationInfo, Vec<NoteId>), NotePathsError> {
+        let requested: BTreeSet<NoteId> = notes.copied().collect();
+        let info = self.get_note_authentication_info(requested.iter()).await?;
+        let missing = requested.into_iter().filter(|note| !info.contains_note(note)).collect();
+
+        Ok((info, missing))
+    }
+
+    /// Returns the proofs needed to verify the accounts and nullifiers of `txs` against the
+    /// latest block, with each account, nullifier leaf and tree node included once.
+    async fn get_minimal_proof_set(
+        &self,
+        txs: &[ProvenTransaction],
+    ) -> Result<MinimalProofSet, BlockInputsError> {
+        let accounts: BTreeSet<_> = txs.iter().map(|tx| tx.account_id()).collect();
+        let nullifiers: BTreeSet<_> = txs.iter().flat_map(|tx| tx.get_nullifiers()).collect();
+
+        let inputs = self
+            .get_block_inputs(accounts.into_iter(), nullifiers.iter(), std::iter::empty())
+            .await?;
+
+        MinimalProofSet::from_block_inputs(inputs)
+    }
 }
 
 #[async_trait]
@@ -73,7 +434,7 @@ pub trait ApplyBlock: Send + Sync + 'static {
 // ================================================================================================
 
 /// Information needed from the store to verify a transaction.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct TransactionInputs {
     /// Account ID
     pub account_id: AccountId,
@@ -86,7 +447,176 @@ pub struct TransactionInputs {
     /// List of unauthenticated notes that were not found in the store
     pub missing_unauthenticated_notes: Vec<NoteId>,
     /// The current block height
-    pub current_block_height: u32,
+    pub current_block_height: BlockNumber,
+    /// Proofs authenticating the above data, if the store supplied them
+    pub proofs: Option<InputProofs>,
+    /// Opaque version of the account, if the store versions accounts
+    ///
+    /// A [DefaultStore] with [DefaultStore::with_account_versions] sends it back with the block
+    /// containing the transaction, which the store rejects with
+    /// [ApplyBlockError::AccountVersionConflict] if the account changed since.
+    pub account_version: Option<Vec<u8>>,
+}
+
+/// Proofs authenticating [TransactionInputs] against the roots of a block header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputProofs {
+    /// Header of the block at which the transaction inputs were read
+    pub block_header: BlockHeader,
+    /// Authentication path of the account hash against the header's account root
+    pub account_proof: MerklePath,
+    /// Opening of each nullifier against the header's nullifier root
+    pub nullifier_proofs: BTreeMap<Nullifier, SmtProof>,
+}
+
+impl TransactionInputs {
+    /// Returns the inputs made of separately obtained parts, e.g. parts cached from earlier
+    /// responses of the store.
+    ///
+    /// The assembled inputs carry no proofs nor account version, as the parts may have been read
+    /// at different blocks.
+    pub fn assemble(
+        account_id: AccountId,
+        account_state: Option<Digest>,
+        nullifier_status: BTreeMap<Nullifier, Option<NonZeroU32>>,
+        missing_notes: Vec<NoteId>,
+        height: BlockNumber,
+    ) -> Self {
+        Self {
+            account_id,
+            account_hash: account_state,
+            nullifiers: nullifier_status,
+            missing_unauthenticated_notes: missing_notes,
+            current_block_height: height,
+            proofs: None,
+            account_version: None,
+        }
+    }
+
+    /// Returns true if the account is not in the store yet, but the inputs carry the state of
+    /// nullifiers, e.g. for a transaction creating a new account and consuming notes.
+    ///
+    /// Such inputs are valid: a new account has no hash in the store until the transaction
+    /// creating it is applied, while nullifiers are tracked independently of accounts.
+    pub fn is_partial(&self) -> bool {
+        self.account_hash.is_none() && !self.nullifiers.is_empty()
+    }
+
+    /// Checks that the inputs cover every nullifier of `tx`, and that none of them was already
+    /// consumed.
+    ///
+    /// # Errors
+    /// Returns an error for the first nullifier of `tx` which is missing from the inputs or was
+    /// already consumed.
+    pub fn check_nullifiers(&self, tx: &ProvenTransaction) -> Result<(), TxInputsError> {
+        for nullifier in tx.get_nullifiers() {
+            match self.nullifiers.get(&nullifier) {
+                None => return Err(TxInputsError::MissingNullifierInfo(nullifier)),
+                Some(Some(_)) => return Err(TxInputsError::NullifierAlreadySpent(nullifier)),
+                Some(None) => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the account hash and the nullifiers' block numbers against a trusted chain tip.
+    ///
+    /// `root` is the hash of the block header the caller trusts as the chain tip. The header
+    /// carried by `proofs` must hash to it, and the account and nullifier proofs must verify
+    /// against the account and nullifier roots of that header.
+    pub fn verify_against_root(
+        &self,
+        root: Digest,
+        proofs: &InputProofs,
+    ) -> Result<(), TxInputsError> {
+        let header = &proofs.block_header;
+        if header.hash() != root {
+            return Err(TxInputsError::BlockHeaderMismatch {
+                expected: root,
+                actual: header.hash(),
+            });
+        }
+        if BlockNumber::from(header.block_num()) != self.current_block_height {
+            return Err(TxInputsError::MalformedResponse(format!(
+                "proofs are for block {}, but inputs are for block {}",
+                header.block_num(),
+                self.current_block_height
+            )));
+        }
+
+        // An account which is not in the store has an empty leaf in the account tree.
+        let account_leaf = self.account_hash.unwrap_or_default();
+        proofs
+            .account_proof
+            .verify(self.account_id.into(), account_leaf, &header.account_root())
+            .map_err(|_| TxInputsError::InvalidAccountProof(self.account_id))?;
+
+        for (nullifier, block_num) in &self.nullifiers {
+            let leaf_value = match block_num {
+                Some(block_num) => [Felt::from(block_num.get()), ZERO, ZERO, ZERO],
+                None => EMPTY_WORD,
+            };
+
+            let verified = proofs.nullifier_proofs.get(nullifier).is_some_and(|proof| {
+                proof.verify_membership(&nullifier.inner(), &leaf_value, &header.nullifier_root())
+            });
+            if !verified {
+                return Err(TxInputsError::InvalidNullifierProof(*nullifier));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the nullifiers of `requested` for which `returned` holds no entry, in the order they
+/// were requested.
+///
+/// A non-empty result means the store silently omitted some of the requested nullifiers.
+pub fn missing_nullifiers<'a, V>(
+    requested: impl IntoIterator<Item = &'a Nullifier>,
+    returned: &BTreeMap<Nullifier, V>,
+) -> Vec<Nullifier> {
+    requested
+        .into_iter()
+        .filter(|nullifier| !returned.contains_key(nullifier))
+        .copied()
+        .collect()
+}
+
+/// Returns the note ids of `notes` without duplicates and in ascending order, so identical sets
+/// of notes always produce identical requests.
+///
+/// # Errors
+/// Returns [NotePathsError::InvalidNoteId] for the first note id which is the zero digest, which
+/// is not the hash of any note.
+pub fn normalize_note_ids<'a>(
+    notes: impl IntoIterator<Item = &'a NoteId>,
+) -> Result<Vec<NoteId>, NotePathsError> {
+    let mut normalized = BTreeSet::new();
+    for note_id in notes {
+        if note_id.inner() == Digest::default() {
+            return Err(NotePathsError::InvalidNoteId(*note_id));
+        }
+        normalized.insert(*note_id);
+    }
+
+    Ok(normalized.into_iter().collect())
+}
+
+/// Checks that the account hash returned by the store matches the initial account hash of
+/// `proven_tx`, unless the transaction creates its account and so declares no initial hash.
+fn check_account_state(
+    proven_tx: &ProvenTransaction,
+    tx_inputs: &TransactionInputs,
+) -> Result<(), TxInputsError> {
+    let expected = proven_tx.account_update().init_state_hash();
+    if expected == Digest::default() || tx_inputs.account_hash == Some(expected) {
+        return Ok(());
+    }
+
+    Err(TxInputsError::AccountStateMismatch { expected, got: tx_inputs.account_hash })
 }
 
 impl Display for TransactionInputs {
@@ -121,16 +651,31 @@ impl TryFrom<GetTransactionInputsResponse> for TransactionInputs {
             .ok_or(GetTransactionInputsResponse::missing_field(stringify!(account_state)))?
             .try_into()?;
 
+        let current_block_height = BlockNumber::from(response.block_height);
+
         let mut nullifiers = BTreeMap::new();
+        let mut nullifier_proofs = BTreeMap::new();
         for nullifier_record in response.nullifiers {
             let nullifier = nullifier_record
                 .nullifier
                 .ok_or(NullifierTransactionInputRecord::missing_field(stringify!(nullifier)))?
                 .try_into()?;
 
+            // A nullifier can't have been consumed in a block the store doesn't have yet.
+            if BlockNumber::from(nullifier_record.block_num) > current_block_height {
+                return Err(ConversionError::BlockNumberAfterBlockHeight {
+                    block_num: nullifier_record.block_num,
+                    block_height: current_block_height.into(),
+                });
+            }
+
             // Note that this intentionally maps 0 to None as this is the definition used in
             // protobuf.
             nullifiers.insert(nullifier, NonZeroU32::new(nullifier_record.block_num));
+
+            if let Some(opening) = nullifier_record.opening {
+                nullifier_proofs.insert(nullifier, opening.try_into()?);
+            }
         }
 
         let missing_unauthenticated_notes = response
@@ -139,7 +684,22 @@ impl TryFrom<GetTransactionInputsResponse> for TransactionInputs {
             .map(|digest| Ok(RpoDigest::try_from(digest)?.into()))
             .collect::<Result<Vec<_>, ConversionError>>()?;
 
-        let current_block_height = response.block_height;
+        // Proofs are only usable if the store supplied all of them.
+        let proofs = match (response.block_header, response.account_proof) {
+            (Some(block_header), Some(account_proof))
+                if nullifier_proofs.len() == nullifiers.len() =>
+            {
+                Some(InputProofs {
+                    block_header: block_header.try_into()?,
+                    account_proof: (&account_proof).try_into()?,
+                    nullifier_proofs,
+                })
+            },
+            _ => None,
+        };
+
+        // Stores which don't version accounts send an empty version.
+        let account_version = Some(response.account_version).filter(|version| !version.is_empty());
 
         Ok(Self {
             account_id,
@@ -147,130 +707,1931 @@ impl TryFrom<GetTransactionInputsResponse> for TransactionInputs {
             nullifiers,
             missing_unauthenticated_notes,
             current_block_height,
+            proofs,
+            account_version,
+        })
+    }
+}
+
+/// Encodes the inputs as the store sends them, e.g. to record and replay store responses.
+///
+/// Converting the response back with [TransactionInputs::try_from] yields the same inputs, and
+/// encoding those again yields the same response. Unconsumed nullifiers are encoded as block 0,
+/// unknown accounts with the default hash, and missing proofs and versions as absent or empty
+/// fields.
+impl From<TransactionInputs> for GetTransactionInputsResponse {
+    fn from(tx_inputs: TransactionInputs) -> Self {
+        let (block_header, account_proof, mut nullifier_proofs) = match tx_inputs.proofs {
+            Some(proofs) => (
+                Some(proofs.block_header.into()),
+                Some(proofs.account_proof.into()),
+                proofs.nullifier_proofs,
+            ),
+            None => (None, None, BTreeMap::new()),
+        };
+
+        let nullifiers = tx_inputs
+            .nullifiers
+            .into_iter()
+            .map(|(nullifier, block_num)| NullifierTransactionInputRecord {
+                nullifier: Some(nullifier.into()),
+                // Unconsumed nullifiers are encoded as block 0, as expected by `TryFrom`.
+                block_num: block_num.map_or(0, NonZeroU32::get),
+                opening: nullifier_proofs.remove(&nullifier).map(Into::into),
+            })
+            .collect();
+
+        Self {
+            // Accounts unknown to the store are encoded with the default hash, like the store does.
+            account_state: Some(AccountTransactionInputRecord {
+                account_id: Some(tx_inputs.account_id.into()),
+                account_hash: Some(tx_inputs.account_hash.unwrap_or_default().into()),
+            }),
+            nullifiers,
+            missing_unauthenticated_notes: tx_inputs
+                .missing_unauthenticated_notes
+                .iter()
+                .map(Into::into)
+                .collect(),
+            block_height: tx_inputs.current_block_height.into(),
+            block_header,
+            account_proof,
+            account_version: tx_inputs.account_version.unwrap_or_default(),
+        }
+    }
+}
+
+// CHAIN STATS
+// ================================================================================================
+
+/// Aggregate statistics of the chain.
+///
+/// Serializes roots as hex strings, for consumption by dashboards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct ChainStats {
+    /// Number of blocks in the chain, including the genesis block
+    pub num_blocks: u32,
+    /// Number of accounts in the account tree
+    pub num_accounts: u64,
+    /// Number of consumed nullifiers
+    pub num_nullifiers: u64,
+    #[serde(serialize_with = "serialize_digest")]
+    pub account_root: Digest,
+    #[serde(serialize_with = "serialize_digest")]
+    pub nullifier_root: Digest,
+    /// Root of the chain MMR, committing to all blocks of the chain
+    #[serde(serialize_with = "serialize_digest")]
+    pub chain_root: Digest,
+}
+
+impl TryFrom<GetChainStatsResponse> for ChainStats {
+    type Error = ConversionError;
+
+    fn try_from(response: GetChainStatsResponse) -> Result<Self, Self::Error> {
+        Ok(Self {
+            num_blocks: response.num_blocks,
+            num_accounts: response.num_accounts,
+            num_nullifiers: response.num_nullifiers,
+            account_root: response
+                .account_root
+                .ok_or(GetChainStatsResponse::missing_field(stringify!(account_root)))?
+                .try_into()?,
+            nullifier_root: response
+                .nullifier_root
+                .ok_or(GetChainStatsResponse::missing_field(stringify!(nullifier_root)))?
+                .try_into()?,
+            chain_root: response
+                .chain_root
+                .ok_or(GetChainStatsResponse::missing_field(stringify!(chain_root)))?
+                .try_into()?,
         })
     }
 }
 
+fn serialize_digest<S: Serializer>(digest: &Digest, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&digest.to_hex())
+}
+
 // DEFAULT STORE IMPLEMENTATION
 // ================================================================================================
 
-pub struct DefaultStore {
-    store: store_client::ApiClient<Channel>,
+/// The delay before the first retry of a failed store request.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+
+/// The maximum delay between retries of a failed store request.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// The number of blocks received from a block sync stream which are buffered before waiting for
+/// the consumer.
+const SYNC_BLOCKS_BUFFER_SIZE: usize = 16;
+
+pub struct DefaultStore<C = store_client::ApiClient<Channel>> {
+    /// Sends blocks to the store
+    applier: BlockApplier<C>,
+    /// Whether the transaction which produced each nullifier is recorded
+    track_nullifier_origins: bool,
+    /// Whether the version of the account read by each transaction is recorded
+    track_account_versions: bool,
+    /// Whether the account hash returned by the store must match the initial account hash of
+    /// the transaction
+    strict_account_state: bool,
+    /// Whether the chain heights reported with transaction inputs are tracked
+    track_block_height: bool,
+    /// Highest chain height reported with transaction inputs, if tracked
+    last_seen_height: Mutex<Option<u32>>,
+    /// Caches of note authentication info returned by the store for each tenant, if enabled
+    note_auth_cache: Option<Mutex<TenantNoteAuthCaches>>,
+    /// What to do with note authentication info which doesn't open against the expected chain
+    root_consistency: RootConsistencyPolicy,
+    /// Delays between the retries of failed requests, holding the source of randomness for all
+    /// randomized behavior of the store
+    backoff: Backoff,
+    /// Queue of blocks which could not be applied because the store was unavailable, if enabled
+    apply_queue: Option<Arc<ApplyQueue>>,
+    /// Spawns the retry task of the apply queue once
+    retry_task: Once,
+    /// Limits the number of concurrent read requests, if enabled
+    limiter: Option<PriorityLimiter>,
+    /// Maximum time a read request may wait for a slot of the limiter
+    queue_timeout: Option<Duration>,
+    /// Source of the current time for all time-dependent behavior of the store
+    clock: Arc<dyn Clock>,
+    /// Delay after which a second, hedged request is sent for reads which didn't complete, if
+    /// enabled
+    hedge_delay: Option<Duration>,
+    /// Replica of the store which receives the hedged requests, instead of the store itself
+    replica: Option<C>,
+    /// Routes nullifier requests to the shards holding the nullifiers, if the nullifier tree is
+    /// sharded
+    shard_router: Option<ShardRouter<C>>,
+    /// Counts the requests sent to the store during the current round
+    round_trips: RoundTripCounter,
+    /// Cache of the responses to read requests, shared with the applier which clears it
+    response_cache: ResponseCache,
+    /// Wraps the requests sent to the store in spans, shared with the applier
+    tracer: CallTracer,
+    /// Holds the client of the store and tracks the state of its connection, shared with the
+    /// applier
+    connection: ConnectionMonitor<C>,
+    /// Tenant which the requests are scoped to unless overridden, if any
+    tenant: Option<AsciiMetadataValue>,
+    /// Deadlines of the requests sent to the store
+    timeouts: StoreTimeouts,
+    /// Number of the last block applied through the store, shared with the applier
+    applied_block_num: AppliedBlockNum,
+    /// Whether reads ask for a state including the last applied block
+    read_your_writes: bool,
+    /// Maximum number of items of each block inputs request, if large requests are chunked
+    block_inputs_chunk_size: Option<NonZeroUsize>,
+    /// Decides which failed reads may be retried
+    retry_policy: RetryPolicy,
+    /// Cache of note authentication info and account states which survives restarts, if enabled
+    #[cfg(feature = "persistent-cache")]
+    persistent_cache: Option<PersistentCache>,
 }
 
 impl DefaultStore {
-    /// TODO: this should probably take store connection string and create a connection internally
+    /// Returns a store sending its requests through `store`.
+    ///
+    /// Prefer [DefaultStore::connect] or [DefaultStore::connect_lazy] unless the channel is built
+    /// by the caller, e.g. in tests.
     pub fn new(store: store_client::ApiClient<Channel>) -> Self {
-        Self { store }
+        Self::from_client(store)
     }
-}
-
-#[async_trait]
-impl ApplyBlock for DefaultStore {
-    #[instrument(target = "miden-block-producer", skip_all, err)]
-    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
-        let request = tonic::Request::new(ApplyBlockRequest { block: block.to_bytes() });
 
-        let _ = self
-            .store
-            .clone()
-            .apply_block(request)
-            .await
-            .map_err(|status| ApplyBlockError::GrpcClientError(status.message().to_string()))?;
+    /// Returns a store connected to the store at `endpoint`.
+    ///
+    /// # Errors
+    /// Returns an error if `endpoint` is not a valid URI, or the connection to the store fails.
+    pub async fn connect(endpoint: impl Into<String>) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(endpoint.into())?.connect().await?;
 
-        Ok(())
+        Ok(Self::new(store_client::ApiClient::new(channel)))
     }
-}
 
-#[async_trait]
-impl Store for DefaultStore {
-    #[instrument(target = "miden-block-producer", skip_all, err)]
-    async fn get_tx_inputs(
-        &self,
-        proven_tx: &ProvenTransaction,
-    ) -> Result<TransactionInputs, TxInputsError> {
-        let message = GetTransactionInputsRequest {
-            account_id: Some(proven_tx.account_id().into()),
-            nullifiers: proven_tx.get_nullifiers().map(Into::into).collect(),
-            unauthenticated_notes: proven_tx
-                .get_unauthenticated_notes()
-                .map(|note| note.id().into())
-                .collect(),
-        };
+    /// Returns a store which connects to the store at `endpoint` on its first request.
+    ///
+    /// This allows creating the store before the store server is up. Requests sent while the
+    /// store server is unreachable fail with a gRPC error.
+    ///
+    /// # Errors
+    /// Returns an error if `endpoint` is not a valid URI.
+    pub fn connect_lazy(endpoint: impl Into<String>) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(endpoint.into())?.connect_lazy();
 
-        info!(target: COMPONENT, tx_id = %proven_tx.id().to_hex());
-        debug!(target: COMPONENT, ?message);
+        Ok(Self::new(store_client::ApiClient::new(channel)))
+    }
 
-        let request = tonic::Request::new(message);
-        let response = self
-            .store
-            .clone()
-            .get_transaction_inputs(request)
-            .await
-            .map_err(|status| TxInputsError::GrpcClientError(status.message().to_string()))?
-            .into_inner();
+    /// Returns a store connected to the store at `endpoint` over TLS configured by `tls`, e.g.
+    /// with the CA certificate of the store and a client identity.
+    ///
+    /// Stores connected with [DefaultStore::connect] and [DefaultStore::connect_lazy] don't
+    /// encrypt their traffic, which is only suitable if the store runs on the same host.
+    ///
+    /// # Errors
+    /// Returns an error if `endpoint` is not a valid URI, the certificates or identity of `tls`
+    /// are malformed, or the connection to the store fails, e.g. because its certificate isn't
+    /// signed by the configured CA.
+    #[cfg(feature = "tls")]
+    pub async fn connect_with_tls(
+        endpoint: impl Into<String>,
+        tls: ClientTlsConfig,
+    ) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(endpoint.into())?.tls_config(tls)?.connect().await?;
 
-        debug!(target: COMPONENT, ?response);
+        Ok(Self::new(store_client::ApiClient::new(channel)))
+    }
 
-        let tx_inputs: TransactionInputs = response.try_into()?;
+    /// Enables re-establishing the channel to the store at `endpoint` after repeated requests
+    /// failed because the store was unavailable. See [DefaultStore::with_reconnect].
+    pub fn with_endpoint_reconnect(self, endpoint: Endpoint, cooldown: Duration) -> Self {
+        self.with_reconnect(move || store_client::ApiClient::new(endpoint.connect_lazy()), cooldown)
+    }
+}
 
-        if tx_inputs.account_id != proven_tx.account_id() {
-            return Err(TxInputsError::MalformedResponse(format!(
-                "incorrect account id returned from store. Got: {}, expected: {}",
-                tx_inputs.account_id,
-                proven_tx.account_id()
-            )));
-        }
+impl<C: StoreClient> DefaultStore<C> {
+    /// Returns a store sending its requests through `store`.
+    pub fn from_client(store: C) -> Self {
+        let round_trips = RoundTripCounter::default();
+        let response_cache = ResponseCache::default();
+        let tracer = CallTracer::default();
+        let connection = ConnectionMonitor::new(store);
+        let applied_block_num = AppliedBlockNum::default();
 
-        debug!(target: COMPONENT, %tx_inputs);
+        Self {
+            applier: BlockApplier {
+                nullifier_origins: Default::default(),
+                account_versions: Default::default(),
+                round_trips: round_trips.clone(),
+                response_cache: response_cache.clone(),
+                tracer: tracer.clone(),
+                connection: connection.clone(),
+                tenant: None,
+                timeouts: StoreTimeouts::default(),
+                applied_block_num: applied_block_num.clone(),
+            },
+            track_nullifier_origins: false,
+            track_account_versions: false,
+            strict_account_state: false,
+            track_block_height: false,
+            last_seen_height: Mutex::new(None),
+            note_auth_cache: None,
+            root_consistency: RootConsistencyPolicy::default(),
+            backoff: Backoff::new(RETRY_BASE_DELAY, RETRY_MAX_DELAY),
+            apply_queue: None,
+            retry_task: Once::new(),
+            limiter: None,
+            queue_timeout: None,
+            clock: Arc::new(SystemClock),
+            hedge_delay: None,
+            replica: None,
+            shard_router: None,
+            round_trips,
+            response_cache,
+            tracer,
+            connection,
+            tenant: None,
+            timeouts: StoreTimeouts::default(),
+            applied_block_num,
+            read_your_writes: false,
+            block_inputs_chunk_size: None,
+            retry_policy: RetryPolicy::default(),
+            #[cfg(feature = "persistent-cache")]
+            persistent_cache: None,
+        }
+    }
 
-        Ok(tx_inputs)
+    /// Enables recording of the transaction which produced each nullifier.
+    ///
+    /// Nullifiers are tagged with their transaction when the transaction inputs are requested,
+    /// and the tags are sent to the store together with the block which contains them. At most
+    /// [DEFAULT_MAX_RECORDED_ENTRIES] tags are kept unless set otherwise with
+    /// [DefaultStore::with_max_recorded_entries], the oldest tags are dropped beyond that.
+    pub fn with_nullifier_origins(mut self) -> Self {
+        self.track_nullifier_origins = true;
+        self
     }
 
-    async fn get_block_inputs(
-        &self,
-        updated_accounts: impl Iterator<Item = AccountId> + Send,
-        produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
-        notes: impl Iterator<Item = &NoteId> + Send,
-    ) -> Result<BlockInputs, BlockInputsError> {
-        let request = tonic::Request::new(GetBlockInputsRequest {
-            account_ids: updated_accounts.map(Into::into).collect(),
-            nullifiers: produced_nullifiers.map(digest::Digest::from).collect(),
-            unauthenticated_notes: notes.map(digest::Digest::from).collect(),
-        });
+    /// Sets the maximum number of nullifier origins, and separately of account versions, recorded
+    /// until their block is applied.
+    ///
+    /// The entries of transactions which are verified but never included in a block are only
+    /// dropped once the maximum is reached.
+    pub fn with_max_recorded_entries(self, max_entries: usize) -> Self {
+        self.applier.nullifier_origins.set_max_entries(max_entries);
+        self.applier.account_versions.set_max_entries(max_entries);
+        self
+    }
 
-        let store_response = self
-            .store
-            .clone()
-            .get_block_inputs(request)
-            .await
-            .map_err(|err| BlockInputsError::GrpcClientError(err.message().to_string()))?
-            .into_inner();
+    /// Enables optimistic locking of the accounts of the applied blocks.
+    ///
+    /// The version of the account returned by the store with the transaction inputs is recorded
+    /// for each transaction, and the versions are sent to the store together with the block
+    /// which contains the transactions. The store rejects the block with
+    /// [ApplyBlockError::AccountVersionConflict] if one of its accounts changed since it was read,
+    /// so concurrent updates of an account are not lost. Like nullifier origins, at most
+    /// [DefaultStore::with_max_recorded_entries] versions are kept.
+    pub fn with_account_versions(mut self) -> Self {
+        self.track_account_versions = true;
+        self
+    }
 
-        Ok(store_response.try_into()?)
+    /// Enables tracking of the chain height reported by the store with transaction inputs, see
+    /// [DefaultStore::last_seen_height].
+    ///
+    /// A warning is logged when the store reports a lower height than before, which indicates
+    /// that the store rewound, or that the request was served by a stale replica.
+    pub fn with_height_tracking(mut self) -> Self {
+        self.track_block_height = true;
+        self
     }
 
-    async fn get_note_authentication_info(
-        &self,
-        notes: impl Iterator<Item = &NoteId> + Send,
-    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
-        let request = tonic::Request::new(GetNoteAuthenticationInfoRequest {
-            note_ids: notes.map(digest::Digest::from).collect(),
-        });
+    /// Enables the check of the account hash returned by the store against the initial account
+    /// hash of the transaction.
+    ///
+    /// With the check, the transaction inputs of a transaction built against a stale state of
+    /// an existing account are rejected with [TxInputsError::AccountStateMismatch] before the
+    /// transaction is verified. Transactions creating an account declare no initial hash, and
+    /// are not checked.
+    pub fn with_strict_account_state(mut self) -> Self {
+        self.strict_account_state = true;
+        self
+    }
 
-        let store_response = self
-            .store
-            .clone()
-            .get_note_authentication_info(request)
-            .await
-            .map_err(|err| NotePathsError::GrpcClientError(err.message().to_string()))?
-            .into_inner();
+    /// Enables caching of note authentication info returned by the store.
+    ///
+    /// The cache is cleared whenever a block is applied, as the cached block inclusion proofs are
+    /// only valid for the chain length they were created at. Each tenant the store sends requests
+    /// for gets its own cache with the bounds of `cache`.
+    pub fn with_note_auth_cache(mut self, cache: NoteAuthCache) -> Self {
+        self.note_auth_cache = Some(Mutex::new(TenantNoteAuthCaches::new(&cache)));
+        self
+    }
 
-        let note_authentication_info = store_response
-            .proofs
-            .ok_or(GetTransactionInputsResponse::missing_field("proofs"))?
-            .try_into()?;
+    /// Sets what [DefaultStore::get_note_authentication_info_at] does with note authentication
+    /// info which doesn't open against the expected chain.
+    ///
+    /// By default, such info is rejected. See [RootConsistencyPolicy].
+    pub fn with_root_consistency_policy(mut self, policy: RootConsistencyPolicy) -> Self {
+        self.root_consistency = policy;
+        self
+    }
 
-        Ok(note_authentication_info)
+    /// Enables the persistent read-through cache of note authentication info and account states.
+    ///
+    /// Cached responses survive a restart of the block producer, and are only removed when a
+    /// block is applied.
+    #[cfg(feature = "persistent-cache")]
+    pub fn with_persistent_cache(mut self, cache: PersistentCache) -> Self {
+        self.persistent_cache = Some(cache);
+        self
     }
+
+    /// Seeds the randomness used by the store, making all of its randomized behavior, e.g. the
+    /// jitter of its [backoff](Self::backoff), deterministic.
+    ///
+    /// This is intended for tests and reproducible benchmarks. By default, the store is seeded
+    /// from entropy.
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.backoff = self.backoff.with_rng_seed(seed);
+        self
+    }
+
+    /// Limits the number of concurrent read requests to the store.
+    ///
+    /// When all slots are taken, waiting requests get free slots in order of their [Priority].
+    pub fn with_concurrency_limit(mut self, max_concurrent: usize) -> Self {
+        self.limiter = Some(PriorityLimiter::new(max_concurrent));
+        self
+    }
+
+    /// Fails read requests which waited for longer than `timeout` for a slot of the concurrency
+    /// limit, instead of sending them to the store.
+    ///
+    /// Has no effect unless the concurrency limit is set with
+    /// [with_concurrency_limit](Self::with_concurrency_limit).
+    pub fn with_queue_timeout(mut self, timeout: Duration) -> Self {
+        self.queue_timeout = Some(timeout);
+        self
+    }
+
+    /// Replaces the system clock used by all time-dependent behavior of the store.
+    ///
+    /// This is intended for tests, which can then advance time without waiting.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.connection.set_clock(clock.clone());
+        self.backoff = self.backoff.with_clock(clock.clone());
+        self.clock = clock;
+        self
+    }
+
+    /// Enables rebuilding the client of the store with `connect` after repeated requests failed
+    /// because the store was unavailable, e.g. to re-establish a channel which failed
+    /// permanently.
+    ///
+    /// The client is rebuilt at most once per `cooldown`, and the state of the connection is
+    /// reset to [ConnectionState::Connecting].
+    pub fn with_reconnect(
+        self,
+        connect: impl Fn() -> C + Send + Sync + 'static,
+        cooldown: Duration,
+    ) -> Self {
+        self.connection.set_reconnect(Box::new(connect), cooldown);
+        self
+    }
+
+    /// Enables hedging of read requests: if a read did not complete within `delay`, an identical
+    /// request is sent, and the response which arrives first is used while the other request is
+    /// cancelled.
+    ///
+    /// Setting `delay` to a high percentile of the read latency of the store, e.g. the p95,
+    /// hedges only the slowest reads, which cuts tail latency at the cost of some extra load.
+    /// Hedged requests are sent to the replica set with [with_replica](Self::with_replica), or
+    /// to the store itself otherwise.
+    pub fn with_hedged_reads(mut self, delay: Duration) -> Self {
+        self.hedge_delay = Some(delay);
+        self
+    }
+
+    /// Sends hedged read requests to `replica` instead of the store itself.
+    ///
+    /// Has no effect unless hedging is enabled with [with_hedged_reads](Self::with_hedged_reads).
+    pub fn with_replica(mut self, replica: C) -> Self {
+        self.replica = Some(replica);
+        self
+    }
+
+    /// Requests the witnesses of nullifiers from the shards holding them, as given by `router`,
+    /// instead of the store itself.
+    ///
+    /// Requests which cover nullifiers of several shards, e.g. [Store::get_block_inputs], are
+    /// split into one request per shard, which are sent concurrently and whose results are
+    /// merged.
+    pub fn with_shard_router(mut self, router: ShardRouter<C>) -> Self {
+        self.shard_router = Some(router);
+        self
+    }
+
+    /// Enables caching of the responses to read requests for `ttl`.
+    ///
+    /// Reads identical to a read sent less than `ttl` ago are answered from the cache instead of
+    /// the store, e.g. when retrying the requests of a block-building round. The cache is cleared
+    /// whenever a block is applied, so responses are never served across block boundaries.
+    pub fn with_response_cache(self, ttl: Duration) -> Self {
+        self.response_cache.set_ttl(ttl);
+        self
+    }
+
+    /// Sets the address of the store, which is recorded in the OpenTelemetry spans of the
+    /// requests sent to it.
+    #[cfg(feature = "otel")]
+    pub fn with_store_address(self, address: impl Into<String>) -> Self {
+        self.tracer.set_store_address(address.into());
+        self
+    }
+
+    /// Scopes all requests sent to the store to `tenant`, unless overridden by
+    /// [scoped_to_tenant].
+    ///
+    /// The tenant is sent as the [TENANT_METADATA_KEY] metadata of each request. A store which
+    /// requires a tenant rejects requests without one, and its error message is returned as a
+    /// gRPC client error.
+    pub fn with_tenant(mut self, tenant: AsciiMetadataValue) -> Self {
+        self.applier.tenant = Some(tenant.clone());
+        self.tenant = Some(tenant);
+        self
+    }
+
+    /// Sets the deadlines of the requests sent to the store.
+    ///
+    /// By default, requests have no deadline. See [StoreTimeouts].
+    pub fn with_timeouts(mut self, timeouts: StoreTimeouts) -> Self {
+        self.applier.timeouts = timeouts;
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Enables read-your-writes consistency for stores served by several replicas.
+    ///
+    /// Once a block was applied through this store, the reads sent to the store carry its
+    /// number as the [MIN_BLOCK_NUM_METADATA_KEY] metadata. The store, or the load balancer in
+    /// front of it, is expected to serve them from a replica which applied at least that block,
+    /// or to wait until one did, so reads never observe a state before the applied blocks.
+    pub fn with_read_your_writes(mut self) -> Self {
+        self.read_your_writes = true;
+        self
+    }
+
+    /// Splits block inputs requests of more than `max_items` account IDs, nullifiers and notes
+    /// in total into several requests, e.g. to keep the responses for very large blocks below the
+    /// gRPC message size limit.
+    ///
+    /// The requests are sent one after the other, and their responses are merged into the block
+    /// inputs of the whole request. The merged block inputs fail with
+    /// [BlockInputsError::InconsistentChunks] if a block was applied while the requests were
+    /// sent, as their witnesses then don't open against the same state.
+    pub fn with_block_inputs_chunking(mut self, max_items: NonZeroUsize) -> Self {
+        self.block_inputs_chunk_size = Some(max_items);
+        self
+    }
+
+    /// Sets which failed reads may be retried, e.g. to retry application-specific status codes
+    /// of a store deployment.
+    ///
+    /// Reads which may be retried fail with transient errors, which a [RetryingStore] wrapping
+    /// the store retries. By default, reads are retried if the store was unavailable or didn't
+    /// respond in time. See [RetryPolicy].
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Enables queueing of blocks which could not be applied because the store was transiently
+    /// unavailable.
+    ///
+    /// Queued blocks are persisted in `dir` and retried by a background task until they are
+    /// applied or rejected by the store, which gives at-least-once semantics to
+    /// [ApplyBlock::apply_block]. The retries are spaced out by the [backoff](Self::backoff) of
+    /// the store.
+    ///
+    /// The background task is started when the first block is applied or the queue is flushed,
+    /// so it retries the blocks with the final configuration of the store, e.g. its tenant and
+    /// timeouts. Blocks left in `dir` by a previous run are retried before any new block.
+    pub fn with_apply_queue(mut self, dir: impl Into<PathBuf>) -> io::Result<Self> {
+        self.apply_queue = Some(Arc::new(ApplyQueue::open(dir)?));
+        Ok(self)
+    }
+
+    /// Returns the number of blocks waiting in the apply queue.
+    pub async fn pending_applies(&self) -> usize {
+        match &self.apply_queue {
+            Some(queue) => queue.len().await,
+            None => 0,
+        }
+    }
+
+    /// Applies all blocks waiting in the apply queue, in order.
+    ///
+    /// Returns an error if the store is still unavailable, in which case the remaining blocks
+    /// stay queued.
+    pub async fn flush(&self) -> Result<(), ApplyBlockError> {
+        match self.started_apply_queue() {
+            Some(queue) => queue.flush(&self.applier).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Starts counting the requests sent to the store, e.g. at the start of a block-building
+    /// round, and returns a guard reading the count.
+    ///
+    /// Every request counts, including hedged requests and requests to the shards of the store.
+    /// Reads answered from the note authentication cache don't. See [RoundTripCounter::enter].
+    pub fn enter_round(&self) -> RoundGuard {
+        self.round_trips.enter()
+    }
+
+    /// Returns the state of the connection to the store.
+    ///
+    /// The state is inferred from the outcomes of the recent requests sent to the store: a
+    /// request failing because the store is unavailable indicates a transient failure, and any
+    /// request reaching the store indicates the connection is up again.
+    pub fn connection_state(&self) -> ConnectionState {
+        self.connection.state()
+    }
+
+    /// Returns a snapshot of the metrics of the requests sent to the store, including the blocks
+    /// applied by the retry task of the apply queue.
+    ///
+    /// The snapshot is taken under one lock, so its counts are consistent with each other.
+    pub fn metrics_snapshot(&self) -> StoreMetrics {
+        self.tracer.metrics().snapshot()
+    }
+
+    /// Returns the highest chain height reported by the store with transaction inputs, or `None`
+    /// if no inputs were returned yet or [DefaultStore::with_height_tracking] is not enabled.
+    ///
+    /// Lower heights reported later don't replace it.
+    pub fn last_seen_height(&self) -> Option<u32> {
+        *self.last_seen_height.lock().expect("last seen height lock poisoned")
+    }
+
+    /// Returns the apply queue if enabled, spawning its retry task on first use.
+    fn started_apply_queue(&self) -> Option<&Arc<ApplyQueue>> {
+        let queue = self.apply_queue.as_ref()?;
+        self.retry_task.call_once(|| {
+            tokio::spawn(
+                Arc::clone(queue).run_retry_task(self.applier.clone(), self.backoff.clone()),
+            );
+        });
+
+        Some(queue)
+    }
+
+    /// Returns the schedule of the retries of failed requests of the store.
+    ///
+    /// The returned backoff shares the randomness and the clock of the store, so a
+    /// [RetryingStore] wrapping the store with it retries deterministically if the store is
+    /// [seeded](Self::with_rng_seed).
+    pub fn backoff(&self) -> Backoff {
+        self.backoff.clone()
+    }
+
+    /// Returns the delay before the given retry attempt of a failed store request.
+    ///
+    /// The bound of the delay grows exponentially with the attempt number up to
+    /// [RETRY_MAX_DELAY], see [Backoff].
+    pub fn retry_delay(&self, attempt: u32) -> Duration {
+        self.backoff.delay(attempt)
+    }
+}
+
+#[async_trait]
+impl<C: StoreClient> ApplyBlock for DefaultStore<C> {
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        match self.started_apply_queue() {
+            Some(queue) => queue.apply(&self.applier, block).await?,
+            None => self.applier.apply_block(block).await?,
+        }
+
+        self.clear_caches();
+
+        Ok(())
+    }
+}
+
+/// Sends blocks to the store, together with the recorded origins of their nullifiers and the
+/// recorded versions of their accounts.
+///
+/// The applier is shared between the [DefaultStore] and the retry task of its apply queue.
+#[derive(Clone)]
+struct BlockApplier<C> {
+    /// Maps the nullifiers of verified transactions to the transaction which produced them, if
+    /// tracking of nullifier origins is enabled
+    nullifier_origins: Recorder<Nullifier, TransactionId>,
+    /// Maps verified transactions to the version of their account read by them, if tracking of
+    /// account versions is enabled
+    account_versions: Recorder<TransactionId, Vec<u8>>,
+    /// Counts the blocks sent to the store during the current round
+    round_trips: RoundTripCounter,
+    /// Cache of the responses to read requests, which is cleared once a block is applied
+    response_cache: ResponseCache,
+    /// Wraps the requests sent to the store in spans
+    tracer: CallTracer,
+    /// Holds the client of the store and tracks the state of its connection
+    connection: ConnectionMonitor<C>,
+    /// Tenant which the blocks are applied for unless overridden, if any
+    tenant: Option<AsciiMetadataValue>,
+    /// Deadlines of the requests applying blocks
+    timeouts: StoreTimeouts,
+    /// Number of the last block applied, shared with the store
+    applied_block_num: AppliedBlockNum,
+}
+
+impl<C: StoreClient> BlockApplier<C> {
+    /// Sends the block to the store, and returns the accounts updated by it together with their
+    /// new hashes.
+    async fn apply_block_with_diff(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError> {
+        let request = self.request(self.apply_block_request(block));
+
+        self.round_trips.record();
+        let response = self
+            .connection
+            .track(
+                self.tracer
+                    .trace::<ApplyBlockRequest, _>(self.connection.client().apply_block(request)),
+            )
+            .await
+            .map_err(ApplyBlockError::from)?;
+
+        // Cached responses may reflect the state before the block.
+        self.response_cache.clear();
+        self.forget_recorded(block);
+        self.applied_block_num.record(block.header().block_num());
+
+        let updated_accounts = response
+            .into_inner()
+            .updated_accounts
+            .into_iter()
+            .map(|summary| {
+                let account_id = summary
+                    .account_id
+                    .ok_or(AccountSummary::missing_field(stringify!(account_id)))?
+                    .try_into()?;
+                let account_hash = summary
+                    .account_hash
+                    .ok_or(AccountSummary::missing_field(stringify!(account_hash)))?
+                    .try_into()?;
+
+                Ok((account_id, account_hash))
+            })
+            .collect::<Result<_, ConversionError>>()?;
+
+        Ok(updated_accounts)
+    }
+
+    /// Sends the blocks to the store in one request, and returns the number of the last applied
+    /// block.
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError> {
+        let request = self.request(ApplyBlocksRequest {
+            blocks: blocks.iter().map(|block| self.apply_block_request(block)).collect(),
+        });
+
+        self.round_trips.record();
+        let response = self
+            .connection
+            .track(
+                self.tracer
+                    .trace::<ApplyBlocksRequest, _>(self.connection.client().apply_blocks(request)),
+            )
+            .await
+            .map_err(ApplyBlockError::from)?
+            .into_inner();
+
+        // The blocks before a rejected block are applied nevertheless.
+        self.response_cache.clear();
+        self.applied_block_num.record(response.last_applied_block_num);
+        for block in blocks
+            .iter()
+            .filter(|block| block.header().block_num() <= response.last_applied_block_num)
+        {
+            self.forget_recorded(block);
+        }
+
+        let last_applied_block_num = BlockNumber::from(response.last_applied_block_num);
+        if !response.error.is_empty() {
+            return Err(ApplyBlockError::BlockRejected {
+                last_applied_block_num,
+                reason: response.error,
+            });
+        }
+
+        Ok(last_applied_block_num)
+    }
+
+    /// Wraps `message` in a request carrying the current tenant as metadata, and the deadline of
+    /// its type if any.
+    fn request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        insert_tenant(&mut request, current_tenant(self.tenant.as_ref()));
+        self.timeouts.set_deadline(&mut request);
+
+        request
+    }
+
+    /// Returns the request applying `block`, with the recorded origins of its nullifiers and the
+    /// recorded versions of its accounts.
+    ///
+    /// Each distinct version read by the transactions of an account is sent, so the block is
+    /// rejected if any of the transactions read an outdated version.
+    fn apply_block_request(&self, block: &Block) -> ApplyBlockRequest {
+        let nullifier_origins = block
+            .nullifiers()
+            .iter()
+            .filter_map(|nullifier| {
+                self.nullifier_origins.get(nullifier).map(|transaction_id| NullifierOrigin {
+                    nullifier: Some(nullifier.into()),
+                    transaction_id: Some(transaction_id.into()),
+                })
+            })
+            .collect();
+
+        let account_versions = block
+            .transactions()
+            .filter_map(|(transaction_id, account_id)| {
+                self.account_versions.get(&transaction_id).map(|version| (account_id, version))
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|(account_id, version)| AccountVersion {
+                account_id: Some(account_id.into()),
+                version,
+            })
+            .collect();
+
+        ApplyBlockRequest {
+            block: block.to_bytes(),
+            nullifier_origins,
+            account_versions,
+        }
+    }
+
+    /// Drops the recorded origins of the nullifiers and versions of the accounts of `block`, once
+    /// the store applied it.
+    fn forget_recorded(&self, block: &Block) {
+        self.nullifier_origins.forget(block.nullifiers().iter().copied());
+        self.account_versions
+            .forget(block.transactions().map(|(transaction_id, _)| transaction_id));
+    }
+}
+
+#[async_trait]
+impl<C: StoreClient> ApplyBlock for BlockApplier<C> {
+    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        self.apply_block_with_diff(block).await.map(|_| ())
+    }
+}
+
+impl<C: StoreClient> DefaultStore<C> {
+    /// Same as [Store::get_tx_inputs], with the given priority instead of [Priority::High].
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    pub async fn get_tx_inputs_with_priority(
+        &self,
+        proven_tx: &ProvenTransaction,
+        priority: Priority,
+    ) -> Result<TransactionInputs, TxInputsError> {
+        let message = tx_inputs_request(proven_tx);
+
+        info!(target: COMPONENT, tx_id = %proven_tx.id().to_hex());
+        debug!(target: COMPONENT, ?message);
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_transaction_inputs(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        debug!(target: COMPONENT, ?response);
+
+        self.check_tx_inputs(proven_tx, response)
+    }
+
+    /// Returns the inputs of each of `txs`, in the order of `txs`, requesting the inputs of each
+    /// transaction separately with up to `max_in_flight` requests in flight at once.
+    ///
+    /// Unlike [Store::get_tx_inputs_batch], this doesn't need the batch request, so the latency
+    /// of the requests overlaps even with stores which don't support it. Each request is sent
+    /// through its own clone of the client, like all requests.
+    ///
+    /// # Errors
+    /// Returns the error of the first transaction whose inputs couldn't be obtained, in the order
+    /// of `txs`. The requests which are still in flight are then dropped.
+    pub async fn get_tx_inputs_concurrent(
+        &self,
+        txs: &[ProvenTransaction],
+        max_in_flight: NonZeroUsize,
+    ) -> Result<Vec<TransactionInputs>, TxInputsError> {
+        let requests = txs.iter().map(|proven_tx| self.get_tx_inputs(proven_tx));
+
+        // `buffered` yields the responses in the order of the requests, unlike `buffer_unordered`.
+        futures::StreamExt::buffered(futures::stream::iter(requests), max_in_flight.get())
+            .try_collect()
+            .await
+    }
+
+    /// Same as [Store::get_tx_inputs_batch], with the given priority instead of
+    /// [Priority::High].
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    pub async fn get_tx_inputs_batch_with_priority(
+        &self,
+        txs: &[ProvenTransaction],
+        priority: Priority,
+    ) -> Result<Vec<TransactionInputs>, TxInputsError> {
+        let message = GetTransactionInputsBatchRequest {
+            transactions: txs.iter().map(tx_inputs_request).collect(),
+        };
+
+        info!(target: COMPONENT, num_txs = txs.len());
+        debug!(target: COMPONENT, ?message);
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_transaction_inputs_batch(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        debug!(target: COMPONENT, ?response);
+
+        if response.transactions.len() != txs.len() {
+            return Err(TxInputsError::MalformedResponse(format!(
+                "incorrect number of transaction inputs returned from store. Got: {}, expected: {}",
+                response.transactions.len(),
+                txs.len()
+            )));
+        }
+
+        txs.iter()
+            .zip(response.transactions)
+            .map(|(proven_tx, response)| self.check_tx_inputs(proven_tx, response))
+            .collect()
+    }
+
+    /// Records the chain height reported by the store, if tracked, warning if it is lower than
+    /// the highest height reported before.
+    fn record_height(&self, height: u32) {
+        if !self.track_block_height {
+            return;
+        }
+
+        let mut last_seen = self.last_seen_height.lock().expect("last seen height lock poisoned");
+        if let Some(previous) = last_seen.filter(|previous| height < *previous) {
+            warn!(
+                target: COMPONENT,
+                height,
+                last_seen = previous,
+                "Store reported a lower chain height than before, it rewound or the read hit a \
+                 stale replica"
+            );
+            return;
+        }
+        *last_seen = Some(height);
+    }
+
+    /// Parses the inputs of `proven_tx` returned by the store, and checks that they belong to
+    /// its account and cover all of its nullifiers.
+    fn check_tx_inputs(
+        &self,
+        proven_tx: &ProvenTransaction,
+        response: GetTransactionInputsResponse,
+    ) -> Result<TransactionInputs, TxInputsError> {
+        let tx_inputs: TransactionInputs = response.try_into()?;
+
+        if tx_inputs.account_id != proven_tx.account_id() {
+            return Err(TxInputsError::MalformedResponse(format!(
+                "incorrect account id returned from store. Got: {}, expected: {}",
+                tx_inputs.account_id,
+                proven_tx.account_id()
+            )));
+        }
+
+        if self.strict_account_state {
+            check_account_state(proven_tx, &tx_inputs)?;
+        }
+
+        self.record_height(tx_inputs.current_block_height.into());
+
+        let requested: Vec<Nullifier> = proven_tx.get_nullifiers().collect();
+        let missing = missing_nullifiers(&requested, &tx_inputs.nullifiers);
+        if !missing.is_empty() {
+            return Err(TxInputsError::IncompleteNullifierResponse(missing));
+        }
+
+        debug!(target: COMPONENT, %tx_inputs);
+        if tx_inputs.is_partial() {
+            debug!(
+                target: COMPONENT,
+                account_id = %tx_inputs.account_id,
+                "Account is not in the store yet, only nullifiers are known"
+            );
+        }
+
+        if self.track_nullifier_origins {
+            self.applier
+                .nullifier_origins
+                .record(proven_tx.get_nullifiers().map(|nullifier| (nullifier, proven_tx.id())));
+        }
+
+        if let Some(version) = &tx_inputs.account_version {
+            if self.track_account_versions {
+                self.applier.account_versions.record([(proven_tx.id(), version.clone())]);
+            }
+        }
+
+        Ok(tx_inputs)
+    }
+
+    /// Same as [Store::get_block_inputs], with the given priority instead of [Priority::High].
+    ///
+    /// Accounts, nullifiers and notes passed several times, e.g. by several transactions of the
+    /// block, are deliberately requested only once. They are requested in ascending order, so
+    /// the same inputs always result in the same request.
+    pub async fn get_block_inputs_with_priority(
+        &self,
+        updated_accounts: impl Iterator<Item = AccountId> + Send,
+        produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
+        notes: impl Iterator<Item = &NoteId> + Send,
+        priority: Priority,
+    ) -> Result<BlockInputs, BlockInputsError> {
+        let updated_accounts: BTreeSet<_> = updated_accounts.collect();
+        let produced_nullifiers: BTreeSet<_> = produced_nullifiers.collect();
+        let (nullifiers, sharded_nullifiers) = match &self.shard_router {
+            // The witnesses of the nullifiers are requested from their shards instead.
+            Some(router) => (Vec::new(), router.route(produced_nullifiers)),
+            None => (
+                produced_nullifiers.into_iter().map(digest::Digest::from).collect(),
+                BTreeMap::new(),
+            ),
+        };
+        let message = GetBlockInputsRequest {
+            account_ids: updated_accounts.into_iter().map(Into::into).collect(),
+            nullifiers,
+            unauthenticated_notes: normalize_note_ids(notes)?
+                .iter()
+                .map(digest::Digest::from)
+                .collect(),
+        };
+
+        let chunks = match self.block_inputs_chunk_size {
+            Some(max_items) => split_block_inputs_request(message, max_items),
+            None => vec![message],
+        };
+
+        let _permit = self.acquire_slot(priority).await.map_err(BlockInputsError::from)?;
+        let (store_response, shard_nullifiers) = tokio::join!(
+            self.get_block_inputs_chunks(chunks, priority),
+            self.get_sharded_nullifier_inputs(sharded_nullifiers, priority),
+        );
+        let mut store_response = store_response?;
+        store_response
+            .nullifiers
+            .extend(shard_nullifiers.map_err(BlockInputsError::from)?);
+
+        store_response.try_into()
+    }
+
+    /// Same as [Store::get_note_authentication_info], with the given priority instead of
+    /// [Priority::Normal].
+    pub async fn get_note_authentication_info_with_priority(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+        priority: Priority,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        let notes = normalize_note_ids(notes)?;
+        if let Some(cache) = &self.note_auth_cache {
+            let cache = cache.lock().expect("note auth cache lock poisoned");
+            let info = cache.get(&current_tenant(self.tenant.as_ref()), &notes);
+            self.tracer.metrics().record_note_auth_cache(info.is_some());
+            if let Some(info) = info {
+                return Ok(info);
+            }
+        }
+
+        let message = GetNoteAuthenticationInfoRequest {
+            note_ids: notes.iter().map(digest::Digest::from).collect(),
+        };
+
+        let _permit = self.acquire_slot(priority).await.map_err(NotePathsError::from)?;
+        let store_response = self
+            .send_persistent_read(message, priority, |mut store, request| async move {
+                store.get_note_authentication_info(request).await
+            })
+            .await
+            .map_err(NotePathsError::from)?
+            .into_inner();
+
+        self.cache_note_authentication_info(store_response)
+    }
+
+    /// Returns the authentication info of the given notes, whose block inclusion proofs open
+    /// against the chain MMR with the given peaks, e.g. the chain a block is being built on.
+    ///
+    /// Info which doesn't open against the chain, e.g. because it was cached before a
+    /// concurrently built block was applied, is handled according to the
+    /// [RootConsistencyPolicy] of the store.
+    ///
+    /// # Errors
+    /// Returns [NotePathsError::RootMismatch] if the info doesn't open against the chain and the
+    /// policy doesn't allow using it, in addition to the errors of
+    /// [Store::get_note_authentication_info].
+    pub async fn get_note_authentication_info_at(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+        chain_peaks: &MmrPeaks,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        let notes = normalize_note_ids(notes)?;
+        let info = self
+            .get_note_authentication_info_with_priority(notes.iter(), Priority::Normal)
+            .await?;
+        let Some(block_num) = mismatched_block(&info, chain_peaks) else {
+            return Ok(info);
+        };
+
+        match self.root_consistency {
+            RootConsistencyPolicy::Reject => Err(NotePathsError::RootMismatch {
+                expected: chain_peaks.hash_peaks(),
+                block_num,
+            }),
+            RootConsistencyPolicy::WarnAndUse => {
+                warn!(
+                    target: COMPONENT,
+                    block_num,
+                    expected = %chain_peaks.hash_peaks(),
+                    "Using note authentication info which doesn't open against the chain root"
+                );
+                Ok(info)
+            },
+            RootConsistencyPolicy::Refetch => {
+                debug!(
+                    target: COMPONENT,
+                    block_num,
+                    "Note authentication info doesn't open against the chain root, refetching"
+                );
+                let info = self.refetch_note_authentication_info(&notes, Priority::Normal).await?;
+                match mismatched_block(&info, chain_peaks) {
+                    Some(block_num) => Err(NotePathsError::RootMismatch {
+                        expected: chain_peaks.hash_peaks(),
+                        block_num,
+                    }),
+                    None => Ok(info),
+                }
+            },
+        }
+    }
+
+    /// Same as [Store::notes_authenticated_since], with the given priority instead of
+    /// [Priority::Normal].
+    pub async fn notes_authenticated_since_with_priority(
+        &self,
+        notes: &[NoteId],
+        since: BlockNumber,
+        priority: Priority,
+    ) -> Result<Vec<NoteId>, NotePathsError> {
+        let message = GetNotesAuthenticatedSinceRequest {
+            note_ids: normalize_note_ids(notes)?.iter().map(digest::Digest::from).collect(),
+            since_block_num: since.into(),
+        };
+
+        let _permit = self.acquire_slot(priority).await.map_err(NotePathsError::from)?;
+        let response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_notes_authenticated_since(request).await
+            })
+            .await
+            .map_err(NotePathsError::from)?
+            .into_inner();
+
+        let note_ids = response
+            .note_ids
+            .iter()
+            .map(|note_id| Ok(RpoDigest::try_from(note_id)?.into()))
+            .collect::<Result<_, ConversionError>>()?;
+
+        Ok(note_ids)
+    }
+
+    /// Same as [Store::nullifier_origin], with the given priority instead of [Priority::Low].
+    pub async fn nullifier_origin_with_priority(
+        &self,
+        nullifier: Nullifier,
+        priority: Priority,
+    ) -> Result<Option<TransactionId>, TxInputsError> {
+        let message = GetNullifierOriginRequest { nullifier: Some(nullifier.into()) };
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let store_response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_nullifier_origin(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        Ok(store_response.transaction_id.map(TryInto::try_into).transpose()?)
+    }
+
+    /// Same as [Store::get_note_spend_block], with the given priority instead of
+    /// [Priority::Normal].
+    pub async fn get_note_spend_block_with_priority(
+        &self,
+        nullifier: Nullifier,
+        priority: Priority,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        let message = CheckNullifiersRequest { nullifiers: vec![nullifier.into()] };
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let store_response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.check_nullifiers(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        let [opening] = <[_; 1]>::try_from(store_response.proofs).map_err(|proofs| {
+            TxInputsError::MalformedResponse(format!(
+                "expected one nullifier proof, got {}",
+                proofs.len()
+            ))
+        })?;
+        // The value of the nullifier in the tree encodes the block which consumed it.
+        let value = SmtProof::try_from(opening)?
+            .get(&nullifier.inner())
+            .ok_or(TxInputsError::InvalidNullifierProof(nullifier))?;
+        if value == EMPTY_WORD {
+            return Ok(None);
+        }
+
+        // Values which don't encode a block number must not pass as unspent.
+        leaf_value_to_block_num(value)
+            .map(Some)
+            .map_err(|err| TxInputsError::NullifierSpendError {
+                nullifier,
+                reason: err.to_string(),
+            })
+    }
+
+    /// Same as [Store::get_pending_nullifiers], with the given priority instead of
+    /// [Priority::Normal].
+    pub async fn get_pending_nullifiers_with_priority(
+        &self,
+        priority: Priority,
+    ) -> Result<Vec<Nullifier>, TxInputsError> {
+        let message = GetPendingNullifiersRequest {};
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let store_response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_pending_nullifiers(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        let nullifiers = store_response
+            .nullifiers
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, ConversionError>>()?;
+
+        Ok(nullifiers)
+    }
+
+    /// Same as [Store::get_account_state], with the given priority instead of
+    /// [Priority::Normal].
+    pub async fn get_account_state_with_priority(
+        &self,
+        account_id: AccountId,
+        priority: Priority,
+    ) -> Result<AccountState, TxInputsError> {
+        let message = GetAccountStateRequest { account_id: Some(account_id.into()) };
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let store_response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_account_state(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        let account_state = store_response
+            .account_state
+            .ok_or(GetAccountStateResponse::missing_field(stringify!(account_state)))?
+            .try_into()?;
+
+        Ok(account_state)
+    }
+
+    /// Same as [Store::get_account_state_forks], with the given priority instead of
+    /// [Priority::Normal].
+    pub async fn get_account_state_forks_with_priority(
+        &self,
+        account_id: AccountId,
+        priority: Priority,
+    ) -> Result<Vec<(RpoDigest, RpoDigest)>, TxInputsError> {
+        let message = GetAccountStateForksRequest { account_id: Some(account_id.into()) };
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let store_response = self
+            .send_persistent_read(message, priority, |mut store, request| async move {
+                store.get_account_state_forks(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        let candidates = store_response
+            .candidates
+            .into_iter()
+            .map(|candidate| {
+                let account_hash = candidate
+                    .account_hash
+                    .ok_or(AccountStateCandidate::missing_field(stringify!(account_hash)))?
+                    .try_into()?;
+                let tip = candidate
+                    .tip
+                    .ok_or(AccountStateCandidate::missing_field(stringify!(tip)))?
+                    .try_into()?;
+
+                Ok((account_hash, tip))
+            })
+            .collect::<Result<_, ConversionError>>()?;
+
+        Ok(candidates)
+    }
+
+    /// Same as [Store::get_account_creation_block], with the given priority instead of
+    /// [Priority::Low].
+    pub async fn get_account_creation_block_with_priority(
+        &self,
+        account_id: AccountId,
+        priority: Priority,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        let message = GetAccountCreationBlockRequest { account_id: Some(account_id.into()) };
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let store_response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_account_creation_block(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        Ok(store_response.block_num.map(BlockNumber::from))
+    }
+
+    /// Same as [Store::get_nullifier_root_history], with the given priority instead of
+    /// [Priority::Low].
+    pub async fn get_nullifier_root_history_with_priority(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        priority: Priority,
+    ) -> Result<Vec<(BlockNumber, RpoDigest)>, TxInputsError> {
+        let message = GetNullifierRootHistoryRequest {
+            from_block_num: from.into(),
+            to_block_num: to.into(),
+        };
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let store_response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_nullifier_root_history(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        let roots = store_response
+            .roots
+            .into_iter()
+            .map(|record| {
+                let nullifier_root = record
+                    .nullifier_root
+                    .ok_or(NullifierRootRecord::missing_field(stringify!(nullifier_root)))?
+                    .try_into()?;
+
+                Ok((record.block_num.into(), nullifier_root))
+            })
+            .collect::<Result<_, ConversionError>>()?;
+
+        Ok(roots)
+    }
+
+    /// Same as [Store::get_chain_stats], with the given priority instead of [Priority::Low].
+    pub async fn get_chain_stats_with_priority(
+        &self,
+        priority: Priority,
+    ) -> Result<ChainStats, TxInputsError> {
+        let message = GetChainStatsRequest {};
+
+        let _permit = self.acquire_slot(priority).await.map_err(TxInputsError::from)?;
+        let store_response = self
+            .send_read(message, priority, |mut store, request| async move {
+                store.get_chain_stats(request).await
+            })
+            .await
+            .map_err(TxInputsError::from)?
+            .into_inner();
+
+        Ok(store_response.try_into()?)
+    }
+
+    // HELPERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Requests the authentication info of `notes` from the store, bypassing the response and
+    /// note authentication caches, and replaces the cached info of the notes.
+    async fn refetch_note_authentication_info(
+        &self,
+        notes: &[NoteId],
+        priority: Priority,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        let message = GetNoteAuthenticationInfoRequest {
+            note_ids: notes.iter().map(digest::Digest::from).collect(),
+        };
+
+        let _permit = self.acquire_slot(priority).await.map_err(NotePathsError::from)?;
+        let store_response = self
+            .send_hedged(message.clone(), priority, |mut store, request| async move {
+                store.get_note_authentication_info(request).await
+            })
+            .await
+            .map_err(NotePathsError::from)?;
+        #[cfg(feature = "persistent-cache")]
+        if let Some(cache) = &self.persistent_cache {
+            cache.insert(
+                current_tenant(self.tenant.as_ref()).as_ref(),
+                &message,
+                store_response.get_ref(),
+            );
+        }
+
+        self.cache_note_authentication_info(store_response.into_inner())
+    }
+
+    /// Decodes the note authentication info of `store_response`, and caches it if the note
+    /// authentication cache is enabled.
+    fn cache_note_authentication_info(
+        &self,
+        store_response: GetNoteAuthenticationInfoResponse,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        let note_authentication_info: NoteAuthenticationInfo = store_response
+            .proofs
+            .ok_or(GetTransactionInputsResponse::missing_field("proofs"))?
+            .try_into()?;
+
+        if let Some(cache) = &self.note_auth_cache {
+            cache
+                .lock()
+                .expect("note auth cache lock poisoned")
+                .insert(current_tenant(self.tenant.as_ref()), &note_authentication_info);
+        }
+
+        Ok(note_authentication_info)
+    }
+
+    /// Sends the read request `message` with `send`, unless the response to an identical request
+    /// is cached.
+    async fn send_read<M, R, Fut>(
+        &self,
+        message: M,
+        priority: Priority,
+        send: impl Fn(C, tonic::Request<M>) -> Fut,
+    ) -> Result<tonic::Response<R>, Status>
+    where
+        M: Clone + prost::Message,
+        R: Clone + Send + Sync + 'static,
+        Fut: Future<Output = Result<tonic::Response<R>, Status>>,
+    {
+        let tenant = current_tenant(self.tenant.as_ref());
+        let cached = self.response_cache.get(tenant.clone(), &message, self.clock.now());
+        if self.response_cache.is_enabled() {
+            self.tracer.metrics().record_response_cache(cached.is_ok());
+        }
+        let generation = match cached {
+            Ok(response) => return Ok(tonic::Response::new(response)),
+            Err(generation) => generation,
+        };
+
+        let response = self.send_hedged(message.clone(), priority, send).await?;
+        self.response_cache.insert(
+            tenant,
+            &message,
+            response.get_ref().clone(),
+            generation,
+            self.clock.now(),
+        );
+
+        Ok(response)
+    }
+
+    /// Clears the caches which may hold responses from before a block was applied.
+    ///
+    /// The response cache is cleared by the applier itself, as blocks may also be applied by the
+    /// retry task of the apply queue.
+    fn clear_caches(&self) {
+        if let Some(cache) = &self.note_auth_cache {
+            cache.lock().expect("note auth cache lock poisoned").clear();
+        }
+        #[cfg(feature = "persistent-cache")]
+        if let Some(cache) = &self.persistent_cache {
+            cache.clear();
+        }
+    }
+
+    /// Same as [Self::send_read], but answers the request from the persistent cache if enabled,
+    /// and persists the response otherwise.
+    async fn send_persistent_read<M, R, Fut>(
+        &self,
+        message: M,
+        priority: Priority,
+        send: impl Fn(C, tonic::Request<M>) -> Fut,
+    ) -> Result<tonic::Response<R>, Status>
+    where
+        M: Clone + prost::Message,
+        R: Clone + Default + prost::Message + 'static,
+        Fut: Future<Output = Result<tonic::Response<R>, Status>>,
+    {
+        #[cfg(feature = "persistent-cache")]
+        if let Some(response) = self
+            .persistent_cache
+            .as_ref()
+            .and_then(|cache| cache.get(current_tenant(self.tenant.as_ref()).as_ref(), &message))
+        {
+            return Ok(tonic::Response::new(response));
+        }
+
+        let response = self.send_read(message.clone(), priority, send).await?;
+        #[cfg(feature = "persistent-cache")]
+        if let Some(cache) = &self.persistent_cache {
+            cache.insert(
+                current_tenant(self.tenant.as_ref()).as_ref(),
+                &message,
+                response.get_ref(),
+            );
+        }
+
+        Ok(response)
+    }
+
+    /// Sends the read request `message` with `send`, hedging it if enabled, and reclassifies its
+    /// failure according to the [RetryPolicy] of the store.
+    async fn send_hedged<M, R, Fut>(
+        &self,
+        message: M,
+        priority: Priority,
+        send: impl Fn(C, tonic::Request<M>) -> Fut,
+    ) -> Result<tonic::Response<R>, Status>
+    where
+        M: Clone,
+        Fut: Future<Output = Result<tonic::Response<R>, Status>>,
+    {
+        self.send_maybe_hedged(message, priority, send)
+            .await
+            .map_err(|status| self.retry_policy.reclassify(status))
+    }
+
+    /// Sends the read request `message` with `send`, hedging it if enabled.
+    ///
+    /// All reads are idempotent, so sending a read twice and dropping one of the requests is
+    /// safe.
+    async fn send_maybe_hedged<M, R, Fut>(
+        &self,
+        message: M,
+        priority: Priority,
+        send: impl Fn(C, tonic::Request<M>) -> Fut,
+    ) -> Result<tonic::Response<R>, Status>
+    where
+        M: Clone,
+        Fut: Future<Output = Result<tonic::Response<R>, Status>>,
+    {
+        self.round_trips.record();
+        let primary = self.connection.track(self.tracer.trace::<M, _>(send(
+            self.connection.client(),
+            self.request(message.clone(), priority),
+        )));
+        let Some(delay) = self.hedge_delay else {
+            return primary.await;
+        };
+
+        let mut primary = pin!(primary);
+        tokio::select! {
+            response = &mut primary => return response,
+            () = self.clock.sleep(delay) => {},
+        }
+
+        debug!(target: COMPONENT, ?delay, "Store read is slow, sending hedged request");
+        self.round_trips.record();
+        let replica = self.replica.clone().unwrap_or_else(|| self.connection.client());
+        let hedged = self.tracer.trace::<M, _>(send(replica, self.request(message, priority)));
+
+        // The request which loses the race is dropped, which cancels it.
+        tokio::select! {
+            response = primary => response,
+            response = hedged => response,
+        }
+    }
+
+    /// Returns the number of the latest block of the store, from the header returned with the
+    /// block inputs of no accounts, nullifiers and notes.
+    ///
+    /// The request bypasses the response cache and the concurrency limit.
+    async fn fetch_chain_tip<E>(&self) -> Result<BlockNumber, E>
+    where
+        E: From<Status> + From<ConversionError>,
+    {
+        let store_response = self
+            .send_hedged(
+                GetBlockInputsRequest::default(),
+                Priority::High,
+                |mut store, request| async move { store.get_block_inputs(request).await },
+            )
+            .await?
+            .into_inner();
+
+        Ok(store_response
+            .block_header
+            .ok_or(GetBlockInputsResponse::missing_field(stringify!(block_header)))?
+            .block_num
+            .into())
+    }
+
+    /// Wraps `message` in a request carrying `priority`, the current tenant and, with
+    /// read-your-writes consistency, the last applied block as metadata, and the deadline of its
+    /// type if any.
+    fn request<T>(&self, message: T, priority: Priority) -> tonic::Request<T> {
+        let mut request = prioritized_request(message, priority);
+        insert_tenant(&mut request, current_tenant(self.tenant.as_ref()));
+        if self.read_your_writes {
+            self.applied_block_num.insert_into(&mut request);
+        }
+        self.timeouts.set_deadline(&mut request);
+
+        request
+    }
+
+    /// Sends the chunks of a block inputs request one after the other, and merges their
+    /// responses.
+    async fn get_block_inputs_chunks(
+        &self,
+        chunks: Vec<GetBlockInputsRequest>,
+        priority: Priority,
+    ) -> Result<GetBlockInputsResponse, BlockInputsError> {
+        let mut responses = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            let response = self
+                .send_read(chunk, priority, |mut store, request| async move {
+                    store.get_block_inputs(request).await
+                })
+                .await?;
+            responses.push(response.into_inner());
+        }
+
+        merge_block_inputs_responses(responses)
+    }
+
+    /// Requests the block inputs of the nullifiers of each shard from the store of the shard, and
+    /// returns the witnesses of all nullifiers.
+    ///
+    /// The requests to the shards are sent concurrently. If any of them fails, the others are
+    /// cancelled.
+    async fn get_sharded_nullifier_inputs(
+        &self,
+        sharded_nullifiers: BTreeMap<usize, Vec<Nullifier>>,
+        priority: Priority,
+    ) -> Result<Vec<NullifierBlockInputRecord>, Status> {
+        let Some(router) = &self.shard_router else {
+            return Ok(Vec::new());
+        };
+
+        let mut requests = JoinSet::new();
+        for (shard, nullifiers) in sharded_nullifiers {
+            let mut client =
+                router.shard(shard).expect("nullifiers are routed to existing shards").clone();
+            let message = GetBlockInputsRequest {
+                nullifiers: nullifiers.iter().map(digest::Digest::from).collect(),
+                ..Default::default()
+            };
+            let request = self.request(message, priority);
+            self.round_trips.record();
+            let tracer = self.tracer.clone();
+            requests.spawn(async move {
+                tracer.trace::<GetBlockInputsRequest, _>(client.get_block_inputs(request)).await
+            });
+        }
+
+        let mut records = Vec::new();
+        while let Some(response) = requests.join_next().await {
+            let response = response.map_err(|err| Status::internal(err.to_string()))??;
+            records.extend(response.into_inner().nullifiers);
+        }
+
+        Ok(records)
+    }
+
+    /// Waits for a free request slot if the number of concurrent requests is limited.
+    ///
+    /// Returns an error if no slot became free within the queue timeout, as measured by the clock
+    /// of the store.
+    async fn acquire_slot(&self, priority: Priority) -> Result<Option<Permit>, Status> {
+        let Some(limiter) = &self.limiter else {
+            return Ok(None);
+        };
+        let Some(timeout) = self.queue_timeout else {
+            return Ok(Some(limiter.acquire(priority).await));
+        };
+
+        // The request stops waiting once the timeout elapsed, which gives up its place in the
+        // queue of the limiter.
+        tokio::select! {
+            biased;
+            permit = limiter.acquire(priority) => Ok(Some(permit)),
+            () = self.clock.sleep(timeout) => Err(Status::deadline_exceeded(format!(
+                "request waited for a request slot for longer than the timeout of {timeout:?}"
+            ))),
+        }
+    }
+}
+
+/// Returns the number of the block following block `chain_tip`.
+///
+/// # Errors
+/// Returns [TxInputsError::BlockNumberOverflow] if `chain_tip` is the largest block number.
+fn next_block_number_after(chain_tip: BlockNumber) -> Result<BlockNumber, TxInputsError> {
+    chain_tip.next().ok_or(TxInputsError::BlockNumberOverflow(chain_tip))
+}
+
+/// Returns the request for the inputs of `proven_tx`.
+fn tx_inputs_request(proven_tx: &ProvenTransaction) -> GetTransactionInputsRequest {
+    GetTransactionInputsRequest {
+        account_id: Some(proven_tx.account_id().into()),
+        nullifiers: proven_tx.get_nullifiers().map(Into::into).collect(),
+        unauthenticated_notes: proven_tx
+            .get_unauthenticated_notes()
+            .map(|note| note.id().into())
+            .collect(),
+    }
+}
+
+/// Wraps `message` in a request carrying `priority` as metadata.
+fn prioritized_request<T>(message: T, priority: Priority) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    request
+        .metadata_mut()
+        .insert(Priority::METADATA_KEY, MetadataValue::from_static(priority.as_str()));
+
+    request
+}
+
+#[async_trait]
+impl<C: StoreClient> Store for DefaultStore<C> {
+    async fn get_tx_inputs(
+        &self,
+        proven_tx: &ProvenTransaction,
+    ) -> Result<TransactionInputs, TxInputsError> {
+        self.get_tx_inputs_with_priority(proven_tx, Priority::High).await
+    }
+
+    async fn get_tx_inputs_batch(
+        &self,
+        txs: &[ProvenTransaction],
+    ) -> Result<Vec<TransactionInputs>, TxInputsError> {
+        self.get_tx_inputs_batch_with_priority(txs, Priority::High).await
+    }
+
+    async fn get_block_inputs(
+        &self,
+        updated_accounts: impl Iterator<Item = AccountId> + Send,
+        produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<BlockInputs, BlockInputsError> {
+        self.get_block_inputs_with_priority(
+            updated_accounts,
+            produced_nullifiers,
+            notes,
+            Priority::High,
+        )
+        .await
+    }
+
+    async fn get_note_authentication_info(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        self.get_note_authentication_info_with_priority(notes, Priority::Normal).await
+    }
+
+    async fn notes_authenticated_since(
+        &self,
+        notes: &[NoteId],
+        since: BlockNumber,
+    ) -> Result<Vec<NoteId>, NotePathsError> {
+        self.notes_authenticated_since_with_priority(notes, since, Priority::Normal)
+            .await
+    }
+
+    async fn nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, TxInputsError> {
+        self.nullifier_origin_with_priority(nullifier, Priority::Low).await
+    }
+
+    async fn get_note_spend_block(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.get_note_spend_block_with_priority(nullifier, Priority::Normal).await
+    }
+
+    async fn get_pending_nullifiers(&self) -> Result<Vec<Nullifier>, TxInputsError> {
+        self.get_pending_nullifiers_with_priority(Priority::Normal).await
+    }
+
+    async fn get_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountState, TxInputsError> {
+        self.get_account_state_with_priority(account_id, Priority::Normal).await
+    }
+
+    async fn get_account_state_forks(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(RpoDigest, RpoDigest)>, TxInputsError> {
+        self.get_account_state_forks_with_priority(account_id, Priority::Normal).await
+    }
+
+    async fn get_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.get_account_creation_block_with_priority(account_id, Priority::Low).await
+    }
+
+    async fn get_nullifier_root_history(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, RpoDigest)>, TxInputsError> {
+        self.get_nullifier_root_history_with_priority(from, to, Priority::Low).await
+    }
+
+    async fn get_chain_stats(&self) -> Result<ChainStats, TxInputsError> {
+        self.get_chain_stats_with_priority(Priority::Low).await
+    }
+
+    /// Bypasses the response cache and the concurrency limit, so the check reflects the store
+    /// itself. The store doesn't accept writes while blocks wait in the apply queue.
+    async fn check_health(&self) -> Result<StoreStatus, BlockInputsError> {
+        Ok(StoreStatus {
+            chain_tip: self.fetch_chain_tip::<BlockInputsError>().await?,
+            accepting_writes: Some(self.pending_applies().await == 0),
+        })
+    }
+
+    /// Only requests the header of the chain tip, instead of the chain statistics.
+    async fn next_block_number(&self) -> Result<BlockNumber, TxInputsError> {
+        next_block_number_after(self.fetch_chain_tip::<TxInputsError>().await?)
+    }
+
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    async fn apply_block_with_diff(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError> {
+        // The diff is only known once the store applied the block, so the block is never queued.
+        // Blocks already in the queue are applied first, to keep blocks in order.
+        self.flush().await?;
+        let updated_accounts = self.applier.apply_block_with_diff(block).await?;
+        self.clear_caches();
+
+        Ok(updated_accounts)
+    }
+
+    #[instrument(target = "miden-block-producer", skip_all, err)]
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError> {
+        // Blocks already in the queue are applied first, to keep blocks in order.
+        self.flush().await?;
+        let result = self.applier.apply_blocks(blocks).await;
+
+        // Some of the blocks may have been applied even if the request failed.
+        self.clear_caches();
+
+        result
+    }
+
+    fn sync_blocks_from(
+        &self,
+        from: BlockNumber,
+    ) -> impl Stream<Item = Result<Block, TxInputsError>> + Send + 'static {
+        let store = self.connection.client();
+        let tracer = self.tracer.clone();
+        let connection = self.connection.clone();
+        let tenant = current_tenant(self.tenant.as_ref());
+        let (sender, receiver) = mpsc::channel(SYNC_BLOCKS_BUFFER_SIZE);
+        self.round_trips.record();
+
+        tokio::spawn(async move {
+            if let Err(err) =
+                forward_blocks(store, &tracer, &connection, tenant, from, &sender).await
+            {
+                let _ = sender.send(Err(err)).await;
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
+}
+
+/// Receives the blocks from block `from` up to the chain tip from the store, and forwards them to
+/// `sender` until the store or the receiver ends the stream.
+async fn forward_blocks<C: StoreClient>(
+    mut store: C,
+    tracer: &CallTracer,
+    connection: &ConnectionMonitor<C>,
+    tenant: Option<AsciiMetadataValue>,
+    from: BlockNumber,
+    sender: &mpsc::Sender<Result<Block, TxInputsError>>,
+) -> Result<(), TxInputsError> {
+    let mut request = tonic::Request::new(SyncBlocksRequest { from_block_num: from.into() });
+    insert_tenant(&mut request, tenant);
+    let blocks = connection
+        .track(tracer.trace::<SyncBlocksRequest, _>(store.sync_blocks(request)))
+        .await
+        .map_err(TxInputsError::from)?
+        .into_inner();
+    let mut blocks = pin!(blocks);
+
+    while let Some(response) = blocks.next().await.transpose().map_err(TxInputsError::from)? {
+        let block = Block::read_from_bytes(&response.block)
+            .map_err(|err| TxInputsError::MalformedResponse(err.to_string()))?;
+
+        if sender.send(Ok(block)).await.is_err() {
+            break;
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,117 @@
+use std::{
+    any::type_name,
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use prost::Message;
+use rusqlite::{params, Connection, OptionalExtension};
+use tonic::metadata::AsciiMetadataValue;
+use tracing::warn;
+
+use crate::COMPONENT;
+
+// PERSISTENT CACHE
+// ================================================================================================
+
+/// Read-through cache of store responses, persisted in a local SQLite database so it survives a
+/// restart of the block producer.
+///
+/// Responses are keyed by the tenant, the type and the encoded contents of their request, like the
+/// in-memory response cache, so responses are never served to another tenant. The cache is cleared
+/// whenever a block is applied through the [DefaultStore](super::DefaultStore) using it, so it must
+/// not be shared with block producers applying blocks to other stores.
+///
+/// Failures to read or write the database are logged and treated as cache misses, so they never
+/// fail a request.
+#[derive(Clone)]
+pub struct PersistentCache {
+    db: Arc<Mutex<Connection>>,
+}
+
+impl PersistentCache {
+    /// Opens the cache persisted in the database at `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let db = Connection::open(path)?;
+        db.execute(
+            "CREATE TABLE IF NOT EXISTS responses (
+                tenant BLOB NOT NULL,
+                request_type TEXT NOT NULL,
+                request BLOB NOT NULL,
+                response BLOB NOT NULL,
+                PRIMARY KEY (tenant, request_type, request)
+            )",
+            [],
+        )?;
+
+        Ok(Self { db: Arc::new(Mutex::new(db)) })
+    }
+
+    /// Returns the cached response to `request` of `tenant`, if any.
+    pub fn get<M: Message, R: Message + Default>(
+        &self,
+        tenant: Option<&AsciiMetadataValue>,
+        request: &M,
+    ) -> Option<R> {
+        let response: Option<Vec<u8>> = self
+            .db()
+            .query_row(
+                "SELECT response FROM responses
+                    WHERE tenant = ?1 AND request_type = ?2 AND request = ?3",
+                params![tenant_key(tenant), type_name::<M>(), request.encode_to_vec()],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap_or_else(|err| {
+                warn!(target: COMPONENT, %err, "Failed to read from the persistent cache");
+                None
+            });
+
+        R::decode(response?.as_slice())
+            .inspect_err(|err| {
+                warn!(target: COMPONENT, %err, "Failed to decode a persistently cached response")
+            })
+            .ok()
+    }
+
+    /// Caches `response` to `request` of `tenant`, replacing any previously cached response.
+    pub fn insert<M: Message, R: Message>(
+        &self,
+        tenant: Option<&AsciiMetadataValue>,
+        request: &M,
+        response: &R,
+    ) {
+        let result = self.db().execute(
+            "INSERT OR REPLACE INTO responses (tenant, request_type, request, response)
+                VALUES (?1, ?2, ?3, ?4)",
+            params![
+                tenant_key(tenant),
+                type_name::<M>(),
+                request.encode_to_vec(),
+                response.encode_to_vec()
+            ],
+        );
+        if let Err(err) = result {
+            warn!(target: COMPONENT, %err, "Failed to write to the persistent cache");
+        }
+    }
+
+    /// Removes all cached responses.
+    pub fn clear(&self) {
+        if let Err(err) = self.db().execute("DELETE FROM responses", []) {
+            warn!(target: COMPONENT, %err, "Failed to clear the persistent cache");
+        }
+    }
+
+    fn db(&self) -> MutexGuard<'_, Connection> {
+        self.db.lock().expect("persistent cache lock poisoned")
+    }
+}
+
+/// Returns the key of `tenant` in the database, which is empty for requests without a tenant.
+///
+/// Metadata values never contain a NUL byte, so the key of a tenant is prefixed with one to keep
+/// it apart from the key of requests without a tenant.
+fn tenant_key(tenant: Option<&AsciiMetadataValue>) -> Vec<u8> {
+    tenant.map_or_else(Vec::new, |tenant| [b"\0", tenant.as_bytes()].concat())
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use miden_node_proto::domain::{blocks::BlockNumber, nullifiers::block_num_to_leaf_value};
+use miden_objects::{
+    block::Block,
+    crypto::merkle::{DefaultMerkleStore, LeafIndex, NodeIndex, Smt, SMT_DEPTH},
+    Digest, ACCOUNT_TREE_DEPTH,
+};
+
+use crate::{
+    block::BlockInputs,
+    errors::{ApplyBlockError, BlockRoot},
+};
+
+// BLOCK PRECHECK
+// ================================================================================================
+
+/// Recomputes the account and nullifier roots after `block` from its contents and the witnesses
+/// of the store state before it, and checks them against the roots declared in its header.
+///
+/// # Errors
+/// Returns [ApplyBlockError::RootMismatch] for the first root which differs, and
+/// [ApplyBlockError::InvalidStoreWitness] if `inputs` lack a witness required by the block, or
+/// the witnesses don't match the roots of the store.
+pub(super) fn check_block_roots(
+    block: &Block,
+    inputs: &BlockInputs,
+) -> Result<(), ApplyBlockError> {
+    let header = block.header();
+
+    let account_root = compute_account_root(block, inputs)?;
+    if account_root != header.account_root() {
+        return Err(ApplyBlockError::RootMismatch {
+            which: BlockRoot::Account,
+            expected: header.account_root(),
+            got: account_root,
+        });
+    }
+
+    let nullifier_root = compute_nullifier_root(block, inputs)?;
+    if nullifier_root != header.nullifier_root() {
+        return Err(ApplyBlockError::RootMismatch {
+            which: BlockRoot::Nullifier,
+            expected: header.nullifier_root(),
+            got: nullifier_root,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the account root after updating the accounts of `block`.
+fn compute_account_root(block: &Block, inputs: &BlockInputs) -> Result<Digest, ApplyBlockError> {
+    let mut store = DefaultMerkleStore::default();
+    let mut leaves = Vec::new();
+    for update in block.updated_accounts() {
+        let witness = inputs.accounts.get(&update.account_id()).ok_or_else(|| {
+            ApplyBlockError::InvalidStoreWitness(format!(
+                "missing witness of account {}",
+                update.account_id()
+            ))
+        })?;
+        let index = u64::from(update.account_id());
+        store
+            .add_merkle_path(index, witness.hash, witness.proof.clone())
+            .map_err(|err| ApplyBlockError::InvalidStoreWitness(err.to_string()))?;
+        leaves.push((index, update.new_state_hash()));
+    }
+
+    let mut root = inputs.block_header.account_root();
+    for (index, hash) in leaves {
+        root = NodeIndex::new(ACCOUNT_TREE_DEPTH, index)
+            .and_then(|node| store.set_node(root, node, hash))
+            .map_err(|err| ApplyBlockError::InvalidStoreWitness(err.to_string()))?
+            .root;
+    }
+
+    Ok(root)
+}
+
+/// Returns the nullifier root after consuming the nullifiers of `block`.
+fn compute_nullifier_root(block: &Block, inputs: &BlockInputs) -> Result<Digest, ApplyBlockError> {
+    let prior_root = inputs.block_header.nullifier_root();
+    let mut store = DefaultMerkleStore::default();
+    // Leaves touched by the block, used to compute the updated leaf hashes
+    let mut leaves = Smt::new();
+    // Maps leaf index -> any nullifier in that leaf, for all touched leaves
+    let mut touched = BTreeMap::new();
+    for nullifier in block.nullifiers() {
+        let proof = inputs.nullifiers.get(nullifier).ok_or_else(|| {
+            ApplyBlockError::InvalidStoreWitness(format!(
+                "missing witness of nullifier {nullifier}"
+            ))
+        })?;
+        let leaf_index = LeafIndex::<SMT_DEPTH>::from(nullifier.inner());
+        if proof.leaf().index() != leaf_index || proof.compute_root() != prior_root {
+            return Err(ApplyBlockError::InvalidStoreWitness(format!(
+                "invalid witness of nullifier {nullifier}"
+            )));
+        }
+
+        store
+            .add_merkle_path(leaf_index.value(), proof.leaf().hash(), proof.path().clone())
+            .map_err(|err| ApplyBlockError::InvalidStoreWitness(err.to_string()))?;
+        for (key, value) in proof.leaf().entries() {
+            leaves.insert(*key, *value);
+        }
+        touched.insert(leaf_index.value(), nullifier.inner());
+    }
+
+    let new_value = block_num_to_leaf_value(BlockNumber::from(block.header().block_num()));
+    for nullifier in block.nullifiers() {
+        if leaves.get_value(&nullifier.inner()) != Smt::EMPTY_VALUE {
+            return Err(ApplyBlockError::InvalidStoreWitness(format!(
+                "nullifier {nullifier} is already consumed"
+            )));
+        }
+        leaves.insert(nullifier.inner(), new_value);
+    }
+
+    let mut root = prior_root;
+    for (leaf_index, key) in touched {
+        root = NodeIndex::new(SMT_DEPTH, leaf_index)
+            .and_then(|node| store.set_node(root, node, leaves.get_leaf(&key).hash()))
+            .map_err(|err| ApplyBlockError::InvalidStoreWitness(err.to_string()))?
+            .root;
+    }
+
+    Ok(root)
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use miden_objects::{
+    accounts::AccountId,
+    crypto::merkle::{LeafIndex, PartialMerkleTree, SmtLeaf, SMT_DEPTH},
+    BlockHeader, Digest,
+};
+
+use crate::{block::BlockInputs, errors::BlockInputsError};
+
+// MINIMAL PROOF SET
+// ================================================================================================
+
+/// The proofs needed to verify the accounts and nullifiers of a set of transactions against a
+/// block header, returned by [Store::get_minimal_proof_set](super::Store::get_minimal_proof_set).
+///
+/// Each account and each leaf of the nullifier tree is included once, and the paths of the
+/// accounts and of the nullifier leaves are merged into partial trees, so nodes shared by several
+/// paths are included once too.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinimalProofSet {
+    /// Header of the block whose roots the proofs verify against
+    pub block_header: BlockHeader,
+    /// Hashes of the accounts of the transactions
+    pub account_hashes: BTreeMap<AccountId, Digest>,
+    /// Paths of the accounts in the account tree
+    pub account_paths: PartialMerkleTree,
+    /// Leaves of the nullifier tree holding the nullifiers of the transactions, by leaf index
+    pub nullifier_leaves: BTreeMap<u64, SmtLeaf>,
+    /// Paths of the leaves in the nullifier tree
+    pub nullifier_paths: PartialMerkleTree,
+}
+
+impl MinimalProofSet {
+    /// Returns the proof set built from the witnesses of `inputs`.
+    ///
+    /// # Errors
+    /// Returns an error if the witnesses don't share the same roots.
+    pub fn from_block_inputs(inputs: BlockInputs) -> Result<Self, BlockInputsError> {
+        let mut account_hashes = BTreeMap::new();
+        let mut account_paths = PartialMerkleTree::new();
+        for (account_id, witness) in inputs.accounts {
+            account_paths.add_path(account_id.into(), witness.hash, witness.proof)?;
+            account_hashes.insert(account_id, witness.hash);
+        }
+
+        let mut nullifier_leaves = BTreeMap::new();
+        let mut nullifier_paths = PartialMerkleTree::new();
+        for (nullifier, proof) in inputs.nullifiers {
+            // Nullifiers in the same leaf share its opening.
+            let leaf_index = LeafIndex::<SMT_DEPTH>::from(nullifier.inner()).value();
+            if nullifier_leaves.contains_key(&leaf_index) {
+                continue;
+            }
+
+            nullifier_paths.add_path(leaf_index, proof.leaf().hash(), proof.path().clone())?;
+            nullifier_leaves.insert(leaf_index, proof.leaf().clone());
+        }
+
+        Ok(Self {
+            block_header: inputs.block_header,
+            account_hashes,
+            account_paths,
+            nullifier_leaves,
+            nullifier_paths,
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use tonic::metadata::AsciiMetadataValue;
+
+// READ YOUR WRITES
+// ================================================================================================
+
+/// The gRPC metadata key under which the minimum block number of a read is sent to the store.
+pub const MIN_BLOCK_NUM_METADATA_KEY: &str = "x-min-block-num";
+
+/// Number of the last block applied through a [DefaultStore](super::DefaultStore), shared
+/// between the store and its applier.
+///
+/// A load-balanced store may serve a read from a replica which didn't apply the latest block
+/// yet. Reads carrying the number as [MIN_BLOCK_NUM_METADATA_KEY] metadata are meant to be routed
+/// to a replica which applied at least that block, or to wait until one did.
+#[derive(Clone, Debug, Default)]
+pub(super) struct AppliedBlockNum(Arc<AtomicU32>);
+
+impl AppliedBlockNum {
+    /// Records that block `block_num` was applied.
+    pub(super) fn record(&self, block_num: u32) {
+        self.0.fetch_max(block_num, Ordering::SeqCst);
+    }
+
+    /// Returns the number of the last applied block, or `None` if no block was applied yet.
+    ///
+    /// The genesis block is never applied through a store, so zero means no block.
+    pub(super) fn get(&self) -> Option<u32> {
+        Some(self.0.load(Ordering::SeqCst)).filter(|block_num| *block_num != 0)
+    }
+
+    /// Adds the number of the last applied block to the metadata of `request`, if any.
+    pub(super) fn insert_into<T>(&self, request: &mut tonic::Request<T>) {
+        if let Some(block_num) = self.get() {
+            request
+                .metadata_mut()
+                .insert(MIN_BLOCK_NUM_METADATA_KEY, AsciiMetadataValue::from(block_num));
+        }
+    }
+}
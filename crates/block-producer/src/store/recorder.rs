@@ -0,0 +1,149 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// The default maximum number of entries kept by a [Recorder].
+pub const DEFAULT_MAX_RECORDED_ENTRIES: usize = 16_384;
+
+// RECORDER
+// ================================================================================================
+
+/// Bounded record of data read with the transaction inputs which is sent to the store together
+/// with the block containing the transaction, e.g. the origins of nullifiers.
+///
+/// Entries are removed once their block is applied. Transactions whose inputs are read but which
+/// never make it into a block are not removed this way, so the oldest entries are evicted once
+/// the record holds its maximum number of entries.
+///
+/// The record is shared between a [DefaultStore](super::DefaultStore) and its block applier. The
+/// `InMemoryStore` records the origins of nullifiers the same way.
+#[derive(Clone, Debug)]
+pub(super) struct Recorder<K, V> {
+    inner: Arc<Mutex<RecorderState<K, V>>>,
+}
+
+#[derive(Debug)]
+struct RecorderState<K, V> {
+    /// Recorded values, together with the sequence number of their insertion
+    entries: BTreeMap<K, (u64, V)>,
+    /// Recorded keys by sequence number, from the oldest to the newest
+    insertion_order: BTreeMap<u64, K>,
+    /// Sequence number of the next insertion
+    next_seq: u64,
+    max_entries: usize,
+}
+
+impl<K: Clone + Ord, V: Clone> Recorder<K, V> {
+    /// Sets the maximum number of recorded entries, evicting the oldest entries beyond it.
+    pub fn set_max_entries(&self, max_entries: usize) {
+        let mut state = self.state();
+        state.max_entries = max_entries;
+        state.evict();
+    }
+
+    /// Returns the number of recorded entries.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// Records the given entries, replacing the values recorded for the same keys.
+    pub fn record(&self, entries: impl IntoIterator<Item = (K, V)>) {
+        let mut state = self.state();
+        for (key, value) in entries {
+            state.remove(&key);
+
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.insertion_order.insert(seq, key.clone());
+            state.entries.insert(key, (seq, value));
+        }
+        state.evict();
+    }
+
+    /// Returns the value recorded for `key`, if any.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.state().entries.get(key).map(|(_, value)| value.clone())
+    }
+
+    /// Removes the entries recorded for the given keys.
+    pub fn forget(&self, keys: impl IntoIterator<Item = K>) {
+        let mut state = self.state();
+        for key in keys {
+            state.remove(&key);
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, RecorderState<K, V>> {
+        self.inner.lock().expect("recorder lock poisoned")
+    }
+}
+
+impl<K, V> Default for Recorder<K, V> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(RecorderState {
+                entries: BTreeMap::new(),
+                insertion_order: BTreeMap::new(),
+                next_seq: 0,
+                max_entries: DEFAULT_MAX_RECORDED_ENTRIES,
+            })),
+        }
+    }
+}
+
+impl<K: Ord, V> RecorderState<K, V> {
+    fn remove(&mut self, key: &K) {
+        if let Some((seq, _)) = self.entries.remove(key) {
+            self.insertion_order.remove(&seq);
+        }
+    }
+
+    /// Evicts the oldest entries until the maximum number of entries is respected.
+    fn evict(&mut self) {
+        while self.entries.len() > self.max_entries {
+            let Some((_, key)) = self.insertion_order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_entries_are_evicted() {
+        let recorder = Recorder::default();
+        recorder.set_max_entries(2);
+
+        recorder.record([(1, 'a'), (2, 'b')]);
+        recorder.record([(1, 'c')]);
+        recorder.record([(3, 'd')]);
+
+        assert_eq!(recorder.len(), 2);
+        assert_eq!(recorder.get(&1), Some('c'));
+        assert_eq!(recorder.get(&2), None);
+        assert_eq!(recorder.get(&3), Some('d'));
+    }
+
+    #[test]
+    fn forgotten_entries_are_not_evicted_again() {
+        let recorder = Recorder::default();
+        recorder.set_max_entries(2);
+
+        recorder.record([(1, 'a'), (2, 'b')]);
+        recorder.forget([1]);
+        recorder.record([(3, 'c')]);
+
+        assert_eq!(recorder.len(), 2);
+        assert_eq!(recorder.get(&2), Some('b'));
+        assert_eq!(recorder.get(&3), Some('c'));
+    }
+}
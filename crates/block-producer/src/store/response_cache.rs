@@ -0,0 +1,115 @@
+use std::{
+    any::{type_name, Any},
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use prost::Message;
+use tonic::metadata::AsciiMetadataValue;
+
+// RESPONSE CACHE
+// ================================================================================================
+
+/// Identifies a request by its tenant, its type, which determines the method it is sent to, and
+/// its encoded contents.
+type CacheKey = (Option<AsciiMetadataValue>, &'static str, Vec<u8>);
+
+/// Short-lived cache of the responses to read requests, keyed by the request contents and tenant,
+/// so responses are never served to another tenant.
+///
+/// The cache is shared between a [DefaultStore](super::DefaultStore) and its block applier, and is
+/// cleared whenever a block is applied, so responses are never served across block boundaries.
+/// Caching is disabled until a TTL is set.
+#[derive(Clone, Default)]
+pub(super) struct ResponseCache {
+    inner: Arc<Mutex<CacheState>>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// How long responses are served from the cache, or `None` if caching is disabled
+    ttl: Option<Duration>,
+    entries: HashMap<CacheKey, CachedResponse>,
+    /// Incremented whenever the cache is cleared, to discard responses to requests which were
+    /// sent before
+    generation: u64,
+}
+
+struct CachedResponse {
+    expires_at: Instant,
+    response: Box<dyn Any + Send + Sync>,
+}
+
+impl ResponseCache {
+    /// Enables caching of responses for `ttl`.
+    pub fn set_ttl(&self, ttl: Duration) {
+        self.state().ttl = Some(ttl);
+    }
+
+    /// Returns true if responses are cached.
+    pub fn is_enabled(&self) -> bool {
+        self.state().ttl.is_some()
+    }
+
+    /// Returns the cached response to `request` of `tenant` if it did not expire at `now`, together
+    /// with the generation of the cache which must be passed to [ResponseCache::insert] otherwise.
+    pub fn get<M: Message, R: Clone + 'static>(
+        &self,
+        tenant: Option<AsciiMetadataValue>,
+        request: &M,
+        now: Instant,
+    ) -> Result<R, u64> {
+        let state = self.state();
+        let key = (tenant, type_name::<M>(), request.encode_to_vec());
+
+        state
+            .entries
+            .get(&key)
+            .filter(|entry| entry.expires_at > now)
+            .and_then(|entry| entry.response.downcast_ref::<R>())
+            .cloned()
+            .ok_or(state.generation)
+    }
+
+    /// Caches `response` to `request` of `tenant`, unless caching is disabled or the cache was
+    /// cleared since `generation` was returned by [ResponseCache::get].
+    ///
+    /// Expired entries are evicted, so the cache only holds the responses of the last TTL.
+    pub fn insert<M: Message, R: Send + Sync + 'static>(
+        &self,
+        tenant: Option<AsciiMetadataValue>,
+        request: &M,
+        response: R,
+        generation: u64,
+        now: Instant,
+    ) {
+        let mut state = self.state();
+        let Some(ttl) = state.ttl else {
+            return;
+        };
+        if state.generation != generation {
+            return;
+        }
+
+        state.entries.retain(|_, entry| entry.expires_at > now);
+        state.entries.insert(
+            (tenant, type_name::<M>(), request.encode_to_vec()),
+            CachedResponse {
+                expires_at: now + ttl,
+                response: Box::new(response),
+            },
+        );
+    }
+
+    /// Removes all cached responses.
+    pub fn clear(&self) {
+        let mut state = self.state();
+        state.entries.clear();
+        state.generation += 1;
+    }
+
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        self.inner.lock().expect("response cache lock poisoned")
+    }
+}
@@ -0,0 +1,141 @@
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+use miden_node_proto::domain::blocks::BlockNumber;
+use miden_objects::block::Block;
+use tokio_stream::{Stream, StreamExt};
+use tracing::info;
+
+use super::Store;
+use crate::{errors::TxInputsError, COMPONENT};
+
+/// Default maximum number of consecutive reconnections of a [ResumableSync].
+pub const DEFAULT_MAX_RECONNECTS: u32 = 3;
+
+/// Default delay of a [ResumableSync] before reconnecting to the store.
+pub const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+type BlockStream = Pin<Box<dyn Stream<Item = Result<Block, TxInputsError>> + Send>>;
+
+// RESUMABLE SYNC
+// ================================================================================================
+
+/// Syncs the blocks of a [Store] in ascending order, resuming after the last delivered block
+/// when the sync stream is interrupted.
+///
+/// The sync tracks the number of the last block it delivered, and reopens the stream with
+/// [Store::sync_blocks_from] at the following block when the stream fails because the store is
+/// temporarily unavailable. Blocks are therefore delivered exactly once and without gaps, even
+/// across reconnections. Dropping the sync cancels the stream, and a new sync created from
+/// [ResumableSync::next_block] picks up where it stopped.
+pub struct ResumableSync<S> {
+    store: Arc<S>,
+    /// Number of the block delivered first
+    from: BlockNumber,
+    /// Number of the last block delivered, if any
+    last_delivered: Option<BlockNumber>,
+    /// Maximum number of consecutive reconnections without delivering a block
+    max_reconnects: u32,
+    /// Delay before reconnecting to the store
+    reconnect_delay: Duration,
+    /// Number of reconnections since the last delivered block
+    reconnects: u32,
+    /// The open sync stream, if any
+    stream: Option<BlockStream>,
+}
+
+impl<S: Store> ResumableSync<S> {
+    /// Returns a sync of the blocks of `store` starting at block `from`.
+    pub fn new(store: Arc<S>, from: BlockNumber) -> Self {
+        Self {
+            store,
+            from,
+            last_delivered: None,
+            max_reconnects: DEFAULT_MAX_RECONNECTS,
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+            reconnects: 0,
+            stream: None,
+        }
+    }
+
+    /// Sets the maximum number of consecutive reconnections before a transient error is
+    /// returned.
+    pub fn with_max_reconnects(mut self, max_reconnects: u32) -> Self {
+        self.max_reconnects = max_reconnects;
+        self
+    }
+
+    /// Sets the delay before reconnecting to the store.
+    pub fn with_reconnect_delay(mut self, reconnect_delay: Duration) -> Self {
+        self.reconnect_delay = reconnect_delay;
+        self
+    }
+
+    /// Returns the number of the last block delivered, or `None` if no block was delivered yet.
+    pub fn last_delivered(&self) -> Option<BlockNumber> {
+        self.last_delivered
+    }
+
+    /// Returns the number of the next block to be delivered, or `None` if the last delivered
+    /// block has the largest block number.
+    pub fn next_block(&self) -> Option<BlockNumber> {
+        match self.last_delivered {
+            Some(block_num) => block_num.next(),
+            None => Some(self.from),
+        }
+    }
+
+    /// Returns the next block, an error, or `None` once the chain tip is reached.
+    ///
+    /// Transient errors are retried by reconnecting up to the configured number of times, other
+    /// errors are returned immediately. After an error or the chain tip, the next call reopens
+    /// the stream at the next block, so blocks applied in the meantime are synced too.
+    pub async fn next(&mut self) -> Option<Result<Block, TxInputsError>> {
+        loop {
+            let next_block = self.next_block()?;
+            let stream = self
+                .stream
+                .get_or_insert_with(|| Box::pin(self.store.sync_blocks_from(next_block)));
+
+            match stream.next().await {
+                None => {
+                    self.stream = None;
+                    return None;
+                },
+                Some(Ok(block)) => {
+                    let block_num = BlockNumber::from(block.header().block_num());
+                    if block_num < next_block {
+                        // Already delivered before the stream was reopened.
+                        continue;
+                    }
+                    if block_num > next_block {
+                        self.stream = None;
+                        return Some(Err(TxInputsError::MalformedResponse(format!(
+                            "sync stream skipped from block {next_block} to block {block_num}"
+                        ))));
+                    }
+
+                    self.last_delivered = Some(block_num);
+                    self.reconnects = 0;
+                    return Some(Ok(block));
+                },
+                Some(Err(err)) if err.is_transient() && self.reconnects < self.max_reconnects => {
+                    self.stream = None;
+                    self.reconnects += 1;
+                    info!(
+                        target: COMPONENT,
+                        %next_block,
+                        reconnect = self.reconnects,
+                        %err,
+                        "Sync stream interrupted, reconnecting"
+                    );
+                    tokio::time::sleep(self.reconnect_delay).await;
+                },
+                Some(Err(err)) => {
+                    self.stream = None;
+                    self.reconnects = 0;
+                    return Some(Err(err));
+                },
+            }
+        }
+    }
+}
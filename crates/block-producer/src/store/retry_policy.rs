@@ -0,0 +1,91 @@
+use std::{fmt::Debug, sync::Arc};
+
+use tonic::{codegen::Bytes, Code, Status};
+
+/// Whether a failed store request may be retried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// The request may succeed when retried later.
+    Retry,
+    /// The request fails again when retried.
+    DoNotRetry,
+}
+
+type Classifier = Arc<dyn Fn(&Status) -> RetryDecision + Send + Sync>;
+
+// RETRY POLICY
+// ================================================================================================
+
+/// Decides which failed read requests of a [DefaultStore](super::DefaultStore) may be retried,
+/// see [DefaultStore::with_retry_policy](super::DefaultStore::with_retry_policy).
+///
+/// Errors of retryable requests are transient, e.g. [TxInputsError::is_transient], and are
+/// retried by a [RetryingStore](super::RetryingStore) wrapping the store. By default, requests
+/// which failed because the store was unavailable or didn't respond in time are retried, see
+/// [RetryPolicy::default_decision].
+///
+/// [TxInputsError::is_transient]: crate::errors::TxInputsError::is_transient
+#[derive(Clone, Default)]
+pub struct RetryPolicy {
+    /// Replaces the default classification of failed requests, if set
+    classifier: Option<Classifier>,
+}
+
+impl RetryPolicy {
+    /// Returns a policy deciding with `classifier` which failed requests may be retried.
+    ///
+    /// The classifier replaces the default classification entirely. Classifiers which only
+    /// handle application-specific codes can fall back to [RetryPolicy::default_decision] for
+    /// the other codes.
+    pub fn with_classifier(
+        classifier: impl Fn(&Status) -> RetryDecision + Send + Sync + 'static,
+    ) -> Self {
+        Self { classifier: Some(Arc::new(classifier)) }
+    }
+
+    /// Returns whether a request which failed with `status` may be retried.
+    pub fn decide(&self, status: &Status) -> RetryDecision {
+        match &self.classifier {
+            Some(classifier) => classifier(status),
+            None => Self::default_decision(status),
+        }
+    }
+
+    /// Returns whether a request which failed with `status` may be retried by default: only if
+    /// the store was unavailable or didn't respond in time.
+    pub fn default_decision(status: &Status) -> RetryDecision {
+        match status.code() {
+            Code::Unavailable | Code::DeadlineExceeded => RetryDecision::Retry,
+            _ => RetryDecision::DoNotRetry,
+        }
+    }
+
+    /// Returns `status` with a code which converts to a transient error exactly if the policy
+    /// retries it.
+    ///
+    /// Statuses classified like by default are returned unchanged. Otherwise, the code is
+    /// replaced by [Code::Unavailable] for retried statuses, and by [Code::Unknown] for the
+    /// others, keeping the message, details and metadata of the status.
+    pub(super) fn reclassify(&self, status: Status) -> Status {
+        let code = match (self.decide(&status), Self::default_decision(&status)) {
+            (RetryDecision::Retry, RetryDecision::DoNotRetry) => Code::Unavailable,
+            (RetryDecision::DoNotRetry, RetryDecision::Retry) => Code::Unknown,
+            _ => return status,
+        };
+
+        Status::with_details_and_metadata(
+            code,
+            status.message(),
+            Bytes::copy_from_slice(status.details()),
+            status.metadata().clone(),
+        )
+    }
+}
+
+impl Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("classifier", &self.classifier.as_ref().map(|_| "custom"))
+            .finish()
+    }
+}
@@ -0,0 +1,240 @@
+use std::future::Future;
+
+use async_trait::async_trait;
+use miden_node_proto::{
+    domain::{blocks::BlockNumber, notes::NoteAuthenticationInfo},
+    AccountState,
+};
+use miden_objects::{
+    accounts::AccountId,
+    block::Block,
+    notes::{NoteId, Nullifier},
+    transaction::TransactionId,
+    Digest,
+};
+use miden_processor::crypto::RpoDigest;
+use tokio_stream::Stream;
+use tracing::info;
+
+use super::{ApplyBlock, Backoff, ChainStats, Store, StoreStatus, TransactionInputs};
+use crate::{
+    block::BlockInputs,
+    errors::{ApplyBlockError, BlockInputsError, NotePathsError, TxInputsError},
+    ProvenTransaction, COMPONENT,
+};
+
+// RETRYING STORE
+// ================================================================================================
+
+/// A [Store] which retries the idempotent reads of the wrapped store with jittered exponential
+/// backoff when they fail because the store is temporarily unavailable.
+///
+/// The transaction inputs, block inputs and note authentication info requests are retried, up to
+/// a maximum number of attempts. Errors returned by a reachable store, e.g. malformed responses,
+/// are returned immediately. Blocks are never re-sent, and all other requests are forwarded to
+/// the wrapped store as is.
+pub struct RetryingStore<S> {
+    inner: S,
+    /// Maximum number of attempts of a request, including the first one
+    max_attempts: u32,
+    /// Delays between the attempts of a request
+    backoff: Backoff,
+}
+
+impl<S: Store> RetryingStore<S> {
+    /// Returns a store retrying the reads of `inner` until one of `max_attempts` attempts
+    /// succeeds, waiting between the attempts as scheduled by `backoff`.
+    ///
+    /// # Panics
+    /// Panics if `max_attempts` is zero.
+    pub fn new(inner: S, max_attempts: u32, backoff: Backoff) -> Self {
+        assert!(max_attempts > 0, "a request needs at least one attempt");
+
+        Self { inner, max_attempts, backoff }
+    }
+
+    /// Returns the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Calls `request` until it succeeds, fails with an error which `is_transient` rejects, or
+    /// the attempts are exhausted, and returns the outcome of the last call.
+    async fn retry<T, E, F, Fut>(
+        &self,
+        mut request: F,
+        is_transient: fn(&E) -> bool,
+    ) -> Result<T, E>
+    where
+        E: std::fmt::Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retry = 0;
+        loop {
+            match request().await {
+                Err(err) if is_transient(&err) && retry + 1 < self.max_attempts => {
+                    let delay = self.backoff.delay(retry);
+                    info!(
+                        target: COMPONENT,
+                        attempt = retry + 1,
+                        ?delay,
+                        %err,
+                        "Store request failed, retrying"
+                    );
+                    self.backoff.sleep(delay).await;
+                    retry += 1;
+                },
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Store> ApplyBlock for RetryingStore<S> {
+    async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        self.inner.apply_block(block).await
+    }
+}
+
+#[async_trait]
+impl<S: Store> Store for RetryingStore<S> {
+    async fn get_tx_inputs(
+        &self,
+        proven_tx: &ProvenTransaction,
+    ) -> Result<TransactionInputs, TxInputsError> {
+        self.retry(|| self.inner.get_tx_inputs(proven_tx), TxInputsError::is_transient)
+            .await
+    }
+
+    async fn get_tx_inputs_batch(
+        &self,
+        txs: &[ProvenTransaction],
+    ) -> Result<Vec<TransactionInputs>, TxInputsError> {
+        self.retry(|| self.inner.get_tx_inputs_batch(txs), TxInputsError::is_transient)
+            .await
+    }
+
+    async fn get_block_inputs(
+        &self,
+        updated_accounts: impl Iterator<Item = AccountId> + Send,
+        produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<BlockInputs, BlockInputsError> {
+        // The iterators are consumed by each attempt.
+        let updated_accounts: Vec<_> = updated_accounts.collect();
+        let produced_nullifiers: Vec<_> = produced_nullifiers.collect();
+        let notes: Vec<_> = notes.collect();
+
+        self.retry(
+            || {
+                self.inner.get_block_inputs(
+                    updated_accounts.iter().copied(),
+                    produced_nullifiers.iter().copied(),
+                    notes.iter().copied(),
+                )
+            },
+            BlockInputsError::is_transient,
+        )
+        .await
+    }
+
+    async fn get_note_authentication_info(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        let notes: Vec<_> = notes.collect();
+
+        self.retry(
+            || self.inner.get_note_authentication_info(notes.iter().copied()),
+            NotePathsError::is_transient,
+        )
+        .await
+    }
+
+    async fn notes_authenticated_since(
+        &self,
+        notes: &[NoteId],
+        since: BlockNumber,
+    ) -> Result<Vec<NoteId>, NotePathsError> {
+        self.inner.notes_authenticated_since(notes, since).await
+    }
+
+    async fn nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, TxInputsError> {
+        self.inner.nullifier_origin(nullifier).await
+    }
+
+    async fn get_note_spend_block(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.inner.get_note_spend_block(nullifier).await
+    }
+
+    async fn get_pending_nullifiers(&self) -> Result<Vec<Nullifier>, TxInputsError> {
+        self.inner.get_pending_nullifiers().await
+    }
+
+    async fn get_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountState, TxInputsError> {
+        self.inner.get_account_state(account_id).await
+    }
+
+    async fn get_account_state_forks(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(RpoDigest, RpoDigest)>, TxInputsError> {
+        self.inner.get_account_state_forks(account_id).await
+    }
+
+    async fn get_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.inner.get_account_creation_block(account_id).await
+    }
+
+    async fn get_nullifier_root_history(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, RpoDigest)>, TxInputsError> {
+        self.inner.get_nullifier_root_history(from, to).await
+    }
+
+    async fn get_chain_stats(&self) -> Result<ChainStats, TxInputsError> {
+        self.inner.get_chain_stats().await
+    }
+
+    async fn check_health(&self) -> Result<StoreStatus, BlockInputsError> {
+        self.inner.check_health().await
+    }
+
+    async fn next_block_number(&self) -> Result<BlockNumber, TxInputsError> {
+        self.inner.next_block_number().await
+    }
+
+    async fn apply_block_with_diff(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError> {
+        self.inner.apply_block_with_diff(block).await
+    }
+
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError> {
+        self.inner.apply_blocks(blocks).await
+    }
+
+    fn sync_blocks_from(
+        &self,
+        from: BlockNumber,
+    ) -> impl Stream<Item = Result<Block, TxInputsError>> + Send + 'static {
+        self.inner.sync_blocks_from(from)
+    }
+}
@@ -0,0 +1,57 @@
+use miden_node_proto::domain::{blocks::BlockInclusionProof, notes::NoteAuthenticationInfo};
+use miden_objects::crypto::merkle::{MmrPeaks, MmrProof};
+
+// ROOT CONSISTENCY POLICY
+// ================================================================================================
+
+/// What a [DefaultStore](super::DefaultStore) does with note authentication info whose block
+/// inclusion proofs don't open against the chain the block is being built on.
+///
+/// This happens when the proofs were fetched, or cached, before the store applied a block which
+/// was built concurrently. Using such proofs in a block makes the block invalid. See
+/// [DefaultStore::get_note_authentication_info_at](super::DefaultStore::get_note_authentication_info_at).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RootConsistencyPolicy {
+    /// Fail with [NotePathsError::RootMismatch](crate::errors::NotePathsError::RootMismatch).
+    #[default]
+    Reject,
+    /// Request the proofs from the store again, bypassing all caches, and fail if they still
+    /// don't open against the chain.
+    Refetch,
+    /// Log a warning and use the proofs anyway.
+    WarnAndUse,
+}
+
+/// Returns the number of the first block whose inclusion proof in `info` doesn't open against
+/// the chain MMR with the given peaks, or `None` if all proofs do.
+pub(super) fn mismatched_block(
+    info: &NoteAuthenticationInfo,
+    chain_peaks: &MmrPeaks,
+) -> Option<u32> {
+    info.block_proofs
+        .iter()
+        .find(|proof| !opens_against(proof, chain_peaks))
+        .map(|proof| proof.block_header.block_num())
+}
+
+/// Returns true if `proof` is a path from its block to a peak of the chain MMR with the given
+/// peaks.
+fn opens_against(proof: &BlockInclusionProof, chain_peaks: &MmrPeaks) -> bool {
+    if proof.chain_length as usize != chain_peaks.num_leaves() {
+        return false;
+    }
+
+    let opening = MmrProof {
+        forest: proof.chain_length as usize,
+        position: proof.block_header.block_num() as usize,
+        merkle_path: proof.mmr_path.clone(),
+    };
+    let Some(peak) = chain_peaks.peaks().get(opening.peak_index()) else {
+        return false;
+    };
+
+    opening
+        .merkle_path
+        .compute_root(opening.relative_pos() as u64, proof.block_header.hash())
+        .is_ok_and(|root| root == *peak)
+}
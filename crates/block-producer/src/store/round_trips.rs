@@ -0,0 +1,61 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+// ROUND TRIP COUNTER
+// ================================================================================================
+
+/// Counts the requests sent to the store during a round, e.g. the building of one block.
+///
+/// A round starts when [RoundTripCounter::enter] is called and lasts until the returned guard is
+/// dropped. Requests sent outside of a round are not counted.
+#[derive(Clone, Debug, Default)]
+pub struct RoundTripCounter {
+    /// Count of the current round, if a round was entered
+    active: Arc<Mutex<Option<Arc<AtomicUsize>>>>,
+}
+
+impl RoundTripCounter {
+    /// Starts a new round, and returns a guard which reads its count and ends it when dropped.
+    ///
+    /// Entering a round while another one is active ends the other round.
+    pub fn enter(&self) -> RoundGuard {
+        let count = Arc::new(AtomicUsize::new(0));
+        *self.active.lock().expect("round trip counter lock poisoned") = Some(Arc::clone(&count));
+
+        RoundGuard { counter: self.clone(), count }
+    }
+
+    /// Counts one round trip towards the current round, if any.
+    pub fn record(&self) {
+        if let Some(count) = &*self.active.lock().expect("round trip counter lock poisoned") {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A round of a [RoundTripCounter], which ends when dropped.
+#[derive(Debug)]
+pub struct RoundGuard {
+    counter: RoundTripCounter,
+    count: Arc<AtomicUsize>,
+}
+
+impl RoundGuard {
+    /// Returns the number of round trips in the round so far.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for RoundGuard {
+    fn drop(&mut self) {
+        let mut active = self.counter.active.lock().expect("round trip counter lock poisoned");
+
+        // A later round may have replaced this one already.
+        if active.as_ref().is_some_and(|count| Arc::ptr_eq(count, &self.count)) {
+            *active = None;
+        }
+    }
+}
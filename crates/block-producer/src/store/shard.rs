@@ -0,0 +1,61 @@
+use std::{collections::BTreeMap, num::NonZeroUsize};
+
+use miden_node_proto::domain::nullifiers::shard_of;
+use miden_objects::notes::Nullifier;
+
+use super::StoreClient;
+
+// SHARD ROUTER
+// ================================================================================================
+
+/// Routes nullifier requests to the stores of the shards holding the nullifiers, for deployments
+/// which split the nullifier tree across several stores.
+///
+/// Nullifiers are assigned to shards with [shard_of], so the client of shard `i`
+/// must send its requests to the store serving shard `i`.
+#[derive(Clone)]
+pub struct ShardRouter<C> {
+    shards: Vec<C>,
+    num_shards: NonZeroUsize,
+}
+
+impl<C: StoreClient> ShardRouter<C> {
+    /// Returns a router sending the nullifiers of shard `i` to `shards[i]`, or `None` if
+    /// `shards` is empty.
+    pub fn new(shards: Vec<C>) -> Option<Self> {
+        let num_shards = NonZeroUsize::new(shards.len())?;
+
+        Some(Self { shards, num_shards })
+    }
+
+    /// Returns the number of shards.
+    pub fn num_shards(&self) -> NonZeroUsize {
+        self.num_shards
+    }
+
+    /// Returns the index of the shard holding `nullifier`.
+    pub fn shard_of(&self, nullifier: &Nullifier) -> usize {
+        shard_of(nullifier, self.num_shards)
+    }
+
+    /// Returns the client of the shard with the given index, or `None` if there is no such
+    /// shard.
+    pub fn shard(&self, index: usize) -> Option<&C> {
+        self.shards.get(index)
+    }
+
+    /// Groups `nullifiers` by the index of the shard holding them.
+    ///
+    /// Shards without any of the nullifiers are left out.
+    pub fn route<'a>(
+        &self,
+        nullifiers: impl IntoIterator<Item = &'a Nullifier>,
+    ) -> BTreeMap<usize, Vec<Nullifier>> {
+        let mut routed = BTreeMap::<usize, Vec<Nullifier>>::new();
+        for nullifier in nullifiers {
+            routed.entry(self.shard_of(nullifier)).or_default().push(*nullifier);
+        }
+
+        routed
+    }
+}
@@ -0,0 +1,40 @@
+use std::future::Future;
+
+use tonic::metadata::AsciiMetadataValue;
+
+// TENANT
+// ================================================================================================
+
+/// The gRPC metadata key under which the tenant is sent to the store.
+pub const TENANT_METADATA_KEY: &str = "x-tenant-id";
+
+tokio::task_local! {
+    /// Tenant overriding the default tenant of the store for the requests sent by the current task
+    static TENANT: AsciiMetadataValue;
+}
+
+/// Awaits `call` with all requests it sends to a [DefaultStore](super::DefaultStore) scoped to
+/// `tenant`, overriding the store's default tenant.
+///
+/// The override applies to the requests sent by the task awaiting `call`. Blocks queued by the
+/// apply queue of the store are retried for the tenant they were applied for, and the caches of
+/// the store only serve responses to requests of the same tenant.
+pub async fn scoped_to_tenant<F: Future>(tenant: AsciiMetadataValue, call: F) -> F::Output {
+    TENANT.scope(tenant, call).await
+}
+
+/// Returns the tenant of the requests sent by the current task, which is the tenant of the
+/// enclosing [scoped_to_tenant] call if any, or `default` otherwise.
+pub(super) fn current_tenant(default: Option<&AsciiMetadataValue>) -> Option<AsciiMetadataValue> {
+    TENANT.try_with(Clone::clone).ok().or_else(|| default.cloned())
+}
+
+/// Adds `tenant` to the metadata of `request`, if any.
+pub(super) fn insert_tenant<T>(
+    request: &mut tonic::Request<T>,
+    tenant: Option<AsciiMetadataValue>,
+) {
+    if let Some(tenant) = tenant {
+        request.metadata_mut().insert(TENANT_METADATA_KEY, tenant);
+    }
+}
@@ -0,0 +1,3755 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    future::Future,
+    num::{NonZeroU32, NonZeroUsize},
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::future::{self, BoxFuture, FutureExt};
+use miden_node_proto::{
+    domain::{
+        blocks::BlockNumber,
+        notes::NoteAuthenticationInfo,
+        nullifiers::{shard_of, NullifierWitness},
+    },
+    errors::{ConversionError, StaleBlockDetail, STALE_BLOCK_DETAIL_TYPE_URL},
+    generated::{
+        requests::{
+            ApplyBlockRequest, ApplyBlocksRequest, CheckNullifiersRequest,
+            GetAccountCreationBlockRequest, GetAccountStateForksRequest, GetAccountStateRequest,
+            GetBlockInputsRequest, GetChainStatsRequest, GetNoteAuthenticationInfoRequest,
+            GetNotesAuthenticatedSinceRequest, GetNullifierOriginRequest,
+            GetNullifierRootHistoryRequest, GetPendingNullifiersRequest,
+            GetTransactionInputsBatchRequest, GetTransactionInputsRequest, SyncBlocksRequest,
+        },
+        responses::{
+            AccountTransactionInputRecord, ApplyBlockResponse, ApplyBlocksResponse,
+            CheckNullifiersResponse, GetAccountCreationBlockResponse, GetAccountStateForksResponse,
+            GetAccountStateResponse, GetBlockInputsResponse, GetChainStatsResponse,
+            GetNoteAuthenticationInfoResponse, GetNotesAuthenticatedSinceResponse,
+            GetNullifierOriginResponse, GetNullifierRootHistoryResponse,
+            GetPendingNullifiersResponse, GetTransactionInputsBatchResponse,
+            GetTransactionInputsResponse, NullifierTransactionInputRecord, SyncBlocksResponse,
+        },
+        store::api_client::ApiClient,
+    },
+    AccountState,
+};
+#[cfg(feature = "in-process")]
+use miden_node_store::{
+    config::StoreConfig, genesis::GenesisState, nullifier_tree::NullifierBlockWitness,
+    server::StoreApi,
+};
+use miden_objects::{
+    accounts::{delta::AccountUpdateDetails, AccountId},
+    block::{Block, BlockAccountUpdate},
+    crypto::merkle::{LeafIndex, MerklePath, Mmr, NodeIndex, SimpleSmt, Smt, SMT_DEPTH},
+    notes::{NoteId, Nullifier},
+    transaction::TransactionId,
+    utils::{Deserializable, Serializable},
+    BlockHeader, Digest, Felt, StarkField, ACCOUNT_TREE_DEPTH, EMPTY_WORD, ZERO,
+};
+#[cfg(feature = "in-process")]
+use miden_objects::{
+    block::{compute_tx_hash, NoteBatch},
+    crypto::merkle::{MmrPeaks, PartialMmr},
+};
+use miden_processor::crypto::RpoDigest;
+use proptest::prelude::*;
+use prost::Message;
+use tokio::time::Instant;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{metadata::MetadataMap, transport::Endpoint, Code, Request, Response, Status};
+
+#[cfg(feature = "persistent-cache")]
+use super::PersistentCache;
+use super::{
+    next_block_number_after, normalize_note_ids, scoped_to_tenant, ApplyBlock, ApplyCostEstimate,
+    Backoff, CacheMetrics, ChainStats, ConnectionState, DefaultStore, GrpcErrorDetail,
+    GrpcErrorDetails, InMemoryStore, InputProofs, Priority, ResumableSync, RetryDecision,
+    RetryPolicy, RetryingStore, ShardRouter, Store, StoreClient, StoreMetrics, StoreStatus,
+    StoreTimeouts, TransactionInputs, ACCOUNT_ENTRY_BYTES, MIN_BLOCK_NUM_METADATA_KEY,
+    NULLIFIER_ENTRY_BYTES, RETRY_MAX_DELAY, TENANT_METADATA_KEY,
+};
+#[cfg(feature = "in-process")]
+use super::{CachingStore, InProcessStore, NoteAuthCache, RootConsistencyPolicy};
+#[cfg(feature = "in-process")]
+use crate::test_utils::block::note_created_smt_from_note_batches;
+use crate::{
+    block::BlockInputs,
+    errors::{ApplyBlockError, BlockInputsError, BlockRoot, NotePathsError, TxInputsError},
+    test_utils::{
+        block::MockBlockBuilder, mock_account_id, note::mock_output_note, MockClock,
+        MockPrivateAccount, MockProvenTxBuilder, MockStoreSuccess, MockStoreSuccessBuilder,
+    },
+    ProvenTransaction,
+};
+
+// HELPERS
+// ================================================================================================
+
+/// Builds transaction inputs for one account with one consumed and one unconsumed nullifier,
+/// together with valid proofs for them.
+fn tx_inputs_with_proofs() -> (TransactionInputs, InputProofs) {
+    let account_id = mock_account_id(42);
+    let account_hash = Digest::from([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+    let consumed = Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(5)]));
+    let unconsumed = Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(6)]));
+    let block_num = 7;
+
+    let account_tree =
+        SimpleSmt::<ACCOUNT_TREE_DEPTH>::with_leaves([(account_id.into(), account_hash.into())])
+            .unwrap();
+    let nullifier_tree =
+        Smt::with_entries([(consumed.inner(), [Felt::from(3u32), ZERO, ZERO, ZERO])]).unwrap();
+
+    let block_header = BlockHeader::new(
+        0,
+        Digest::default(),
+        block_num,
+        Digest::default(),
+        account_tree.root(),
+        nullifier_tree.root(),
+        Digest::default(),
+        Digest::default(),
+        Digest::default(),
+        Digest::default(),
+        1,
+    );
+
+    let proofs = InputProofs {
+        block_header,
+        account_proof: account_tree.open(&account_id.into()).path,
+        nullifier_proofs: BTreeMap::from_iter([
+            (consumed, nullifier_tree.open(&consumed.inner())),
+            (unconsumed, nullifier_tree.open(&unconsumed.inner())),
+        ]),
+    };
+
+    let tx_inputs = TransactionInputs {
+        account_id,
+        account_hash: Some(account_hash),
+        nullifiers: BTreeMap::from_iter([(consumed, NonZeroU32::new(3)), (unconsumed, None)]),
+        missing_unauthenticated_notes: Vec::new(),
+        current_block_height: block_num.into(),
+        proofs: Some(proofs.clone()),
+        account_version: None,
+    };
+
+    (tx_inputs, proofs)
+}
+
+// FAKE STORE CLIENT
+// ================================================================================================
+
+/// Answers the requests of one type sent to a [FakeClient].
+type Handler<Req, Resp> =
+    Arc<dyn Fn(Request<Req>) -> BoxFuture<'static, Result<Resp, Status>> + Send + Sync>;
+
+/// A request received by a [FakeClient].
+struct RecordedRequest {
+    metadata: MetadataMap,
+    message: Box<dyn Any + Send>,
+}
+
+/// Client of a fake store, which answers each type of request with the handler registered for
+/// it and rejects the requests without one.
+///
+/// All requests are recorded together with their metadata, including the rejected ones.
+#[derive(Clone, Default)]
+struct FakeClient {
+    /// Maps the type of a request to its [Handler]
+    handlers: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+fn unsupported<T>() -> Result<T, Status> {
+    Err(Status::unimplemented("not supported by the test client"))
+}
+
+impl FakeClient {
+    /// Answers the requests of type `Req` with `handler`.
+    fn on<Req: 'static, Resp: Send + 'static>(
+        self,
+        handler: impl Fn(Request<Req>) -> Result<Resp, Status> + Send + Sync + 'static,
+    ) -> Self {
+        self.on_async(move |request| future::ready(handler(request)))
+    }
+
+    /// Answers the requests of type `Req` with the outcome of the future returned by `handler`.
+    fn on_async<Req: 'static, Resp: 'static, Fut>(
+        mut self,
+        handler: impl Fn(Request<Req>) -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = Result<Resp, Status>> + Send + 'static,
+    {
+        let handler: Handler<Req, Resp> =
+            Arc::new(move |request: Request<Req>| handler(request).boxed());
+        Arc::make_mut(&mut self.handlers).insert(TypeId::of::<Req>(), Arc::new(handler));
+        self
+    }
+
+    /// Returns the received requests of type `M`, in order.
+    fn messages<M: Clone + 'static>(&self) -> Vec<M> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|request| request.message.downcast_ref::<M>().cloned())
+            .collect()
+    }
+
+    /// Returns the metadata of the received requests of type `M`, in order.
+    fn metadata<M: 'static>(&self) -> Vec<MetadataMap> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.message.is::<M>())
+            .map(|request| request.metadata.clone())
+            .collect()
+    }
+
+    async fn call<Req, Resp>(&self, request: Request<Req>) -> Result<Response<Resp>, Status>
+    where
+        Req: Clone + Send + 'static,
+        Resp: 'static,
+    {
+        self.requests.lock().unwrap().push(RecordedRequest {
+            metadata: request.metadata().clone(),
+            message: Box::new(request.get_ref().clone()),
+        });
+
+        let handler = self
+            .handlers
+            .get(&TypeId::of::<Req>())
+            .and_then(|handler| handler.downcast_ref::<Handler<Req, Resp>>())
+            .cloned();
+        match handler {
+            Some(handler) => handler(request).await.map(Response::new),
+            None => unsupported(),
+        }
+    }
+}
+
+#[async_trait]
+impl StoreClient for FakeClient {
+    type BlockStream = tokio_stream::Empty<Result<SyncBlocksResponse, Status>>;
+
+    async fn apply_block(
+        &mut self,
+        request: Request<ApplyBlockRequest>,
+    ) -> Result<Response<ApplyBlockResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn apply_blocks(
+        &mut self,
+        request: Request<ApplyBlocksRequest>,
+    ) -> Result<Response<ApplyBlocksResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_transaction_inputs(
+        &mut self,
+        request: Request<GetTransactionInputsRequest>,
+    ) -> Result<Response<GetTransactionInputsResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_transaction_inputs_batch(
+        &mut self,
+        request: Request<GetTransactionInputsBatchRequest>,
+    ) -> Result<Response<GetTransactionInputsBatchResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_block_inputs(
+        &mut self,
+        request: Request<GetBlockInputsRequest>,
+    ) -> Result<Response<GetBlockInputsResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_note_authentication_info(
+        &mut self,
+        request: Request<GetNoteAuthenticationInfoRequest>,
+    ) -> Result<Response<GetNoteAuthenticationInfoResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_notes_authenticated_since(
+        &mut self,
+        request: Request<GetNotesAuthenticatedSinceRequest>,
+    ) -> Result<Response<GetNotesAuthenticatedSinceResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_nullifier_origin(
+        &mut self,
+        request: Request<GetNullifierOriginRequest>,
+    ) -> Result<Response<GetNullifierOriginResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn check_nullifiers(
+        &mut self,
+        request: Request<CheckNullifiersRequest>,
+    ) -> Result<Response<CheckNullifiersResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_pending_nullifiers(
+        &mut self,
+        request: Request<GetPendingNullifiersRequest>,
+    ) -> Result<Response<GetPendingNullifiersResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_account_state(
+        &mut self,
+        request: Request<GetAccountStateRequest>,
+    ) -> Result<Response<GetAccountStateResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_account_state_forks(
+        &mut self,
+        request: Request<GetAccountStateForksRequest>,
+    ) -> Result<Response<GetAccountStateForksResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_account_creation_block(
+        &mut self,
+        request: Request<GetAccountCreationBlockRequest>,
+    ) -> Result<Response<GetAccountCreationBlockResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_nullifier_root_history(
+        &mut self,
+        request: Request<GetNullifierRootHistoryRequest>,
+    ) -> Result<Response<GetNullifierRootHistoryResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn get_chain_stats(
+        &mut self,
+        request: Request<GetChainStatsRequest>,
+    ) -> Result<Response<GetChainStatsResponse>, Status> {
+        self.call(request).await
+    }
+
+    async fn sync_blocks(
+        &mut self,
+        request: Request<SyncBlocksRequest>,
+    ) -> Result<Response<Self::BlockStream>, Status> {
+        self.call(request).await
+    }
+}
+
+// VERIFY AGAINST ROOT
+// ================================================================================================
+
+#[test]
+fn verify_against_root_accepts_valid_proofs() {
+    let (tx_inputs, proofs) = tx_inputs_with_proofs();
+
+    let root = proofs.block_header.hash();
+    assert_eq!(tx_inputs.verify_against_root(root, &proofs), Ok(()));
+}
+
+#[test]
+fn verify_against_root_rejects_untrusted_header() {
+    let (tx_inputs, proofs) = tx_inputs_with_proofs();
+
+    let root = Digest::default();
+    assert_eq!(
+        tx_inputs.verify_against_root(root, &proofs),
+        Err(TxInputsError::BlockHeaderMismatch {
+            expected: root,
+            actual: proofs.block_header.hash()
+        })
+    );
+}
+
+#[test]
+fn verify_against_root_rejects_tampered_account_hash() {
+    let (mut tx_inputs, proofs) = tx_inputs_with_proofs();
+    tx_inputs.account_hash = None;
+
+    let root = proofs.block_header.hash();
+    assert_eq!(
+        tx_inputs.verify_against_root(root, &proofs),
+        Err(TxInputsError::InvalidAccountProof(tx_inputs.account_id))
+    );
+}
+
+#[test]
+fn verify_against_root_rejects_tampered_nullifier() {
+    let (mut tx_inputs, mut proofs) = tx_inputs_with_proofs();
+    let consumed = *tx_inputs.nullifiers.iter().find(|(_, block)| block.is_some()).unwrap().0;
+    let unconsumed = *tx_inputs.nullifiers.iter().find(|(_, block)| block.is_none()).unwrap().0;
+
+    // Claiming a consumed nullifier is unconsumed must be rejected.
+    tx_inputs.nullifiers.insert(consumed, None);
+    let root = proofs.block_header.hash();
+    assert_eq!(
+        tx_inputs.verify_against_root(root, &proofs),
+        Err(TxInputsError::InvalidNullifierProof(consumed))
+    );
+
+    // As well as swapping the proofs of two nullifiers.
+    tx_inputs.nullifiers.insert(consumed, NonZeroU32::new(3));
+    let consumed_proof = proofs.nullifier_proofs[&consumed].clone();
+    proofs.nullifier_proofs.insert(unconsumed, consumed_proof);
+    assert_eq!(
+        tx_inputs.verify_against_root(root, &proofs),
+        Err(TxInputsError::InvalidNullifierProof(unconsumed))
+    );
+}
+
+// CHECK NULLIFIERS
+// ================================================================================================
+
+#[test]
+fn check_nullifiers_accepts_unconsumed_nullifiers() {
+    let (tx_inputs, _) = tx_inputs_with_proofs();
+    let unconsumed = *tx_inputs.nullifiers.iter().find(|(_, block)| block.is_none()).unwrap().0;
+
+    let tx = MockProvenTxBuilder::with_account_index(0).nullifiers(vec![unconsumed]).build();
+    assert_eq!(tx_inputs.check_nullifiers(&tx), Ok(()));
+}
+
+#[test]
+fn check_nullifiers_rejects_spent_nullifier() {
+    let (tx_inputs, _) = tx_inputs_with_proofs();
+    let consumed = *tx_inputs.nullifiers.iter().find(|(_, block)| block.is_some()).unwrap().0;
+    let unconsumed = *tx_inputs.nullifiers.iter().find(|(_, block)| block.is_none()).unwrap().0;
+
+    let tx = MockProvenTxBuilder::with_account_index(0)
+        .nullifiers(vec![unconsumed, consumed])
+        .build();
+    assert_eq!(
+        tx_inputs.check_nullifiers(&tx),
+        Err(TxInputsError::NullifierAlreadySpent(consumed))
+    );
+}
+
+#[test]
+fn check_nullifiers_rejects_missing_nullifier() {
+    let (tx_inputs, _) = tx_inputs_with_proofs();
+    let unconsumed = *tx_inputs.nullifiers.iter().find(|(_, block)| block.is_none()).unwrap().0;
+    let missing = Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(100)]));
+
+    let tx = MockProvenTxBuilder::with_account_index(0)
+        .nullifiers(vec![unconsumed, missing])
+        .build();
+    assert_eq!(
+        tx_inputs.check_nullifiers(&tx),
+        Err(TxInputsError::MissingNullifierInfo(missing))
+    );
+}
+
+#[tokio::test]
+async fn tx_inputs_with_omitted_nullifier_are_rejected() {
+    let held = Nullifier::from([Felt::new(1); 4]);
+    let omitted = Nullifier::from([Felt::new(2); 4]);
+    let store = DefaultStore::from_client(Shard::new([held]).client());
+
+    let tx = MockProvenTxBuilder::with_account_index(0)
+        .nullifiers(vec![held, omitted])
+        .build();
+    assert_eq!(
+        store.get_tx_inputs(&tx).await.unwrap_err(),
+        TxInputsError::IncompleteNullifierResponse(vec![omitted])
+    );
+}
+
+#[tokio::test]
+async fn tx_inputs_of_new_account_with_consumed_nullifiers_are_partial() {
+    let consumed = Nullifier::from([Felt::new(1); 4]);
+    let store = DefaultStore::from_client(Shard::new([consumed]).client());
+
+    // The shard client knows no accounts, so the transaction's account is new.
+    let tx = MockProvenTxBuilder::with_account_index(0).nullifiers(vec![consumed]).build();
+    let tx_inputs = store.get_tx_inputs(&tx).await.unwrap();
+
+    assert_eq!(tx_inputs.account_hash, None);
+    assert_eq!(tx_inputs.nullifiers, BTreeMap::from([(consumed, NonZeroU32::new(1))]));
+    assert!(tx_inputs.is_partial());
+    assert_eq!(
+        tx_inputs.check_nullifiers(&tx),
+        Err(TxInputsError::NullifierAlreadySpent(consumed))
+    );
+
+    // Inputs of known accounts, or without nullifiers, are complete.
+    let known = TransactionInputs {
+        account_hash: Some(Digest::default()),
+        ..tx_inputs.clone()
+    };
+    assert!(!known.is_partial());
+    let without_nullifiers = TransactionInputs { nullifiers: BTreeMap::new(), ..tx_inputs };
+    assert!(!without_nullifiers.is_partial());
+}
+
+#[tokio::test]
+async fn tx_inputs_batch_is_fetched_in_one_request_in_order() {
+    let consumed = Nullifier::from([Felt::new(1); 4]);
+    let shard = Shard::new([consumed]);
+    let client = shard.client();
+    let store = DefaultStore::from_client(client.clone());
+
+    let txs: Vec<_> = (1..4)
+        .map(|index| {
+            MockProvenTxBuilder::with_account_index(index)
+                .nullifiers(vec![consumed])
+                .build()
+        })
+        .collect();
+    let tx_inputs = store.get_tx_inputs_batch(&txs).await.unwrap();
+
+    assert_eq!(client.messages::<GetTransactionInputsBatchRequest>().len(), 1);
+    let account_ids: Vec<_> = tx_inputs.iter().map(|inputs| inputs.account_id).collect();
+    let expected: Vec<_> = txs.iter().map(|tx| tx.account_id()).collect();
+    assert_eq!(account_ids, expected);
+    assert!(tx_inputs
+        .iter()
+        .all(|inputs| inputs.nullifiers == BTreeMap::from([(consumed, NonZeroU32::new(1))])));
+
+    // Each returned entry is checked against the account of its transaction.
+    let store = DefaultStore::from_client(shard.with_reversed_batches().client());
+    assert!(matches!(
+        store.get_tx_inputs_batch(&txs).await,
+        Err(TxInputsError::MalformedResponse(_))
+    ));
+}
+
+#[tokio::test]
+async fn strict_account_state_rejects_stale_initial_account_hash() {
+    let account = MockPrivateAccount::<3>::from(1);
+    let client = Shard::new([]).with_account_hash(account.states[0]).client();
+    let store = DefaultStore::from_client(client.clone()).with_strict_account_state();
+
+    let current =
+        MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1]).build();
+    let tx_inputs = store.get_tx_inputs(&current).await.unwrap();
+    assert_eq!(tx_inputs.account_hash, Some(account.states[0]));
+
+    let stale =
+        MockProvenTxBuilder::with_account(account.id, account.states[1], account.states[2]).build();
+    assert_eq!(
+        store.get_tx_inputs(&stale).await.unwrap_err(),
+        TxInputsError::AccountStateMismatch {
+            expected: account.states[1],
+            got: Some(account.states[0]),
+        }
+    );
+
+    // Transactions creating their account declare no initial hash.
+    let new =
+        MockProvenTxBuilder::with_account(account.id, Digest::default(), account.states[0]).build();
+    store.get_tx_inputs(&new).await.unwrap();
+
+    // Without strict mode, the hashes are compared during verification instead.
+    let store = DefaultStore::from_client(client);
+    store.get_tx_inputs(&stale).await.unwrap();
+}
+
+// NOTE IDS
+// ================================================================================================
+
+#[test]
+fn note_ids_are_deduplicated_in_ascending_order() {
+    let note_id = |i: u64| NoteId::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(i)]));
+    let notes = [note_id(3), note_id(1), note_id(3), note_id(2), note_id(1)];
+
+    assert_eq!(normalize_note_ids(&notes), Ok(vec![note_id(1), note_id(2), note_id(3)]));
+    assert_eq!(normalize_note_ids(&[]), Ok(vec![]));
+}
+
+#[tokio::test]
+async fn zero_note_ids_are_rejected_before_any_request() {
+    let valid = NoteId::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(1)]));
+    let zero = NoteId::from(Digest::default());
+    assert_eq!(normalize_note_ids(&[valid, zero]), Err(NotePathsError::InvalidNoteId(zero)));
+
+    // The ids are checked before the requests are sent, so the store is never reached.
+    let store = DefaultStore::from_client(Shard::new([]).client());
+    assert_eq!(
+        store.get_note_authentication_info([valid, zero].iter()).await.unwrap_err(),
+        NotePathsError::InvalidNoteId(zero)
+    );
+    assert_eq!(
+        store
+            .notes_authenticated_since(&[zero], BlockNumber::from(1))
+            .await
+            .unwrap_err(),
+        NotePathsError::InvalidNoteId(zero)
+    );
+    assert_eq!(
+        store
+            .get_block_inputs(std::iter::empty(), std::iter::empty(), [zero].iter())
+            .await
+            .unwrap_err(),
+        BlockInputsError::InvalidNotes(NotePathsError::InvalidNoteId(zero))
+    );
+}
+
+// ASSEMBLE
+// ================================================================================================
+
+#[test]
+fn assembled_tx_inputs_equal_parsed_response() {
+    let account_id = mock_account_id(42);
+    let account_hash = Digest::from([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+    let consumed = Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(5)]));
+    let unconsumed = Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(6)]));
+    let missing_note = mock_output_note(1).id();
+
+    let response = GetTransactionInputsResponse {
+        account_state: Some(AccountTransactionInputRecord {
+            account_id: Some(account_id.into()),
+            account_hash: Some(account_hash.into()),
+        }),
+        nullifiers: vec![
+            NullifierTransactionInputRecord {
+                nullifier: Some(consumed.into()),
+                block_num: 3,
+                opening: None,
+            },
+            NullifierTransactionInputRecord {
+                nullifier: Some(unconsumed.into()),
+                block_num: 0,
+                opening: None,
+            },
+        ],
+        missing_unauthenticated_notes: vec![(&missing_note).into()],
+        block_height: 7,
+        ..Default::default()
+    };
+
+    let assembled = TransactionInputs::assemble(
+        account_id,
+        Some(account_hash),
+        BTreeMap::from_iter([(consumed, NonZeroU32::new(3)), (unconsumed, None)]),
+        vec![missing_note],
+        BlockNumber::from(7),
+    );
+    assert_eq!(assembled, TransactionInputs::try_from(response).unwrap());
+}
+
+// PROTOBUF ROUND TRIP
+// ================================================================================================
+
+/// Generates digests made of canonical field elements, which are preserved by the protobuf
+/// encoding.
+fn arb_digest() -> impl Strategy<Value = Digest> {
+    prop::array::uniform4(0..Felt::MODULUS)
+        .prop_map(|elements| Digest::from(elements.map(Felt::new)))
+}
+
+fn arb_block_header() -> impl Strategy<Value = BlockHeader> {
+    let header_fields = (
+        any::<u32>(),
+        arb_digest(),
+        any::<u32>(),
+        arb_digest(),
+        arb_digest(),
+        arb_digest(),
+        arb_digest(),
+        arb_digest(),
+        arb_digest(),
+        arb_digest(),
+        any::<u32>(),
+    );
+
+    header_fields.prop_map(
+        |(
+            version,
+            prev_hash,
+            block_num,
+            chain_root,
+            account_root,
+            nullifier_root,
+            note_root,
+            tx_hash,
+            kernel_root,
+            proof_hash,
+            timestamp,
+        )| {
+            BlockHeader::new(
+                version,
+                prev_hash,
+                block_num,
+                chain_root,
+                account_root,
+                nullifier_root,
+                note_root,
+                tx_hash,
+                kernel_root,
+                proof_hash,
+                timestamp,
+            )
+        },
+    )
+}
+
+/// Generates valid transaction inputs from independent parts, so failing cases shrink towards
+/// fewer nullifiers and notes, and towards inputs without proofs.
+impl Arbitrary for TransactionInputs {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        // The default hash stands for an account unknown to the store, so it is never generated.
+        let account_hash = arb_digest()
+            .prop_filter("default hash means no account", |hash| *hash != Digest::default());
+        let nullifiers = prop::collection::btree_map(
+            arb_digest().prop_map(Nullifier::from),
+            any::<u32>().prop_map(NonZeroU32::new),
+            0..8,
+        );
+        let proofs = (
+            arb_block_header(),
+            prop::collection::vec(arb_digest(), 0..=ACCOUNT_TREE_DEPTH as usize),
+        );
+
+        (
+            (0..4u8).prop_map(mock_account_id),
+            prop::option::of(account_hash),
+            nullifiers,
+            prop::collection::vec(arb_digest().prop_map(NoteId::from), 0..8),
+            any::<u32>(),
+            prop::option::of(proofs),
+            // Empty versions mean no version.
+            prop::option::of(prop::collection::vec(any::<u8>(), 1..40)),
+        )
+            .prop_map(
+                |(
+                    account_id,
+                    account_hash,
+                    nullifiers,
+                    missing_notes,
+                    block_height,
+                    proofs,
+                    account_version,
+                )| {
+                    // Nullifiers are consumed at or before the current block height.
+                    let block_height = nullifiers
+                        .values()
+                        .flatten()
+                        .map(|block_num| block_num.get())
+                        .fold(block_height, u32::max);
+                    // Openings against the empty tree are valid proofs for any nullifier.
+                    let proofs = proofs.map(|(block_header, account_path)| InputProofs {
+                        block_header,
+                        account_proof: MerklePath::new(account_path),
+                        nullifier_proofs: nullifiers
+                            .keys()
+                            .map(|nullifier| (*nullifier, Smt::new().open(&nullifier.inner())))
+                            .collect(),
+                    });
+
+                    TransactionInputs {
+                        account_id,
+                        account_hash,
+                        nullifiers,
+                        missing_unauthenticated_notes: missing_notes,
+                        current_block_height: block_height.into(),
+                        proofs,
+                        account_version,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+proptest! {
+    /// Runs the default number of cases, set `PROPTEST_CASES` to run more of them locally.
+    #[test]
+    fn tx_inputs_survive_protobuf_round_trip(tx_inputs: TransactionInputs) {
+        let response = GetTransactionInputsResponse::from(tx_inputs.clone());
+
+        prop_assert_eq!(TransactionInputs::try_from(response).unwrap(), tx_inputs);
+    }
+
+    #[test]
+    fn tx_inputs_responses_survive_round_trip(tx_inputs: TransactionInputs) {
+        // Responses sent by the store, which lists nullifiers in ascending order.
+        let response = GetTransactionInputsResponse::from(tx_inputs);
+        let tx_inputs = TransactionInputs::try_from(response.clone()).unwrap();
+
+        prop_assert_eq!(GetTransactionInputsResponse::from(tx_inputs), response);
+    }
+}
+
+#[test]
+fn tx_inputs_with_nullifier_consumed_after_block_height_are_rejected() {
+    let nullifier = Nullifier::from([Felt::new(1); 4]);
+    let tx_inputs = TransactionInputs {
+        account_id: mock_account_id(0),
+        account_hash: None,
+        nullifiers: BTreeMap::from([(nullifier, NonZeroU32::new(5))]),
+        missing_unauthenticated_notes: Vec::new(),
+        current_block_height: BlockNumber::from(5),
+        proofs: None,
+        account_version: None,
+    };
+    let response = GetTransactionInputsResponse::from(tx_inputs.clone());
+    assert_eq!(TransactionInputs::try_from(response), Ok(tx_inputs.clone()));
+
+    let response = GetTransactionInputsResponse {
+        block_height: 4,
+        ..GetTransactionInputsResponse::from(tx_inputs)
+    };
+    assert_eq!(
+        TransactionInputs::try_from(response),
+        Err(ConversionError::BlockNumberAfterBlockHeight { block_num: 5, block_height: 4 })
+    );
+}
+
+// PENDING NULLIFIERS
+// ================================================================================================
+
+#[tokio::test]
+async fn pending_nullifiers_include_nullifiers_of_block_being_built() {
+    let store = MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build();
+    let nullifier = Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(5)]));
+
+    assert_eq!(store.get_pending_nullifiers().await, Ok(Vec::new()));
+
+    store
+        .get_block_inputs(std::iter::empty(), [nullifier].iter(), std::iter::empty())
+        .await
+        .unwrap();
+
+    assert_eq!(store.get_pending_nullifiers().await, Ok(vec![nullifier]));
+}
+
+// ACCOUNT STATE
+// ================================================================================================
+
+#[tokio::test]
+async fn account_state_is_returned_by_dedicated_request() {
+    let account_id = mock_account_id(42);
+    let account_hash = Digest::from([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+    let store = DefaultStore::from_client(Shard::new([]).with_account_hash(account_hash).client());
+
+    let account_state = store.get_account_state(account_id).await.unwrap();
+    assert_eq!(account_state.account_id, account_id);
+    assert_eq!(account_state.account_hash, Some(account_hash));
+    assert_eq!(store.metrics_snapshot().methods["GetAccountState"].calls, 1);
+
+    // The default hash stands for an unknown account.
+    let store = DefaultStore::from_client(Shard::new([]).client());
+    assert_eq!(store.get_account_state(account_id).await.unwrap().account_hash, None);
+}
+
+// ACCOUNT STATE FORKS
+// ================================================================================================
+
+#[tokio::test]
+async fn account_state_forks_return_candidates_of_competing_tips() {
+    let account_id = mock_account_id(42);
+    let account_hash = Digest::from([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+    let store =
+        MockStoreSuccessBuilder::from_accounts([(account_id, account_hash)].into_iter()).build();
+    let tip = store.block_headers.read().await.last_key_value().unwrap().1.hash();
+
+    assert_eq!(store.get_account_state_forks(account_id).await, Ok(vec![(account_hash, tip)]));
+
+    // Simulate a fork, where the account has a different hash at a competing tip.
+    let fork_hash = Digest::from([Felt::new(5), Felt::new(6), Felt::new(7), Felt::new(8)]);
+    let fork_tip = Digest::from([Felt::new(9), ZERO, ZERO, ZERO]);
+    store
+        .account_forks
+        .write()
+        .await
+        .insert(account_id, vec![(fork_hash, fork_tip)]);
+
+    assert_eq!(
+        store.get_account_state_forks(account_id).await,
+        Ok(vec![(account_hash, tip), (fork_hash, fork_tip)])
+    );
+    assert_eq!(store.get_account_state_forks(mock_account_id(43)).await, Ok(Vec::new()));
+}
+
+// CONNECT
+// ================================================================================================
+
+#[tokio::test]
+async fn lazily_connected_store_fails_on_first_request_to_dead_endpoint() {
+    let store = DefaultStore::connect_lazy("http://127.0.0.1:1").unwrap();
+    let tx = MockProvenTxBuilder::with_account_index(0).build();
+
+    assert!(matches!(
+        store.get_tx_inputs(&tx).await,
+        Err(TxInputsError::StoreUnavailable { .. })
+    ));
+    assert!(DefaultStore::connect_lazy("not a uri").is_err());
+}
+
+#[cfg(feature = "tls")]
+#[tokio::test]
+async fn connecting_with_malformed_ca_certificate_fails() {
+    use tonic::transport::{Certificate, ClientTlsConfig};
+
+    let ca =
+        Certificate::from_pem("-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n");
+    let tls = ClientTlsConfig::new().ca_certificate(ca).domain_name("store");
+
+    let error = DefaultStore::connect_with_tls("https://127.0.0.1:1", tls)
+        .await
+        .err()
+        .expect("connecting with a malformed CA certificate should fail");
+
+    // The cause is reported by the source of the transport error.
+    let mut messages = vec![error.to_string()];
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        messages.push(cause.to_string());
+        source = cause.source();
+    }
+    assert!(messages.iter().any(|message| message.contains("certificate")), "{messages:?}");
+}
+
+// RETRY DELAYS
+// ================================================================================================
+
+/// Returns a store whose client connects lazily, so no store server is needed.
+fn lazy_default_store() -> DefaultStore {
+    let channel = Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+
+    DefaultStore::new(ApiClient::new(channel))
+}
+
+#[tokio::test]
+async fn retry_delays_are_deterministic_for_seeded_stores() {
+    let store_a = lazy_default_store().with_rng_seed(42);
+    let store_b = lazy_default_store().with_rng_seed(42);
+
+    let delays_a: Vec<_> = (0..10).map(|attempt| store_a.retry_delay(attempt)).collect();
+    let delays_b: Vec<_> = (0..10).map(|attempt| store_b.retry_delay(attempt)).collect();
+
+    assert_eq!(delays_a, delays_b);
+}
+
+#[tokio::test(start_paused = true)]
+async fn reads_and_queued_blocks_are_retried_with_the_seeded_backoff_of_the_store() {
+    let dir = std::env::temp_dir()
+        .join(format!("miden-apply-queue-{}-store-backoff", std::process::id()));
+    let clock = Arc::new(MockClock::new());
+    let failure = Failure::default();
+    failure.fail_with(Some(Status::unavailable("store is restarting")));
+    let store = DefaultStore::from_client(failure.client())
+        .with_rng_seed(42)
+        .with_clock(clock.clone())
+        .with_apply_queue(&dir)
+        .unwrap();
+
+    // Reads retried with the backoff of the store draw the same delays as the store.
+    let seeded = lazy_default_store().with_rng_seed(42).backoff();
+    let delays: Vec<_> = (0..4).map(|retry| store.backoff().delay(retry)).collect();
+    assert_eq!(delays, (0..4).map(|retry| seeded.delay(retry)).collect::<Vec<_>>());
+
+    // The retry task fails to apply the queued block, and waits on the clock of the store.
+    let block =
+        MockBlockBuilder::new(&MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build())
+            .await
+            .build();
+    store.apply_block(&block).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    failure.fail_with(None);
+    tokio::time::sleep(RETRY_MAX_DELAY).await;
+    assert_eq!(store.pending_applies().await, 1);
+
+    clock.advance(RETRY_MAX_DELAY);
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    assert_eq!(store.pending_applies().await, 0);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn queued_blocks_are_retried_with_the_configuration_of_the_built_store() {
+    let dir = std::env::temp_dir()
+        .join(format!("miden-apply-queue-{}-late-configuration", std::process::id()));
+    let clock = Arc::new(MockClock::new());
+    let failure = Failure::default();
+    failure.fail_with(Some(Status::unavailable("store is restarting")));
+    let client = failure.client();
+
+    // The store is configured after its apply queue was enabled.
+    let store = DefaultStore::from_client(client.clone())
+        .with_apply_queue(&dir)
+        .unwrap()
+        .with_tenant("acme".parse().unwrap())
+        .with_timeouts(StoreTimeouts {
+            apply_block: Some(Duration::from_secs(30)),
+            ..Default::default()
+        })
+        .with_clock(clock.clone());
+
+    let block =
+        MockBlockBuilder::new(&MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build())
+            .await
+            .build();
+    store.apply_block(&block).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    failure.fail_with(None);
+    clock.advance(RETRY_MAX_DELAY);
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    assert_eq!(store.pending_applies().await, 0);
+
+    // The retries carry the tenant and the deadline of the store, like the first attempt.
+    let attempts = client.metadata::<ApplyBlockRequest>();
+    assert!(attempts.len() > 1);
+    assert_eq!(tenants_of::<ApplyBlockRequest>(&client), vec!["acme"; attempts.len()]);
+    assert!(attempts.iter().all(|metadata| metadata.contains_key("grpc-timeout")));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn queued_request_times_out_when_clock_advances() {
+    let clock = Arc::new(MockClock::new());
+    let store = Arc::new(
+        lazy_default_store()
+            .with_concurrency_limit(1)
+            .with_queue_timeout(Duration::from_secs(1))
+            .with_clock(clock.clone()),
+    );
+
+    // Take the only slot, so the request below has to wait for it.
+    let permit = store.acquire_slot(Priority::High).await.unwrap();
+    let request = tokio::spawn({
+        let store = Arc::clone(&store);
+        async move { store.get_pending_nullifiers().await }
+    });
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+
+    // The request gives up while the slot is still taken.
+    clock.advance(Duration::from_secs(2));
+    match request.await.unwrap() {
+        Err(TxInputsError::StoreUnavailable { message, .. }) => {
+            assert!(message.contains("request slot"))
+        },
+        result => panic!("unexpected result: {result:?}"),
+    }
+    drop(permit);
+}
+
+// NULLIFIER ROOT HISTORY
+// ================================================================================================
+
+#[tokio::test]
+async fn nullifier_root_history_matches_applied_blocks() {
+    let store = MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build();
+    let initial_header = *store.block_headers.read().await.first_key_value().unwrap().1;
+    let from = BlockNumber::from(initial_header.block_num());
+
+    let mut expected = vec![(from, initial_header.nullifier_root())];
+    for i in 1..=2 {
+        let nullifier = Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(i)]));
+        let block =
+            MockBlockBuilder::new(&store).await.produced_nullifiers(vec![nullifier]).build();
+        store.apply_block(&block).await.unwrap();
+        expected.push((block.header().block_num().into(), block.header().nullifier_root()));
+    }
+
+    let (next, last) = (expected[1].0, expected[2].0);
+    assert_eq!(store.get_nullifier_root_history(from, last).await, Ok(expected.clone()));
+    assert_eq!(store.get_nullifier_root_history(next, next).await, Ok(expected[1..2].to_vec()));
+}
+
+// CHAIN STATS
+// ================================================================================================
+
+#[tokio::test]
+async fn chain_stats_reflect_applied_blocks() {
+    let accounts =
+        (1..=3u8).map(|i| (mock_account_id(i), Digest::from([Felt::from(i), ZERO, ZERO, ZERO])));
+    let store = MockStoreSuccessBuilder::from_accounts(accounts).build();
+
+    let initial = store.get_chain_stats().await.unwrap();
+    assert_eq!(initial.num_accounts, 3);
+    assert_eq!(initial.num_nullifiers, 0);
+    assert_eq!(initial.account_root, store.account_root().await);
+
+    let nullifiers: Vec<_> = (1..=2u64)
+        .map(|i| Nullifier::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(i)])))
+        .collect();
+    let block = MockBlockBuilder::new(&store)
+        .await
+        .produced_nullifiers(nullifiers.clone())
+        .build();
+    store.apply_block(&block).await.unwrap();
+
+    // The mock block builder doesn't compute the nullifier root of the block.
+    let block_num = Felt::from(block.header().block_num());
+    let nullifier_tree = Smt::with_entries(
+        nullifiers
+            .iter()
+            .map(|nullifier| (nullifier.inner(), [block_num, ZERO, ZERO, ZERO])),
+    )
+    .unwrap();
+
+    let stats = store.get_chain_stats().await.unwrap();
+    assert_eq!(stats.num_blocks, initial.num_blocks + 1);
+    assert_eq!(stats.num_accounts, 3);
+    assert_eq!(stats.num_nullifiers, 2);
+    assert_eq!(stats.account_root, block.header().account_root());
+    assert_eq!(stats.nullifier_root, nullifier_tree.root());
+    assert_ne!(stats.chain_root, initial.chain_root);
+}
+
+// APPLY BLOCK WITH DIFF
+// ================================================================================================
+
+#[tokio::test]
+async fn apply_block_with_diff_returns_updated_accounts() {
+    let accounts: Vec<_> = (1..=3u8).map(|i| MockPrivateAccount::<3>::from(i as u32)).collect();
+    let store = MockStoreSuccessBuilder::from_accounts(
+        accounts.iter().map(|account| (account.id, account.states[0])),
+    )
+    .build();
+
+    // Only the first two accounts are updated by the block.
+    let updates: Vec<_> = accounts[..2]
+        .iter()
+        .map(|account| {
+            BlockAccountUpdate::new(
+                account.id,
+                account.states[1],
+                AccountUpdateDetails::Private,
+                vec![],
+            )
+        })
+        .collect();
+    let block = MockBlockBuilder::new(&store).await.account_updates(updates).build();
+
+    let diff = store.apply_block_with_diff(&block).await.unwrap();
+
+    let expected: Vec<_> =
+        accounts[..2].iter().map(|account| (account.id, account.states[1])).collect();
+    assert_eq!(diff, expected);
+    assert_eq!(store.account_root().await, block.header().account_root());
+}
+
+// PRECHECK BLOCK
+// ================================================================================================
+
+#[tokio::test]
+async fn precheck_block_detects_wrong_nullifier_root() {
+    let account = MockPrivateAccount::<3>::from(1);
+    let store =
+        MockStoreSuccessBuilder::from_accounts([(account.id, account.states[0])].into_iter())
+            .build();
+    let nullifiers = vec![
+        Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]),
+        Nullifier::from([ZERO, ZERO, ZERO, Felt::new(2)]),
+    ];
+    let update = BlockAccountUpdate::new(
+        account.id,
+        account.states[1],
+        AccountUpdateDetails::Private,
+        vec![],
+    );
+
+    // The mock block builder leaves the nullifier root empty.
+    let block = MockBlockBuilder::new(&store)
+        .await
+        .account_updates(vec![update.clone()])
+        .produced_nullifiers(nullifiers.clone())
+        .build();
+    let header = block.header();
+    let nullifier_tree =
+        Smt::with_entries(nullifiers.iter().map(|nullifier| {
+            (nullifier.inner(), [Felt::from(header.block_num()), ZERO, ZERO, ZERO])
+        }))
+        .unwrap();
+
+    assert_eq!(
+        store.precheck_block(&block).await,
+        Err(ApplyBlockError::RootMismatch {
+            which: BlockRoot::Nullifier,
+            expected: Digest::default(),
+            got: nullifier_tree.root(),
+        })
+    );
+
+    let header = BlockHeader::new(
+        header.version(),
+        header.prev_hash(),
+        header.block_num(),
+        header.chain_root(),
+        header.account_root(),
+        nullifier_tree.root(),
+        header.note_root(),
+        header.tx_hash(),
+        header.kernel_root(),
+        header.proof_hash(),
+        header.timestamp(),
+    );
+    let block = Block::new(header, vec![update], Vec::new(), nullifiers).unwrap();
+    store.precheck_block(&block).await.unwrap();
+}
+
+// APPLY COST ESTIMATE
+// ================================================================================================
+
+#[tokio::test]
+async fn estimate_apply_cost_counts_new_state_only() {
+    let known = MockPrivateAccount::<3>::from(1);
+    let unknown = MockPrivateAccount::<3>::from(2);
+    let store =
+        MockStoreSuccessBuilder::from_accounts([(known.id, known.states[0])].into_iter()).build();
+    let consumed = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+    let fresh = [
+        Nullifier::from([ZERO, ZERO, ZERO, Felt::new(2)]),
+        Nullifier::from([ZERO, ZERO, ZERO, Felt::new(3)]),
+    ];
+
+    let block = MockBlockBuilder::new(&store).await.produced_nullifiers(vec![consumed]).build();
+    store.apply_block(&block).await.unwrap();
+
+    let updates = vec![
+        BlockAccountUpdate::new(known.id, known.states[1], AccountUpdateDetails::Private, vec![]),
+        BlockAccountUpdate::new(
+            unknown.id,
+            unknown.states[0],
+            AccountUpdateDetails::Private,
+            vec![],
+        ),
+    ];
+    let block = MockBlockBuilder::new(&store)
+        .await
+        .account_updates(updates)
+        .produced_nullifiers(vec![consumed, fresh[0], fresh[1]])
+        .build();
+
+    let estimate = store.estimate_apply_cost(&block).await.unwrap();
+    assert_eq!(
+        estimate,
+        ApplyCostEstimate {
+            new_nullifiers: 2,
+            updated_accounts: 2,
+            new_accounts: 1,
+            estimated_bytes: block.to_bytes().len() as u64
+                + 2 * NULLIFIER_ENTRY_BYTES
+                + ACCOUNT_ENTRY_BYTES,
+        }
+    );
+}
+
+// MINIMAL PROOF SET
+// ================================================================================================
+
+#[tokio::test]
+async fn minimal_proof_set_covers_all_transactions_without_duplicate_proofs() {
+    let accounts: Vec<MockPrivateAccount> = (1..3).map(MockPrivateAccount::from).collect();
+    let consumed = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+    // Shares the leaf of the consumed nullifier, whose index is its most significant element.
+    let same_leaf = Nullifier::from([Felt::new(2), ZERO, ZERO, Felt::new(1)]);
+    let other_leaf = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(3)]);
+    let store = MockStoreSuccessBuilder::from_accounts(
+        accounts.iter().map(|account| (account.id, account.states[0])),
+    )
+    .initial_nullifiers(BTreeSet::from([consumed.inner()]))
+    .build();
+
+    // The first two transactions share their account and a nullifier.
+    let txs = vec![
+        MockProvenTxBuilder::with_account(
+            accounts[0].id,
+            accounts[0].states[0],
+            accounts[0].states[1],
+        )
+        .nullifiers(vec![consumed, same_leaf])
+        .build(),
+        MockProvenTxBuilder::with_account(
+            accounts[0].id,
+            accounts[0].states[1],
+            accounts[0].states[2],
+        )
+        .nullifiers(vec![same_leaf])
+        .build(),
+        MockProvenTxBuilder::with_account(
+            accounts[1].id,
+            accounts[1].states[0],
+            accounts[1].states[1],
+        )
+        .nullifiers(vec![other_leaf])
+        .build(),
+    ];
+
+    let proof_set = store.get_minimal_proof_set(&txs).await.unwrap();
+
+    let expected_hashes: BTreeMap<_, _> =
+        accounts.iter().map(|account| (account.id, account.states[0])).collect();
+    assert_eq!(proof_set.account_hashes, expected_hashes);
+    for (account_id, hash) in &proof_set.account_hashes {
+        let index = NodeIndex::new(ACCOUNT_TREE_DEPTH, u64::from(*account_id)).unwrap();
+        assert_eq!(proof_set.account_paths.get_node(index), Ok(*hash));
+    }
+    assert_eq!(proof_set.account_paths.root(), store.account_root().await);
+
+    let leaf_indices: BTreeSet<_> = [consumed, same_leaf, other_leaf]
+        .iter()
+        .map(|nullifier| LeafIndex::<SMT_DEPTH>::from(nullifier.inner()).value())
+        .collect();
+    assert_eq!(leaf_indices.len(), 2);
+    assert!(proof_set.nullifier_leaves.keys().copied().eq(leaf_indices));
+    for (leaf_index, leaf) in &proof_set.nullifier_leaves {
+        let index = NodeIndex::new(SMT_DEPTH, *leaf_index).unwrap();
+        assert_eq!(proof_set.nullifier_paths.get_node(index), Ok(leaf.hash()));
+    }
+
+    let nullifier_tree =
+        Smt::with_entries([(consumed.inner(), [Felt::new(1), ZERO, ZERO, ZERO])]).unwrap();
+    assert_eq!(proof_set.nullifier_paths.root(), nullifier_tree.root());
+    assert_eq!(proof_set.block_header.nullifier_root(), nullifier_tree.root());
+}
+
+// RETRYING STORE
+// ================================================================================================
+
+/// Store which is unavailable for the first reads sent to it, and forwards the following ones
+/// to a [MockStoreSuccess]. Blocks are always rejected as if the store was unavailable, and sync
+/// streams opened while it is unavailable are interrupted after their first block.
+struct FlakyStore {
+    inner: MockStoreSuccess,
+    /// Number of reads which fail before the store becomes available
+    failures: AtomicUsize,
+    /// Whether the transaction inputs are malformed instead of the store being unavailable
+    malformed: bool,
+    /// Number of requests received by the store
+    calls: AtomicUsize,
+}
+
+impl FlakyStore {
+    fn new(inner: MockStoreSuccess, failures: usize) -> Self {
+        Self {
+            inner,
+            failures: AtomicUsize::new(failures),
+            malformed: false,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    fn with_malformed_responses(mut self) -> Self {
+        self.malformed = true;
+        self
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Records a request, and returns true if it fails.
+    fn fails(&self) -> bool {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        self.failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |failures| failures.checked_sub(1))
+            .is_ok()
+    }
+}
+
+#[async_trait]
+impl ApplyBlock for FlakyStore {
+    async fn apply_block(&self, _block: &Block) -> Result<(), ApplyBlockError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Err(ApplyBlockError::StoreUnavailable {
+            code: Code::Unavailable,
+            message: "store is restarting".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl Store for FlakyStore {
+    async fn get_tx_inputs(
+        &self,
+        proven_tx: &ProvenTransaction,
+    ) -> Result<TransactionInputs, TxInputsError> {
+        if self.fails() {
+            return Err(if self.malformed {
+                TxInputsError::MalformedResponse("missing account hash".to_string())
+            } else {
+                TxInputsError::StoreUnavailable {
+                    code: Code::Unavailable,
+                    message: "store is restarting".to_string(),
+                }
+            });
+        }
+        self.inner.get_tx_inputs(proven_tx).await
+    }
+
+    async fn get_block_inputs(
+        &self,
+        updated_accounts: impl Iterator<Item = AccountId> + Send,
+        produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<BlockInputs, BlockInputsError> {
+        if self.fails() {
+            return Err(BlockInputsError::StoreUnavailable {
+                code: Code::Unavailable,
+                message: "store is restarting".to_string(),
+            });
+        }
+        self.inner.get_block_inputs(updated_accounts, produced_nullifiers, notes).await
+    }
+
+    async fn get_note_authentication_info(
+        &self,
+        notes: impl Iterator<Item = &NoteId> + Send,
+    ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        if self.fails() {
+            return Err(NotePathsError::StoreUnavailable {
+                code: Code::Unavailable,
+                message: "store is restarting".to_string(),
+            });
+        }
+        self.inner.get_note_authentication_info(notes).await
+    }
+
+    async fn notes_authenticated_since(
+        &self,
+        notes: &[NoteId],
+        since: BlockNumber,
+    ) -> Result<Vec<NoteId>, NotePathsError> {
+        self.inner.notes_authenticated_since(notes, since).await
+    }
+
+    async fn nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, TxInputsError> {
+        self.inner.nullifier_origin(nullifier).await
+    }
+
+    async fn get_note_spend_block(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.inner.get_note_spend_block(nullifier).await
+    }
+
+    async fn get_pending_nullifiers(&self) -> Result<Vec<Nullifier>, TxInputsError> {
+        self.inner.get_pending_nullifiers().await
+    }
+
+    async fn get_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountState, TxInputsError> {
+        self.inner.get_account_state(account_id).await
+    }
+
+    async fn get_account_state_forks(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(RpoDigest, RpoDigest)>, TxInputsError> {
+        self.inner.get_account_state_forks(account_id).await
+    }
+
+    async fn get_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.inner.get_account_creation_block(account_id).await
+    }
+
+    async fn get_nullifier_root_history(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, RpoDigest)>, TxInputsError> {
+        self.inner.get_nullifier_root_history(from, to).await
+    }
+
+    async fn get_chain_stats(&self) -> Result<ChainStats, TxInputsError> {
+        self.inner.get_chain_stats().await
+    }
+
+    async fn apply_block_with_diff(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError> {
+        self.inner.apply_block_with_diff(block).await
+    }
+
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError> {
+        self.inner.apply_blocks(blocks).await
+    }
+
+    fn sync_blocks_from(
+        &self,
+        from: BlockNumber,
+    ) -> impl Stream<Item = Result<Block, TxInputsError>> + Send + 'static {
+        let interrupted = self.fails();
+        let interruption = interrupted.then(|| {
+            Err(TxInputsError::StoreUnavailable {
+                code: Code::Unavailable,
+                message: "store is restarting".to_string(),
+            })
+        });
+
+        self.inner
+            .sync_blocks_from(from)
+            .take(if interrupted { 1 } else { usize::MAX })
+            .chain(tokio_stream::iter(interruption))
+    }
+}
+
+/// Returns a store retrying the reads of `store` up to three times, without delay.
+fn retrying(store: FlakyStore) -> RetryingStore<FlakyStore> {
+    RetryingStore::new(store, 3, Backoff::new(Duration::ZERO, Duration::ZERO))
+}
+
+#[tokio::test]
+async fn retrying_store_retries_reads_until_the_store_is_available() {
+    let account = MockPrivateAccount::<3>::from(1);
+    let mock_store = || {
+        MockStoreSuccessBuilder::from_accounts([(account.id, account.states[0])].into_iter())
+            .build()
+    };
+    let tx =
+        MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1]).build();
+
+    let store = retrying(FlakyStore::new(mock_store(), 2));
+    let tx_inputs = store.get_tx_inputs(&tx).await.unwrap();
+    assert_eq!(tx_inputs.account_hash, Some(account.states[0]));
+    assert_eq!(store.inner().calls(), 3);
+
+    let store = retrying(FlakyStore::new(mock_store(), 2));
+    let block_inputs = store
+        .get_block_inputs([account.id].into_iter(), std::iter::empty(), std::iter::empty())
+        .await
+        .unwrap();
+    assert!(block_inputs.accounts.contains_key(&account.id));
+    assert_eq!(store.inner().calls(), 3);
+
+    let store = retrying(FlakyStore::new(mock_store(), 2));
+    store.get_note_authentication_info(std::iter::empty()).await.unwrap();
+    assert_eq!(store.inner().calls(), 3);
+
+    // The last attempt fails too.
+    let store = retrying(FlakyStore::new(mock_store(), 3));
+    assert!(matches!(
+        store.get_tx_inputs(&tx).await,
+        Err(TxInputsError::StoreUnavailable { .. })
+    ));
+    assert_eq!(store.inner().calls(), 3);
+}
+
+#[tokio::test]
+async fn retrying_store_does_not_retry_malformed_responses_or_blocks() {
+    let account = MockPrivateAccount::<3>::from(1);
+    let mock_store = || {
+        MockStoreSuccessBuilder::from_accounts([(account.id, account.states[0])].into_iter())
+            .build()
+    };
+    let tx =
+        MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1]).build();
+
+    let store = retrying(FlakyStore::new(mock_store(), 2).with_malformed_responses());
+    assert!(matches!(
+        store.get_tx_inputs(&tx).await,
+        Err(TxInputsError::MalformedResponse(_))
+    ));
+    assert_eq!(store.inner().calls(), 1);
+
+    let store = retrying(FlakyStore::new(mock_store(), 0));
+    let block = MockBlockBuilder::new(&mock_store()).await.build();
+    assert!(store.apply_block(&block).await.unwrap_err().is_transient());
+    assert_eq!(store.inner().calls(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn retrying_store_recovers_from_programmed_failures() {
+    let account = MockPrivateAccount::<3>::from(1);
+    let mock_store =
+        MockStoreSuccessBuilder::from_accounts([(account.id, account.states[0])].into_iter())
+            .build();
+    mock_store
+        .set_latency("get_tx_inputs", Duration::from_millis(100))
+        .program_failures("get_tx_inputs", vec![Code::Unavailable, Code::Unavailable]);
+    let backoff = || Backoff::new(Duration::from_secs(1), Duration::from_secs(10)).with_rng_seed(7);
+    let store = RetryingStore::new(mock_store, 3, backoff());
+    let tx =
+        MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1]).build();
+
+    let started = Instant::now();
+    let tx_inputs = store.get_tx_inputs(&tx).await.unwrap();
+    assert_eq!(tx_inputs.account_hash, Some(account.states[0]));
+    // Three calls of 100ms each, and the delays of two retries drawn by an identical backoff.
+    let expected = backoff();
+    assert_eq!(
+        started.elapsed(),
+        Duration::from_millis(300) + expected.delay(0) + expected.delay(1)
+    );
+}
+
+#[tokio::test]
+async fn retrying_store_fails_fast_on_programmed_non_transient_failures() {
+    let account = MockPrivateAccount::<3>::from(1);
+    let mock_store =
+        MockStoreSuccessBuilder::from_accounts([(account.id, account.states[0])].into_iter())
+            .build();
+    mock_store
+        .program_failures("get_block_inputs", vec![Code::InvalidArgument])
+        .program_failures("apply_block", vec![Code::Unavailable]);
+    let store = RetryingStore::new(mock_store, 3, Backoff::new(Duration::ZERO, Duration::ZERO));
+
+    let get_block_inputs =
+        || store.get_block_inputs([account.id].into_iter(), std::iter::empty(), std::iter::empty());
+    assert!(matches!(
+        get_block_inputs().await,
+        Err(BlockInputsError::GrpcClientError { code: Code::InvalidArgument, .. })
+    ));
+    get_block_inputs().await.unwrap();
+
+    // Blocks are not re-sent, even if the store is unavailable.
+    let block = MockBlockBuilder::new(store.inner()).await.build();
+    assert!(matches!(
+        store.apply_block(&block).await,
+        Err(ApplyBlockError::StoreUnavailable { .. })
+    ));
+    assert_eq!(*store.inner().num_apply_block_called.read().await, 0);
+    store.apply_block(&block).await.unwrap();
+    assert_eq!(*store.inner().num_apply_block_called.read().await, 1);
+}
+
+#[test]
+fn backoff_delays_are_jittered_below_a_doubling_bound() {
+    let backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(500));
+
+    let bounds: Vec<_> = (0..4).map(|retry| backoff.bound(retry)).collect();
+    assert_eq!(bounds, [100, 200, 400, 500].map(Duration::from_millis));
+    for retry in 0..4 {
+        assert!(backoff.delay(retry) <= backoff.bound(retry));
+    }
+}
+
+// SYNC BLOCKS
+// ================================================================================================
+
+#[tokio::test]
+async fn sync_blocks_from_yields_contiguous_range_up_to_tip() {
+    let store = MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build();
+
+    let mut block_nums = Vec::new();
+    for _ in 0..3 {
+        let block = MockBlockBuilder::new(&store).await.build();
+        block_nums.push(BlockNumber::from(block.header().block_num()));
+        store.apply_block(&block).await.unwrap();
+    }
+
+    let synced: Vec<_> = store
+        .sync_blocks_from(block_nums[1])
+        .map(|block| BlockNumber::from(block.unwrap().header().block_num()))
+        .collect()
+        .await;
+    assert_eq!(synced, block_nums[1..]);
+
+    // Blocks after the tip are not known yet, so the stream is empty.
+    let synced: Vec<_> = store.sync_blocks_from(block_nums[2].next().unwrap()).collect().await;
+    assert!(synced.is_empty());
+}
+
+#[tokio::test]
+async fn resumable_sync_resumes_interrupted_streams_without_gaps_or_duplicates() {
+    let inner = MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build();
+
+    let mut block_nums = Vec::new();
+    for _ in 0..5 {
+        let block = MockBlockBuilder::new(&inner).await.build();
+        block_nums.push(BlockNumber::from(block.header().block_num()));
+        inner.apply_block(&block).await.unwrap();
+    }
+
+    // The first two streams are interrupted after one block each.
+    let store = Arc::new(FlakyStore::new(inner, 2));
+    let mut sync =
+        ResumableSync::new(store.clone(), block_nums[0]).with_reconnect_delay(Duration::ZERO);
+
+    let mut synced = Vec::new();
+    while let Some(block) = sync.next().await {
+        synced.push(BlockNumber::from(block.unwrap().header().block_num()));
+    }
+
+    assert_eq!(synced, block_nums);
+    assert_eq!(sync.last_delivered(), block_nums.last().copied());
+    assert_eq!(store.calls(), 3);
+}
+
+#[tokio::test]
+async fn resumable_sync_gives_up_after_max_reconnects() {
+    let inner = MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build();
+    let block = MockBlockBuilder::new(&inner).await.build();
+    let block_num = BlockNumber::from(block.header().block_num());
+    inner.apply_block(&block).await.unwrap();
+
+    let store = Arc::new(FlakyStore::new(inner, 10));
+    let mut sync = ResumableSync::new(store.clone(), block_num.next().unwrap())
+        .with_max_reconnects(2)
+        .with_reconnect_delay(Duration::ZERO);
+
+    // Past the tip, so each stream only carries the interruption.
+    let err = sync.next().await.unwrap().unwrap_err();
+    assert!(err.is_transient());
+    assert_eq!(store.calls(), 3);
+    assert_eq!(sync.last_delivered(), None);
+}
+
+// IN-PROCESS STORE
+// ================================================================================================
+
+/// Writes an empty genesis state to a fresh directory, and returns the config of a store using
+/// it.
+#[cfg(feature = "in-process")]
+fn in_process_store_config(name: &str) -> StoreConfig {
+    let dir =
+        std::env::temp_dir().join(format!("miden-in-process-store-{}-{name}", std::process::id()));
+    let config = StoreConfig {
+        database_filepath: dir.join("miden-store.sqlite3"),
+        genesis_filepath: dir.join("genesis.dat"),
+        blockstore_dir: dir.join("blocks"),
+        ..Default::default()
+    };
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&config.genesis_filepath, GenesisState::new(Vec::new(), 1, 1).to_bytes()).unwrap();
+
+    config
+}
+
+/// Builds the block following the chain tip of `store`, consuming `nullifiers` and creating
+/// `notes`, from the inputs returned by the store.
+#[cfg(feature = "in-process")]
+async fn next_in_process_block(
+    store: &InProcessStore,
+    nullifiers: Vec<Nullifier>,
+    notes: Vec<NoteBatch>,
+) -> Block {
+    let block_inputs = store
+        .get_block_inputs(std::iter::empty(), nullifiers.iter(), std::iter::empty())
+        .await
+        .unwrap();
+    let prev_header = block_inputs.block_header;
+    let nullifier_root = NullifierBlockWitness::new(
+        prev_header.nullifier_root(),
+        BlockNumber::from(prev_header.block_num() + 1),
+        block_inputs.nullifiers.into_iter().collect(),
+    )
+    .verify_and_compute_new_root()
+    .unwrap();
+    let chain_root = chain_peaks_after(block_inputs.chain_peaks, &prev_header).hash_peaks();
+
+    block_after(&prev_header, chain_root, nullifier_root, nullifiers, notes)
+}
+
+/// Returns the peaks of the chain MMR of `store`, which includes its chain tip.
+#[cfg(feature = "in-process")]
+async fn current_chain_peaks(store: &InProcessStore) -> MmrPeaks {
+    let block_inputs = store
+        .get_block_inputs(std::iter::empty(), std::iter::empty(), std::iter::empty())
+        .await
+        .unwrap();
+
+    chain_peaks_after(block_inputs.chain_peaks, &block_inputs.block_header)
+}
+
+/// Returns `chain_peaks` after adding the block of `header` to the chain MMR.
+///
+/// The peaks returned with the block inputs don't include the chain tip.
+#[cfg(feature = "in-process")]
+fn chain_peaks_after(chain_peaks: MmrPeaks, header: &BlockHeader) -> MmrPeaks {
+    let mut chain = PartialMmr::from_peaks(chain_peaks);
+    chain.add(header.hash(), false);
+    chain.peaks()
+}
+
+/// Builds `count` empty blocks following the genesis block of the fresh `store`.
+///
+/// The block at index `invalid`, if any, has a wrong nullifier root, so the store rejects it.
+#[cfg(feature = "in-process")]
+async fn empty_blocks_after_genesis(
+    store: &InProcessStore,
+    count: usize,
+    invalid: Option<usize>,
+) -> Vec<Block> {
+    let mut prev_header = store
+        .get_block_inputs(std::iter::empty(), std::iter::empty(), std::iter::empty())
+        .await
+        .unwrap()
+        .block_header;
+    let mut chain = Mmr::new();
+    chain.add(prev_header.hash());
+
+    (0..count)
+        .map(|index| {
+            let nullifier_root = if invalid == Some(index) {
+                Digest::default()
+            } else {
+                prev_header.nullifier_root()
+            };
+            let block = block_after(
+                &prev_header,
+                chain.peaks().hash_peaks(),
+                nullifier_root,
+                Vec::new(),
+                Vec::new(),
+            );
+            chain.add(block.hash());
+            prev_header = block.header();
+
+            block
+        })
+        .collect()
+}
+
+/// Builds the block following `prev_header`, consuming `nullifiers` and creating `notes`.
+#[cfg(feature = "in-process")]
+fn block_after(
+    prev_header: &BlockHeader,
+    chain_root: Digest,
+    nullifier_root: Digest,
+    nullifiers: Vec<Nullifier>,
+    notes: Vec<NoteBatch>,
+) -> Block {
+    let header = BlockHeader::new(
+        prev_header.version(),
+        prev_header.hash(),
+        prev_header.block_num() + 1,
+        chain_root,
+        prev_header.account_root(),
+        nullifier_root,
+        note_created_smt_from_note_batches(notes.iter()).root(),
+        compute_tx_hash(std::iter::empty()),
+        prev_header.kernel_root(),
+        Digest::default(),
+        prev_header.timestamp() + 1,
+    );
+
+    Block::new(header, Vec::new(), notes, nullifiers).unwrap()
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn in_process_store_serves_tx_inputs_and_applies_blocks() {
+    let config = in_process_store_config("tx-inputs");
+    let store = InProcessStore::load_in_process(&config).await.unwrap();
+
+    let tx = MockProvenTxBuilder::with_account_index(0).nullifiers_range(0..1).build();
+    let nullifier = tx.get_nullifiers().next().unwrap();
+
+    let tx_inputs = store.get_tx_inputs(&tx).await.unwrap();
+    assert_eq!(tx_inputs.account_hash, None);
+    assert_eq!(tx_inputs.nullifiers, BTreeMap::from([(nullifier, None)]));
+    assert_eq!(tx_inputs.current_block_height, BlockNumber::GENESIS);
+
+    let block = next_in_process_block(&store, vec![nullifier], Vec::new()).await;
+    let block_num = block.header().block_num();
+    store.apply_block(&block).await.unwrap();
+
+    let tx_inputs = store.get_tx_inputs(&tx).await.unwrap();
+    assert_eq!(tx_inputs.nullifiers, BTreeMap::from([(nullifier, NonZeroU32::new(block_num))]));
+    assert_eq!(tx_inputs.current_block_height, BlockNumber::from(block_num));
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn note_spend_block_is_returned_for_spent_nullifiers_only() {
+    let config = in_process_store_config("note-spend-block");
+    let store = InProcessStore::load_in_process(&config).await.unwrap();
+
+    let spent = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+    let unspent = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(2)]);
+    let block = next_in_process_block(&store, vec![spent], Vec::new()).await;
+    let block_num = block.header().block_num();
+    store.apply_block(&block).await.unwrap();
+
+    assert_eq!(store.get_note_spend_block(spent).await, Ok(Some(BlockNumber::from(block_num))));
+    assert_eq!(store.get_note_spend_block(unspent).await, Ok(None));
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn corrupt_note_spend_block_is_an_error() {
+    let corrupt = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+    let unspent = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(2)]);
+    let value = [Felt::new(u64::from(u32::MAX) + 1), ZERO, ZERO, ZERO];
+    let store = DefaultStore::from_client(Shard::from_leaves([(corrupt, value)]).client());
+
+    assert!(matches!(
+        store.get_note_spend_block(corrupt).await,
+        Err(TxInputsError::NullifierSpendError { nullifier, .. }) if nullifier == corrupt
+    ));
+    assert_eq!(store.get_note_spend_block(unspent).await, Ok(None));
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn notes_without_authentication_info_are_reported_missing() {
+    let config = in_process_store_config("missing-notes");
+    let store = InProcessStore::load_in_process(&config).await.unwrap();
+
+    let notes = [mock_output_note(1), mock_output_note(2)];
+    let block = next_in_process_block(&store, Vec::new(), vec![notes.to_vec()]).await;
+    store.apply_block(&block).await.unwrap();
+    let missing = mock_output_note(3).id();
+    let requested = [notes[0].id(), missing, notes[1].id()];
+
+    let (info, missing_notes) =
+        store.get_note_authentication_info_and_missing(requested.iter()).await.unwrap();
+
+    assert_eq!(info.note_ids(), BTreeSet::from([notes[0].id(), notes[1].id()]));
+    assert_eq!(missing_notes, vec![missing]);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn sequence_of_blocks_is_applied_in_one_request() {
+    let config = in_process_store_config("apply-blocks");
+    let store = InProcessStore::load_in_process(&config).await.unwrap();
+
+    let blocks = empty_blocks_after_genesis(&store, 3, None).await;
+    let round = store.enter_round();
+    assert_eq!(store.apply_blocks(&blocks).await, Ok(BlockNumber::from(3)));
+    assert_eq!(round.count(), 1);
+
+    let block_inputs = store
+        .get_block_inputs(std::iter::empty(), std::iter::empty(), std::iter::empty())
+        .await
+        .unwrap();
+    assert_eq!(block_inputs.block_header, blocks[2].header());
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn applying_blocks_stops_at_rejected_block() {
+    let config = in_process_store_config("apply-blocks-rejected");
+    let store = InProcessStore::load_in_process(&config).await.unwrap();
+
+    let blocks = empty_blocks_after_genesis(&store, 3, Some(1)).await;
+    assert!(matches!(
+        store.apply_blocks(&blocks).await,
+        Err(ApplyBlockError::BlockRejected { last_applied_block_num, .. })
+            if last_applied_block_num == BlockNumber::from(1)
+    ));
+
+    // The block before the rejected one stays applied.
+    let block_inputs = store
+        .get_block_inputs(std::iter::empty(), std::iter::empty(), std::iter::empty())
+        .await
+        .unwrap();
+    assert_eq!(block_inputs.block_header, blocks[0].header());
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn missing_note_is_returned_once_authenticated() {
+    let config = in_process_store_config("notes-authenticated-since");
+    let store = InProcessStore::load_in_process(&config).await.unwrap();
+
+    let note = mock_output_note(1);
+    let note_id = note.id();
+    assert!(store
+        .notes_authenticated_since(&[note_id], BlockNumber::GENESIS)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let block = next_in_process_block(&store, Vec::new(), vec![vec![note]]).await;
+    let block_num = BlockNumber::from(block.header().block_num());
+    store.apply_block(&block).await.unwrap();
+
+    // The note was created after the genesis block, but not after the block which created it.
+    assert_eq!(
+        store.notes_authenticated_since(&[note_id], BlockNumber::GENESIS).await.unwrap(),
+        [note_id]
+    );
+    assert!(store.notes_authenticated_since(&[note_id], block_num).await.unwrap().is_empty());
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn chunked_block_inputs_equal_single_shot_block_inputs() {
+    let config = in_process_store_config("chunked-block-inputs");
+    let store = InProcessStore::load_in_process(&config).await.unwrap();
+
+    let notes: Vec<_> = (1..=3).map(mock_output_note).collect();
+    let note_ids: Vec<_> = notes.iter().map(|note| note.id()).collect();
+    let nullifiers: Vec<_> =
+        (1..=4).map(|i| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)])).collect();
+    let account_ids: Vec<_> = (0..3).map(mock_account_id).collect();
+
+    // Half of the nullifiers are consumed, and the notes are created in a block below the tip.
+    let block = next_in_process_block(&store, nullifiers[..2].to_vec(), vec![notes]).await;
+    store.apply_block(&block).await.unwrap();
+    let block = next_in_process_block(&store, Vec::new(), Vec::new()).await;
+    store.apply_block(&block).await.unwrap();
+
+    let expected = store
+        .get_block_inputs(account_ids.iter().copied(), nullifiers.iter(), note_ids.iter())
+        .await
+        .unwrap();
+
+    // The 10 accounts, nullifiers and notes are requested in chunks of 3, 3, 3 and 1 items.
+    let store = store.with_block_inputs_chunking(NonZeroUsize::new(3).unwrap());
+    let calls_before = store.metrics_snapshot().methods["GetBlockInputs"].calls;
+    let chunked = store
+        .get_block_inputs(account_ids.iter().copied(), nullifiers.iter(), note_ids.iter())
+        .await
+        .unwrap();
+    assert_eq!(store.metrics_snapshot().methods["GetBlockInputs"].calls - calls_before, 4);
+
+    assert_eq!(chunked.block_header, expected.block_header);
+    assert_eq!(chunked.chain_peaks, expected.chain_peaks);
+    assert_eq!(chunked.accounts, expected.accounts);
+    assert_eq!(chunked.nullifiers, expected.nullifiers);
+    assert_eq!(
+        chunked.found_unauthenticated_notes.note_proofs,
+        expected.found_unauthenticated_notes.note_proofs
+    );
+    let proven_blocks = |block_inputs: &BlockInputs| {
+        block_inputs
+            .found_unauthenticated_notes
+            .block_proofs
+            .iter()
+            .map(|proof| (proof.block_header.block_num(), proof.mmr_path.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(proven_blocks(&chunked), proven_blocks(&expected));
+    assert_eq!(proven_blocks(&chunked).len(), 1);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn account_creation_block_is_returned_for_known_accounts() {
+    let config = in_process_store_config("account-creation-block");
+    let store = InProcessStore::load_in_process(&config).await.unwrap();
+
+    let account_id = mock_account_id(1);
+    let account_hash = Digest::from([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+    assert_eq!(store.get_account_creation_block(account_id).await, Ok(None));
+
+    // Rebuild the next block so it creates the account, on top of the empty genesis account tree.
+    let block = next_in_process_block(&store, Vec::new(), Vec::new()).await;
+    let header = block.header();
+    let account_root =
+        SimpleSmt::<ACCOUNT_TREE_DEPTH>::with_leaves([(account_id.into(), account_hash.into())])
+            .unwrap()
+            .root();
+    let header = BlockHeader::new(
+        header.version(),
+        header.prev_hash(),
+        header.block_num(),
+        header.chain_root(),
+        account_root,
+        header.nullifier_root(),
+        header.note_root(),
+        header.tx_hash(),
+        header.kernel_root(),
+        header.proof_hash(),
+        header.timestamp(),
+    );
+    let update =
+        BlockAccountUpdate::new(account_id, account_hash, AccountUpdateDetails::Private, vec![]);
+    let block = Block::new(header, vec![update], Vec::new(), Vec::new()).unwrap();
+    store.apply_block(&block).await.unwrap();
+
+    assert_eq!(
+        store.get_account_creation_block(account_id).await,
+        Ok(Some(header.block_num().into()))
+    );
+    assert_eq!(store.get_account_creation_block(mock_account_id(2)).await, Ok(None));
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[cfg(feature = "persistent-cache")]
+#[tokio::test]
+async fn persistent_cache_serves_entries_after_restart() {
+    let config = in_process_store_config("persistent-cache");
+    let cache_path = config.genesis_filepath.with_file_name("cache.sqlite3");
+    let store = InProcessStore::load_in_process(&config)
+        .await
+        .unwrap()
+        .with_persistent_cache(PersistentCache::open(&cache_path).unwrap());
+
+    let note = mock_output_note(1);
+    let note_id = note.id();
+    let block = next_in_process_block(&store, Vec::new(), vec![vec![note]]).await;
+    store.apply_block(&block).await.unwrap();
+    let info = store.get_note_authentication_info([note_id].iter()).await.unwrap();
+    drop(store);
+
+    // The restarted store can't reach any store server, so the info must come from the cache.
+    let store =
+        lazy_default_store().with_persistent_cache(PersistentCache::open(&cache_path).unwrap());
+    let cached_info = store.get_note_authentication_info([note_id].iter()).await.unwrap();
+    assert_eq!(cached_info.note_proofs, info.note_proofs);
+    assert_eq!(cached_info.block_proofs[0].block_header, info.block_proofs[0].block_header);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+// NULLIFIER ORIGINS
+// ================================================================================================
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn origins_of_dropped_transactions_do_not_grow_the_record() {
+    let config = in_process_store_config("nullifier-origins");
+    let store = InProcessStore::load_in_process(&config)
+        .await
+        .unwrap()
+        .with_nullifier_origins()
+        .with_max_recorded_entries(2);
+
+    // None of the transactions is ever included in a block.
+    let txs: Vec<_> = (0..4)
+        .map(|i| MockProvenTxBuilder::with_account_index(0).nullifiers_range(i..i + 1).build())
+        .collect();
+    for tx in &txs {
+        store.get_tx_inputs(tx).await.unwrap();
+    }
+
+    assert_eq!(store.applier.nullifier_origins.len(), 2);
+    let nullifier = txs[3].get_nullifiers().next().unwrap();
+    assert_eq!(store.applier.nullifier_origins.get(&nullifier), Some(txs[3].id()));
+    let nullifier = txs[0].get_nullifiers().next().unwrap();
+    assert_eq!(store.applier.nullifier_origins.get(&nullifier), None);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+// ACCOUNT VERSIONS
+// ================================================================================================
+
+/// Builds the block following the chain tip of `store`, in which the transaction `tx_id` updates
+/// the account `account_id` to `account_hash`.
+#[cfg(feature = "in-process")]
+async fn next_in_process_block_updating_account(
+    store: &InProcessStore,
+    account_id: AccountId,
+    account_hash: Digest,
+    tx_id: TransactionId,
+) -> Block {
+    let block_inputs = store
+        .get_block_inputs([account_id].into_iter(), std::iter::empty(), std::iter::empty())
+        .await
+        .unwrap();
+    let prev_header = block_inputs.block_header;
+    let account_root = block_inputs.accounts[&account_id]
+        .proof
+        .compute_root(u64::from(account_id), account_hash)
+        .unwrap();
+    let chain_root = chain_peaks_after(block_inputs.chain_peaks, &prev_header).hash_peaks();
+    let header = BlockHeader::new(
+        prev_header.version(),
+        prev_header.hash(),
+        prev_header.block_num() + 1,
+        chain_root,
+        account_root,
+        prev_header.nullifier_root(),
+        note_created_smt_from_note_batches(std::iter::empty()).root(),
+        compute_tx_hash([(tx_id, account_id)].into_iter()),
+        prev_header.kernel_root(),
+        Digest::default(),
+        prev_header.timestamp() + 1,
+    );
+    let update = BlockAccountUpdate::new(
+        account_id,
+        account_hash,
+        AccountUpdateDetails::Private,
+        vec![tx_id],
+    );
+
+    Block::new(header, vec![update], Vec::new(), Vec::new()).unwrap()
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn blocks_with_outdated_account_versions_are_rejected() {
+    let config = in_process_store_config("account-versions");
+    let api = Arc::new(StoreApi::load(&config).await.unwrap());
+    let store = InProcessStore::in_process(api.clone()).with_account_versions();
+    let other_producer = InProcessStore::in_process(api);
+
+    let tx = MockProvenTxBuilder::with_account_index(0).nullifiers_range(0..1).build();
+    let account_id = tx.account_id();
+    let new_hash = |i: u64| Digest::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+    let tx_inputs = store.get_tx_inputs(&tx).await.unwrap();
+    assert!(tx_inputs.account_version.is_some());
+
+    // The account changes after the transaction read it.
+    let concurrent_tx_id = TransactionId::from(new_hash(100));
+    let block = next_in_process_block_updating_account(
+        &other_producer,
+        account_id,
+        new_hash(1),
+        concurrent_tx_id,
+    )
+    .await;
+    other_producer.apply_block(&block).await.unwrap();
+
+    let block =
+        next_in_process_block_updating_account(&store, account_id, new_hash(2), tx.id()).await;
+    assert_eq!(
+        store.apply_block(&block).await,
+        Err(ApplyBlockError::AccountVersionConflict(account_id))
+    );
+
+    // A transaction reading the current version is applied.
+    let tx = MockProvenTxBuilder::with_account_index(0).nullifiers_range(1..2).build();
+    store.get_tx_inputs(&tx).await.unwrap();
+    let block =
+        next_in_process_block_updating_account(&store, account_id, new_hash(2), tx.id()).await;
+    store.apply_block(&block).await.unwrap();
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn versions_of_dropped_transactions_do_not_grow_the_record() {
+    let config = in_process_store_config("dropped-account-versions");
+    let store = InProcessStore::load_in_process(&config)
+        .await
+        .unwrap()
+        .with_account_versions()
+        .with_max_recorded_entries(2);
+
+    // None of the transactions is ever included in a block.
+    let txs: Vec<_> = (0..4)
+        .map(|i| MockProvenTxBuilder::with_account_index(0).nullifiers_range(i..i + 1).build())
+        .collect();
+    for tx in &txs {
+        store.get_tx_inputs(tx).await.unwrap();
+    }
+
+    assert_eq!(store.applier.account_versions.len(), 2);
+    assert!(store.applier.account_versions.get(&txs[3].id()).is_some());
+    assert_eq!(store.applier.account_versions.get(&txs[0].id()), None);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+// ROOT CONSISTENCY
+// ================================================================================================
+
+/// A store with the given root consistency policy, whose note authentication cache holds the
+/// proofs of a note from before a concurrently built block was applied.
+#[cfg(feature = "in-process")]
+struct StaleNoteAuth {
+    store: InProcessStore,
+    note_id: NoteId,
+    /// Peaks of the chain the cached proofs open against
+    stale_peaks: MmrPeaks,
+    /// Peaks of the chain after the concurrently built block was applied
+    chain_peaks: MmrPeaks,
+    config: StoreConfig,
+}
+
+#[cfg(feature = "in-process")]
+impl StaleNoteAuth {
+    async fn new(name: &str, policy: RootConsistencyPolicy) -> Self {
+        let config = in_process_store_config(name);
+        let api = Arc::new(StoreApi::load(&config).await.unwrap());
+        let builder = InProcessStore::in_process(api.clone());
+        let store = InProcessStore::in_process(api)
+            .with_note_auth_cache(NoteAuthCache::builder().build())
+            .with_root_consistency_policy(policy);
+
+        let note = mock_output_note(1);
+        let note_id = note.id();
+        let block = next_in_process_block(&builder, Vec::new(), vec![vec![note]]).await;
+        builder.apply_block(&block).await.unwrap();
+
+        let stale_peaks = current_chain_peaks(&builder).await;
+        let info = store.get_note_authentication_info_at([note_id].iter(), &stale_peaks).await;
+        assert_eq!(info.unwrap().block_proofs[0].chain_length as usize, stale_peaks.num_leaves());
+
+        // The block is applied through another store, so the cache of `store` isn't cleared.
+        let block = next_in_process_block(&builder, Vec::new(), Vec::new()).await;
+        builder.apply_block(&block).await.unwrap();
+        let chain_peaks = current_chain_peaks(&builder).await;
+
+        Self {
+            store,
+            note_id,
+            stale_peaks,
+            chain_peaks,
+            config,
+        }
+    }
+
+    async fn get_note_authentication_info(&self) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        self.store
+            .get_note_authentication_info_at([self.note_id].iter(), &self.chain_peaks)
+            .await
+    }
+}
+
+#[cfg(feature = "in-process")]
+impl Drop for StaleNoteAuth {
+    fn drop(&mut self) {
+        fs::remove_dir_all(self.config.genesis_filepath.parent().unwrap()).unwrap();
+    }
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn stale_note_auth_info_is_rejected() {
+    let stale = StaleNoteAuth::new("root-consistency-reject", RootConsistencyPolicy::Reject).await;
+
+    assert_eq!(
+        stale.get_note_authentication_info().await.unwrap_err(),
+        NotePathsError::RootMismatch {
+            expected: stale.chain_peaks.hash_peaks(),
+            block_num: 1,
+        }
+    );
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn stale_note_auth_info_is_refetched() {
+    let stale =
+        StaleNoteAuth::new("root-consistency-refetch", RootConsistencyPolicy::Refetch).await;
+
+    let info = stale.get_note_authentication_info().await.unwrap();
+    assert_eq!(info.block_proofs[0].chain_length as usize, stale.chain_peaks.num_leaves());
+
+    // The refetched proofs replace the cached ones.
+    let info = stale.store.get_note_authentication_info([stale.note_id].iter()).await.unwrap();
+    assert_eq!(info.block_proofs[0].chain_length as usize, stale.chain_peaks.num_leaves());
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn stale_note_auth_info_is_used_with_a_warning() {
+    let stale =
+        StaleNoteAuth::new("root-consistency-warn", RootConsistencyPolicy::WarnAndUse).await;
+
+    let info = stale.get_note_authentication_info().await.unwrap();
+    assert_eq!(info.block_proofs[0].chain_length as usize, stale.stale_peaks.num_leaves());
+}
+
+// HEDGED READS
+// ================================================================================================
+
+/// Returns the chain stats of a store with `num_blocks` blocks and default roots.
+fn chain_stats(num_blocks: u32) -> GetChainStatsResponse {
+    GetChainStatsResponse {
+        num_blocks,
+        account_root: Some(Digest::default().into()),
+        nullifier_root: Some(Digest::default().into()),
+        chain_root: Some(Digest::default().into()),
+        ..Default::default()
+    }
+}
+
+/// Returns transaction inputs of the requested account with a default hash, served at
+/// `block_height`.
+fn tx_inputs_at(
+    request: GetTransactionInputsRequest,
+    block_height: u32,
+) -> GetTransactionInputsResponse {
+    GetTransactionInputsResponse {
+        account_state: Some(AccountTransactionInputRecord {
+            account_id: request.account_id,
+            account_hash: Some(Digest::default().into()),
+        }),
+        block_height,
+        ..Default::default()
+    }
+}
+
+/// Returns a client answering chain stats, block inputs and transaction inputs requests after a
+/// fixed delay, reporting `num_blocks` to identify which client answered.
+fn delayed_client(delay: Duration, num_blocks: u32) -> FakeClient {
+    FakeClient::default()
+        .on_async(move |request: Request<GetTransactionInputsRequest>| async move {
+            tokio::time::sleep(delay).await;
+            Ok(tx_inputs_at(request.into_inner(), num_blocks - 1))
+        })
+        .on_async(move |_: Request<GetBlockInputsRequest>| async move {
+            tokio::time::sleep(delay).await;
+            Ok(GetBlockInputsResponse {
+                block_header: Some(miden_node_proto::generated::block::BlockHeader {
+                    block_num: num_blocks - 1,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        })
+        .on_async(move |_: Request<GetChainStatsRequest>| async move {
+            tokio::time::sleep(delay).await;
+            Ok(chain_stats(num_blocks))
+        })
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_primary_read_is_beaten_by_hedged_replica() {
+    let primary = delayed_client(Duration::from_secs(10), 1);
+    let replica = delayed_client(Duration::from_millis(10), 2);
+
+    // Without hedging, the read waits for the slow primary.
+    let store = DefaultStore::from_client(primary.clone()).with_replica(replica.clone());
+    assert_eq!(store.get_chain_stats().await.unwrap().num_blocks, 1);
+
+    // The hedge delay is measured on the clock of the store, so the primary wins as long as the
+    // clock stands still.
+    let clock = Arc::new(MockClock::new());
+    let store = Arc::new(
+        DefaultStore::from_client(primary)
+            .with_hedged_reads(Duration::from_millis(100))
+            .with_replica(replica)
+            .with_clock(clock.clone()),
+    );
+    assert_eq!(store.get_chain_stats().await.unwrap().num_blocks, 1);
+
+    let read = tokio::spawn({
+        let store = Arc::clone(&store);
+        async move { store.get_chain_stats().await }
+    });
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    clock.advance(Duration::from_millis(100));
+    let started = Instant::now();
+    assert_eq!(read.await.unwrap().unwrap().num_blocks, 2);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn tx_inputs_are_requested_concurrently_in_order() {
+    let client = delayed_client(Duration::from_millis(100), 1);
+    let store = DefaultStore::from_client(client);
+    let txs: Vec<_> = (0..8)
+        .map(|index| MockProvenTxBuilder::with_account_index(index).build())
+        .collect();
+
+    let started = Instant::now();
+    let tx_inputs = store
+        .get_tx_inputs_concurrent(&txs, NonZeroUsize::new(4).unwrap())
+        .await
+        .unwrap();
+
+    // Two rounds of four requests take 200ms, eight sequential requests would take 800ms.
+    assert!(started.elapsed() < Duration::from_millis(300));
+    let account_ids: Vec<_> = tx_inputs.iter().map(|tx_inputs| tx_inputs.account_id).collect();
+    let expected: Vec<_> = txs.iter().map(|tx| tx.account_id()).collect();
+    assert_eq!(account_ids, expected);
+}
+
+// HEALTH CHECK
+// ================================================================================================
+
+#[tokio::test]
+async fn health_check_reports_chain_tip_of_the_store() {
+    let client = delayed_client(Duration::ZERO, 5);
+    let store = DefaultStore::from_client(client).with_response_cache(Duration::from_secs(60));
+
+    let status = store.check_health().await.unwrap();
+    assert_eq!(
+        status,
+        StoreStatus {
+            chain_tip: BlockNumber::from(4),
+            accepting_writes: Some(true)
+        }
+    );
+
+    // Health checks always reach the store.
+    store.check_health().await.unwrap();
+    assert_eq!(store.metrics_snapshot().methods["GetBlockInputs"].calls, 2);
+
+    // The default implementation reports the chain tip of the block inputs, but can't tell
+    // whether the store accepts blocks.
+    let store = MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build();
+    let status = store.check_health().await.unwrap();
+    assert_eq!(status.chain_tip, BlockNumber::from(1));
+    assert_eq!(status.accepting_writes, None);
+}
+
+#[tokio::test]
+async fn next_block_number_follows_the_chain_tip() {
+    let client = delayed_client(Duration::ZERO, 5);
+    let store = DefaultStore::from_client(client);
+    assert_eq!(store.next_block_number().await.unwrap(), BlockNumber::from(5));
+
+    // The default implementation counts the blocks of the chain, including the genesis block.
+    let mut chain_mmr = Mmr::new();
+    for i in 0..3 {
+        chain_mmr.add(Digest::from([Felt::new(i), ZERO, ZERO, ZERO]));
+    }
+    let store = MockStoreSuccessBuilder::from_accounts(std::iter::empty())
+        .initial_chain_mmr(chain_mmr)
+        .build();
+    assert_eq!(store.next_block_number().await.unwrap(), BlockNumber::from(3));
+}
+
+#[test]
+fn next_block_number_overflows_after_the_largest_block_number() {
+    assert_eq!(
+        next_block_number_after(BlockNumber::from(u32::MAX - 1)).unwrap(),
+        BlockNumber::MAX
+    );
+    assert!(matches!(
+        next_block_number_after(BlockNumber::MAX),
+        Err(TxInputsError::BlockNumberOverflow(BlockNumber::MAX))
+    ));
+}
+
+// ROUND TRIPS
+// ================================================================================================
+
+#[tokio::test]
+async fn round_trips_of_a_round_are_counted() {
+    let store = DefaultStore::from_client(delayed_client(Duration::ZERO, 1));
+
+    // Requests sent before the round don't count.
+    store.get_chain_stats().await.unwrap();
+
+    let round = store.enter_round();
+    for _ in 0..3 {
+        store.get_chain_stats().await.unwrap();
+    }
+    assert_eq!(round.count(), 3);
+
+    // Nor do requests sent after the round ended.
+    drop(round);
+    store.get_chain_stats().await.unwrap();
+    assert_eq!(store.enter_round().count(), 0);
+}
+
+// CONNECTION STATE
+// ================================================================================================
+
+/// Failure of a fake store, which fails its requests with the given status while it is set.
+#[derive(Clone, Default)]
+struct Failure(Arc<Mutex<Option<Status>>>);
+
+impl Failure {
+    fn fail_with(&self, failure: Option<Status>) {
+        *self.0.lock().unwrap() = failure;
+    }
+
+    /// Returns a client answering chain stats and note authentication info requests and
+    /// accepting blocks, which fail while the failure is set.
+    ///
+    /// Note authentication info requests clear the failure when failing, so only the first of
+    /// them fails.
+    fn client(&self) -> FakeClient {
+        let (apply, note_auth, stats) = (self.clone(), self.clone(), self.clone());
+        FakeClient::default()
+            .on(move |_: Request<ApplyBlockRequest>| match apply.0.lock().unwrap().clone() {
+                Some(failure) => Err(failure),
+                None => Ok(ApplyBlockResponse::default()),
+            })
+            .on(move |_: Request<GetNoteAuthenticationInfoRequest>| {
+                match note_auth.0.lock().unwrap().take() {
+                    Some(failure) => Err(failure),
+                    None => {
+                        Ok(GetNoteAuthenticationInfoResponse { proofs: Some(Default::default()) })
+                    },
+                }
+            })
+            .on(move |_: Request<GetChainStatsRequest>| match stats.0.lock().unwrap().clone() {
+                Some(failure) => Err(failure),
+                None => Ok(chain_stats(1)),
+            })
+    }
+}
+
+#[tokio::test]
+async fn connection_state_reflects_recent_call_outcomes() {
+    let failure = Failure::default();
+    failure.fail_with(Some(Status::unavailable("store is down")));
+    let store = DefaultStore::from_client(failure.client());
+    assert_eq!(store.connection_state(), ConnectionState::Connecting);
+
+    store.get_chain_stats().await.unwrap_err();
+    assert_eq!(store.connection_state(), ConnectionState::TransientFailure);
+
+    failure.fail_with(None);
+    store.get_chain_stats().await.unwrap();
+    assert_eq!(store.connection_state(), ConnectionState::Connected);
+
+    // Errors returned by a reachable store don't indicate a connection failure.
+    store.get_pending_nullifiers().await.unwrap_err();
+    assert_eq!(store.connection_state(), ConnectionState::Connected);
+}
+
+#[tokio::test]
+async fn failed_channel_is_rebuilt_after_repeated_failures() {
+    // The channel fails permanently, while the store comes back after a restart.
+    let channel = Failure::default();
+    channel.fail_with(Some(Status::unavailable("channel failed")));
+    let restarted = Failure::default();
+    restarted.fail_with(Some(Status::unavailable("store is restarting")));
+
+    let clock = Arc::new(MockClock::new());
+    let reconnects = Arc::new(AtomicUsize::new(0));
+    let connect = {
+        let restarted = restarted.clone();
+        let reconnects = reconnects.clone();
+        move || {
+            reconnects.fetch_add(1, Ordering::SeqCst);
+            restarted.client()
+        }
+    };
+    let store = DefaultStore::from_client(channel.client())
+        .with_clock(clock.clone())
+        .with_reconnect(connect, Duration::from_secs(10));
+
+    for _ in 0..3 {
+        store.get_chain_stats().await.unwrap_err();
+    }
+    assert_eq!(reconnects.load(Ordering::SeqCst), 1);
+    assert_eq!(store.connection_state(), ConnectionState::Connecting);
+
+    // The channel is not rebuilt again within the cooldown.
+    for _ in 0..3 {
+        store.get_chain_stats().await.unwrap_err();
+    }
+    assert_eq!(reconnects.load(Ordering::SeqCst), 1);
+
+    clock.advance(Duration::from_secs(10));
+    store.get_chain_stats().await.unwrap_err();
+    assert_eq!(reconnects.load(Ordering::SeqCst), 2);
+
+    restarted.fail_with(None);
+    store.get_chain_stats().await.unwrap();
+    assert_eq!(store.connection_state(), ConnectionState::Connected);
+}
+
+// RETRY POLICY
+// ================================================================================================
+
+#[tokio::test]
+async fn custom_retry_classifier_overrides_default_classification() {
+    let note_id = mock_output_note(1).id();
+    let shard_migrating = || Status::failed_precondition("shard is migrating");
+    let retrying = |store| {
+        RetryingStore::new(
+            store,
+            3,
+            Backoff::new(Duration::from_millis(1), Duration::from_millis(1)),
+        )
+    };
+
+    // By default, the application-specific status is not retried.
+    let failure = Failure::default();
+    failure.fail_with(Some(shard_migrating()));
+    let store = retrying(DefaultStore::from_client(failure.client()));
+    let err = store.get_note_authentication_info([note_id].iter()).await.unwrap_err();
+    assert!(!err.is_transient());
+
+    // The classifier retries it, and falls back to the default classification otherwise.
+    let policy = RetryPolicy::with_classifier(|status| match status.code() {
+        Code::FailedPrecondition if status.message() == "shard is migrating" => {
+            RetryDecision::Retry
+        },
+        Code::DeadlineExceeded => RetryDecision::DoNotRetry,
+        _ => RetryPolicy::default_decision(status),
+    });
+    failure.fail_with(Some(shard_migrating()));
+    let store = retrying(DefaultStore::from_client(failure.client()).with_retry_policy(policy));
+    let info = store.get_note_authentication_info([note_id].iter()).await.unwrap();
+    assert!(info.note_proofs.is_empty());
+
+    // Statuses which are retried by default can be excluded, keeping their message.
+    failure.fail_with(Some(Status::deadline_exceeded("store is busy")));
+    let err = store.get_note_authentication_info([note_id].iter()).await.unwrap_err();
+    assert!(!err.is_transient());
+    assert!(err.to_string().contains("store is busy"));
+}
+
+// GRPC ERROR DETAILS
+// ================================================================================================
+
+#[tokio::test]
+async fn error_details_from_trailers_are_surfaced() {
+    let error_info = GrpcErrorDetail {
+        type_url: "type.googleapis.com/google.rpc.ErrorInfo".to_string(),
+        value: vec![1, 2, 3],
+    };
+    // Encoded `google.rpc.Status`, whose details are field 3.
+    let mut status_details = Vec::new();
+    prost::encoding::message::encode(3, &error_info, &mut status_details);
+
+    let failure = Failure::default();
+    failure.fail_with(Some(Status::with_details(
+        Code::FailedPrecondition,
+        "account is locked",
+        status_details.into(),
+    )));
+    let store = DefaultStore::from_client(failure.client());
+
+    let err = store.get_chain_stats().await.unwrap_err();
+    assert_eq!(
+        err,
+        TxInputsError::GrpcErrorWithDetails(GrpcErrorDetails {
+            code: Code::FailedPrecondition,
+            message: "account is locked".to_string(),
+            details: vec![error_info],
+        })
+    );
+    assert!(err.to_string().contains("type.googleapis.com/google.rpc.ErrorInfo"));
+
+    // Errors without details keep the plain message.
+    failure.fail_with(Some(Status::failed_precondition("account is locked")));
+    let err = store.get_chain_stats().await.unwrap_err();
+    assert_eq!(
+        err,
+        TxInputsError::GrpcClientError {
+            code: Code::FailedPrecondition,
+            message: "account is locked".to_string(),
+        }
+    );
+    assert_eq!(err.to_string(), "gRPC client failed with FailedPrecondition: account is locked");
+}
+
+#[tokio::test]
+async fn stale_blocks_are_reported_with_the_expected_block_number() {
+    let stale_block = GrpcErrorDetail {
+        type_url: STALE_BLOCK_DETAIL_TYPE_URL.to_string(),
+        value: StaleBlockDetail { expected: 7, got: 5 }.encode_to_vec(),
+    };
+    let mut status_details = Vec::new();
+    prost::encoding::message::encode(3, &stale_block, &mut status_details);
+
+    let failure = Failure::default();
+    failure.fail_with(Some(Status::with_details(
+        Code::FailedPrecondition,
+        "block is stale",
+        status_details.into(),
+    )));
+    let store = DefaultStore::from_client(failure.client());
+    let mock_store = MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build();
+    let block = MockBlockBuilder::new(&mock_store).await.build();
+
+    let err = store.apply_block(&block).await.unwrap_err();
+    assert_eq!(err, ApplyBlockError::StaleBlock { expected: 7, got: 5 });
+
+    // Rejections without structured details fall back to the plain message.
+    failure.fail_with(Some(Status::failed_precondition("block is stale")));
+    let err = store.apply_block(&block).await.unwrap_err();
+    assert_eq!(
+        err,
+        ApplyBlockError::GrpcClientError {
+            code: Code::FailedPrecondition,
+            message: "block is stale".to_string(),
+        }
+    );
+
+    failure.fail_with(None);
+    store.apply_block(&block).await.unwrap();
+}
+
+#[test]
+fn store_errors_keep_the_status_code() {
+    let status = |code| Status::new(code, "store is busy");
+    let message = "store is busy".to_string();
+
+    for code in [Code::Unavailable, Code::DeadlineExceeded, Code::ResourceExhausted] {
+        assert_eq!(
+            ApplyBlockError::from(status(code)),
+            ApplyBlockError::StoreUnavailable { code, message: message.clone() }
+        );
+    }
+    for code in [Code::Unavailable, Code::DeadlineExceeded] {
+        assert_eq!(
+            BlockInputsError::from(status(code)),
+            BlockInputsError::StoreUnavailable { code, message: message.clone() }
+        );
+        assert_eq!(
+            NotePathsError::from(status(code)),
+            NotePathsError::StoreUnavailable { code, message: message.clone() }
+        );
+    }
+    assert_eq!(
+        BlockInputsError::from(status(Code::Internal)),
+        BlockInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: message.clone()
+        }
+    );
+    assert_eq!(
+        NotePathsError::from(status(Code::NotFound)),
+        NotePathsError::GrpcClientError { code: Code::NotFound, message }
+    );
+}
+
+// TENANTS
+// ================================================================================================
+
+/// Returns a client of a multi-tenant store answering chain stats requests, which rejects
+/// requests without a tenant.
+fn tenant_client() -> FakeClient {
+    FakeClient::default().on(|request: Request<GetChainStatsRequest>| {
+        request
+            .metadata()
+            .get(TENANT_METADATA_KEY)
+            .ok_or_else(|| Status::unauthenticated("request carries no tenant id"))?;
+        Ok(chain_stats(1))
+    })
+}
+
+/// Returns the tenants of the requests of type `M` received by `client`, skipping requests
+/// without one.
+fn tenants_of<M: 'static>(client: &FakeClient) -> Vec<String> {
+    client
+        .metadata::<M>()
+        .iter()
+        .filter_map(|metadata| metadata.get(TENANT_METADATA_KEY))
+        .map(|tenant| tenant.to_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn requests_carry_the_tenant_and_can_override_it() {
+    let client = tenant_client();
+
+    // A store requiring a tenant rejects requests without one.
+    let store = DefaultStore::from_client(client.clone());
+    let err = store.get_chain_stats().await.unwrap_err();
+    assert!(err.to_string().contains("request carries no tenant id"));
+
+    let store = store.with_tenant("acme".parse().unwrap());
+    store.get_chain_stats().await.unwrap();
+    scoped_to_tenant("globex".parse().unwrap(), store.get_chain_stats())
+        .await
+        .unwrap();
+    store.get_chain_stats().await.unwrap();
+
+    assert_eq!(tenants_of::<GetChainStatsRequest>(&client), ["acme", "globex", "acme"]);
+}
+
+#[tokio::test]
+async fn cached_responses_are_only_served_to_their_tenant() {
+    let client = tenant_client();
+    let store = DefaultStore::from_client(client.clone())
+        .with_tenant("acme".parse().unwrap())
+        .with_response_cache(Duration::from_secs(60));
+
+    store.get_chain_stats().await.unwrap();
+    store.get_chain_stats().await.unwrap();
+    for _ in 0..2 {
+        scoped_to_tenant("globex".parse().unwrap(), store.get_chain_stats())
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(tenants_of::<GetChainStatsRequest>(&client), ["acme", "globex"]);
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn cached_note_auth_info_is_only_served_to_its_tenant() {
+    let config = in_process_store_config("note-auth-cache-tenants");
+    let store = InProcessStore::load_in_process(&config)
+        .await
+        .unwrap()
+        .with_note_auth_cache(NoteAuthCache::builder().build());
+
+    let note = mock_output_note(1);
+    let note_id = note.id();
+    let block = next_in_process_block(&store, Vec::new(), vec![vec![note]]).await;
+    store.apply_block(&block).await.unwrap();
+
+    store.get_note_authentication_info([note_id].iter()).await.unwrap();
+    store.get_note_authentication_info([note_id].iter()).await.unwrap();
+    for _ in 0..2 {
+        scoped_to_tenant(
+            "globex".parse().unwrap(),
+            store.get_note_authentication_info([note_id].iter()),
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(store.metrics_snapshot().methods["GetNoteAuthenticationInfo"].calls, 2);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+// READ YOUR WRITES
+// ================================================================================================
+
+/// Replication state of a load-balanced store, whose blocks are applied by the primary and whose
+/// chain stats and transaction inputs are served by a replica. The replica only catches up with
+/// the primary when a chain stats read asks for a block it didn't apply yet.
+#[derive(Clone, Default)]
+struct Replication {
+    /// Number of the last block applied by the primary
+    applied: Arc<AtomicU32>,
+    /// Number of the last block applied by the replica
+    replicated: Arc<AtomicU32>,
+}
+
+impl Replication {
+    fn client(&self) -> FakeClient {
+        let (apply, tx_inputs, stats) = (self.clone(), self.clone(), self.clone());
+        FakeClient::default()
+            .on(move |request: Request<ApplyBlockRequest>| {
+                let block = Block::read_from_bytes(&request.into_inner().block).unwrap();
+                apply.applied.fetch_max(block.header().block_num(), Ordering::SeqCst);
+                Ok(ApplyBlockResponse::default())
+            })
+            .on(move |request: Request<GetTransactionInputsRequest>| {
+                // Transaction inputs are served at the block applied by the replica.
+                let block_height = tx_inputs.replicated.load(Ordering::SeqCst);
+                Ok(tx_inputs_at(request.into_inner(), block_height))
+            })
+            .on(move |request: Request<GetChainStatsRequest>| {
+                if let Some(min_block_num) = request.metadata().get(MIN_BLOCK_NUM_METADATA_KEY) {
+                    // The replica waits until it applied the requested block.
+                    let min_block_num: u32 = min_block_num.to_str().unwrap().parse().unwrap();
+                    assert!(min_block_num <= stats.applied.load(Ordering::SeqCst));
+                    stats.replicated.fetch_max(min_block_num, Ordering::SeqCst);
+                }
+
+                Ok(chain_stats(stats.replicated.load(Ordering::SeqCst) + 1))
+            })
+    }
+}
+
+#[tokio::test]
+async fn reads_after_apply_observe_the_applied_block() {
+    let mock_store = MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build();
+    let block = MockBlockBuilder::new(&mock_store).await.build();
+    let block_num = block.header().block_num();
+
+    // Without the hint, the replica serves the state before the block.
+    let store = DefaultStore::from_client(Replication::default().client());
+    store.apply_block(&block).await.unwrap();
+    assert_eq!(store.get_chain_stats().await.unwrap().num_blocks, 1);
+
+    let store = DefaultStore::from_client(Replication::default().client()).with_read_your_writes();
+    assert_eq!(store.get_chain_stats().await.unwrap().num_blocks, 1);
+    store.apply_block(&block).await.unwrap();
+    assert_eq!(store.get_chain_stats().await.unwrap().num_blocks, block_num + 1);
+}
+
+#[tokio::test]
+async fn lower_chain_height_than_seen_before_is_not_recorded() {
+    let replication = Replication::default();
+    let store = DefaultStore::from_client(replication.client()).with_height_tracking();
+    let tx = MockProvenTxBuilder::with_account_index(0).build();
+    assert_eq!(store.last_seen_height(), None);
+
+    replication.replicated.store(5, Ordering::SeqCst);
+    assert_eq!(
+        store.get_tx_inputs(&tx).await.unwrap().current_block_height,
+        BlockNumber::from(5)
+    );
+    assert_eq!(store.last_seen_height(), Some(5));
+
+    // A stale replica serves the state at an older block, which is logged as a warning.
+    replication.replicated.store(3, Ordering::SeqCst);
+    assert_eq!(
+        store.get_tx_inputs(&tx).await.unwrap().current_block_height,
+        BlockNumber::from(3)
+    );
+    assert_eq!(store.last_seen_height(), Some(5));
+
+    // Heights are not tracked unless enabled.
+    let store = DefaultStore::from_client(replication.client());
+    store.get_tx_inputs(&tx).await.unwrap();
+    assert_eq!(store.last_seen_height(), None);
+}
+
+// TIMEOUTS
+// ================================================================================================
+
+#[tokio::test]
+async fn requests_carry_the_deadline_of_their_method() {
+    let client = FakeClient::default();
+    let store = DefaultStore::from_client(client.clone()).with_timeouts(StoreTimeouts {
+        tx_inputs: Some(Duration::from_secs(1)),
+        block_inputs: Some(Duration::from_secs(2)),
+        // A zero timeout means no deadline.
+        note_auth: Some(Duration::ZERO),
+        apply_block: Some(Duration::from_secs(30)),
+    });
+    let tx = MockProvenTxBuilder::with_account_index(0).build();
+    let block =
+        MockBlockBuilder::new(&MockStoreSuccessBuilder::from_accounts(std::iter::empty()).build())
+            .await
+            .build();
+
+    store.get_tx_inputs(&tx).await.unwrap_err();
+    store
+        .get_block_inputs(std::iter::empty(), std::iter::empty(), std::iter::empty())
+        .await
+        .unwrap_err();
+    store.get_note_authentication_info(std::iter::empty()).await.unwrap_err();
+    store.get_chain_stats().await.unwrap_err();
+    store.apply_block(&block).await.unwrap_err();
+
+    let has_deadline = |metadata: Vec<MetadataMap>| -> Vec<bool> {
+        metadata.iter().map(|metadata| metadata.contains_key("grpc-timeout")).collect()
+    };
+    assert_eq!(has_deadline(client.metadata::<ApplyBlockRequest>()), [true]);
+    assert_eq!(has_deadline(client.metadata::<GetBlockInputsRequest>()), [true]);
+    assert_eq!(has_deadline(client.metadata::<GetChainStatsRequest>()), [false]);
+    assert_eq!(has_deadline(client.metadata::<GetNoteAuthenticationInfoRequest>()), [false]);
+    assert_eq!(has_deadline(client.metadata::<GetTransactionInputsRequest>()), [true]);
+}
+
+#[test]
+fn store_timeouts_default_to_no_deadline() {
+    let timeouts = StoreTimeouts::default();
+
+    assert_eq!(timeouts.for_request::<GetTransactionInputsRequest>(), None);
+    assert_eq!(timeouts.for_request::<ApplyBlockRequest>(), None);
+
+    let timeouts = StoreTimeouts {
+        apply_block: Some(Duration::from_secs(30)),
+        ..Default::default()
+    };
+    assert_eq!(timeouts.for_request::<ApplyBlocksRequest>(), Some(Duration::from_secs(30)));
+    assert_eq!(timeouts.for_request::<GetBlockInputsRequest>(), None);
+}
+
+// SHARD ROUTING
+// ================================================================================================
+
+/// A fake store holding the nullifiers of one shard.
+#[derive(Clone)]
+struct Shard {
+    nullifier_tree: Arc<Smt>,
+    /// Whether batches of transaction inputs are returned in reverse order
+    reverse_batches: bool,
+    /// Hash returned for all accounts, where the zero digest means the account is unknown
+    account_hash: Digest,
+}
+
+impl Shard {
+    fn new(nullifiers: impl IntoIterator<Item = Nullifier>) -> Self {
+        Self::from_leaves(
+            nullifiers
+                .into_iter()
+                .map(|nullifier| (nullifier, [Felt::from(1u32), ZERO, ZERO, ZERO])),
+        )
+    }
+
+    /// Returns a shard whose nullifier tree holds the given leaf values, which may be corrupt.
+    fn from_leaves(leaves: impl IntoIterator<Item = (Nullifier, [Felt; 4])>) -> Self {
+        let entries = leaves.into_iter().map(|(nullifier, value)| (nullifier.inner(), value));
+        Self {
+            nullifier_tree: Arc::new(Smt::with_entries(entries).unwrap()),
+            reverse_batches: false,
+            account_hash: Digest::default(),
+        }
+    }
+
+    fn with_reversed_batches(mut self) -> Self {
+        self.reverse_batches = true;
+        self
+    }
+
+    fn with_account_hash(mut self, account_hash: Digest) -> Self {
+        self.account_hash = account_hash;
+        self
+    }
+
+    /// Returns a client of the shard, answering transaction inputs, block inputs, nullifier and
+    /// account state requests and accepting all blocks.
+    fn client(&self) -> FakeClient {
+        let (tx_inputs, batch, block_inputs, nullifiers, account_state) =
+            (self.clone(), self.clone(), self.clone(), self.clone(), self.clone());
+        FakeClient::default()
+            .on(|_: Request<ApplyBlockRequest>| Ok(ApplyBlockResponse::default()))
+            .on(move |request: Request<GetTransactionInputsRequest>| {
+                Ok(tx_inputs.tx_inputs(request.into_inner()))
+            })
+            .on(move |request: Request<GetTransactionInputsBatchRequest>| {
+                let mut transactions: Vec<_> = request
+                    .into_inner()
+                    .transactions
+                    .into_iter()
+                    .map(|tx_request| batch.tx_inputs(tx_request))
+                    .collect();
+                if batch.reverse_batches {
+                    transactions.reverse();
+                }
+
+                Ok(GetTransactionInputsBatchResponse { transactions })
+            })
+            .on(move |request: Request<GetBlockInputsRequest>| {
+                Ok(block_inputs.block_inputs(request.into_inner()))
+            })
+            .on(move |request: Request<CheckNullifiersRequest>| {
+                let proofs = request
+                    .into_inner()
+                    .nullifiers
+                    .into_iter()
+                    .map(|nullifier| {
+                        nullifiers.nullifier_tree.open(&nullifier.try_into().unwrap()).into()
+                    })
+                    .collect();
+
+                Ok(CheckNullifiersResponse { proofs })
+            })
+            .on(move |request: Request<GetAccountStateRequest>| {
+                Ok(GetAccountStateResponse {
+                    account_state: Some(AccountTransactionInputRecord {
+                        account_id: request.into_inner().account_id,
+                        account_hash: Some(account_state.account_hash.into()),
+                    }),
+                })
+            })
+    }
+
+    fn tx_inputs(&self, request: GetTransactionInputsRequest) -> GetTransactionInputsResponse {
+        // Only the nullifiers held by the shard are returned.
+        let nullifiers = request
+            .nullifiers
+            .into_iter()
+            .filter_map(|nullifier| {
+                let value = self.nullifier_tree.get_value(&nullifier.try_into().unwrap());
+                (value != EMPTY_WORD).then_some(NullifierTransactionInputRecord {
+                    nullifier: Some(nullifier),
+                    block_num: value[0].as_int() as u32,
+                    opening: None,
+                })
+            })
+            .collect();
+
+        GetTransactionInputsResponse {
+            account_state: Some(AccountTransactionInputRecord {
+                account_id: request.account_id,
+                account_hash: Some(self.account_hash.into()),
+            }),
+            nullifiers,
+            // The held nullifiers are consumed in block 1.
+            block_height: 1,
+            ..Default::default()
+        }
+    }
+
+    fn block_inputs(&self, request: GetBlockInputsRequest) -> GetBlockInputsResponse {
+        let block_header = BlockHeader::new(
+            0,
+            Digest::default(),
+            0,
+            Digest::default(),
+            Digest::default(),
+            self.nullifier_tree.root(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            Digest::default(),
+            1,
+        );
+        let nullifiers = request
+            .nullifiers
+            .into_iter()
+            .map(|nullifier| {
+                let nullifier: Nullifier = nullifier.try_into().unwrap();
+                NullifierWitness {
+                    nullifier,
+                    proof: self.nullifier_tree.open(&nullifier.inner()),
+                }
+                .into()
+            })
+            .collect();
+
+        GetBlockInputsResponse {
+            block_header: Some(block_header.into()),
+            nullifiers,
+            found_unauthenticated_notes: Some(Default::default()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Returns the nullifiers of all block inputs requests received by `client`, in order.
+fn requested_nullifiers(client: &FakeClient) -> Vec<Nullifier> {
+    client
+        .messages::<GetBlockInputsRequest>()
+        .into_iter()
+        .flat_map(|request| request.nullifiers)
+        .map(|nullifier| nullifier.try_into().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn block_inputs_of_nullifiers_are_fetched_from_their_shards() {
+    let num_shards = NonZeroUsize::new(2).unwrap();
+    let nullifiers: Vec<_> = (1..=6u64).map(|i| Nullifier::from([Felt::new(i); 4])).collect();
+    let nullifiers_of_shard = |shard: usize| -> Vec<Nullifier> {
+        nullifiers
+            .iter()
+            .filter(|nullifier| shard_of(nullifier, num_shards) == shard)
+            .copied()
+            .collect()
+    };
+
+    let store = Shard::new([]).client();
+    let shards = [Shard::new(nullifiers_of_shard(0)), Shard::new(nullifiers_of_shard(1))];
+    let clients: Vec<_> = shards.iter().map(Shard::client).collect();
+    let router = ShardRouter::new(clients.clone()).unwrap();
+    for nullifier in &nullifiers {
+        assert_eq!(router.shard_of(nullifier), shard_of(nullifier, num_shards));
+    }
+
+    let block_inputs = DefaultStore::from_client(store.clone())
+        .with_shard_router(router)
+        .get_block_inputs(std::iter::empty(), nullifiers.iter(), std::iter::empty())
+        .await
+        .unwrap();
+
+    // Each shard is only asked for its own nullifiers, and the store for none of them.
+    assert!(requested_nullifiers(&store).is_empty());
+    for (shard, client) in clients.iter().enumerate() {
+        let mut requested = requested_nullifiers(client);
+        requested.sort();
+        let mut expected = nullifiers_of_shard(shard);
+        expected.sort();
+        assert!(!expected.is_empty());
+        assert_eq!(requested, expected);
+    }
+
+    // The witnesses of all nullifiers are merged, each opening against the tree of its shard.
+    assert_eq!(block_inputs.nullifiers.len(), nullifiers.len());
+    for (nullifier, proof) in &block_inputs.nullifiers {
+        let shard = &shards[shard_of(nullifier, num_shards)];
+        assert_eq!(proof.compute_root(), shard.nullifier_tree.root());
+        assert_eq!(proof.get(&nullifier.inner()), Some([Felt::from(1u32), ZERO, ZERO, ZERO]));
+    }
+}
+
+#[tokio::test]
+async fn block_inputs_are_requested_once_in_ascending_order() {
+    let client = Shard::new([]).client();
+    let store = DefaultStore::from_client(client.clone());
+
+    let account_ids = [mock_account_id(2), mock_account_id(1), mock_account_id(2)];
+    let nullifiers: Vec<_> =
+        [3, 1, 3, 2].into_iter().map(|i| Nullifier::from([Felt::new(i); 4])).collect();
+    let note_ids = [mock_output_note(2).id(), mock_output_note(1).id(), mock_output_note(2).id()];
+
+    store
+        .get_block_inputs(account_ids.iter().copied(), nullifiers.iter(), note_ids.iter())
+        .await
+        .unwrap();
+
+    let requests = client.messages::<GetBlockInputsRequest>();
+    assert_eq!(requests.len(), 1);
+    let expected_accounts: Vec<miden_node_proto::generated::account::AccountId> =
+        BTreeSet::from(account_ids).into_iter().map(Into::into).collect();
+    assert_eq!(requests[0].account_ids, expected_accounts);
+    assert_eq!(
+        requested_nullifiers(&client),
+        BTreeSet::from_iter(nullifiers).into_iter().collect::<Vec<_>>()
+    );
+    let expected_notes: Vec<_> = normalize_note_ids(note_ids.iter())
+        .unwrap()
+        .iter()
+        .map(miden_node_proto::generated::digest::Digest::from)
+        .collect();
+    assert_eq!(expected_notes.len(), 2);
+    assert_eq!(requests[0].unauthenticated_notes, expected_notes);
+}
+
+// CACHING STORE
+// ================================================================================================
+
+/// Returns the number of note authentication info requests sent to the store.
+#[cfg(feature = "in-process")]
+fn note_auth_requests(store: &CachingStore<InProcessStore>) -> u64 {
+    store.inner().metrics_snapshot().methods["GetNoteAuthenticationInfo"].calls
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn repeated_note_auth_requests_are_served_from_cache() {
+    let config = in_process_store_config("caching-store-hits");
+    let store = CachingStore::new(InProcessStore::load_in_process(&config).await.unwrap());
+
+    let notes = [mock_output_note(1), mock_output_note(2)];
+    let block = next_in_process_block(store.inner(), Vec::new(), vec![notes.to_vec()]).await;
+    store.apply_block(&block).await.unwrap();
+    let note_ids = [notes[0].id(), notes[1].id()];
+
+    let info = store.get_note_authentication_info(note_ids[..1].iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 1);
+    let cached = store.get_note_authentication_info(note_ids[..1].iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 1);
+    assert_eq!(cached.note_proofs, info.note_proofs);
+
+    // Only the uncached note is requested.
+    let info = store.get_note_authentication_info(note_ids.iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 2);
+    assert_eq!(info.note_ids(), BTreeSet::from(note_ids));
+    assert_eq!(info.block_proofs.len(), 1);
+    let cached = store.get_note_authentication_info(note_ids.iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 2);
+    assert_eq!(cached.note_proofs, info.note_proofs);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn applying_block_invalidates_note_auth_cache() {
+    let config = in_process_store_config("caching-store-invalidation");
+    let store = CachingStore::new(InProcessStore::load_in_process(&config).await.unwrap());
+
+    let note = mock_output_note(1);
+    let block = next_in_process_block(store.inner(), Vec::new(), vec![vec![note.clone()]]).await;
+    store.apply_block(&block).await.unwrap();
+    let note_ids = [note.id()];
+
+    store.get_note_authentication_info(note_ids.iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 1);
+
+    let block = next_in_process_block(store.inner(), Vec::new(), Vec::new()).await;
+    store.apply_block(&block).await.unwrap();
+    let info = store.get_note_authentication_info(note_ids.iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 2);
+    assert_eq!(info.block_proofs[0].chain_length, block.header().block_num() + 1);
+
+    store.invalidate();
+    store.get_note_authentication_info(note_ids.iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 3);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn note_auth_info_of_different_chain_lengths_is_not_combined() {
+    let config = in_process_store_config("caching-store-chain-length");
+    let store = CachingStore::new(InProcessStore::load_in_process(&config).await.unwrap());
+
+    let notes = [mock_output_note(1), mock_output_note(2)];
+    let block = next_in_process_block(store.inner(), Vec::new(), vec![notes.to_vec()]).await;
+    store.apply_block(&block).await.unwrap();
+    let note_ids = [notes[0].id(), notes[1].id()];
+    store.get_note_authentication_info(note_ids[..1].iter()).await.unwrap();
+
+    // The chain grows without going through the caching store.
+    let block = next_in_process_block(store.inner(), Vec::new(), Vec::new()).await;
+    store.inner().apply_block(&block).await.unwrap();
+
+    // The proof of the cached note is relative to the shorter chain, so all notes are fetched.
+    let info = store.get_note_authentication_info(note_ids.iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 3);
+    assert_eq!(info.note_ids(), BTreeSet::from(note_ids));
+    assert!(info
+        .block_proofs
+        .iter()
+        .all(|proof| proof.chain_length == block.header().block_num() + 1));
+
+    // The notes are cached at the longer chain.
+    store.get_note_authentication_info(note_ids.iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 3);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "in-process")]
+#[tokio::test]
+async fn caching_store_is_bounded() {
+    let config = in_process_store_config("caching-store-bounded");
+    let store = CachingStore::with_cache(
+        InProcessStore::load_in_process(&config).await.unwrap(),
+        NoteAuthCache::builder().max_entries(1).build(),
+    );
+
+    let notes = [mock_output_note(1), mock_output_note(2)];
+    let block = next_in_process_block(store.inner(), Vec::new(), vec![notes.to_vec()]).await;
+    store.apply_block(&block).await.unwrap();
+    let note_ids = [notes[0].id(), notes[1].id()];
+
+    store.get_note_authentication_info(note_ids[..1].iter()).await.unwrap();
+    store.get_note_authentication_info(note_ids[1..].iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 2);
+
+    // The first note was evicted.
+    store.get_note_authentication_info(note_ids[..1].iter()).await.unwrap();
+    assert_eq!(note_auth_requests(&store), 3);
+
+    fs::remove_dir_all(config.genesis_filepath.parent().unwrap()).unwrap();
+}
+
+// RESPONSE CACHE
+// ================================================================================================
+
+#[tokio::test]
+async fn identical_reads_within_ttl_are_served_from_cache() {
+    let clock = Arc::new(MockClock::new());
+    let client = Shard::new([]).client();
+    let store = DefaultStore::from_client(client.clone())
+        .with_response_cache(Duration::from_secs(1))
+        .with_clock(clock.clone());
+    let nullifiers = [Nullifier::from([Felt::new(1); 4])];
+    let get_block_inputs =
+        || store.get_block_inputs(std::iter::empty(), nullifiers.iter(), std::iter::empty());
+
+    // The second request is answered from the cache.
+    let block_inputs = get_block_inputs().await.unwrap();
+    get_block_inputs().await.unwrap();
+    assert_eq!(requested_nullifiers(&client).len(), 1);
+
+    // Expired responses are not served.
+    clock.advance(Duration::from_secs(2));
+    get_block_inputs().await.unwrap();
+    get_block_inputs().await.unwrap();
+    assert_eq!(requested_nullifiers(&client).len(), 2);
+
+    // Nor are responses from before a block was applied.
+    let block = Block::new(block_inputs.block_header, Vec::new(), Vec::new(), Vec::new()).unwrap();
+    store.apply_block(&block).await.unwrap();
+    get_block_inputs().await.unwrap();
+    assert_eq!(requested_nullifiers(&client).len(), 3);
+}
+
+// METRICS
+// ================================================================================================
+
+#[tokio::test]
+async fn metrics_snapshot_reflects_calls_errors_and_cache_lookups() {
+    let store = DefaultStore::from_client(Shard::new([]).client())
+        .with_response_cache(Duration::from_secs(60));
+    let nullifiers = [Nullifier::from([Felt::new(1); 4])];
+    let note = NoteId::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(1)]));
+    assert_eq!(store.metrics_snapshot(), StoreMetrics::default());
+
+    // The second read is answered from the cache, and the note authentication info is not
+    // supported by the client.
+    for _ in 0..2 {
+        store
+            .get_block_inputs(std::iter::empty(), nullifiers.iter(), std::iter::empty())
+            .await
+            .unwrap();
+    }
+    store.get_note_authentication_info([note].iter()).await.unwrap_err();
+
+    let metrics = store.metrics_snapshot();
+    let block_inputs = metrics.methods["GetBlockInputs"];
+    assert_eq!((block_inputs.calls, block_inputs.completed, block_inputs.errors), (1, 1, 0));
+    assert!(block_inputs.average_latency().is_some());
+    let note_auth = metrics.methods["GetNoteAuthenticationInfo"];
+    assert_eq!((note_auth.calls, note_auth.completed, note_auth.errors), (1, 1, 1));
+    assert_eq!(metrics.methods.len(), 2);
+    assert_eq!(metrics.errors, HashMap::from([(Code::Unimplemented, 1)]));
+    assert_eq!(metrics.in_flight, 0);
+    assert_eq!(metrics.response_cache, CacheMetrics { hits: 1, misses: 2 });
+    assert_eq!(metrics.response_cache.hit_rate(), Some(1.0 / 3.0));
+    assert_eq!(metrics.note_auth_cache, CacheMetrics::default());
+    assert_eq!(metrics.note_auth_cache.hit_rate(), None);
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn completed_store_calls_are_exported_as_metrics() {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    use super::{STORE_RPC_CALLS_METRIC, STORE_RPC_LATENCY_METRIC};
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let store = DefaultStore::from_client(Shard::new([]).client());
+    let nullifiers = [Nullifier::from([Felt::new(1); 4])];
+    let note = NoteId::from(Digest::from([ZERO, ZERO, ZERO, Felt::new(1)]));
+
+    // The local recorder is only installed on this thread, so the calls are driven by a
+    // single-threaded runtime.
+    metrics::with_local_recorder(&recorder, || {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(async {
+            for _ in 0..2 {
+                store
+                    .get_block_inputs(std::iter::empty(), nullifiers.iter(), std::iter::empty())
+                    .await
+                    .unwrap();
+            }
+            store.get_note_authentication_info([note].iter()).await.unwrap_err();
+        });
+    });
+
+    let snapshot = snapshotter.snapshot().into_vec();
+    let value = |name: &str, labels: &[(&str, &str)]| {
+        snapshot
+            .iter()
+            .find(|(key, ..)| {
+                let key = key.key();
+                key.name() == name
+                    && key.labels().count() == labels.len()
+                    && key.labels().all(|label| labels.contains(&(label.key(), label.value())))
+            })
+            .map(|(.., value)| value)
+    };
+
+    assert_eq!(
+        value(STORE_RPC_CALLS_METRIC, &[("method", "GetBlockInputs"), ("outcome", "success")]),
+        Some(&DebugValue::Counter(2))
+    );
+    assert_eq!(
+        value(STORE_RPC_CALLS_METRIC, &[("method", "GetBlockInputs"), ("outcome", "error")]),
+        None
+    );
+    assert_eq!(
+        value(
+            STORE_RPC_CALLS_METRIC,
+            &[("method", "GetNoteAuthenticationInfo"), ("outcome", "error")]
+        ),
+        Some(&DebugValue::Counter(1))
+    );
+    assert!(matches!(
+        value(STORE_RPC_LATENCY_METRIC, &[("method", "GetBlockInputs")]),
+        Some(DebugValue::Histogram(latencies)) if latencies.len() == 2
+    ));
+}
+
+// OPENTELEMETRY SPANS
+// ================================================================================================
+
+/// Layer recording the fields of all closed spans.
+#[cfg(feature = "otel")]
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    closed: Arc<Mutex<Vec<SpanFields>>>,
+}
+
+#[cfg(feature = "otel")]
+#[derive(Clone, Default)]
+struct SpanFields(BTreeMap<String, String>);
+
+#[cfg(feature = "otel")]
+impl tracing::field::Visit for SpanFields {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+#[cfg(feature = "otel")]
+impl<S> tracing_subscriber::Layer<S> for SpanRecorder
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let mut fields = SpanFields::default();
+        attrs.record(&mut fields);
+        ctx.span(id).unwrap().extensions_mut().insert(fields);
+    }
+
+    fn on_record(
+        &self,
+        id: &tracing::span::Id,
+        values: &tracing::span::Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let span = ctx.span(id).unwrap();
+        let mut extensions = span.extensions_mut();
+        values.record(extensions.get_mut::<SpanFields>().unwrap());
+    }
+
+    fn on_close(&self, id: tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let span = ctx.span(&id).unwrap();
+        let fields = span.extensions_mut().remove::<SpanFields>().unwrap();
+        self.closed.lock().unwrap().push(fields);
+    }
+}
+
+#[cfg(feature = "otel")]
+#[tokio::test]
+async fn store_calls_produce_spans_with_rpc_attributes() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let recorder = SpanRecorder::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let client = delayed_client(Duration::ZERO, 1);
+    let store = DefaultStore::from_client(client).with_store_address("http://127.0.0.1:28943");
+    store.get_chain_stats().await.unwrap();
+    store.get_pending_nullifiers().await.unwrap_err();
+
+    let spans: Vec<_> = recorder
+        .closed
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|span| span.0.contains_key("rpc.system"))
+        .map(|span| span.0.clone())
+        .collect();
+    assert_eq!(spans.len(), 2);
+
+    for (span, method, code) in
+        [(&spans[0], "GetChainStats", "0"), (&spans[1], "GetPendingNullifiers", "12")]
+    {
+        assert_eq!(span["rpc.system"], "grpc");
+        assert_eq!(span["rpc.service"], "store.Api");
+        assert_eq!(span["rpc.method"], method);
+        assert_eq!(span["otel.kind"], "client");
+        assert_eq!(span["server.address"], "http://127.0.0.1:28943");
+        assert_eq!(span["rpc.grpc.status_code"], code);
+    }
+    assert_eq!(spans[1]["otel.status_code"], "ERROR");
+}
+
+// IN-MEMORY STORE
+// ================================================================================================
+
+#[tokio::test]
+async fn in_memory_store_returns_seeded_tx_inputs() {
+    let account = MockPrivateAccount::<3>::from(1);
+    let consumed = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+    let unconsumed = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(2)]);
+    let store = InMemoryStore::new()
+        .with_accounts([(account.id, account.states[0])])
+        .with_nullifiers([(consumed, BlockNumber::from(5))]);
+
+    let tx = MockProvenTxBuilder::with_account(account.id, account.states[0], account.states[1])
+        .nullifiers(vec![consumed, unconsumed])
+        .build();
+    let tx_inputs = store.get_tx_inputs(&tx).await.unwrap();
+
+    assert_eq!(tx_inputs.account_hash, Some(account.states[0]));
+    assert_eq!(
+        tx_inputs.nullifiers,
+        BTreeMap::from([(consumed, NonZeroU32::new(5)), (unconsumed, None)])
+    );
+    assert!(tx_inputs.missing_unauthenticated_notes.is_empty());
+    assert_eq!(tx_inputs.current_block_height, BlockNumber::GENESIS);
+}
+
+#[tokio::test]
+async fn in_memory_store_records_applied_blocks() {
+    let account = MockPrivateAccount::<3>::from(1);
+    let nullifier = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+    let store = InMemoryStore::new().with_accounts([(account.id, account.states[0])]);
+
+    let genesis = store.check_health().await.unwrap();
+    assert_eq!(genesis.chain_tip, BlockNumber::GENESIS);
+
+    let update = BlockAccountUpdate::new(
+        account.id,
+        account.states[1],
+        AccountUpdateDetails::Private,
+        vec![],
+    );
+    let block = MockBlockBuilder::from_in_memory(&store)
+        .account_updates(vec![update])
+        .produced_nullifiers(vec![nullifier])
+        .build();
+    store.apply_block(&block).await.unwrap();
+
+    let applied: Vec<_> = store.applied_blocks().iter().map(Block::hash).collect();
+    assert_eq!(applied, vec![block.hash()]);
+    assert_eq!(
+        store.get_account_state(account.id).await.unwrap().account_hash,
+        Some(account.states[1])
+    );
+    assert_eq!(store.get_note_spend_block(nullifier).await, Ok(Some(BlockNumber::from(1))));
+    assert_eq!(store.next_block_number().await, Ok(BlockNumber::from(2)));
+}
+
+#[tokio::test]
+async fn in_memory_store_rejects_invalid_blocks() {
+    let nullifier = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+    let store = InMemoryStore::new();
+
+    let block = MockBlockBuilder::from_in_memory(&store)
+        .produced_nullifiers(vec![nullifier])
+        .build();
+    store.apply_block(&block).await.unwrap();
+    assert_eq!(
+        store.apply_block(&block).await,
+        Err(ApplyBlockError::StaleBlock { expected: 2, got: 1 })
+    );
+
+    // The next block consumes the nullifier again.
+    let block = MockBlockBuilder::from_in_memory(&store)
+        .produced_nullifiers(vec![nullifier])
+        .build();
+    assert!(matches!(
+        store.apply_block(&block).await,
+        Err(ApplyBlockError::GrpcClientError { code: Code::InvalidArgument, .. })
+    ));
+
+    // The next block doesn't follow the chain tip.
+    let header = block.header();
+    let header = BlockHeader::new(
+        header.version(),
+        Digest::default(),
+        header.block_num(),
+        header.chain_root(),
+        header.account_root(),
+        header.nullifier_root(),
+        header.note_root(),
+        header.tx_hash(),
+        header.kernel_root(),
+        header.proof_hash(),
+        header.timestamp(),
+    );
+    let block = Block::new(header, Vec::new(), Vec::new(), Vec::new()).unwrap();
+    assert!(matches!(
+        store.apply_block(&block).await,
+        Err(ApplyBlockError::GrpcClientError { code: Code::InvalidArgument, .. })
+    ));
+
+    assert_eq!(store.applied_blocks().len(), 1);
+}
+
+#[tokio::test]
+async fn in_memory_store_records_nullifier_origins_on_apply() {
+    let store = InMemoryStore::new();
+    let tx = MockProvenTxBuilder::with_account_index(0).nullifiers_range(0..1).build();
+    let nullifier = tx.get_nullifiers().next().unwrap();
+
+    // Reading the transaction inputs doesn't consume the nullifier.
+    store.get_tx_inputs(&tx).await.unwrap();
+    assert_eq!(store.nullifier_origin(nullifier).await, Ok(None));
+
+    let block = MockBlockBuilder::from_in_memory(&store)
+        .produced_nullifiers(vec![nullifier])
+        .build();
+    store.apply_block(&block).await.unwrap();
+    assert_eq!(store.nullifier_origin(nullifier).await, Ok(Some(tx.id())));
+}
@@ -0,0 +1,61 @@
+use std::{any::type_name, time::Duration};
+
+use miden_node_proto::generated::requests::{
+    ApplyBlockRequest, ApplyBlocksRequest, GetBlockInputsRequest, GetNoteAuthenticationInfoRequest,
+    GetTransactionInputsBatchRequest, GetTransactionInputsRequest,
+};
+
+// STORE TIMEOUTS
+// ================================================================================================
+
+/// Deadlines of the requests sent to the store by a [DefaultStore](super::DefaultStore).
+///
+/// A request which doesn't complete before its deadline fails as if the store was unavailable,
+/// so a hung store can't stall block production. A deadline of `None` or zero means the request
+/// has no deadline, which is the default for all requests.
+///
+/// Applying a block makes the store update its database and its trees, so it typically needs a
+/// larger deadline than the reads. Note that a block whose request timed out may still have been
+/// applied by the store.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StoreTimeouts {
+    /// Deadline of the requests for the inputs of one or several transactions
+    pub tx_inputs: Option<Duration>,
+    /// Deadline of the requests for the inputs of a block
+    pub block_inputs: Option<Duration>,
+    /// Deadline of the requests for note authentication info
+    pub note_auth: Option<Duration>,
+    /// Deadline of the requests applying one or several blocks
+    pub apply_block: Option<Duration>,
+}
+
+impl StoreTimeouts {
+    /// Returns the deadline of the requests of type `M`, or `None` if they have no deadline.
+    pub fn for_request<M>(&self) -> Option<Duration> {
+        let request = type_name::<M>();
+        let timeout = if request == type_name::<GetTransactionInputsRequest>()
+            || request == type_name::<GetTransactionInputsBatchRequest>()
+        {
+            self.tx_inputs
+        } else if request == type_name::<GetBlockInputsRequest>() {
+            self.block_inputs
+        } else if request == type_name::<GetNoteAuthenticationInfoRequest>() {
+            self.note_auth
+        } else if request == type_name::<ApplyBlockRequest>()
+            || request == type_name::<ApplyBlocksRequest>()
+        {
+            self.apply_block
+        } else {
+            None
+        };
+
+        timeout.filter(|timeout| !timeout.is_zero())
+    }
+
+    /// Sets the deadline of `request`, if requests of its type have one.
+    pub(super) fn set_deadline<M>(&self, request: &mut tonic::Request<M>) {
+        if let Some(timeout) = self.for_request::<M>() {
+            request.set_timeout(timeout);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(feature = "otel")]
+use std::sync::{Arc, Mutex};
+use std::{any::type_name, future::Future};
+
+#[cfg(feature = "otel")]
+use tonic::Code;
+use tonic::Status;
+#[cfg(feature = "otel")]
+use tracing::{field::Empty, info_span, Instrument};
+
+use super::metrics::MetricsRecorder;
+#[cfg(feature = "otel")]
+use crate::COMPONENT;
+
+// CALL TRACER
+// ================================================================================================
+
+/// Records the metrics of each request sent to the store, and wraps it in a span if the `otel`
+/// feature is enabled.
+///
+/// The spans carry the attributes of the OpenTelemetry semantic conventions for gRPC clients, so
+/// a `tracing-opentelemetry` layer exports them as OpenTelemetry client spans. Without the
+/// feature, only the metrics are recorded.
+///
+/// The tracer is shared between a [DefaultStore](super::DefaultStore) and its block applier.
+#[derive(Clone, Debug, Default)]
+pub(super) struct CallTracer {
+    /// Address of the store, recorded as the `server.address` attribute if known
+    #[cfg(feature = "otel")]
+    store_address: Arc<Mutex<Option<String>>>,
+    /// Metrics of the requests
+    metrics: MetricsRecorder,
+}
+
+impl CallTracer {
+    /// Sets the address of the store recorded in the spans.
+    #[cfg(feature = "otel")]
+    pub fn set_store_address(&self, address: String) {
+        *self.store_address.lock().expect("store address lock poisoned") = Some(address);
+    }
+
+    /// Returns the recorder of the metrics of the requests.
+    pub fn metrics(&self) -> &MetricsRecorder {
+        &self.metrics
+    }
+
+    /// Awaits `call`, which sends a request of type `M` to the store, and records its metrics.
+    ///
+    /// With the `otel` feature, the call is instrumented with a span of the gRPC method receiving
+    /// `M`, which records the status code of the response.
+    pub async fn trace<M, R>(
+        &self,
+        call: impl Future<Output = Result<R, Status>>,
+    ) -> Result<R, Status> {
+        #[cfg(feature = "otel")]
+        let call = {
+            let method = method_name::<M>();
+            let store_address =
+                self.store_address.lock().expect("store address lock poisoned").clone();
+            let span = info_span!(
+                target: COMPONENT,
+                "store_call",
+                otel.name = format!("store.Api/{method}"),
+                otel.kind = "client",
+                otel.status_code = Empty,
+                rpc.system = "grpc",
+                rpc.service = "store.Api",
+                rpc.method = method,
+                rpc.grpc.status_code = Empty,
+                server.address = store_address.as_deref(),
+            );
+
+            async move {
+                let result = call.instrument(span.clone()).await;
+                let code = match &result {
+                    Ok(_) => Code::Ok,
+                    Err(status) => status.code(),
+                };
+                span.record("rpc.grpc.status_code", code as i32);
+                if code != Code::Ok {
+                    span.record("otel.status_code", "ERROR");
+                }
+
+                result
+            }
+        };
+
+        self.metrics.record(method_name::<M>(), call).await
+    }
+}
+
+/// Returns the name of the gRPC method receiving requests of type `M`, e.g. `GetBlockInputs` for
+/// `GetBlockInputsRequest`.
+fn method_name<M>() -> &'static str {
+    let name = type_name::<M>();
+    let name = name.rsplit("::").next().unwrap_or(name);
+
+    name.strip_suffix("Request").unwrap_or(name)
+}
@@ -12,7 +12,7 @@ use super::MockStoreSuccess;
 use crate::{
     block::BlockInputs,
     block_builder::prover::{block_witness::BlockWitness, BlockProver},
-    store::Store,
+    store::{InMemoryStore, Store},
     TransactionBatch,
 };
 
@@ -127,6 +127,18 @@ impl MockBlockBuilder {
         }
     }
 
+    pub fn from_in_memory(store: &InMemoryStore) -> Self {
+        Self {
+            store_accounts: store.account_tree(),
+            store_chain_mmr: store.chain_mmr(),
+            last_block_header: store.chain_tip(),
+
+            updated_accounts: None,
+            created_notes: None,
+            produced_nullifiers: None,
+        }
+    }
+
     pub fn account_updates(mut self, updated_accounts: Vec<BlockAccountUpdate>) -> Self {
         for update in &updated_accounts {
             self.store_accounts
@@ -0,0 +1,45 @@
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::watch;
+
+use crate::store::Clock;
+
+/// [Clock] which only advances when told to.
+///
+/// Sleeps on this clock complete once the clock has been advanced past their deadline.
+#[derive(Debug)]
+pub struct MockClock {
+    now: watch::Sender<Instant>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self { now: watch::Sender::new(Instant::now()) }
+    }
+
+    /// Moves the clock forward by `duration`, waking all sleeps which have elapsed.
+    pub fn advance(&self, duration: Duration) {
+        self.now.send_modify(|now| *now += duration);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.borrow()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let mut now = self.now.subscribe();
+        let deadline = *now.borrow() + duration;
+        // The sender lives as long as the clock, so waiting cannot fail.
+        let _ = now.wait_for(|now| *now >= deadline).await;
+    }
+}
@@ -20,3 +20,7 @@ pub mod block;
 pub mod batch;
 
 pub mod note;
+
+mod clock;
+
+pub use clock::MockClock;
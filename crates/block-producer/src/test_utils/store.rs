@@ -1,17 +1,30 @@
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     num::NonZeroU32,
     ops::Not,
+    sync::Mutex,
+    time::Duration,
 };
 
 use async_trait::async_trait;
-use miden_node_proto::domain::{blocks::BlockInclusionProof, notes::NoteAuthenticationInfo};
+use miden_node_proto::{
+    domain::{
+        blocks::{BlockInclusionProof, BlockNumber},
+        notes::NoteAuthenticationInfo,
+        nullifiers::leaf_value_to_block_num,
+    },
+    AccountState,
+};
 use miden_objects::{
     block::{Block, NoteBatch},
     crypto::merkle::{Mmr, SimpleSmt, Smt, ValuePath},
     notes::{NoteId, NoteInclusionProof, Nullifier},
+    transaction::TransactionId,
     BlockHeader, ACCOUNT_TREE_DEPTH, EMPTY_WORD, ZERO,
 };
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tonic::{Code, Status};
 
 use super::*;
 use crate::{
@@ -19,7 +32,8 @@ use crate::{
     block::{AccountWitness, BlockInputs},
     errors::NotePathsError,
     store::{
-        ApplyBlock, ApplyBlockError, BlockInputsError, Store, TransactionInputs, TxInputsError,
+        ApplyBlock, ApplyBlockError, BlockInputsError, ChainStats, Store, TransactionInputs,
+        TxInputsError,
     },
     test_utils::block::{
         block_output_notes, flatten_output_notes, note_created_smt_from_note_batches,
@@ -155,10 +169,20 @@ impl MockStoreSuccessBuilder {
             )]))),
             num_apply_block_called: Default::default(),
             notes: Arc::new(RwLock::new(notes)),
+            nullifier_origins: Default::default(),
+            pending_nullifiers: Default::default(),
+            account_forks: Default::default(),
+            blocks: Default::default(),
+            account_creations: Default::default(),
+            latencies: Default::default(),
+            programmed_failures: Default::default(),
         }
     }
 }
 
+/// Maps account id -> (account hash, tip) of the account at competing chain tips
+type AccountForks = BTreeMap<AccountId, Vec<(Digest, Digest)>>;
+
 pub struct MockStoreSuccess {
     /// Map account id -> account hash
     pub accounts: Arc<RwLock<SimpleSmt<ACCOUNT_TREE_DEPTH>>>,
@@ -177,29 +201,116 @@ pub struct MockStoreSuccess {
 
     /// Maps note id -> note inclusion proof for all created notes
     pub notes: Arc<RwLock<BTreeMap<NoteId, NoteInclusionProof>>>,
+    /// Maps nullifier -> transaction which produced it, for all verified transactions
+    pub nullifier_origins: Arc<RwLock<BTreeMap<Nullifier, TransactionId>>>,
+    /// Stores the nullifiers requested for the block being built, until a block is applied
+    pub pending_nullifiers: Arc<RwLock<BTreeSet<Nullifier>>>,
+    /// Maps account id -> (account hash, tip) of the account at competing chain tips, simulating
+    /// a fork
+    pub account_forks: Arc<RwLock<AccountForks>>,
+    /// Maps block number -> block, for all applied blocks
+    pub blocks: Arc<RwLock<BTreeMap<u32, Block>>>,
+    /// Maps account id -> number of the block which created the account, for all accounts created
+    /// by applied blocks
+    pub account_creations: Arc<RwLock<BTreeMap<AccountId, u32>>>,
+    /// Maps method name -> latency injected into each call of the method
+    latencies: Arc<Mutex<BTreeMap<&'static str, Duration>>>,
+    /// Maps method name -> status codes of the next calls of the method, which fail
+    programmed_failures: Arc<Mutex<BTreeMap<&'static str, VecDeque<Code>>>>,
 }
 
+/// Names of the methods of [MockStoreSuccess] which latency and failures can be injected into.
+///
+/// Blocks applied by `apply_block_with_diff` and `apply_blocks` go through `apply_block`.
+const MOCK_METHODS: &[&str] = &[
+    "apply_block",
+    "get_tx_inputs",
+    "get_block_inputs",
+    "get_note_authentication_info",
+    "notes_authenticated_since",
+    "nullifier_origin",
+    "get_note_spend_block",
+    "get_pending_nullifiers",
+    "get_account_state",
+    "get_account_state_forks",
+    "get_account_creation_block",
+    "get_nullifier_root_history",
+    "get_chain_stats",
+];
+
 impl MockStoreSuccess {
     pub async fn account_root(&self) -> Digest {
         let locked_accounts = self.accounts.read().await;
 
         locked_accounts.root()
     }
+
+    /// Delays each call of `method` by `latency`.
+    ///
+    /// The delay is awaited with [tokio::time::sleep], so it elapses deterministically in tests
+    /// running with a paused clock.
+    pub fn set_latency(&self, method: &'static str, latency: Duration) -> &Self {
+        assert!(MOCK_METHODS.contains(&method), "unknown mock store method {method}");
+        self.latencies.lock().unwrap().insert(method, latency);
+        self
+    }
+
+    /// Makes the next calls of `method` fail with `codes`, in order, before it succeeds again.
+    ///
+    /// Each code is converted into the error of the method as if the store responded with it,
+    /// e.g. [Code::Unavailable] results in a transient error.
+    pub fn program_failures(&self, method: &'static str, codes: Vec<Code>) -> &Self {
+        assert!(MOCK_METHODS.contains(&method), "unknown mock store method {method}");
+        self.programmed_failures
+            .lock()
+            .unwrap()
+            .entry(method)
+            .or_default()
+            .extend(codes);
+        self
+    }
+
+    /// Awaits the latency injected into `method`, and returns its next programmed failure, if
+    /// any.
+    async fn simulate<E: From<Status>>(&self, method: &'static str) -> Result<(), E> {
+        let latency = self.latencies.lock().unwrap().get(method).copied();
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+
+        let failure = self
+            .programmed_failures
+            .lock()
+            .unwrap()
+            .get_mut(method)
+            .and_then(VecDeque::pop_front);
+        match failure {
+            Some(code) => Err(Status::new(code, format!("programmed failure of {method}")).into()),
+            None => Ok(()),
+        }
+    }
 }
 
 #[async_trait]
 impl ApplyBlock for MockStoreSuccess {
     async fn apply_block(&self, block: &Block) -> Result<(), ApplyBlockError> {
+        self.simulate::<ApplyBlockError>("apply_block").await?;
+
         // Intentionally, we take and hold both locks, to prevent calls to `get_tx_inputs()` from
         // going through while we're updating the store's data structure
         let mut locked_accounts = self.accounts.write().await;
         let mut locked_produced_nullifiers = self.produced_nullifiers.write().await;
 
         // update accounts
+        let header = block.header();
+        let mut locked_account_creations = self.account_creations.write().await;
         for update in block.updated_accounts() {
-            locked_accounts.insert(update.account_id().into(), update.new_state_hash().into());
+            let old_hash =
+                locked_accounts.insert(update.account_id().into(), update.new_state_hash().into());
+            if old_hash == EMPTY_WORD {
+                locked_account_creations.insert(update.account_id(), header.block_num());
+            }
         }
-        let header = block.header();
         debug_assert_eq!(locked_accounts.root(), header.account_root());
 
         // update nullifiers
@@ -234,6 +345,10 @@ impl ApplyBlock for MockStoreSuccess {
 
         // append the block header
         self.block_headers.write().await.insert(header.block_num(), header);
+        self.blocks.write().await.insert(header.block_num(), block.clone());
+
+        // the block being built was applied, so its nullifiers are not pending anymore
+        self.pending_nullifiers.write().await.clear();
 
         // update num_apply_block_called
         *self.num_apply_block_called.write().await += 1;
@@ -248,6 +363,8 @@ impl Store for MockStoreSuccess {
         &self,
         proven_tx: &ProvenTransaction,
     ) -> Result<TransactionInputs, TxInputsError> {
+        self.simulate::<TxInputsError>("get_tx_inputs").await?;
+
         let locked_accounts = self.accounts.read().await;
         let locked_produced_nullifiers = self.produced_nullifiers.read().await;
 
@@ -281,12 +398,19 @@ impl Store for MockStoreSuccess {
             })
             .collect();
 
+        self.nullifier_origins
+            .write()
+            .await
+            .extend(proven_tx.get_nullifiers().map(|nullifier| (nullifier, proven_tx.id())));
+
         Ok(TransactionInputs {
             account_id: proven_tx.account_id(),
             account_hash,
             nullifiers,
             missing_unauthenticated_notes,
-            current_block_height: 0,
+            current_block_height: BlockNumber::GENESIS,
+            proofs: None,
+            account_version: None,
         })
     }
 
@@ -296,6 +420,8 @@ impl Store for MockStoreSuccess {
         produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
         notes: impl Iterator<Item = &NoteId> + Send,
     ) -> Result<BlockInputs, BlockInputsError> {
+        self.simulate::<BlockInputsError>("get_block_inputs").await?;
+
         let locked_accounts = self.accounts.read().await;
         let locked_produced_nullifiers = self.produced_nullifiers.read().await;
 
@@ -315,9 +441,10 @@ impl Store for MockStoreSuccess {
                 .collect()
         };
 
-        let nullifiers = produced_nullifiers
+        let nullifiers: BTreeMap<_, _> = produced_nullifiers
             .map(|nullifier| (*nullifier, locked_produced_nullifiers.open(&nullifier.inner())))
             .collect();
+        self.pending_nullifiers.write().await.extend(nullifiers.keys().copied());
 
         let locked_notes = self.notes.read().await;
         let note_proofs = notes
@@ -356,6 +483,8 @@ impl Store for MockStoreSuccess {
         &self,
         notes: impl Iterator<Item = &NoteId> + Send,
     ) -> Result<NoteAuthenticationInfo, NotePathsError> {
+        self.simulate::<NotePathsError>("get_note_authentication_info").await?;
+
         let locked_notes = self.notes.read().await;
         let locked_headers = self.block_headers.read().await;
         let locked_chain_mmr = self.chain_mmr.read().await;
@@ -384,6 +513,206 @@ impl Store for MockStoreSuccess {
 
         Ok(NoteAuthenticationInfo { block_proofs, note_proofs })
     }
+
+    async fn notes_authenticated_since(
+        &self,
+        notes: &[NoteId],
+        since: BlockNumber,
+    ) -> Result<Vec<NoteId>, NotePathsError> {
+        self.simulate::<NotePathsError>("notes_authenticated_since").await?;
+
+        let locked_notes = self.notes.read().await;
+
+        Ok(notes
+            .iter()
+            .filter(|id| {
+                locked_notes
+                    .get(id)
+                    .is_some_and(|proof| BlockNumber::from(proof.location().block_num()) > since)
+            })
+            .copied()
+            .collect())
+    }
+
+    async fn nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, TxInputsError> {
+        self.simulate::<TxInputsError>("nullifier_origin").await?;
+
+        // Only nullifiers which were committed in a block have a recorded origin.
+        let locked_produced_nullifiers = self.produced_nullifiers.read().await;
+        if locked_produced_nullifiers.get_value(&nullifier.inner()) == EMPTY_WORD {
+            return Ok(None);
+        }
+
+        Ok(self.nullifier_origins.read().await.get(&nullifier).copied())
+    }
+
+    async fn get_note_spend_block(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.simulate::<TxInputsError>("get_note_spend_block").await?;
+
+        let locked_produced_nullifiers = self.produced_nullifiers.read().await;
+        let nullifier_value = locked_produced_nullifiers.get_value(&nullifier.inner());
+        if nullifier_value == EMPTY_WORD {
+            return Ok(None);
+        }
+
+        leaf_value_to_block_num(nullifier_value).map(Some).map_err(|err| {
+            TxInputsError::NullifierSpendError { nullifier, reason: err.to_string() }
+        })
+    }
+
+    async fn get_pending_nullifiers(&self) -> Result<Vec<Nullifier>, TxInputsError> {
+        self.simulate::<TxInputsError>("get_pending_nullifiers").await?;
+
+        Ok(self.pending_nullifiers.read().await.iter().copied().collect())
+    }
+
+    async fn get_account_state(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountState, TxInputsError> {
+        self.simulate::<TxInputsError>("get_account_state").await?;
+
+        let account_hash = self.accounts.read().await.get_leaf(&account_id.into());
+        let account_hash = (account_hash != EMPTY_WORD).then(|| account_hash.into());
+
+        Ok(AccountState { account_id, account_hash })
+    }
+
+    async fn get_account_state_forks(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(Digest, Digest)>, TxInputsError> {
+        self.simulate::<TxInputsError>("get_account_state_forks").await?;
+
+        let mut candidates = Vec::new();
+
+        let account_hash = self.accounts.read().await.get_leaf(&account_id.into());
+        if account_hash != EMPTY_WORD {
+            let locked_headers = self.block_headers.read().await;
+            let (_, tip) = locked_headers.last_key_value().expect("genesis header is always set");
+            candidates.push((account_hash.into(), tip.hash()));
+        }
+
+        if let Some(forks) = self.account_forks.read().await.get(&account_id) {
+            candidates.extend(forks.iter().copied());
+        }
+
+        Ok(candidates)
+    }
+
+    async fn get_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        self.simulate::<TxInputsError>("get_account_creation_block").await?;
+
+        if let Some(block_num) = self.account_creations.read().await.get(&account_id) {
+            return Ok(Some((*block_num).into()));
+        }
+
+        // Accounts which were not created by an applied block are part of the initial state.
+        if self.accounts.read().await.get_leaf(&account_id.into()) == EMPTY_WORD {
+            return Ok(None);
+        }
+        let locked_headers = self.block_headers.read().await;
+        let (initial_block_num, _) =
+            locked_headers.first_key_value().expect("initial header is always set");
+
+        Ok(Some((*initial_block_num).into()))
+    }
+
+    async fn get_nullifier_root_history(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, Digest)>, TxInputsError> {
+        self.simulate::<TxInputsError>("get_nullifier_root_history").await?;
+
+        Ok(self
+            .block_headers
+            .read()
+            .await
+            .range(from.as_u32()..=to.as_u32())
+            .map(|(block_num, header)| ((*block_num).into(), header.nullifier_root()))
+            .collect())
+    }
+
+    async fn get_chain_stats(&self) -> Result<ChainStats, TxInputsError> {
+        self.simulate::<TxInputsError>("get_chain_stats").await?;
+
+        let accounts = self.accounts.read().await;
+        let produced_nullifiers = self.produced_nullifiers.read().await;
+        let chain_mmr = self.chain_mmr.read().await;
+
+        Ok(ChainStats {
+            num_blocks: chain_mmr.forest() as u32,
+            num_accounts: accounts.leaves().count() as u64,
+            num_nullifiers: produced_nullifiers.entries().count() as u64,
+            account_root: accounts.root(),
+            nullifier_root: produced_nullifiers.root(),
+            chain_root: chain_mmr.peaks().hash_peaks(),
+        })
+    }
+
+    async fn apply_block_with_diff(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError> {
+        self.apply_block(block).await?;
+
+        Ok(block
+            .updated_accounts()
+            .iter()
+            .map(|update| (update.account_id(), update.new_state_hash()))
+            .collect())
+    }
+
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError> {
+        for block in blocks {
+            self.apply_block(block).await?;
+        }
+
+        Ok((*self
+            .block_headers
+            .read()
+            .await
+            .last_key_value()
+            .expect("genesis block missing")
+            .0)
+            .into())
+    }
+
+    fn sync_blocks_from(
+        &self,
+        from: BlockNumber,
+    ) -> impl Stream<Item = Result<Block, TxInputsError>> + Send + 'static {
+        let blocks = Arc::clone(&self.blocks);
+        let (sender, receiver) = mpsc::channel(1);
+
+        // The blocks are collected before sending any of them, fixing the chain tip at the start
+        // of the stream.
+        tokio::spawn(async move {
+            let blocks: Vec<_> = blocks
+                .read()
+                .await
+                .range(from.as_u32()..)
+                .map(|(_, block)| block.clone())
+                .collect();
+            for block in blocks {
+                if sender.send(Ok(block)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
 }
 
 #[derive(Default)]
@@ -392,7 +721,10 @@ pub struct MockStoreFailure;
 #[async_trait]
 impl ApplyBlock for MockStoreFailure {
     async fn apply_block(&self, _block: &Block) -> Result<(), ApplyBlockError> {
-        Err(ApplyBlockError::GrpcClientError(String::new()))
+        Err(ApplyBlockError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
     }
 }
 
@@ -411,13 +743,132 @@ impl Store for MockStoreFailure {
         _produced_nullifiers: impl Iterator<Item = &Nullifier> + Send,
         _notes: impl Iterator<Item = &NoteId> + Send,
     ) -> Result<BlockInputs, BlockInputsError> {
-        Err(BlockInputsError::GrpcClientError(String::new()))
+        Err(BlockInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
     }
 
     async fn get_note_authentication_info(
         &self,
         _notes: impl Iterator<Item = &NoteId> + Send,
     ) -> Result<NoteAuthenticationInfo, NotePathsError> {
-        Err(NotePathsError::GrpcClientError(String::new()))
+        Err(NotePathsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn notes_authenticated_since(
+        &self,
+        _notes: &[NoteId],
+        _since: BlockNumber,
+    ) -> Result<Vec<NoteId>, NotePathsError> {
+        Err(NotePathsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn nullifier_origin(
+        &self,
+        _nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, TxInputsError> {
+        Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn get_note_spend_block(
+        &self,
+        _nullifier: Nullifier,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn get_pending_nullifiers(&self) -> Result<Vec<Nullifier>, TxInputsError> {
+        Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn get_account_state(
+        &self,
+        _account_id: AccountId,
+    ) -> Result<AccountState, TxInputsError> {
+        Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn get_account_state_forks(
+        &self,
+        _account_id: AccountId,
+    ) -> Result<Vec<(Digest, Digest)>, TxInputsError> {
+        Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn get_account_creation_block(
+        &self,
+        _account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, TxInputsError> {
+        Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn get_nullifier_root_history(
+        &self,
+        _from: BlockNumber,
+        _to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, Digest)>, TxInputsError> {
+        Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn get_chain_stats(&self) -> Result<ChainStats, TxInputsError> {
+        Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn apply_block_with_diff(
+        &self,
+        _block: &Block,
+    ) -> Result<Vec<(AccountId, Digest)>, ApplyBlockError> {
+        Err(ApplyBlockError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    async fn apply_blocks(&self, _blocks: &[Block]) -> Result<BlockNumber, ApplyBlockError> {
+        Err(ApplyBlockError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        })
+    }
+
+    fn sync_blocks_from(
+        &self,
+        _from: BlockNumber,
+    ) -> impl Stream<Item = Result<Block, TxInputsError>> + Send + 'static {
+        tokio_stream::once(Err(TxInputsError::GrpcClientError {
+            code: Code::Internal,
+            message: String::new(),
+        }))
     }
 }
@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use miden_objects::{crypto::merkle::MerklePath, BlockHeader};
 
 use crate::{
@@ -5,6 +7,70 @@ use crate::{
     generated::block as proto,
 };
 
+// BLOCK NUMBER
+// ================================================================================================
+
+/// The number of a block in the chain, starting with zero for the genesis block.
+///
+/// Unlike a bare `u32`, a block number can't be mistaken for a count, e.g. of nullifiers, and
+/// its arithmetic is checked, so stepping past the first or the last block is an explicit `None`
+/// instead of a wrapped or panicking number.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlockNumber(u32);
+
+impl BlockNumber {
+    /// The number of the genesis block.
+    pub const GENESIS: Self = Self(0);
+
+    /// The largest block number.
+    pub const MAX: Self = Self(u32::MAX);
+
+    /// Returns the number of the block following this one, or `None` if this is the largest
+    /// block number.
+    pub fn next(self) -> Option<Self> {
+        self.0.checked_add(1).map(Self)
+    }
+
+    /// Returns the number of the block preceding this one, or `None` for the genesis block.
+    pub fn prev(self) -> Option<Self> {
+        self.0.checked_sub(1).map(Self)
+    }
+
+    /// Returns the number of blocks from `earlier` to this block, or `None` if `earlier` is a
+    /// later block.
+    pub fn checked_sub(self, earlier: Self) -> Option<u32> {
+        self.0.checked_sub(earlier.0)
+    }
+
+    /// Returns true if this is the number of the genesis block.
+    pub fn is_genesis(self) -> bool {
+        self == Self::GENESIS
+    }
+
+    /// Returns the block number as a `u32`.
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for BlockNumber {
+    fn from(block_num: u32) -> Self {
+        Self(block_num)
+    }
+}
+
+impl From<BlockNumber> for u32 {
+    fn from(block_num: BlockNumber) -> Self {
+        block_num.0
+    }
+}
+
+impl Display for BlockNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
 // BLOCK HEADER
 // ================================================================================================
 
@@ -121,3 +187,40 @@ impl TryFrom<proto::BlockInclusionProof> for BlockInclusionProof {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BlockNumber;
+
+    #[test]
+    fn test_next_block_number() {
+        assert_eq!(BlockNumber::GENESIS.next(), Some(BlockNumber::from(1)));
+        assert_eq!(BlockNumber::from(u32::MAX - 1).next(), Some(BlockNumber::MAX));
+        assert_eq!(BlockNumber::MAX.next(), None);
+    }
+
+    #[test]
+    fn test_prev_block_number() {
+        assert_eq!(BlockNumber::GENESIS.prev(), None);
+        assert_eq!(BlockNumber::from(1).prev(), Some(BlockNumber::GENESIS));
+        assert_eq!(BlockNumber::MAX.prev(), Some(BlockNumber::from(u32::MAX - 1)));
+    }
+
+    #[test]
+    fn test_block_number_distance() {
+        assert_eq!(BlockNumber::from(7).checked_sub(BlockNumber::from(7)), Some(0));
+        assert_eq!(BlockNumber::MAX.checked_sub(BlockNumber::GENESIS), Some(u32::MAX));
+        assert_eq!(BlockNumber::GENESIS.checked_sub(BlockNumber::from(1)), None);
+        assert_eq!(BlockNumber::from(3).checked_sub(BlockNumber::MAX), None);
+    }
+
+    #[test]
+    fn test_block_number_conversions() {
+        for value in [0, 1, u32::MAX] {
+            assert_eq!(u32::from(BlockNumber::from(value)), value);
+        }
+        assert!(BlockNumber::from(0).is_genesis());
+        assert!(!BlockNumber::from(1).is_genesis());
+        assert_eq!(BlockNumber::MAX.to_string(), u32::MAX.to_string());
+    }
+}
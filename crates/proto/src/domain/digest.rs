@@ -62,12 +62,16 @@ impl FromHex for proto::Digest {
         let data = hex::decode(hex)?;
 
         match data.len() {
-            size if size < DIGEST_DATA_SIZE => {
-                Err(ConversionError::InsufficientData { expected: DIGEST_DATA_SIZE, got: size })
-            },
-            size if size > DIGEST_DATA_SIZE => {
-                Err(ConversionError::TooMuchData { expected: DIGEST_DATA_SIZE, got: size })
-            },
+            size if size < DIGEST_DATA_SIZE => Err(ConversionError::InsufficientData {
+                expected: DIGEST_DATA_SIZE,
+                got: size,
+                context: Some("digest"),
+            }),
+            size if size > DIGEST_DATA_SIZE => Err(ConversionError::TooMuchData {
+                expected: DIGEST_DATA_SIZE,
+                got: size,
+                context: Some("digest"),
+            }),
             _ => {
                 let d0 = u64::from_be_bytes(data[..8].try_into().unwrap());
                 let d1 = u64::from_be_bytes(data[8..16].try_into().unwrap());
@@ -80,6 +84,37 @@ impl FromHex for proto::Digest {
     }
 }
 
+// FROM BYTES
+// ================================================================================================
+
+impl TryFrom<&[u8]> for proto::Digest {
+    type Error = ConversionError;
+
+    /// Decodes a digest given either as [DIGEST_DATA_SIZE] raw big-endian bytes, or as the bytes
+    /// of its hex encoding, optionally prefixed with `0x`.
+    ///
+    /// The two forms are distinguished by their length, as the hex encoding of a digest is twice
+    /// as long as its raw bytes.
+    ///
+    /// This is meant for digests supplied outside of protobuf messages, e.g. by clients reading
+    /// them from user input. Digests within protobuf messages, such as the ones decoded into
+    /// transaction inputs, are always encoded as four `fixed64` words, so they have a single form
+    /// and are converted with `TryFrom<proto::Digest>` instead.
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() == DIGEST_DATA_SIZE {
+            let d0 = u64::from_be_bytes(value[..8].try_into().unwrap());
+            let d1 = u64::from_be_bytes(value[8..16].try_into().unwrap());
+            let d2 = u64::from_be_bytes(value[16..24].try_into().unwrap());
+            let d3 = u64::from_be_bytes(value[24..32].try_into().unwrap());
+
+            return Ok(proto::Digest { d0, d1, d2, d3 });
+        }
+
+        let hex = value.strip_prefix(b"0x").unwrap_or(value);
+        proto::Digest::from_hex(hex)
+    }
+}
+
 // INTO
 // ================================================================================================
 
@@ -205,9 +240,11 @@ impl TryFrom<&proto::Digest> for Digest {
 #[cfg(test)]
 mod test {
     use hex::{FromHex, ToHex};
+    use miden_objects::{crypto::hash::rpo::RpoDigest, Felt};
     use proptest::prelude::*;
 
-    use crate::generated::digest::Digest;
+    use super::DIGEST_DATA_SIZE;
+    use crate::{errors::ConversionError, generated::digest::Digest};
 
     #[test]
     fn test_hex_digest() {
@@ -227,6 +264,54 @@ mod test {
         assert_eq!(digest, round_trip.unwrap());
     }
 
+    #[test]
+    fn test_hex_and_byte_digests_convert_to_same_digest() {
+        let digest =
+            RpoDigest::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(u64::MAX >> 1)]);
+        let proto_digest = Digest::from(digest);
+
+        let bytes: Vec<u8> = [proto_digest.d0, proto_digest.d1, proto_digest.d2, proto_digest.d3]
+            .iter()
+            .flat_map(|element| element.to_be_bytes())
+            .collect();
+        let hex: String = ToHex::encode_hex(&proto_digest);
+
+        let hex_prefixed = format!("0x{hex}");
+        for encoded in [bytes.as_slice(), hex.as_bytes(), hex_prefixed.as_bytes()] {
+            let decoded = Digest::try_from(encoded).unwrap();
+            assert_eq!(RpoDigest::try_from(decoded).unwrap(), digest);
+        }
+    }
+
+    #[test]
+    fn test_malformed_hex_digest_is_rejected() {
+        let hex = "z".repeat(2 * DIGEST_DATA_SIZE);
+
+        assert!(matches!(Digest::try_from(hex.as_bytes()), Err(ConversionError::HexError(_))));
+    }
+
+    #[test]
+    fn test_digest_of_wrong_size_reports_its_context() {
+        let hex = "ab".repeat(DIGEST_DATA_SIZE - 1);
+        let err = Digest::try_from(hex.as_bytes()).unwrap_err();
+        assert_eq!(
+            err,
+            ConversionError::InsufficientData {
+                expected: DIGEST_DATA_SIZE,
+                got: DIGEST_DATA_SIZE - 1,
+                context: Some("digest"),
+            }
+        );
+        assert_eq!(err.to_string(), "Not enough data in digest, expected 32, got 31");
+
+        let hex = "ab".repeat(DIGEST_DATA_SIZE + 1);
+        let err = Digest::try_from(hex.as_bytes()).unwrap_err().with_context("note_id");
+        assert_eq!(err.to_string(), "Too much data in note_id, expected 32, got 33");
+
+        let err = ConversionError::TooMuchData { expected: 1, got: 2, context: None };
+        assert_eq!(err.to_string(), "Too much data, expected 1, got 2");
+    }
+
     proptest! {
         #[test]
         fn test_encode_decode(
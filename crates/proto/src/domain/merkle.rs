@@ -1,5 +1,5 @@
 use miden_objects::{
-    crypto::merkle::{LeafIndex, MerklePath, MmrDelta, SmtLeaf, SmtProof},
+    crypto::merkle::{LeafIndex, MerklePath, MmrDelta, SmtLeaf, SmtProof, SMT_DEPTH},
     Digest, Word,
 };
 
@@ -144,6 +144,13 @@ impl TryFrom<generated::smt::SmtOpening> for SmtProof {
             .as_ref()
             .ok_or(generated::smt::SmtOpening::missing_field(stringify!(path)))?
             .try_into()?;
+        if path.depth() != SMT_DEPTH {
+            return Err(ConversionError::InvalidProofDepth {
+                expected: SMT_DEPTH,
+                got: path.depth(),
+            });
+        }
+
         let leaf: SmtLeaf = opening
             .leaf
             .ok_or(generated::smt::SmtOpening::missing_field(stringify!(leaf)))?
@@ -162,3 +169,37 @@ impl From<SmtProof> for generated::smt::SmtOpening {
         }
     }
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod test {
+    use miden_objects::{
+        crypto::merkle::{LeafIndex, MerklePath, SmtLeaf, SmtProof, SMT_DEPTH},
+        Digest,
+    };
+
+    use crate::{errors::ConversionError, generated};
+
+    #[test]
+    fn test_smt_opening_with_wrong_depth_is_rejected() {
+        let leaf = SmtLeaf::new_empty(LeafIndex::new_max_depth(0));
+        let opening = generated::smt::SmtOpening {
+            path: Some(MerklePath::new(vec![Digest::default(); 10]).into()),
+            leaf: Some(leaf.clone().into()),
+        };
+
+        assert_eq!(
+            SmtProof::try_from(opening),
+            Err(ConversionError::InvalidProofDepth { expected: SMT_DEPTH, got: 10 })
+        );
+
+        let opening = generated::smt::SmtOpening {
+            path: Some(MerklePath::new(vec![Digest::default(); SMT_DEPTH as usize]).into()),
+            leaf: Some(leaf.into()),
+        };
+
+        assert!(SmtProof::try_from(opening).is_ok());
+    }
+}
@@ -2,8 +2,9 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use miden_objects::{
     notes::{NoteExecutionHint, NoteId, NoteInclusionProof, NoteMetadata, NoteTag, NoteType},
-    Digest, Felt,
+    Digest, Felt, BLOCK_NOTE_TREE_DEPTH,
 };
+use prost::encoding::encoded_len_varint;
 
 use crate::{
     convert,
@@ -105,6 +106,63 @@ impl NoteAuthenticationInfo {
     pub fn note_ids(&self) -> BTreeSet<NoteId> {
         self.note_proofs.keys().copied().collect()
     }
+
+    /// Returns an estimate of the size in bytes of the encoded authentication info of
+    /// `note_count` notes, e.g. to decide whether to split a request into chunks before sending
+    /// it.
+    ///
+    /// The estimate is an upper bound: it assumes that every note is in a different block, and
+    /// that the MMR paths of the blocks have the maximum length.
+    pub fn estimated_size(note_count: usize) -> usize {
+        note_count * (field_len(note_proof_len()) + field_len(block_proof_len()))
+    }
+}
+
+// SIZE ESTIMATION
+// ================================================================================================
+
+/// Length of the key of a field. All fields of the estimated messages have tags below 16, which
+/// are encoded in a single byte.
+const KEY_LEN: usize = 1;
+
+/// Length of an encoded `fixed32` field.
+const FIXED32_FIELD_LEN: usize = KEY_LEN + 4;
+
+/// Length of an encoded digest, which consists of four `fixed64` fields.
+const DIGEST_LEN: usize = 4 * (KEY_LEN + 8);
+
+/// Maximum length of the MMR path of a block, in a chain of at most `u32::MAX` blocks.
+const MAX_MMR_PATH_LEN: usize = u32::BITS as usize;
+
+/// Returns the length of a message field whose content has length `len`.
+fn field_len(len: usize) -> usize {
+    KEY_LEN + encoded_len_varint(len as u64) + len
+}
+
+/// Returns the length of a Merkle path field with `depth` siblings.
+fn merkle_path_len(depth: usize) -> usize {
+    field_len(depth * field_len(DIGEST_LEN))
+}
+
+/// Returns the maximum length of an encoded [NoteInclusionInBlockProofPb].
+fn note_proof_len() -> usize {
+    let note_index_len = KEY_LEN + encoded_len_varint(u32::MAX.into());
+
+    field_len(DIGEST_LEN)
+        + FIXED32_FIELD_LEN
+        + note_index_len
+        + merkle_path_len(BLOCK_NOTE_TREE_DEPTH.into())
+}
+
+/// Returns the maximum length of an encoded block inclusion proof.
+fn block_proof_len() -> usize {
+    // Version, block number, timestamp, and eight digests.
+    let header_len = KEY_LEN
+        + encoded_len_varint(u32::MAX.into())
+        + 2 * FIXED32_FIELD_LEN
+        + 8 * field_len(DIGEST_LEN);
+
+    field_len(header_len) + merkle_path_len(MAX_MMR_PATH_LEN) + FIXED32_FIELD_LEN
 }
 
 impl From<NoteAuthenticationInfo> for NoteAuthenticationInfoProto {
@@ -128,3 +186,69 @@ impl TryFrom<NoteAuthenticationInfoProto> for NoteAuthenticationInfo {
         Ok(result)
     }
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod test {
+    use miden_objects::{crypto::merkle::MerklePath, BlockHeader};
+    use prost::Message;
+
+    use super::*;
+
+    /// Returns a digest with no zero elements, which are omitted from the encoding.
+    fn digest(seed: u64) -> Digest {
+        Digest::from([
+            Felt::new(seed + 1),
+            Felt::new(seed + 2),
+            Felt::new(seed + 3),
+            Felt::new(seed + 4),
+        ])
+    }
+
+    fn path(depth: usize) -> MerklePath {
+        MerklePath::new((0..depth as u64).map(digest).collect())
+    }
+
+    #[test]
+    fn test_estimated_size_bounds_encoded_size() {
+        const NUM_NOTES: u32 = 10;
+        // A chain of about a million blocks.
+        const MMR_PATH_LEN: usize = 20;
+
+        let mut info = NoteAuthenticationInfo::default();
+        for i in 0..NUM_NOTES {
+            let block_num = 1_000_000 + i;
+            let block_header = BlockHeader::new(
+                0,
+                digest(1),
+                block_num,
+                digest(2),
+                digest(3),
+                digest(4),
+                digest(5),
+                digest(6),
+                digest(7),
+                digest(8),
+                1_700_000_000,
+            );
+            info.block_proofs.push(BlockInclusionProof {
+                block_header,
+                mmr_path: path(MMR_PATH_LEN),
+                chain_length: 1 << MMR_PATH_LEN,
+            });
+
+            let proof =
+                NoteInclusionProof::new(block_num, i as u16, path(BLOCK_NOTE_TREE_DEPTH.into()))
+                    .unwrap();
+            info.note_proofs.insert(digest(i.into()).into(), proof);
+        }
+
+        let encoded_size = NoteAuthenticationInfoProto::from(info).encoded_len();
+        let estimated_size = NoteAuthenticationInfo::estimated_size(NUM_NOTES as usize);
+
+        assert!(estimated_size >= encoded_size, "{estimated_size} < {encoded_size}");
+        assert!(estimated_size <= 2 * encoded_size, "{estimated_size} > 2 * {encoded_size}");
+    }
+}
@@ -1,10 +1,14 @@
+use std::num::NonZeroUsize;
+
 use miden_objects::{
     crypto::{hash::rpo::RpoDigest, merkle::SmtProof},
     notes::Nullifier,
+    Felt, Word, ZERO,
 };
 
+use super::blocks::BlockNumber;
 use crate::{
-    errors::{ConversionError, MissingFieldHelper},
+    errors::{ConversionError, InvalidLeafEncoding, MissingFieldHelper},
     generated::{digest::Digest, responses::NullifierBlockInputRecord},
 };
 
@@ -69,3 +73,68 @@ impl From<NullifierWitness> for NullifierBlockInputRecord {
         }
     }
 }
+
+// NULLIFIER TREE LEAVES
+// ================================================================================================
+
+/// Returns the value of the leaf of a nullifier consumed in block `block_num`, as stored in the
+/// nullifier tree of the store.
+pub fn block_num_to_leaf_value(block_num: BlockNumber) -> Word {
+    [Felt::from(block_num.as_u32()), ZERO, ZERO, ZERO]
+}
+
+/// Returns the number of the block which consumed a nullifier, given the value of its leaf in the
+/// nullifier tree of the store.
+///
+/// There are no nullifiers in the genesis block. The value zero is instead used to signal
+/// absence of a value, and is not a valid block number.
+///
+/// # Errors
+/// Returns [InvalidLeafEncoding] with the offending element if the value is not a valid encoding
+/// of a block number, e.g. because the leaf was corrupted on disk.
+pub fn leaf_value_to_block_num(value: Word) -> Result<BlockNumber, InvalidLeafEncoding> {
+    let [block_num, padding @ ..] = value;
+    if let Some(element) = padding.into_iter().find(|element| *element != ZERO) {
+        return Err(InvalidLeafEncoding(element));
+    }
+
+    u32::try_from(block_num.as_int())
+        .ok()
+        .map(BlockNumber::from)
+        .filter(|block_num| !block_num.is_genesis())
+        .ok_or(InvalidLeafEncoding(block_num))
+}
+
+// SHARDING
+// ================================================================================================
+
+/// Returns the index of the shard holding `nullifier`, for deployments which split the nullifier
+/// tree across `num_shards` stores.
+///
+/// Nullifiers are assigned to shards by their most significant element, so the assignment is
+/// uniform and stable for a given number of shards. Clients routing nullifier requests to shards
+/// must use this function to agree with the stores.
+pub fn shard_of(nullifier: &Nullifier, num_shards: NonZeroUsize) -> usize {
+    (nullifier.most_significant_felt().as_int() % num_shards.get() as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use miden_objects::{notes::Nullifier, Felt, ONE};
+
+    use super::shard_of;
+
+    #[test]
+    fn test_shard_of_assigns_every_nullifier_to_one_shard() {
+        let num_shards = NonZeroUsize::new(3).unwrap();
+        let shards: Vec<_> = (0..9u64)
+            .map(|i| shard_of(&Nullifier::from([Felt::new(i); 4]), num_shards))
+            .collect();
+
+        assert_eq!(shards, [0, 1, 2, 0, 1, 2, 0, 1, 2]);
+        let nullifier = Nullifier::from([ONE; 4]);
+        assert_eq!(shard_of(&nullifier, NonZeroUsize::MIN), 0);
+    }
+}
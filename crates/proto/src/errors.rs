@@ -1,6 +1,9 @@
 use std::{any::type_name, num::TryFromIntError};
 
-use miden_objects::crypto::merkle::{SmtLeafError, SmtProofError};
+use miden_objects::{
+    crypto::merkle::{SmtLeafError, SmtProofError},
+    Felt,
+};
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Error)]
@@ -13,14 +16,26 @@ pub enum ConversionError {
     SmtLeafError(#[from] SmtLeafError),
     #[error("SMT proof error: {0}")]
     SmtProofError(#[from] SmtProofError),
+    #[error("Invalid SMT proof depth, expected {expected}, got {got}")]
+    InvalidProofDepth { expected: u8, got: u8 },
     #[error("Integer conversion error: {0}")]
     TryFromIntError(#[from] TryFromIntError),
-    #[error("Too much data, expected {expected}, got {got}")]
-    TooMuchData { expected: usize, got: usize },
-    #[error("Not enough data, expected {expected}, got {got}")]
-    InsufficientData { expected: usize, got: usize },
+    #[error("Too much data{}, expected {expected}, got {got}", in_context(.context))]
+    TooMuchData {
+        expected: usize,
+        got: usize,
+        context: Option<&'static str>,
+    },
+    #[error("Not enough data{}, expected {expected}, got {got}", in_context(.context))]
+    InsufficientData {
+        expected: usize,
+        got: usize,
+        context: Option<&'static str>,
+    },
     #[error("Value is not in the range 0..MODULUS")]
     NotAValidFelt,
+    #[error("Block number {block_num} is after the current block height {block_height}")]
+    BlockNumberAfterBlockHeight { block_num: u32, block_height: u32 },
     #[error("Field `{field_name}` required to be filled in protobuf representation of {entity}")]
     MissingFieldInProtobufRepresentation {
         entity: &'static str,
@@ -30,6 +45,35 @@ pub enum ConversionError {
 
 impl Eq for ConversionError {}
 
+impl ConversionError {
+    /// Sets the field or entity whose data has the wrong size, for errors about data sizes.
+    ///
+    /// This lets callers which know the field being converted name it, e.g. when a response has
+    /// many repeated fields. Other errors are returned unchanged.
+    pub fn with_context(self, context: &'static str) -> Self {
+        match self {
+            Self::TooMuchData { expected, got, .. } => {
+                Self::TooMuchData { expected, got, context: Some(context) }
+            },
+            Self::InsufficientData { expected, got, .. } => {
+                Self::InsufficientData { expected, got, context: Some(context) }
+            },
+            err => err,
+        }
+    }
+}
+
+/// Formats the context of a data size error for its message, if any.
+fn in_context(context: &Option<&'static str>) -> String {
+    context.map(|context| format!(" in {context}")).unwrap_or_default()
+}
+
+/// The value of a leaf of the nullifier tree doesn't encode a block number, see
+/// [leaf_value_to_block_num](crate::domain::nullifiers::leaf_value_to_block_num).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Leaf value element {0} does not encode a block number")]
+pub struct InvalidLeafEncoding(pub Felt);
+
 pub trait MissingFieldHelper {
     fn missing_field(field_name: &'static str) -> ConversionError;
 }
@@ -42,3 +86,35 @@ impl<T: prost::Message> MissingFieldHelper for T {
         }
     }
 }
+
+// STATUS DETAILS
+// ================================================================================================
+
+/// Type URL of the [StaleBlockDetail] attached to the status of a rejected stale block.
+pub const STALE_BLOCK_DETAIL_TYPE_URL: &str = "type.googleapis.com/miden.store.StaleBlock";
+
+/// Detail of the `FailedPrecondition` status returned when a block whose number doesn't follow
+/// the chain tip is applied, e.g. because it was already applied.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct StaleBlockDetail {
+    /// Number of the block the store expected
+    #[prost(uint32, tag = "1")]
+    pub expected: u32,
+    /// Number of the rejected block
+    #[prost(uint32, tag = "2")]
+    pub got: u32,
+}
+
+/// Type URL of the [AccountVersionConflictDetail] attached to the status of a block rejected
+/// because one of its accounts changed since it was read.
+pub const ACCOUNT_VERSION_CONFLICT_DETAIL_TYPE_URL: &str =
+    "type.googleapis.com/miden.store.AccountVersionConflict";
+
+/// Detail of the `FailedPrecondition` status returned when a block is applied with an outdated
+/// version of one of its accounts.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct AccountVersionConflictDetail {
+    /// ID of the account which changed
+    #[prost(uint64, tag = "1")]
+    pub account_id: u64,
+}
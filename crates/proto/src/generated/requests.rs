@@ -3,6 +3,36 @@
 pub struct ApplyBlockRequest {
     #[prost(bytes = "vec", tag = "1")]
     pub block: ::prost::alloc::vec::Vec<u8>,
+    /// Transactions which produced the block's nullifiers. Nullifiers without a recorded origin
+    /// are omitted.
+    #[prost(message, repeated, tag = "2")]
+    pub nullifier_origins: ::prost::alloc::vec::Vec<NullifierOrigin>,
+    /// Versions of the block's accounts read by the transactions of the block. The block is
+    /// rejected if any of these accounts changed since.
+    #[prost(message, repeated, tag = "3")]
+    pub account_versions: ::prost::alloc::vec::Vec<AccountVersion>,
+}
+/// Applies a contiguous sequence of blocks, stopping at the first block which is rejected.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApplyBlocksRequest {
+    /// Blocks in ascending order of block numbers, each following the previous one.
+    #[prost(message, repeated, tag = "1")]
+    pub blocks: ::prost::alloc::vec::Vec<ApplyBlockRequest>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct NullifierOrigin {
+    #[prost(message, optional, tag = "1")]
+    pub nullifier: ::core::option::Option<super::digest::Digest>,
+    #[prost(message, optional, tag = "2")]
+    pub transaction_id: ::core::option::Option<super::transaction::TransactionId>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountVersion {
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+    /// Version returned by `GetTransactionInputsResponse.account_version`.
+    #[prost(bytes = "vec", tag = "2")]
+    pub version: ::prost::alloc::vec::Vec<u8>,
 }
 /// Returns a list of nullifiers that match the specified prefixes and are recorded in the node.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -163,3 +193,67 @@ pub struct GetAccountProofsRequest {
     #[prost(message, repeated, tag = "3")]
     pub code_commitments: ::prost::alloc::vec::Vec<super::digest::Digest>,
 }
+/// Returns the transaction which produced the given nullifier.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetNullifierOriginRequest {
+    #[prost(message, optional, tag = "1")]
+    pub nullifier: ::core::option::Option<super::digest::Digest>,
+}
+/// Returns the nullifiers reserved by blocks which are being built, but were not applied yet.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetPendingNullifiersRequest {}
+/// Returns the candidate states of an account at the chain tips known to the store.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountStateForksRequest {
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+}
+/// Returns the nullifier root of each block in a range.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetNullifierRootHistoryRequest {
+    /// First block of the range (inclusive).
+    #[prost(fixed32, tag = "1")]
+    pub from_block_num: u32,
+    /// Last block of the range (inclusive).
+    #[prost(fixed32, tag = "2")]
+    pub to_block_num: u32,
+}
+/// Returns aggregate statistics of the chain.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetChainStatsRequest {}
+/// Returns all blocks from a block number up to the chain tip, in ascending order.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SyncBlocksRequest {
+    /// Number of the first block to return.
+    #[prost(fixed32, tag = "1")]
+    pub from_block_num: u32,
+}
+/// Returns which of the given notes were included in a block after the given block.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNotesAuthenticatedSinceRequest {
+    /// IDs of the notes to check.
+    #[prost(message, repeated, tag = "1")]
+    pub note_ids: ::prost::alloc::vec::Vec<super::digest::Digest>,
+    /// Notes included in this block or earlier are not returned.
+    #[prost(fixed32, tag = "2")]
+    pub since_block_num: u32,
+}
+/// Returns the number of the block in which an account was created.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountCreationBlockRequest {
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+}
+/// Returns the inputs of several transactions in one request.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTransactionInputsBatchRequest {
+    /// Inputs requested for each transaction.
+    #[prost(message, repeated, tag = "1")]
+    pub transactions: ::prost::alloc::vec::Vec<GetTransactionInputsRequest>,
+}
+/// Returns the current hash of an account.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountStateRequest {
+    #[prost(message, optional, tag = "1")]
+    pub account_id: ::core::option::Option<super::account::AccountId>,
+}
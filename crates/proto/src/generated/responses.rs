@@ -1,6 +1,20 @@
 // This file is @generated by prost-build.
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct ApplyBlockResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApplyBlockResponse {
+    /// Accounts updated by the applied block, with their new hashes.
+    #[prost(message, repeated, tag = "1")]
+    pub updated_accounts: ::prost::alloc::vec::Vec<super::account::AccountSummary>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApplyBlocksResponse {
+    /// Number of the last applied block, which is the chain tip after the request.
+    #[prost(fixed32, tag = "1")]
+    pub last_applied_block_num: u32,
+    /// Reason why the block following `last_applied_block_num` was rejected, empty if all blocks
+    /// were applied.
+    #[prost(string, tag = "2")]
+    pub error: ::prost::alloc::string::String,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CheckNullifiersResponse {
     /// Each requested nullifier has its corresponding nullifier proof at the same position.
@@ -123,13 +137,16 @@ pub struct AccountTransactionInputRecord {
     pub account_hash: ::core::option::Option<super::digest::Digest>,
 }
 /// A nullifier returned as a response to the GetTransactionInputs
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NullifierTransactionInputRecord {
     #[prost(message, optional, tag = "1")]
     pub nullifier: ::core::option::Option<super::digest::Digest>,
     /// The block at which the nullifier has been consumed, zero if not consumed.
     #[prost(fixed32, tag = "2")]
     pub block_num: u32,
+    /// Opening of the nullifier against the nullifier root of `GetTransactionInputsResponse.block_header`.
+    #[prost(message, optional, tag = "3")]
+    pub opening: ::core::option::Option<super::smt::SmtOpening>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetTransactionInputsResponse {
@@ -141,6 +158,16 @@ pub struct GetTransactionInputsResponse {
     pub missing_unauthenticated_notes: ::prost::alloc::vec::Vec<super::digest::Digest>,
     #[prost(fixed32, tag = "4")]
     pub block_height: u32,
+    /// Header of the block at `block_height`, the returned proofs are against its roots.
+    #[prost(message, optional, tag = "5")]
+    pub block_header: ::core::option::Option<super::block::BlockHeader>,
+    /// Authentication path of the account hash against the account root of `block_header`.
+    #[prost(message, optional, tag = "6")]
+    pub account_proof: ::core::option::Option<super::merkle::MerklePath>,
+    /// Opaque version of the account, to be sent back in `ApplyBlockRequest.account_versions`.
+    /// Empty if the store doesn't version accounts.
+    #[prost(bytes = "vec", tag = "7")]
+    pub account_version: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct SubmitProvenTransactionResponse {
@@ -232,3 +259,98 @@ pub struct AccountStateHeader {
     #[prost(bytes = "vec", optional, tag = "3")]
     pub account_code: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetNullifierOriginResponse {
+    /// ID of the transaction which produced the nullifier, if it was recorded by the store.
+    #[prost(message, optional, tag = "1")]
+    pub transaction_id: ::core::option::Option<super::transaction::TransactionId>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPendingNullifiersResponse {
+    /// Nullifiers reserved by pending blocks.
+    #[prost(message, repeated, tag = "1")]
+    pub nullifiers: ::prost::alloc::vec::Vec<super::digest::Digest>,
+}
+/// A candidate state of an account at one of the chain tips known to the store.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct AccountStateCandidate {
+    /// Hash of the account state at the tip.
+    #[prost(message, optional, tag = "1")]
+    pub account_hash: ::core::option::Option<super::digest::Digest>,
+    /// Hash of the block header of the tip.
+    #[prost(message, optional, tag = "2")]
+    pub tip: ::core::option::Option<super::digest::Digest>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountStateForksResponse {
+    /// Candidate states of the account, one per chain tip at which the account is known.
+    #[prost(message, repeated, tag = "1")]
+    pub candidates: ::prost::alloc::vec::Vec<AccountStateCandidate>,
+}
+/// The nullifier root of a block.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct NullifierRootRecord {
+    /// Block number.
+    #[prost(fixed32, tag = "1")]
+    pub block_num: u32,
+    /// Root of the nullifier tree after the block was applied.
+    #[prost(message, optional, tag = "2")]
+    pub nullifier_root: ::core::option::Option<super::digest::Digest>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNullifierRootHistoryResponse {
+    /// Nullifier roots of the requested blocks, in ascending block order.
+    #[prost(message, repeated, tag = "1")]
+    pub roots: ::prost::alloc::vec::Vec<NullifierRootRecord>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetChainStatsResponse {
+    /// Number of blocks in the chain, including the genesis block.
+    #[prost(uint32, tag = "1")]
+    pub num_blocks: u32,
+    /// Number of accounts in the account tree.
+    #[prost(uint64, tag = "2")]
+    pub num_accounts: u64,
+    /// Number of consumed nullifiers.
+    #[prost(uint64, tag = "3")]
+    pub num_nullifiers: u64,
+    /// Root of the account tree.
+    #[prost(message, optional, tag = "4")]
+    pub account_root: ::core::option::Option<super::digest::Digest>,
+    /// Root of the nullifier tree.
+    #[prost(message, optional, tag = "5")]
+    pub nullifier_root: ::core::option::Option<super::digest::Digest>,
+    /// Root of the chain MMR, committing to all blocks of the chain.
+    #[prost(message, optional, tag = "6")]
+    pub chain_root: ::core::option::Option<super::digest::Digest>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncBlocksResponse {
+    /// Serialized block.
+    #[prost(bytes = "vec", tag = "1")]
+    pub block: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNotesAuthenticatedSinceResponse {
+    /// IDs of the requested notes which were included in a block after the given block.
+    #[prost(message, repeated, tag = "1")]
+    pub note_ids: ::prost::alloc::vec::Vec<super::digest::Digest>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountCreationBlockResponse {
+    /// Number of the block in which the account was created, or absent if the account is unknown.
+    #[prost(fixed32, optional, tag = "1")]
+    pub block_num: ::core::option::Option<u32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTransactionInputsBatchResponse {
+    /// Inputs of each requested transaction, in the order of the request.
+    #[prost(message, repeated, tag = "1")]
+    pub transactions: ::prost::alloc::vec::Vec<GetTransactionInputsResponse>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAccountStateResponse {
+    /// ID and current hash of the account. The hash is the default digest if the account is unknown.
+    #[prost(message, optional, tag = "1")]
+    pub account_state: ::core::option::Option<AccountTransactionInputRecord>,
+}
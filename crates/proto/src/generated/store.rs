@@ -111,6 +111,27 @@ pub mod api_client {
             req.extensions_mut().insert(GrpcMethod::new("store.Api", "ApplyBlock"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn apply_blocks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::requests::ApplyBlocksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::ApplyBlocksResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/ApplyBlocks");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "ApplyBlocks"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn check_nullifiers(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -162,6 +183,32 @@ pub mod api_client {
                 .insert(GrpcMethod::new("store.Api", "CheckNullifiersByPrefix"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_account_creation_block(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountCreationBlockRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountCreationBlockResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetAccountCreationBlock",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetAccountCreationBlock"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_account_details(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -214,6 +261,32 @@ pub mod api_client {
                 .insert(GrpcMethod::new("store.Api", "GetAccountProofs"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_account_state(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountStateRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountStateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetAccountState",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetAccountState"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_account_state_delta(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -240,6 +313,32 @@ pub mod api_client {
                 .insert(GrpcMethod::new("store.Api", "GetAccountStateDelta"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_account_state_forks(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetAccountStateForksRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountStateForksResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetAccountStateForks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetAccountStateForks"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_block_by_number(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -315,6 +414,29 @@ pub mod api_client {
             req.extensions_mut().insert(GrpcMethod::new("store.Api", "GetBlockInputs"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_chain_stats(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetChainStatsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetChainStatsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/GetChainStats");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "GetChainStats"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_note_authentication_info(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -341,6 +463,32 @@ pub mod api_client {
                 .insert(GrpcMethod::new("store.Api", "GetNoteAuthenticationInfo"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_notes_authenticated_since(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetNotesAuthenticatedSinceRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNotesAuthenticatedSinceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetNotesAuthenticatedSince",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetNotesAuthenticatedSince"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_notes_by_id(
             &mut self,
             request: impl tonic::IntoRequest<super::super::requests::GetNotesByIdRequest>,
@@ -362,6 +510,84 @@ pub mod api_client {
             req.extensions_mut().insert(GrpcMethod::new("store.Api", "GetNotesById"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_nullifier_origin(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetNullifierOriginRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNullifierOriginResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetNullifierOrigin",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetNullifierOrigin"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_nullifier_root_history(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetNullifierRootHistoryRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNullifierRootHistoryResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetNullifierRootHistory",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetNullifierRootHistory"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_pending_nullifiers(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetPendingNullifiersRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetPendingNullifiersResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetPendingNullifiers",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetPendingNullifiers"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn get_transaction_inputs(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -388,6 +614,32 @@ pub mod api_client {
                 .insert(GrpcMethod::new("store.Api", "GetTransactionInputs"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_transaction_inputs_batch(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::requests::GetTransactionInputsBatchRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetTransactionInputsBatchResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/store.Api/GetTransactionInputsBatch",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("store.Api", "GetTransactionInputsBatch"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn list_accounts(
             &mut self,
             request: impl tonic::IntoRequest<super::super::requests::ListAccountsRequest>,
@@ -453,6 +705,29 @@ pub mod api_client {
             req.extensions_mut().insert(GrpcMethod::new("store.Api", "ListNullifiers"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn sync_blocks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::requests::SyncBlocksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<
+                tonic::codec::Streaming<super::super::responses::SyncBlocksResponse>,
+            >,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/store.Api/SyncBlocks");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("store.Api", "SyncBlocks"));
+            self.inner.server_streaming(req, path, codec).await
+        }
         pub async fn sync_notes(
             &mut self,
             request: impl tonic::IntoRequest<super::super::requests::SyncNoteRequest>,
@@ -517,6 +792,13 @@ pub mod api_server {
             tonic::Response<super::super::responses::ApplyBlockResponse>,
             tonic::Status,
         >;
+        async fn apply_blocks(
+            &self,
+            request: tonic::Request<super::super::requests::ApplyBlocksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::ApplyBlocksResponse>,
+            tonic::Status,
+        >;
         async fn check_nullifiers(
             &self,
             request: tonic::Request<super::super::requests::CheckNullifiersRequest>,
@@ -533,6 +815,13 @@ pub mod api_server {
             tonic::Response<super::super::responses::CheckNullifiersByPrefixResponse>,
             tonic::Status,
         >;
+        async fn get_account_creation_block(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountCreationBlockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountCreationBlockResponse>,
+            tonic::Status,
+        >;
         async fn get_account_details(
             &self,
             request: tonic::Request<super::super::requests::GetAccountDetailsRequest>,
@@ -547,6 +836,13 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetAccountProofsResponse>,
             tonic::Status,
         >;
+        async fn get_account_state(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountStateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountStateResponse>,
+            tonic::Status,
+        >;
         async fn get_account_state_delta(
             &self,
             request: tonic::Request<super::super::requests::GetAccountStateDeltaRequest>,
@@ -554,6 +850,13 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetAccountStateDeltaResponse>,
             tonic::Status,
         >;
+        async fn get_account_state_forks(
+            &self,
+            request: tonic::Request<super::super::requests::GetAccountStateForksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetAccountStateForksResponse>,
+            tonic::Status,
+        >;
         async fn get_block_by_number(
             &self,
             request: tonic::Request<super::super::requests::GetBlockByNumberRequest>,
@@ -577,6 +880,13 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetBlockInputsResponse>,
             tonic::Status,
         >;
+        async fn get_chain_stats(
+            &self,
+            request: tonic::Request<super::super::requests::GetChainStatsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetChainStatsResponse>,
+            tonic::Status,
+        >;
         async fn get_note_authentication_info(
             &self,
             request: tonic::Request<
@@ -586,6 +896,15 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetNoteAuthenticationInfoResponse>,
             tonic::Status,
         >;
+        async fn get_notes_authenticated_since(
+            &self,
+            request: tonic::Request<
+                super::super::requests::GetNotesAuthenticatedSinceRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetNotesAuthenticatedSinceResponse>,
+            tonic::Status,
+        >;
         async fn get_notes_by_id(
             &self,
             request: tonic::Request<super::super::requests::GetNotesByIdRequest>,
@@ -593,34 +912,75 @@ pub mod api_server {
             tonic::Response<super::super::responses::GetNotesByIdResponse>,
             tonic::Status,
         >;
-        async fn get_transaction_inputs(
+        async fn get_nullifier_origin(
             &self,
-            request: tonic::Request<super::super::requests::GetTransactionInputsRequest>,
+            request: tonic::Request<super::super::requests::GetNullifierOriginRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::GetTransactionInputsResponse>,
+            tonic::Response<super::super::responses::GetNullifierOriginResponse>,
             tonic::Status,
         >;
-        async fn list_accounts(
+        async fn get_nullifier_root_history(
             &self,
-            request: tonic::Request<super::super::requests::ListAccountsRequest>,
+            request: tonic::Request<super::super::requests::GetNullifierRootHistoryRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::ListAccountsResponse>,
+            tonic::Response<super::super::responses::GetNullifierRootHistoryResponse>,
             tonic::Status,
         >;
-        async fn list_notes(
+        async fn get_pending_nullifiers(
             &self,
-            request: tonic::Request<super::super::requests::ListNotesRequest>,
+            request: tonic::Request<super::super::requests::GetPendingNullifiersRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::super::responses::ListNotesResponse>,
+            tonic::Response<super::super::responses::GetPendingNullifiersResponse>,
             tonic::Status,
         >;
-        async fn list_nullifiers(
+        async fn get_transaction_inputs(
             &self,
-            request: tonic::Request<super::super::requests::ListNullifiersRequest>,
+            request: tonic::Request<super::super::requests::GetTransactionInputsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetTransactionInputsResponse>,
+            tonic::Status,
+        >;
+        async fn get_transaction_inputs_batch(
+            &self,
+            request: tonic::Request<super::super::requests::GetTransactionInputsBatchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::GetTransactionInputsBatchResponse>,
+            tonic::Status,
+        >;
+        async fn list_accounts(
+            &self,
+            request: tonic::Request<super::super::requests::ListAccountsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::ListAccountsResponse>,
+            tonic::Status,
+        >;
+        async fn list_notes(
+            &self,
+            request: tonic::Request<super::super::requests::ListNotesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::responses::ListNotesResponse>,
+            tonic::Status,
+        >;
+        async fn list_nullifiers(
+            &self,
+            request: tonic::Request<super::super::requests::ListNullifiersRequest>,
         ) -> std::result::Result<
             tonic::Response<super::super::responses::ListNullifiersResponse>,
             tonic::Status,
         >;
+        /// Server streaming response type for the SyncBlocks method.
+        type SyncBlocksStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::super::responses::SyncBlocksResponse,
+                    tonic::Status,
+                >,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn sync_blocks(
+            &self,
+            request: tonic::Request<super::super::requests::SyncBlocksRequest>,
+        ) -> std::result::Result<tonic::Response<Self::SyncBlocksStream>, tonic::Status>;
         async fn sync_notes(
             &self,
             request: tonic::Request<super::super::requests::SyncNoteRequest>,
@@ -714,13 +1074,398 @@ pub mod api_server {
             match req.uri().path() {
                 "/store.Api/ApplyBlock" => {
                     #[allow(non_camel_case_types)]
-                    struct ApplyBlockSvc<T: Api>(pub Arc<T>);
+                    struct ApplyBlockSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::ApplyBlockRequest,
+                    > for ApplyBlockSvc<T> {
+                        type Response = super::super::responses::ApplyBlockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::ApplyBlockRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::apply_block(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ApplyBlockSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/ApplyBlocks" => {
+                    #[allow(non_camel_case_types)]
+                    struct ApplyBlocksSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::ApplyBlocksRequest,
+                    > for ApplyBlocksSvc<T> {
+                        type Response = super::super::responses::ApplyBlocksResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::ApplyBlocksRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::apply_blocks(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ApplyBlocksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/CheckNullifiers" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckNullifiersSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::CheckNullifiersRequest,
+                    > for CheckNullifiersSvc<T> {
+                        type Response = super::super::responses::CheckNullifiersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::CheckNullifiersRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::check_nullifiers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckNullifiersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/CheckNullifiersByPrefix" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckNullifiersByPrefixSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::CheckNullifiersByPrefixRequest,
+                    > for CheckNullifiersByPrefixSvc<T> {
+                        type Response = super::super::responses::CheckNullifiersByPrefixResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::CheckNullifiersByPrefixRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::check_nullifiers_by_prefix(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckNullifiersByPrefixSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountCreationBlock" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountCreationBlockSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountCreationBlockRequest,
+                    > for GetAccountCreationBlockSvc<T> {
+                        type Response = super::super::responses::GetAccountCreationBlockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountCreationBlockRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_creation_block(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountCreationBlockSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountDetails" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountDetailsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountDetailsRequest,
+                    > for GetAccountDetailsSvc<T> {
+                        type Response = super::super::responses::GetAccountDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountDetailsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_details(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountDetailsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountProofs" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountProofsSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountProofsRequest,
+                    > for GetAccountProofsSvc<T> {
+                        type Response = super::super::responses::GetAccountProofsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountProofsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_proofs(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountProofsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountState" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountStateSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetAccountStateRequest,
+                    > for GetAccountStateSvc<T> {
+                        type Response = super::super::responses::GetAccountStateResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetAccountStateRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_account_state(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountStateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetAccountStateDelta" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountStateDeltaSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::ApplyBlockRequest,
-                    > for ApplyBlockSvc<T> {
-                        type Response = super::super::responses::ApplyBlockResponse;
+                        super::super::requests::GetAccountStateDeltaRequest,
+                    > for GetAccountStateDeltaSvc<T> {
+                        type Response = super::super::responses::GetAccountStateDeltaResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -728,12 +1473,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::ApplyBlockRequest,
+                                super::super::requests::GetAccountStateDeltaRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::apply_block(&inner, request).await
+                                <T as Api>::get_account_state_delta(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -744,7 +1489,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ApplyBlockSvc(inner);
+                        let method = GetAccountStateDeltaSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -760,15 +1505,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/CheckNullifiers" => {
+                "/store.Api/GetAccountStateForks" => {
                     #[allow(non_camel_case_types)]
-                    struct CheckNullifiersSvc<T: Api>(pub Arc<T>);
+                    struct GetAccountStateForksSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::CheckNullifiersRequest,
-                    > for CheckNullifiersSvc<T> {
-                        type Response = super::super::responses::CheckNullifiersResponse;
+                        super::super::requests::GetAccountStateForksRequest,
+                    > for GetAccountStateForksSvc<T> {
+                        type Response = super::super::responses::GetAccountStateForksResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -776,12 +1521,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::CheckNullifiersRequest,
+                                super::super::requests::GetAccountStateForksRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::check_nullifiers(&inner, request).await
+                                <T as Api>::get_account_state_forks(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -792,7 +1537,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CheckNullifiersSvc(inner);
+                        let method = GetAccountStateForksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -808,15 +1553,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/CheckNullifiersByPrefix" => {
+                "/store.Api/GetBlockByNumber" => {
                     #[allow(non_camel_case_types)]
-                    struct CheckNullifiersByPrefixSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockByNumberSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::CheckNullifiersByPrefixRequest,
-                    > for CheckNullifiersByPrefixSvc<T> {
-                        type Response = super::super::responses::CheckNullifiersByPrefixResponse;
+                        super::super::requests::GetBlockByNumberRequest,
+                    > for GetBlockByNumberSvc<T> {
+                        type Response = super::super::responses::GetBlockByNumberResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -824,13 +1569,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::CheckNullifiersByPrefixRequest,
+                                super::super::requests::GetBlockByNumberRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::check_nullifiers_by_prefix(&inner, request)
-                                    .await
+                                <T as Api>::get_block_by_number(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -841,7 +1585,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CheckNullifiersByPrefixSvc(inner);
+                        let method = GetBlockByNumberSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -857,15 +1601,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetAccountDetails" => {
+                "/store.Api/GetBlockHeaderByNumber" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountDetailsSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockHeaderByNumberSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountDetailsRequest,
-                    > for GetAccountDetailsSvc<T> {
-                        type Response = super::super::responses::GetAccountDetailsResponse;
+                        super::super::requests::GetBlockHeaderByNumberRequest,
+                    > for GetBlockHeaderByNumberSvc<T> {
+                        type Response = super::super::responses::GetBlockHeaderByNumberResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -873,12 +1617,13 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountDetailsRequest,
+                                super::super::requests::GetBlockHeaderByNumberRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_details(&inner, request).await
+                                <T as Api>::get_block_header_by_number(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -889,7 +1634,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountDetailsSvc(inner);
+                        let method = GetBlockHeaderByNumberSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -905,15 +1650,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetAccountProofs" => {
+                "/store.Api/GetBlockInputs" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountProofsSvc<T: Api>(pub Arc<T>);
+                    struct GetBlockInputsSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountProofsRequest,
-                    > for GetAccountProofsSvc<T> {
-                        type Response = super::super::responses::GetAccountProofsResponse;
+                        super::super::requests::GetBlockInputsRequest,
+                    > for GetBlockInputsSvc<T> {
+                        type Response = super::super::responses::GetBlockInputsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -921,12 +1666,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountProofsRequest,
+                                super::super::requests::GetBlockInputsRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_proofs(&inner, request).await
+                                <T as Api>::get_block_inputs(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -937,7 +1682,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountProofsSvc(inner);
+                        let method = GetBlockInputsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -953,15 +1698,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetAccountStateDelta" => {
+                "/store.Api/GetChainStats" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAccountStateDeltaSvc<T: Api>(pub Arc<T>);
+                    struct GetChainStatsSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetAccountStateDeltaRequest,
-                    > for GetAccountStateDeltaSvc<T> {
-                        type Response = super::super::responses::GetAccountStateDeltaResponse;
+                        super::super::requests::GetChainStatsRequest,
+                    > for GetChainStatsSvc<T> {
+                        type Response = super::super::responses::GetChainStatsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -969,12 +1714,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetAccountStateDeltaRequest,
+                                super::super::requests::GetChainStatsRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_account_state_delta(&inner, request).await
+                                <T as Api>::get_chain_stats(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -985,7 +1730,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetAccountStateDeltaSvc(inner);
+                        let method = GetChainStatsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1001,15 +1746,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetBlockByNumber" => {
+                "/store.Api/GetNoteAuthenticationInfo" => {
                     #[allow(non_camel_case_types)]
-                    struct GetBlockByNumberSvc<T: Api>(pub Arc<T>);
+                    struct GetNoteAuthenticationInfoSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetBlockByNumberRequest,
-                    > for GetBlockByNumberSvc<T> {
-                        type Response = super::super::responses::GetBlockByNumberResponse;
+                        super::super::requests::GetNoteAuthenticationInfoRequest,
+                    > for GetNoteAuthenticationInfoSvc<T> {
+                        type Response = super::super::responses::GetNoteAuthenticationInfoResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1017,12 +1762,13 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetBlockByNumberRequest,
+                                super::super::requests::GetNoteAuthenticationInfoRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_block_by_number(&inner, request).await
+                                <T as Api>::get_note_authentication_info(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -1033,7 +1779,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetBlockByNumberSvc(inner);
+                        let method = GetNoteAuthenticationInfoSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1049,15 +1795,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetBlockHeaderByNumber" => {
+                "/store.Api/GetNotesAuthenticatedSince" => {
                     #[allow(non_camel_case_types)]
-                    struct GetBlockHeaderByNumberSvc<T: Api>(pub Arc<T>);
+                    struct GetNotesAuthenticatedSinceSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetBlockHeaderByNumberRequest,
-                    > for GetBlockHeaderByNumberSvc<T> {
-                        type Response = super::super::responses::GetBlockHeaderByNumberResponse;
+                        super::super::requests::GetNotesAuthenticatedSinceRequest,
+                    > for GetNotesAuthenticatedSinceSvc<T> {
+                        type Response = super::super::responses::GetNotesAuthenticatedSinceResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1065,12 +1811,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetBlockHeaderByNumberRequest,
+                                super::super::requests::GetNotesAuthenticatedSinceRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_block_header_by_number(&inner, request)
+                                <T as Api>::get_notes_authenticated_since(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -1082,7 +1828,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetBlockHeaderByNumberSvc(inner);
+                        let method = GetNotesAuthenticatedSinceSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1098,15 +1844,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetBlockInputs" => {
+                "/store.Api/GetNotesById" => {
                     #[allow(non_camel_case_types)]
-                    struct GetBlockInputsSvc<T: Api>(pub Arc<T>);
+                    struct GetNotesByIdSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetBlockInputsRequest,
-                    > for GetBlockInputsSvc<T> {
-                        type Response = super::super::responses::GetBlockInputsResponse;
+                        super::super::requests::GetNotesByIdRequest,
+                    > for GetNotesByIdSvc<T> {
+                        type Response = super::super::responses::GetNotesByIdResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1114,12 +1860,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetBlockInputsRequest,
+                                super::super::requests::GetNotesByIdRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_block_inputs(&inner, request).await
+                                <T as Api>::get_notes_by_id(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1130,7 +1876,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetBlockInputsSvc(inner);
+                        let method = GetNotesByIdSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1146,15 +1892,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetNoteAuthenticationInfo" => {
+                "/store.Api/GetNullifierOrigin" => {
                     #[allow(non_camel_case_types)]
-                    struct GetNoteAuthenticationInfoSvc<T: Api>(pub Arc<T>);
+                    struct GetNullifierOriginSvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetNoteAuthenticationInfoRequest,
-                    > for GetNoteAuthenticationInfoSvc<T> {
-                        type Response = super::super::responses::GetNoteAuthenticationInfoResponse;
+                        super::super::requests::GetNullifierOriginRequest,
+                    > for GetNullifierOriginSvc<T> {
+                        type Response = super::super::responses::GetNullifierOriginResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1162,13 +1908,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetNoteAuthenticationInfoRequest,
+                                super::super::requests::GetNullifierOriginRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_note_authentication_info(&inner, request)
-                                    .await
+                                <T as Api>::get_nullifier_origin(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1179,7 +1924,7 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetNoteAuthenticationInfoSvc(inner);
+                        let method = GetNullifierOriginSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1195,15 +1940,15 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
-                "/store.Api/GetNotesById" => {
+                "/store.Api/GetNullifierRootHistory" => {
                     #[allow(non_camel_case_types)]
-                    struct GetNotesByIdSvc<T: Api>(pub Arc<T>);
+                    struct GetNullifierRootHistorySvc<T: Api>(pub Arc<T>);
                     impl<
                         T: Api,
                     > tonic::server::UnaryService<
-                        super::super::requests::GetNotesByIdRequest,
-                    > for GetNotesByIdSvc<T> {
-                        type Response = super::super::responses::GetNotesByIdResponse;
+                        super::super::requests::GetNullifierRootHistoryRequest,
+                    > for GetNullifierRootHistorySvc<T> {
+                        type Response = super::super::responses::GetNullifierRootHistoryResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -1211,12 +1956,12 @@ pub mod api_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::super::requests::GetNotesByIdRequest,
+                                super::super::requests::GetNullifierRootHistoryRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Api>::get_notes_by_id(&inner, request).await
+                                <T as Api>::get_nullifier_root_history(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1227,7 +1972,55 @@ pub mod api_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetNotesByIdSvc(inner);
+                        let method = GetNullifierRootHistorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/store.Api/GetPendingNullifiers" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPendingNullifiersSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetPendingNullifiersRequest,
+                    > for GetPendingNullifiersSvc<T> {
+                        type Response = super::super::responses::GetPendingNullifiersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetPendingNullifiersRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_pending_nullifiers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetPendingNullifiersSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1291,6 +2084,54 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
+                "/store.Api/GetTransactionInputsBatch" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTransactionInputsBatchSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::UnaryService<
+                        super::super::requests::GetTransactionInputsBatchRequest,
+                    > for GetTransactionInputsBatchSvc<T> {
+                        type Response = super::super::responses::GetTransactionInputsBatchResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::GetTransactionInputsBatchRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::get_transaction_inputs_batch(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetTransactionInputsBatchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/store.Api/ListAccounts" => {
                     #[allow(non_camel_case_types)]
                     struct ListAccountsSvc<T: Api>(pub Arc<T>);
@@ -1435,6 +2276,55 @@ pub mod api_server {
                     };
                     Box::pin(fut)
                 }
+                "/store.Api/SyncBlocks" => {
+                    #[allow(non_camel_case_types)]
+                    struct SyncBlocksSvc<T: Api>(pub Arc<T>);
+                    impl<
+                        T: Api,
+                    > tonic::server::ServerStreamingService<
+                        super::super::requests::SyncBlocksRequest,
+                    > for SyncBlocksSvc<T> {
+                        type Response = super::super::responses::SyncBlocksResponse;
+                        type ResponseStream = T::SyncBlocksStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::requests::SyncBlocksRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Api>::sync_blocks(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SyncBlocksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/store.Api/SyncNotes" => {
                     #[allow(non_camel_case_types)]
                     struct SyncNotesSvc<T: Api>(pub Arc<T>);
@@ -0,0 +1,11 @@
+CREATE TABLE
+    nullifier_origins
+(
+    nullifier      BLOB NOT NULL,
+    transaction_id BLOB NOT NULL,
+
+    PRIMARY KEY (nullifier),
+    FOREIGN KEY (nullifier) REFERENCES nullifiers(nullifier),
+    CONSTRAINT nullifier_origins_nullifier_is_digest CHECK (length(nullifier) = 32),
+    CONSTRAINT nullifier_origins_transaction_id_is_digest CHECK (length(transaction_id) = 32)
+) STRICT, WITHOUT ROWID;
@@ -0,0 +1,25 @@
+CREATE TABLE
+    account_creations
+(
+    account_id INTEGER NOT NULL,
+    block_num  INTEGER NOT NULL,
+
+    PRIMARY KEY (account_id),
+    FOREIGN KEY (block_num) REFERENCES block_headers(block_num),
+    CONSTRAINT account_creations_block_num_is_u32 CHECK (block_num BETWEEN 0 AND 0xFFFFFFFF)
+) STRICT, WITHOUT ROWID;
+
+-- Accounts are created by their first transaction, or in the genesis block without one.
+INSERT INTO
+    account_creations (account_id, block_num)
+SELECT
+    account_id,
+    MIN(
+        block_num,
+        IFNULL(
+            (SELECT MIN(block_num) FROM transactions WHERE transactions.account_id = accounts.account_id),
+            block_num
+        )
+    )
+FROM
+    accounts;
@@ -16,7 +16,7 @@ use miden_objects::{
     notes::{NoteId, NoteInclusionProof, NoteMetadata, Nullifier},
     transaction::TransactionId,
     utils::Serializable,
-    BlockHeader, GENESIS_BLOCK,
+    BlockHeader,
 };
 use rusqlite::vtab::array;
 use tokio::sync::oneshot;
@@ -71,7 +71,7 @@ pub struct NoteRecord {
 impl From<NoteRecord> for NotePb {
     fn from(note: NoteRecord) -> Self {
         Self {
-            block_num: note.block_num,
+            block_num: note.block_num.into(),
             note_index: note.note_index.leaf_index_value().into(),
             note_id: Some(note.note_id.into()),
             metadata: Some(note.metadata.into()),
@@ -232,6 +232,22 @@ impl Db {
             })?
     }
 
+    /// Loads the ID of the transaction which produced the given nullifier from the DB.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>> {
+        self.pool
+            .get()
+            .await?
+            .interact(move |conn| sql::select_nullifier_origin(conn, &nullifier))
+            .await
+            .map_err(|err| {
+                DatabaseError::InteractError(format!("Select nullifier origin task failed: {err}"))
+            })?
+    }
+
     /// Loads all the notes from the DB.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn select_all_notes(&self) -> Result<Vec<NoteRecord>> {
@@ -320,6 +336,24 @@ impl Db {
             })?
     }
 
+    /// Loads the number of the block in which the given account was created from the DB.
+    #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
+    pub async fn select_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>> {
+        self.pool
+            .get()
+            .await?
+            .interact(move |conn| sql::select_account_creation_block(conn, account_id))
+            .await
+            .map_err(|err| {
+                DatabaseError::InteractError(format!(
+                    "Select account creation block task failed: {err}"
+                ))
+            })?
+    }
+
     /// Loads public accounts details from the DB.
     #[instrument(target = "miden-store", skip_all, ret(level = "debug"), err)]
     pub async fn select_accounts_by_ids(
@@ -425,6 +459,7 @@ impl Db {
         acquire_done: oneshot::Receiver<()>,
         block: Block,
         notes: Vec<NoteRecord>,
+        nullifier_origins: Vec<(Nullifier, TransactionId)>,
     ) -> Result<()> {
         self.pool
             .get()
@@ -439,6 +474,7 @@ impl Db {
                     &block.header(),
                     &notes,
                     block.nullifiers(),
+                    &nullifier_origins,
                     block.updated_accounts(),
                 )?;
 
@@ -503,7 +539,7 @@ impl Db {
         };
 
         let maybe_block_header_in_store = self
-            .select_block_header_by_block_num(Some(GENESIS_BLOCK))
+            .select_block_header_by_block_num(Some(BlockNumber::GENESIS))
             .await
             .map_err(|err| GenesisError::SelectBlockHeaderByBlockNumError(err.into()))?;
 
@@ -537,6 +573,7 @@ impl Db {
                             &expected_genesis_header,
                             &[],
                             &[],
+                            &[],
                             genesis_block.updated_accounts(),
                         )?;
 
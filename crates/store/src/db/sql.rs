@@ -114,7 +114,8 @@ pub fn select_accounts_by_block_range(
     )?;
 
     let account_ids: Vec<Value> = account_ids.iter().copied().map(u64_to_value).collect();
-    let mut rows = stmt.query(params![block_start, block_end, Rc::new(account_ids)])?;
+    let mut rows =
+        stmt.query(params![block_start.as_u32(), block_end.as_u32(), Rc::new(account_ids)])?;
 
     let mut result = Vec::new();
     while let Some(row) = rows.next()? {
@@ -212,7 +213,8 @@ pub fn select_account_deltas(
     ",
     )?;
 
-    let mut rows = stmt.query(params![u64_to_value(account_id), block_start, block_end])?;
+    let mut rows =
+        stmt.query(params![u64_to_value(account_id), block_start.as_u32(), block_end.as_u32()])?;
     let mut result = Vec::new();
     while let Some(row) = rows.next()? {
         let delta = AccountDelta::read_from_bytes(row.get_ref(0)?.as_blob()?)?;
@@ -221,6 +223,26 @@ pub fn select_account_deltas(
     Ok(result)
 }
 
+/// Select the number of the block in which the given account was created from the DB using the
+/// given [Connection].
+///
+/// # Returns
+///
+/// The block number, or `None` if the account is not in the DB.
+pub fn select_account_creation_block(
+    conn: &mut Connection,
+    account_id: AccountId,
+) -> Result<Option<BlockNumber>> {
+    let mut stmt =
+        conn.prepare_cached("SELECT block_num FROM account_creations WHERE account_id = ?1;")?;
+    let mut rows = stmt.query(params![u64_to_value(account_id)])?;
+
+    match rows.next()? {
+        Some(row) => Ok(Some(BlockNumber::from(row.get::<_, u32>(0)?))),
+        None => Ok(None),
+    }
+}
+
 /// Inserts or updates accounts to the DB using the given [Transaction].
 ///
 /// # Returns
@@ -244,6 +266,9 @@ pub fn upsert_accounts(
     )?;
     let mut select_details_stmt =
         transaction.prepare_cached("SELECT details FROM accounts WHERE account_id = ?1;")?;
+    let mut insert_creation_stmt = transaction.prepare_cached(
+        "INSERT OR IGNORE INTO account_creations (account_id, block_num) VALUES (?1, ?2);",
+    )?;
 
     let mut count = 0;
     for update in accounts.iter() {
@@ -270,7 +295,7 @@ pub fn upsert_accounts(
 
                 insert_delta_stmt.execute(params![
                     u64_to_value(account_id),
-                    block_num,
+                    block_num.as_u32(),
                     delta.to_bytes()
                 ])?;
 
@@ -284,12 +309,15 @@ pub fn upsert_accounts(
         let inserted = upsert_stmt.execute(params![
             u64_to_value(account_id),
             update.new_state_hash().to_bytes(),
-            block_num,
+            block_num.as_u32(),
             full_account.as_ref().map(|account| account.to_bytes()),
         ])?;
 
         debug_assert_eq!(inserted, 1);
 
+        // Only the first update of an account is recorded as its creation.
+        insert_creation_stmt.execute(params![u64_to_value(account_id), block_num.as_u32()])?;
+
         count += inserted;
     }
 
@@ -320,12 +348,64 @@ pub fn insert_nullifiers_for_block(
 
     let mut count = 0;
     for nullifier in nullifiers.iter() {
-        count +=
-            stmt.execute(params![nullifier.to_bytes(), get_nullifier_prefix(nullifier), block_num])?
+        count += stmt.execute(params![
+            nullifier.to_bytes(),
+            get_nullifier_prefix(nullifier),
+            block_num.as_u32()
+        ])?
+    }
+    Ok(count)
+}
+
+/// Insert the transactions which produced the given nullifiers to the DB using the given
+/// [Transaction].
+///
+/// # Returns
+///
+/// The number of affected rows.
+///
+/// # Note
+///
+/// The [Transaction] object is not consumed. It's up to the caller to commit or rollback the
+/// transaction.
+pub fn insert_nullifier_origins(
+    transaction: &Transaction,
+    nullifier_origins: &[(Nullifier, TransactionId)],
+) -> Result<usize> {
+    let mut stmt = transaction.prepare_cached(
+        "INSERT INTO nullifier_origins (nullifier, transaction_id) VALUES (?1, ?2);",
+    )?;
+
+    let mut count = 0;
+    for (nullifier, transaction_id) in nullifier_origins.iter() {
+        count += stmt.execute(params![nullifier.to_bytes(), transaction_id.to_bytes()])?
     }
     Ok(count)
 }
 
+/// Select the transaction which produced the given nullifier from the DB using the given
+/// [Connection].
+///
+/// # Returns
+///
+/// The ID of the transaction, or `None` if the origin of the nullifier was not recorded.
+pub fn select_nullifier_origin(
+    conn: &mut Connection,
+    nullifier: &Nullifier,
+) -> Result<Option<TransactionId>> {
+    let mut stmt =
+        conn.prepare_cached("SELECT transaction_id FROM nullifier_origins WHERE nullifier = ?1;")?;
+    let mut rows = stmt.query(params![nullifier.to_bytes()])?;
+
+    match rows.next()? {
+        Some(row) => {
+            let transaction_id_data = row.get_ref(0)?.as_blob()?;
+            Ok(Some(TransactionId::read_from_bytes(transaction_id_data)?))
+        },
+        None => Ok(None),
+    }
+}
+
 /// Select all nullifiers from the DB using the given [Connection].
 ///
 /// # Returns
@@ -340,7 +420,7 @@ pub fn select_all_nullifiers(conn: &mut Connection) -> Result<Vec<(Nullifier, Bl
     while let Some(row) = rows.next()? {
         let nullifier_data = row.get_ref(0)?.as_blob()?;
         let nullifier = Nullifier::read_from_bytes(nullifier_data)?;
-        let block_number = row.get(1)?;
+        let block_number = BlockNumber::from(row.get::<_, u32>(1)?);
         result.push((nullifier, block_number));
     }
     Ok(result)
@@ -381,13 +461,14 @@ pub fn select_nullifiers_by_block_range(
     ",
     )?;
 
-    let mut rows = stmt.query(params![block_start, block_end, Rc::new(nullifier_prefixes)])?;
+    let mut rows =
+        stmt.query(params![block_start.as_u32(), block_end.as_u32(), Rc::new(nullifier_prefixes)])?;
 
     let mut result = Vec::new();
     while let Some(row) = rows.next()? {
         let nullifier_data = row.get_ref(0)?.as_blob()?;
         let nullifier = Nullifier::read_from_bytes(nullifier_data)?;
-        let block_num = row.get(1)?;
+        let block_num = BlockNumber::from(row.get::<_, u32>(1)?);
         result.push(NullifierInfo { nullifier, block_num });
     }
     Ok(result)
@@ -434,7 +515,7 @@ pub fn select_nullifiers_by_prefix(
     while let Some(row) = rows.next()? {
         let nullifier_data = row.get_ref(0)?.as_blob()?;
         let nullifier = Nullifier::read_from_bytes(nullifier_data)?;
-        let block_num = row.get(1)?;
+        let block_num = BlockNumber::from(row.get::<_, u32>(1)?);
         result.push(NullifierInfo { nullifier, block_num });
     }
     Ok(result)
@@ -499,7 +580,7 @@ pub fn select_all_notes(conn: &mut Connection) -> Result<Vec<NoteRecord>> {
         )?;
 
         notes.push(NoteRecord {
-            block_num: row.get(0)?,
+            block_num: BlockNumber::from(row.get::<_, u32>(0)?),
             note_index: BlockNoteIndex::new(row.get(1)?, row.get(2)?)?,
             note_id,
             metadata,
@@ -548,7 +629,7 @@ pub fn insert_notes(transaction: &Transaction, notes: &[NoteRecord]) -> Result<u
     for note in notes.iter() {
         let details = note.details.as_ref().map(|details| details.to_bytes());
         count += stmt.execute(params![
-            note.block_num,
+            note.block_num.as_u32(),
             note.note_index.batch_idx(),
             note.note_index.note_idx_in_batch(),
             note.note_id.to_bytes(),
@@ -621,11 +702,11 @@ pub fn select_notes_since_block_by_tag_and_sender(
 
     let tags: Vec<Value> = tags.iter().copied().map(u32_to_value).collect();
     let account_ids: Vec<Value> = account_ids.iter().copied().map(u64_to_value).collect();
-    let mut rows = stmt.query(params![Rc::new(tags), Rc::new(account_ids), block_num])?;
+    let mut rows = stmt.query(params![Rc::new(tags), Rc::new(account_ids), block_num.as_u32()])?;
 
     let mut res = Vec::new();
     while let Some(row) = rows.next()? {
-        let block_num = row.get(0)?;
+        let block_num = BlockNumber::from(row.get::<_, u32>(0)?);
         let note_index = BlockNoteIndex::new(row.get(1)?, row.get(2)?)?;
         let note_id_data = row.get_ref(3)?.as_blob()?;
         let note_id = RpoDigest::read_from_bytes(note_id_data)?;
@@ -716,7 +797,7 @@ pub fn select_notes_by_id(conn: &mut Connection, note_ids: &[NoteId]) -> Result<
         )?;
 
         notes.push(NoteRecord {
-            block_num: row.get(0)?,
+            block_num: BlockNumber::from(row.get::<_, u32>(0)?),
             note_index: BlockNoteIndex::new(row.get(1)?, row.get(2)?)?,
             details,
             note_id: note_id.into(),
@@ -814,7 +895,7 @@ pub fn select_block_header_by_block_num(
         Some(block_number) => {
             stmt =
                 conn.prepare_cached("SELECT block_header FROM block_headers WHERE block_num = ?1")?;
-            stmt.query([block_number])?
+            stmt.query([block_number.as_u32()])?
         },
         None => {
             stmt = conn.prepare_cached(
@@ -848,7 +929,8 @@ pub fn select_block_headers(
 ) -> Result<Vec<BlockHeader>> {
     let mut headers = Vec::with_capacity(blocks.len());
 
-    let blocks: Vec<Value> = blocks.iter().copied().map(u32_to_value).collect();
+    let blocks: Vec<Value> =
+        blocks.iter().map(|block_num| u32_to_value(block_num.as_u32())).collect();
     let mut stmt = conn
         .prepare_cached("SELECT block_header FROM block_headers WHERE block_num IN rarray(?1);")?;
     let mut rows = stmt.query(params![Rc::new(blocks)])?;
@@ -909,7 +991,7 @@ pub fn insert_transactions(
             count += stmt.execute(params![
                 transaction_id.to_bytes(),
                 u64_to_value(account_id),
-                block_num
+                block_num.as_u32()
             ])?
         }
     }
@@ -947,12 +1029,13 @@ pub fn select_transactions_by_accounts_and_block_range(
     ",
     )?;
 
-    let mut rows = stmt.query(params![block_start, block_end, Rc::new(account_ids)])?;
+    let mut rows =
+        stmt.query(params![block_start.as_u32(), block_end.as_u32(), Rc::new(account_ids)])?;
 
     let mut result = vec![];
     while let Some(row) = rows.next()? {
         let account_id = column_value_as_u64(row, 0)?;
-        let block_num = row.get(1)?;
+        let block_num = BlockNumber::from(row.get::<_, u32>(1)?);
         let transaction_id_data = row.get_ref(2)?.as_blob()?;
         let transaction_id = TransactionId::read_from_bytes(transaction_id_data)?;
 
@@ -984,22 +1067,15 @@ pub fn get_state_sync(
         select_block_header_by_block_num(conn, notes.first().map(|note| note.block_num))?
             .ok_or(StateSyncError::EmptyBlockHeadersTable)?;
 
-    let account_updates =
-        select_accounts_by_block_range(conn, block_num, block_header.block_num(), account_ids)?;
+    let block_end = BlockNumber::from(block_header.block_num());
 
-    let transactions = select_transactions_by_accounts_and_block_range(
-        conn,
-        block_num,
-        block_header.block_num(),
-        account_ids,
-    )?;
+    let account_updates = select_accounts_by_block_range(conn, block_num, block_end, account_ids)?;
 
-    let nullifiers = select_nullifiers_by_block_range(
-        conn,
-        block_num,
-        block_header.block_num(),
-        nullifier_prefixes,
-    )?;
+    let transactions =
+        select_transactions_by_accounts_and_block_range(conn, block_num, block_end, account_ids)?;
+
+    let nullifiers =
+        select_nullifiers_by_block_range(conn, block_num, block_end, nullifier_prefixes)?;
 
     Ok(StateSyncUpdate {
         notes,
@@ -1041,14 +1117,18 @@ pub fn apply_block(
     block_header: &BlockHeader,
     notes: &[NoteRecord],
     nullifiers: &[Nullifier],
+    nullifier_origins: &[(Nullifier, TransactionId)],
     accounts: &[BlockAccountUpdate],
 ) -> Result<usize> {
+    let block_num = BlockNumber::from(block_header.block_num());
+
     let mut count = 0;
     count += insert_block_header(transaction, block_header)?;
     count += insert_notes(transaction, notes)?;
-    count += upsert_accounts(transaction, accounts, block_header.block_num())?;
-    count += insert_transactions(transaction, block_header.block_num(), accounts)?;
-    count += insert_nullifiers_for_block(transaction, nullifiers, block_header.block_num())?;
+    count += upsert_accounts(transaction, accounts, block_num)?;
+    count += insert_transactions(transaction, block_num, accounts)?;
+    count += insert_nullifiers_for_block(transaction, nullifiers, block_num)?;
+    count += insert_nullifier_origins(transaction, nullifier_origins)?;
     Ok(count)
 }
 
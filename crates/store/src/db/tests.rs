@@ -15,12 +15,16 @@ use miden_objects::{
     block::{BlockAccountUpdate, BlockNoteIndex, BlockNoteTree},
     crypto::{hash::rpo::RpoDigest, merkle::MerklePath},
     notes::{NoteExecutionHint, NoteId, NoteMetadata, NoteType, Nullifier},
+    transaction::TransactionId,
     BlockHeader, Felt, FieldElement, Word, ONE, ZERO,
 };
 use rusqlite::{vtab::array, Connection};
 
 use super::{sql, AccountInfo, NoteRecord, NullifierInfo};
-use crate::db::{migrations::apply_migrations, TransactionSummary};
+use crate::{
+    db::{migrations::apply_migrations, TransactionSummary},
+    types::BlockNumber,
+};
 
 fn create_db() -> Connection {
     let mut conn = Connection::open_in_memory().unwrap();
@@ -29,11 +33,11 @@ fn create_db() -> Connection {
     conn
 }
 
-fn create_block(conn: &mut Connection, block_num: u32) {
+fn create_block(conn: &mut Connection, block_num: BlockNumber) {
     let block_header = BlockHeader::new(
         1_u8.into(),
         num_to_rpo_digest(2),
-        block_num,
+        block_num.as_u32(),
         num_to_rpo_digest(4),
         num_to_rpo_digest(5),
         num_to_rpo_digest(6),
@@ -55,7 +59,7 @@ fn test_sql_insert_nullifiers_for_block() {
 
     let nullifiers = [num_to_nullifier(1 << 48)];
 
-    let block_num = 1;
+    let block_num = BlockNumber::from(1);
     create_block(&mut conn, block_num);
 
     // Insert a new nullifier succeeds
@@ -76,7 +80,8 @@ fn test_sql_insert_nullifiers_for_block() {
     // even if the block number is different
     {
         let transaction = conn.transaction().unwrap();
-        let res = sql::insert_nullifiers_for_block(&transaction, &nullifiers, block_num + 1);
+        let res =
+            sql::insert_nullifiers_for_block(&transaction, &nullifiers, block_num.next().unwrap());
         transaction.commit().unwrap();
         assert!(
             res.is_err(),
@@ -87,7 +92,7 @@ fn test_sql_insert_nullifiers_for_block() {
     // test inserting multiple nullifiers
     {
         let nullifiers: Vec<_> = (0..10).map(num_to_nullifier).collect();
-        let block_num = 1;
+        let block_num = BlockNumber::from(1);
         let transaction = conn.transaction().unwrap();
         let res = sql::insert_nullifiers_for_block(&transaction, &nullifiers, block_num);
         transaction.commit().unwrap();
@@ -107,7 +112,13 @@ fn test_sql_insert_transactions() {
 #[test]
 fn test_sql_select_transactions() {
     fn query_transactions(conn: &mut Connection) -> Vec<TransactionSummary> {
-        sql::select_transactions_by_accounts_and_block_range(conn, 0, 2, &[1]).unwrap()
+        sql::select_transactions_by_accounts_and_block_range(
+            conn,
+            BlockNumber::GENESIS,
+            BlockNumber::from(2),
+            &[1],
+        )
+        .unwrap()
     }
 
     let mut conn = create_db();
@@ -129,7 +140,7 @@ fn test_sql_select_transactions() {
 fn test_sql_select_nullifiers() {
     let mut conn = create_db();
 
-    let block_num = 1;
+    let block_num = BlockNumber::from(1);
     create_block(&mut conn, block_num);
 
     // test querying empty table
@@ -151,11 +162,42 @@ fn test_sql_select_nullifiers() {
     }
 }
 
+#[test]
+fn test_sql_select_nullifier_origin() {
+    let mut conn = create_db();
+
+    let block_num = BlockNumber::from(1);
+    create_block(&mut conn, block_num);
+
+    let nullifier = num_to_nullifier(1 << 48);
+    let transaction_id = TransactionId::from(num_to_rpo_digest(3));
+
+    // test querying a nullifier without origin
+    let origin = sql::select_nullifier_origin(&mut conn, &nullifier).unwrap();
+    assert_eq!(origin, None);
+
+    let transaction = conn.transaction().unwrap();
+    sql::insert_nullifiers_for_block(&transaction, &[nullifier], block_num).unwrap();
+    let res = sql::insert_nullifier_origins(&transaction, &[(nullifier, transaction_id)]);
+    assert_eq!(res.unwrap(), 1, "One element must have been inserted");
+    transaction.commit().unwrap();
+
+    // the nullifier maps back to its originating transaction
+    let origin = sql::select_nullifier_origin(&mut conn, &nullifier).unwrap();
+    assert_eq!(origin, Some(transaction_id));
+
+    // origins can only be recorded for known nullifiers
+    let transaction = conn.transaction().unwrap();
+    let res =
+        sql::insert_nullifier_origins(&transaction, &[(num_to_nullifier(2 << 48), transaction_id)]);
+    assert!(res.is_err(), "Origin of an unknown nullifier must not be inserted");
+}
+
 #[test]
 fn test_sql_select_notes() {
     let mut conn = create_db();
 
-    let block_num = 1;
+    let block_num = BlockNumber::from(1);
     create_block(&mut conn, block_num);
 
     // test querying empty table
@@ -195,7 +237,7 @@ fn test_sql_select_notes() {
 fn test_sql_select_notes_different_execution_hints() {
     let mut conn = create_db();
 
-    let block_num = 1;
+    let block_num = BlockNumber::from(1);
     create_block(&mut conn, block_num);
 
     // test querying empty table
@@ -282,7 +324,7 @@ fn test_sql_select_notes_different_execution_hints() {
 fn test_sql_select_accounts() {
     let mut conn = create_db();
 
-    let block_num = 1;
+    let block_num = BlockNumber::from(1);
     create_block(&mut conn, block_num);
 
     // test querying empty table
@@ -298,7 +340,7 @@ fn test_sql_select_accounts() {
             summary: AccountSummary {
                 account_id: account_id.try_into().unwrap(),
                 account_hash,
-                block_num,
+                block_num: block_num.as_u32(),
             },
             details: None,
         });
@@ -325,7 +367,7 @@ fn test_sql_select_accounts() {
 fn test_sql_public_account_details() {
     let mut conn = create_db();
 
-    let block_num = 1;
+    let block_num = BlockNumber::from(1);
     create_block(&mut conn, block_num);
 
     let account_id =
@@ -450,7 +492,8 @@ fn test_sql_public_account_details() {
 
     account.apply_delta(&delta2).unwrap();
 
-    create_block(&mut conn, block_num + 1);
+    let next_block_num = block_num.next().unwrap();
+    create_block(&mut conn, next_block_num);
 
     let transaction = conn.transaction().unwrap();
     let inserted = sql::upsert_accounts(
@@ -461,7 +504,7 @@ fn test_sql_public_account_details() {
             AccountUpdateDetails::Delta(delta2.clone()),
             vec![],
         )],
-        block_num + 1,
+        next_block_num,
     )
     .unwrap();
 
@@ -479,8 +522,13 @@ fn test_sql_public_account_details() {
     assert_eq!(account_read.vault(), account.vault());
     assert_eq!(account_read.nonce(), account.nonce());
 
-    let read_deltas =
-        sql::select_account_deltas(&mut conn, account_id.into(), 0, block_num + 1).unwrap();
+    let read_deltas = sql::select_account_deltas(
+        &mut conn,
+        account_id.into(),
+        BlockNumber::GENESIS,
+        next_block_num,
+    )
+    .unwrap();
 
     assert_eq!(read_deltas, vec![delta, delta2]);
 }
@@ -490,12 +538,18 @@ fn test_sql_select_nullifiers_by_block_range() {
     let mut conn = create_db();
 
     // test empty table
-    let nullifiers = sql::select_nullifiers_by_block_range(&mut conn, 0, u32::MAX, &[]).unwrap();
+    let nullifiers = sql::select_nullifiers_by_block_range(
+        &mut conn,
+        BlockNumber::GENESIS,
+        BlockNumber::MAX,
+        &[],
+    )
+    .unwrap();
     assert!(nullifiers.is_empty());
 
     // test single item
     let nullifier1 = num_to_nullifier(1 << 48);
-    let block_number1 = 1;
+    let block_number1 = BlockNumber::from(1);
     create_block(&mut conn, block_number1);
 
     let transaction = conn.transaction().unwrap();
@@ -504,8 +558,8 @@ fn test_sql_select_nullifiers_by_block_range() {
 
     let nullifiers = sql::select_nullifiers_by_block_range(
         &mut conn,
-        0,
-        u32::MAX,
+        BlockNumber::GENESIS,
+        BlockNumber::MAX,
         &[sql::get_nullifier_prefix(&nullifier1)],
     )
     .unwrap();
@@ -519,7 +573,7 @@ fn test_sql_select_nullifiers_by_block_range() {
 
     // test two elements
     let nullifier2 = num_to_nullifier(2 << 48);
-    let block_number2 = 2;
+    let block_number2 = BlockNumber::from(2);
     create_block(&mut conn, block_number2);
 
     let transaction = conn.transaction().unwrap();
@@ -532,8 +586,8 @@ fn test_sql_select_nullifiers_by_block_range() {
     // only the nullifiers matching the prefix are included
     let nullifiers = sql::select_nullifiers_by_block_range(
         &mut conn,
-        0,
-        u32::MAX,
+        BlockNumber::GENESIS,
+        BlockNumber::MAX,
         &[sql::get_nullifier_prefix(&nullifier1)],
     )
     .unwrap();
@@ -546,8 +600,8 @@ fn test_sql_select_nullifiers_by_block_range() {
     );
     let nullifiers = sql::select_nullifiers_by_block_range(
         &mut conn,
-        0,
-        u32::MAX,
+        BlockNumber::GENESIS,
+        BlockNumber::MAX,
         &[sql::get_nullifier_prefix(&nullifier2)],
     )
     .unwrap();
@@ -562,8 +616,8 @@ fn test_sql_select_nullifiers_by_block_range() {
     // Nullifiers created at block_end are included
     let nullifiers = sql::select_nullifiers_by_block_range(
         &mut conn,
-        0,
-        1,
+        BlockNumber::GENESIS,
+        block_number1,
         &[sql::get_nullifier_prefix(&nullifier1), sql::get_nullifier_prefix(&nullifier2)],
     )
     .unwrap();
@@ -578,8 +632,8 @@ fn test_sql_select_nullifiers_by_block_range() {
     // Nullifiers created at block_start are not included
     let nullifiers = sql::select_nullifiers_by_block_range(
         &mut conn,
-        1,
-        u32::MAX,
+        block_number1,
+        BlockNumber::MAX,
         &[sql::get_nullifier_prefix(&nullifier1), sql::get_nullifier_prefix(&nullifier2)],
     )
     .unwrap();
@@ -595,8 +649,8 @@ fn test_sql_select_nullifiers_by_block_range() {
     // when the client requests a sync update, and it is already tracking the chain tip.
     let nullifiers = sql::select_nullifiers_by_block_range(
         &mut conn,
-        2,
-        2,
+        block_number2,
+        block_number2,
         &[sql::get_nullifier_prefix(&nullifier1), sql::get_nullifier_prefix(&nullifier2)],
     )
     .unwrap();
@@ -613,7 +667,7 @@ fn test_select_nullifiers_by_prefix() {
 
     // test single item
     let nullifier1 = num_to_nullifier(1 << 48);
-    let block_number1 = 1;
+    let block_number1 = BlockNumber::from(1);
     create_block(&mut conn, block_number1);
 
     let transaction = conn.transaction().unwrap();
@@ -636,7 +690,7 @@ fn test_select_nullifiers_by_prefix() {
 
     // test two elements
     let nullifier2 = num_to_nullifier(2 << 48);
-    let block_number2 = 2;
+    let block_number2 = BlockNumber::from(2);
     create_block(&mut conn, block_number2);
 
     let transaction = conn.transaction().unwrap();
@@ -710,7 +764,7 @@ fn test_db_block_header() {
     let mut conn = create_db();
 
     // test querying empty table
-    let block_number = 1;
+    let block_number = BlockNumber::from(1);
     let res = sql::select_block_header_by_block_num(&mut conn, Some(block_number)).unwrap();
     assert!(res.is_none());
 
@@ -739,13 +793,14 @@ fn test_db_block_header() {
     transaction.commit().unwrap();
 
     // test fetch unknown block header
-    let block_number = 1;
+    let block_number = BlockNumber::from(1);
     let res = sql::select_block_header_by_block_num(&mut conn, Some(block_number)).unwrap();
     assert!(res.is_none());
 
     // test fetch block header by block number
     let res =
-        sql::select_block_header_by_block_num(&mut conn, Some(block_header.block_num())).unwrap();
+        sql::select_block_header_by_block_num(&mut conn, Some(block_header.block_num().into()))
+            .unwrap();
     assert_eq!(res.unwrap(), block_header);
 
     // test fetch latest block header
@@ -781,12 +836,18 @@ fn test_db_block_header() {
 fn test_db_account() {
     let mut conn = create_db();
 
-    let block_num = 1;
+    let block_num = BlockNumber::from(1);
     create_block(&mut conn, block_num);
 
     // test empty table
     let account_ids = vec![ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN, 1, 2, 3, 4, 5];
-    let res = sql::select_accounts_by_block_range(&mut conn, 0, u32::MAX, &account_ids).unwrap();
+    let res = sql::select_accounts_by_block_range(
+        &mut conn,
+        BlockNumber::GENESIS,
+        BlockNumber::MAX,
+        &account_ids,
+    )
+    .unwrap();
     assert!(res.is_empty());
 
     // test insertion
@@ -810,40 +871,95 @@ fn test_db_account() {
     assert_eq!(row_count, 1);
 
     // test successful query
-    let res = sql::select_accounts_by_block_range(&mut conn, 0, u32::MAX, &account_ids).unwrap();
+    let res = sql::select_accounts_by_block_range(
+        &mut conn,
+        BlockNumber::GENESIS,
+        BlockNumber::MAX,
+        &account_ids,
+    )
+    .unwrap();
     assert_eq!(
         res,
         vec![AccountSummary {
             account_id: account_id.try_into().unwrap(),
             account_hash,
-            block_num,
+            block_num: block_num.as_u32(),
         }]
     );
 
     // test query for update outside the block range
-    let res = sql::select_accounts_by_block_range(&mut conn, block_num + 1, u32::MAX, &account_ids)
-        .unwrap();
+    let res = sql::select_accounts_by_block_range(
+        &mut conn,
+        block_num.next().unwrap(),
+        BlockNumber::MAX,
+        &account_ids,
+    )
+    .unwrap();
     assert!(res.is_empty());
 
     // test query with unknown accounts
-    let res = sql::select_accounts_by_block_range(&mut conn, block_num + 1, u32::MAX, &[6, 7, 8])
-        .unwrap();
+    let res = sql::select_accounts_by_block_range(
+        &mut conn,
+        block_num.next().unwrap(),
+        BlockNumber::MAX,
+        &[6, 7, 8],
+    )
+    .unwrap();
     assert!(res.is_empty());
 }
 
+#[test]
+fn test_db_account_creation_block() {
+    let mut conn = create_db();
+    create_block(&mut conn, BlockNumber::from(1));
+    create_block(&mut conn, BlockNumber::from(2));
+
+    let account_id = ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN;
+    assert_eq!(sql::select_account_creation_block(&mut conn, account_id).unwrap(), None);
+
+    // the account is created in block 1 and updated in block 2
+    for block_num in [1, 2].map(BlockNumber::from) {
+        let transaction = conn.transaction().unwrap();
+        sql::upsert_accounts(
+            &transaction,
+            &[BlockAccountUpdate::new(
+                account_id.try_into().unwrap(),
+                num_to_rpo_digest(block_num.as_u32().into()),
+                AccountUpdateDetails::Private,
+                vec![],
+            )],
+            block_num,
+        )
+        .unwrap();
+        transaction.commit().unwrap();
+    }
+
+    assert_eq!(
+        sql::select_account_creation_block(&mut conn, account_id).unwrap(),
+        Some(BlockNumber::from(1))
+    );
+}
+
 #[test]
 fn test_notes() {
     let mut conn = create_db();
 
-    let block_num_1 = 1;
+    let block_num_1 = BlockNumber::from(1);
     create_block(&mut conn, block_num_1);
 
     // test empty table
-    let res = sql::select_notes_since_block_by_tag_and_sender(&mut conn, &[], &[], 0).unwrap();
+    let res =
+        sql::select_notes_since_block_by_tag_and_sender(&mut conn, &[], &[], BlockNumber::GENESIS)
+            .unwrap();
     assert!(res.is_empty());
 
-    let res =
-        sql::select_notes_since_block_by_tag_and_sender(&mut conn, &[1, 2, 3], &[], 0).unwrap();
+    let res = sql::select_notes_since_block_by_tag_and_sender(
+        &mut conn,
+        &[1, 2, 3],
+        &[],
+        BlockNumber::GENESIS,
+    )
+    .unwrap();
     assert!(res.is_empty());
 
     // test insertion
@@ -881,7 +997,9 @@ fn test_notes() {
     transaction.commit().unwrap();
 
     // test empty tags
-    let res = sql::select_notes_since_block_by_tag_and_sender(&mut conn, &[], &[], 0).unwrap();
+    let res =
+        sql::select_notes_since_block_by_tag_and_sender(&mut conn, &[], &[], BlockNumber::GENESIS)
+            .unwrap();
     assert!(res.is_empty());
 
     // test no updates
@@ -890,12 +1008,16 @@ fn test_notes() {
     assert!(res.is_empty());
 
     // test match
-    let res =
-        sql::select_notes_since_block_by_tag_and_sender(&mut conn, &[tag], &[], block_num_1 - 1)
-            .unwrap();
+    let res = sql::select_notes_since_block_by_tag_and_sender(
+        &mut conn,
+        &[tag],
+        &[],
+        block_num_1.prev().unwrap(),
+    )
+    .unwrap();
     assert_eq!(res, vec![note.clone().into()]);
 
-    let block_num_2 = note.block_num + 1;
+    let block_num_2 = note.block_num.next().unwrap();
     create_block(&mut conn, block_num_2);
 
     // insertion second note with same tag, but on higher block
@@ -913,9 +1035,13 @@ fn test_notes() {
     transaction.commit().unwrap();
 
     // only first note is returned
-    let res =
-        sql::select_notes_since_block_by_tag_and_sender(&mut conn, &[tag], &[], block_num_1 - 1)
-            .unwrap();
+    let res = sql::select_notes_since_block_by_tag_and_sender(
+        &mut conn,
+        &[tag],
+        &[],
+        block_num_1.prev().unwrap(),
+    )
+    .unwrap();
     assert_eq!(res, vec![note.clone().into()]);
 
     // only the second note is returned
@@ -962,7 +1088,7 @@ fn mock_block_account_update(account_id: AccountId, num: u64) -> BlockAccountUpd
 }
 
 fn insert_transactions(conn: &mut Connection) -> usize {
-    let block_num = 1;
+    let block_num = BlockNumber::from(1);
     create_block(conn, block_num);
 
     let transaction = conn.transaction().unwrap();
@@ -1,20 +1,25 @@
 use std::io;
 
 use deadpool_sqlite::PoolError;
+use miden_node_proto::errors::{
+    AccountVersionConflictDetail, StaleBlockDetail, ACCOUNT_VERSION_CONFLICT_DETAIL_TYPE_URL,
+    STALE_BLOCK_DETAIL_TYPE_URL,
+};
 use miden_objects::{
     crypto::{
         hash::rpo::RpoDigest,
-        merkle::{MerkleError, MmrError},
+        merkle::{MerkleError, MmrError, NodeIndex},
         utils::DeserializationError,
     },
     notes::Nullifier,
     transaction::OutputNote,
-    AccountDeltaError, AccountError, BlockError, BlockHeader, NoteError,
+    AccountDeltaError, AccountError, BlockError, BlockHeader, Felt, NoteError, Word,
 };
+use prost::Message;
 use rusqlite::types::FromSqlError;
 use thiserror::Error;
 use tokio::sync::oneshot::error::RecvError;
-use tonic::Status;
+use tonic::{Code, Status};
 
 use crate::types::{AccountId, BlockNumber};
 
@@ -25,11 +30,40 @@ use crate::types::{AccountId, BlockNumber};
 pub enum NullifierTreeError {
     #[error("Merkle error: {0}")]
     MerkleError(#[from] MerkleError),
-    #[error("Nullifier {nullifier} for block #{block_num} already exists in the nullifier tree")]
-    NullifierAlreadyExists {
+    #[error("Nullifier tree root mismatch: expected {expected}, but tree has {actual}")]
+    RootMismatch { expected: RpoDigest, actual: RpoDigest },
+    #[error("Nullifier {0} is malformed")]
+    InvalidNullifier(Nullifier),
+    #[error("Opening of nullifier {nullifier} is invalid against nullifier tree root {root}")]
+    InvalidProof { nullifier: Nullifier, root: RpoDigest },
+    #[error("Leaf of nullifier {key} has value {value:?}, which is not a valid block number")]
+    CorruptLeaf { key: RpoDigest, value: Word },
+    #[error("Leaf value element {0} does not encode a block number")]
+    InvalidLeafEncoding(Felt),
+    #[error("Failed to deserialize nullifier tree: {0}")]
+    DeserializationError(DeserializationError),
+    #[error("Block #{block_num} does not follow the latest block #{latest_block_num} in the tree")]
+    NonMonotonicBlock {
+        block_num: BlockNumber,
+        latest_block_num: BlockNumber,
+    },
+    #[error("Nullifier {nullifier} was already consumed in block #{block_num}")]
+    AlreadyConsumed {
         nullifier: Nullifier,
         block_num: BlockNumber,
     },
+    #[error("Nullifier {0} is already reserved for a block being built")]
+    NullifierReserved(Nullifier),
+    #[error("Reservation {0} was not made on this nullifier tree")]
+    UnknownReservation(u64),
+    #[error("Nullifier tree root {0} is unknown or no longer retained")]
+    UnknownRoot(RpoDigest),
+    #[error("Multiproof lacks node {0:?} needed to compute the nullifier tree root")]
+    IncompleteMultiproof(NodeIndex),
+    #[error("Nullifier tree snapshot does not start with a valid header")]
+    InvalidSnapshotHeader,
+    #[error("Nullifier tree snapshot has {actual} bytes after its header, but its header declares {expected}")]
+    TruncatedSnapshot { expected: u64, actual: u64 },
 }
 
 // DATABASE ERRORS
@@ -61,6 +95,8 @@ pub enum DatabaseError {
     MissingDbConnection(#[from] PoolError),
     #[error("Note error: {0}")]
     NoteError(#[from] NoteError),
+    #[error("Nullifier tree error: {0}")]
+    NullifierTreeError(#[from] NullifierTreeError),
     #[error("SQLite error: {0}")]
     SqliteError(#[from] rusqlite::Error),
 
@@ -177,10 +213,12 @@ pub enum InvalidBlockError {
     InvalidOutputNoteType(Box<OutputNote>),
     #[error("Invalid tx hash: expected {expected}, but got {actual}")]
     InvalidTxHash { expected: RpoDigest, actual: RpoDigest },
+    #[error("Origin of nullifier {0} does not refer to a nullifier and transaction of the block")]
+    InvalidNullifierOrigin(Nullifier),
     #[error("Received invalid account tree root")]
     NewBlockInvalidAccountRoot,
-    #[error("New block number must be 1 greater than the current block number")]
-    NewBlockInvalidBlockNum,
+    #[error("New block number must be 1 greater than the current block number: expected {expected}, but got {got}")]
+    NewBlockInvalidBlockNum { expected: BlockNumber, got: BlockNumber },
     #[error("New block chain root is not consistent with chain MMR")]
     NewBlockInvalidChainRoot,
     #[error("Received invalid note root")]
@@ -203,11 +241,15 @@ pub enum ApplyBlockError {
     TokioJoinError(#[from] tokio::task::JoinError),
     #[error("Invalid block error: {0}")]
     InvalidBlockError(#[from] InvalidBlockError),
+    #[error("Nullifier tree error: {0}")]
+    NullifierTreeError(#[from] NullifierTreeError),
 
     // OTHER ERRORS
     // ---------------------------------------------------------------------------------------------
     #[error("Block applying was cancelled because of closed channel on database side: {0}")]
     ClosedChannel(RecvError),
+    #[error("Account {0} changed since it was read by the transactions of the block")]
+    AccountVersionConflict(AccountId),
     #[error("Concurrent write detected")]
     ConcurrentWrite,
     #[error("Database doesn't have any block header data")]
@@ -219,6 +261,31 @@ pub enum ApplyBlockError {
 impl From<ApplyBlockError> for Status {
     fn from(err: ApplyBlockError) -> Self {
         match err {
+            ApplyBlockError::InvalidBlockError(InvalidBlockError::NewBlockInvalidBlockNum {
+                expected,
+                got,
+            }) => Status::with_details(
+                Code::FailedPrecondition,
+                err.to_string(),
+                status_details(
+                    STALE_BLOCK_DETAIL_TYPE_URL,
+                    StaleBlockDetail {
+                        expected: expected.into(),
+                        got: got.into(),
+                    }
+                    .encode_to_vec(),
+                )
+                .into(),
+            ),
+            ApplyBlockError::AccountVersionConflict(account_id) => Status::with_details(
+                Code::FailedPrecondition,
+                err.to_string(),
+                status_details(
+                    ACCOUNT_VERSION_CONFLICT_DETAIL_TYPE_URL,
+                    AccountVersionConflictDetail { account_id }.encode_to_vec(),
+                )
+                .into(),
+            ),
             ApplyBlockError::InvalidBlockError(_) => Status::invalid_argument(err.to_string()),
 
             _ => Status::internal(err.to_string()),
@@ -226,6 +293,25 @@ impl From<ApplyBlockError> for Status {
     }
 }
 
+/// A `google.protobuf.Any` detail of a `google.rpc.Status`.
+#[derive(Clone, PartialEq, Message)]
+struct StatusDetail {
+    #[prost(string, tag = "1")]
+    type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+/// Returns the encoded `google.rpc.Status` details of a status with one detail of type `type_url`.
+fn status_details(type_url: &str, value: Vec<u8>) -> Vec<u8> {
+    let detail = StatusDetail { type_url: type_url.to_string(), value };
+
+    // The details are field 3 of `google.rpc.Status`.
+    let mut details = Vec::new();
+    prost::encoding::message::encode(3, &detail, &mut details);
+    details
+}
+
 #[derive(Error, Debug)]
 pub enum GetBlockHeaderError {
     #[error("Database error: {0}")]
@@ -234,12 +320,39 @@ pub enum GetBlockHeaderError {
     MmrError(#[from] MmrError),
 }
 
+#[derive(Error, Debug)]
+pub enum NullifierRootHistoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] DatabaseError),
+    #[error("Invalid block range: from block {from} is after to block {to}")]
+    InvalidRange { from: BlockNumber, to: BlockNumber },
+    #[error("Requested {requested} blocks, but at most {max} blocks can be requested at once")]
+    RangeTooLarge { requested: u32, max: u32 },
+    #[error("Block {to} is after the chain tip at block {chain_tip}")]
+    BlockAfterChainTip { to: BlockNumber, chain_tip: BlockNumber },
+    #[error("Header of block {0} was pruned from the store")]
+    HistoryPruned(BlockNumber),
+}
+
+impl From<NullifierRootHistoryError> for Status {
+    fn from(err: NullifierRootHistoryError) -> Self {
+        match err {
+            NullifierRootHistoryError::DatabaseError(err) => err.into(),
+            NullifierRootHistoryError::HistoryPruned(_) => Status::not_found(err.to_string()),
+
+            _ => Status::invalid_argument(err.to_string()),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum GetBlockInputsError {
     #[error("Account error: {0}")]
     AccountError(#[from] AccountError),
     #[error("Database error: {0}")]
     DatabaseError(#[from] DatabaseError),
+    #[error("Nullifier tree error: {0}")]
+    NullifierTreeError(#[from] NullifierTreeError),
     #[error("Database doesn't have any block header data")]
     DbBlockHeaderEmpty,
     #[error("Failed to get MMR peaks for forest ({forest}): {error}")]
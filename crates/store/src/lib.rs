@@ -3,7 +3,8 @@ pub mod config;
 pub mod db;
 pub mod errors;
 pub mod genesis;
-mod nullifier_tree;
+pub mod nullifier_tree;
+mod pending_nullifiers;
 pub mod server;
 pub mod state;
 pub mod types;
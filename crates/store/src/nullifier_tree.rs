@@ -1,108 +1,2836 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::Arc,
+};
+
+use miden_node_proto::{domain::nullifiers, errors::InvalidLeafEncoding};
 use miden_objects::{
     crypto::{
-        hash::rpo::RpoDigest,
-        merkle::{MutationSet, Smt, SmtProof, SMT_DEPTH},
+        hash::rpo::{Rpo256, RpoDigest},
+        merkle::{
+            DefaultMerkleStore, LeafIndex, MutationSet, NodeIndex, Smt, SmtLeaf, SmtProof,
+            SMT_DEPTH,
+        },
     },
     notes::Nullifier,
+    utils::serde::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
     Felt, FieldElement, Word,
 };
+use tracing::warn;
 
-use crate::{errors::NullifierTreeError, types::BlockNumber};
+use crate::{errors::NullifierTreeError, types::BlockNumber, COMPONENT};
+
+/// How to handle leaves whose value does not decode to a block number when loading a
+/// [NullifierTree].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CorruptionPolicy {
+    /// Fail loading the tree on the first corrupt leaf.
+    #[default]
+    Abort,
+    /// Leave corrupt leaves out of the tree, logging a warning for each of them.
+    SkipAndWarn,
+    /// Leave corrupt leaves out of the tree, and return them alongside the tree.
+    Collect,
+}
 
 /// Nullifier SMT.
+///
+/// The SMT is shared with the snapshots of the tree, and is only copied when the tree is mutated
+/// while snapshots of it exist. Nullifiers reserved for blocks being built are tracked alongside
+/// the SMT, but are not part of it until committed.
 #[derive(Debug, Clone)]
-pub struct NullifierTree(Smt);
+pub struct NullifierTree {
+    /// The SMT of the committed nullifiers, shared with the snapshots of the tree
+    smt: Arc<Smt>,
+    /// Nullifiers reserved for blocks being built
+    reservations: Reservations,
+    /// State of the warning about the size of the tree
+    growth_warning: GrowthWarning,
+    /// Entries added by the last applications of mutations, if retained
+    root_history: RootHistory,
+    /// Index of the nullifiers by prefix, if enabled
+    prefix_index: PrefixIndex,
+    /// Number of the latest block applied to the tree
+    latest_block_num: BlockNumber,
+    /// Number of consumed nullifiers in the SMT
+    num_nullifiers: usize,
+}
 
 impl NullifierTree {
     /// Construct new nullifier tree from list of items.
+    ///
+    /// Entries with block number zero are corrupt, and are handled according to `policy`. See
+    /// [NullifierTree::from_leaves] for details.
     pub fn with_entries(
         entries: impl IntoIterator<Item = (Nullifier, BlockNumber)>,
-    ) -> Result<Self, NullifierTreeError> {
+        policy: CorruptionPolicy,
+    ) -> Result<(Self, Vec<(RpoDigest, Word)>), NullifierTreeError> {
         let leaves = entries.into_iter().map(|(nullifier, block_num)| {
             (nullifier.inner(), Self::block_num_to_leaf_value(block_num))
         });
 
+        Self::from_leaves(leaves, policy)
+    }
+
+    /// Constructs a nullifier tree from entries sorted by leaf, without duplicate nullifiers, like
+    /// the nullifiers exported by the database.
+    ///
+    /// Entries must be in the order of [NullifierTree::leaves]: by leaf index, and by nullifier
+    /// within a leaf. The resulting tree is the same as with [NullifierTree::with_entries], and
+    /// is built the same way, as the SMT can't be built from precomputed inner nodes. Unlike
+    /// [NullifierTree::with_entries], this takes no [CorruptionPolicy]: exports are expected to be
+    /// sound, so their order is checked in debug builds and corrupt entries are always rejected.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::CorruptLeaf] for an entry with block number zero, and an error
+    /// if a nullifier appears twice in `entries`.
+    pub fn with_sorted_unique_entries(
+        entries: &[(Nullifier, BlockNumber)],
+    ) -> Result<Self, NullifierTreeError> {
+        debug_assert!(
+            entries.windows(2).all(|pair| leaf_order(&pair[0].0) < leaf_order(&pair[1].0)),
+            "nullifier tree entries must be sorted by leaf and unique"
+        );
+
+        let mut leaves = Vec::with_capacity(entries.len());
+        for (nullifier, block_num) in entries {
+            let (key, value) = (nullifier.inner(), Self::block_num_to_leaf_value(*block_num));
+            if block_num.is_genesis() {
+                return Err(NullifierTreeError::CorruptLeaf { key, value });
+            }
+            leaves.push((key, value));
+        }
+
         let inner = Smt::with_entries(leaves)?;
 
-        Ok(Self(inner))
+        Ok(Self::from_smt(inner))
+    }
+
+    /// Constructs a nullifier tree from raw `(key, value)` leaf pairs, as exported by
+    /// [NullifierTree::leaves].
+    ///
+    /// Pairs whose value does not decode to a non-zero block number are corrupt, and are handled
+    /// according to `policy`. Returns the tree together with the corrupt pairs, which are only
+    /// collected with [CorruptionPolicy::Collect].
+    ///
+    /// # Errors
+    /// Returns an error if a pair is corrupt and `policy` is [CorruptionPolicy::Abort], or if the
+    /// pairs contain duplicate keys.
+    pub fn from_leaves(
+        leaves: impl IntoIterator<Item = (RpoDigest, Word)>,
+        policy: CorruptionPolicy,
+    ) -> Result<(Self, Vec<(RpoDigest, Word)>), NullifierTreeError> {
+        let mut valid = Vec::new();
+        let mut corrupt = Vec::new();
+        for (key, value) in leaves {
+            if Self::leaf_value_to_block_num(value).is_ok() {
+                valid.push((key, value));
+                continue;
+            }
+
+            match policy {
+                CorruptionPolicy::Abort => {
+                    return Err(NullifierTreeError::CorruptLeaf { key, value });
+                },
+                CorruptionPolicy::SkipAndWarn => {
+                    warn!(target: COMPONENT, %key, ?value, "Skipping corrupt nullifier tree leaf");
+                },
+                CorruptionPolicy::Collect => corrupt.push((key, value)),
+            }
+        }
+
+        let inner = Smt::with_entries(valid)?;
+
+        let tree = Self::from_smt(inner);
+
+        Ok((tree, corrupt))
+    }
+
+    /// Enables logging a warning the first time the tree holds more than `threshold` nullifiers
+    /// after applying mutations, prompting operators to prune it.
+    ///
+    /// The warning is logged at most once per tree.
+    pub fn with_growth_warning(mut self, threshold: usize) -> Self {
+        self.growth_warning = GrowthWarning {
+            threshold: Some(threshold),
+            warned: false,
+        };
+        self
+    }
+
+    /// Enables retaining the entries added by the last `capacity` applications of mutations, so
+    /// deltas since the roots before them can be serialized with
+    /// [NullifierTree::serialize_delta].
+    pub fn with_root_history(mut self, capacity: usize) -> Self {
+        self.root_history = RootHistory {
+            capacity,
+            applied: VecDeque::with_capacity(capacity),
+        };
+        self
+    }
+
+    /// Enables maintaining an index of the nullifiers of the tree by the `prefix_bits` most
+    /// significant bits of their most significant element, so the nullifiers with a given
+    /// prefix can be listed with [NullifierTree::nullifiers_with_prefix] without a pass over all
+    /// entries of the tree.
+    ///
+    /// The index is built from the current entries, and kept up to date by all mutations of the
+    /// tree. It is not serialized with the tree.
+    ///
+    /// # Panics
+    /// Panics if `prefix_bits` is zero or greater than 64.
+    pub fn with_prefix_index(mut self, prefix_bits: u32) -> Self {
+        assert!((1..=64).contains(&prefix_bits), "prefix must have between 1 and 64 bits");
+
+        let mut index = PrefixIndex { prefix_bits, nullifiers: BTreeMap::new() };
+        for (key, _) in self.smt.entries() {
+            index.insert(*key);
+        }
+        self.prefix_index = index;
+        self
+    }
+
+    /// Returns the nullifiers of the tree with the given prefix, see
+    /// [NullifierTree::with_prefix_index].
+    ///
+    /// Returns an empty list if the index is disabled.
+    pub fn nullifiers_with_prefix(&self, prefix: u64) -> Vec<Nullifier> {
+        self.prefix_index
+            .nullifiers
+            .get(&prefix)
+            .map(|keys| keys.iter().copied().map(Nullifier::from).collect())
+            .unwrap_or_default()
     }
 
     /// Returns the root of the nullifier SMT.
     pub fn root(&self) -> RpoDigest {
-        self.0.root()
+        self.smt.root()
+    }
+
+    /// Returns an error if the root of the tree differs from `expected`, e.g. a reference root
+    /// published by another node.
+    pub fn assert_root(&self, expected: RpoDigest) -> Result<(), NullifierTreeError> {
+        let actual = self.root();
+        if actual != expected {
+            return Err(NullifierTreeError::RootMismatch { expected, actual });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the nullifiers whose block numbers differ between this tree and `reference`,
+    /// sorted by nullifier.
+    ///
+    /// This pinpoints the entries responsible for a root mismatch reported by
+    /// [NullifierTree::assert_root]. The result is empty if and only if both trees have the same
+    /// root.
+    pub fn diagnose_divergence(&self, reference: &NullifierTree) -> Vec<NullifierDivergence> {
+        if self.root() == reference.root() {
+            return Vec::new();
+        }
+
+        let local: BTreeMap<_, _> = self.smt.entries().copied().collect();
+        let reference: BTreeMap<_, _> = reference.smt.entries().copied().collect();
+        let keys: BTreeSet<_> = local.keys().chain(reference.keys()).collect();
+
+        keys.into_iter()
+            .filter(|key| local.get(*key) != reference.get(*key))
+            .map(|key| NullifierDivergence {
+                nullifier: Nullifier::from(*key),
+                local: local.get(key).and_then(|value| Self::leaf_value_to_block_num(*value).ok()),
+                reference: reference
+                    .get(key)
+                    .and_then(|value| Self::leaf_value_to_block_num(*value).ok()),
+            })
+            .collect()
+    }
+
+    /// Serializes the entries added to the tree since its root was `since_root`, which can be
+    /// applied to a tree with that root with [NullifierTree::apply_delta].
+    ///
+    /// This is much smaller than the serialization of the whole tree when replicating it to a tree
+    /// which is a few blocks behind.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::UnknownRoot] if `since_root` is neither the current root nor
+    /// a root retained by [NullifierTree::with_root_history].
+    pub fn serialize_delta(&self, since_root: RpoDigest) -> Result<Vec<u8>, NullifierTreeError> {
+        let entries = self
+            .root_history
+            .entries_since(since_root, self.root())
+            .ok_or(NullifierTreeError::UnknownRoot(since_root))?;
+
+        Ok(NullifierTreeDelta { since_root, root: self.root(), entries }.to_bytes())
+    }
+
+    /// Applies a delta serialized by [NullifierTree::serialize_delta].
+    ///
+    /// # Errors
+    /// Returns an error if the delta cannot be deserialized, and
+    /// [NullifierTreeError::RootMismatch] if the tree is not at the root the delta was serialized
+    /// since, or the delta doesn't result in the root it was serialized at. The tree is left
+    /// unchanged on error.
+    pub fn apply_delta(&mut self, bytes: &[u8]) -> Result<(), NullifierTreeError> {
+        let delta = NullifierTreeDelta::read_from_bytes(bytes)
+            .map_err(NullifierTreeError::DeserializationError)?;
+        self.assert_root(delta.since_root)?;
+
+        let mutations = self.compute_mutations(delta.entries);
+        if mutations.root() != delta.root {
+            return Err(NullifierTreeError::RootMismatch {
+                expected: delta.root,
+                actual: mutations.root(),
+            });
+        }
+
+        self.apply_mutations(mutations)
+    }
+
+    /// Compares two serialized nullifier trees, e.g. the trees exported by two nodes, without
+    /// access to the nodes.
+    ///
+    /// Entries of tree `a` are reported as local and entries of tree `b` as reference, see
+    /// [NullifierTree::diagnose_divergence].
+    ///
+    /// # Errors
+    /// Returns an error if either of the trees cannot be deserialized.
+    pub fn diff_snapshots(a: &[u8], b: &[u8]) -> Result<SnapshotDiff, NullifierTreeError> {
+        let a = Self::from_snapshot_bytes(a)?;
+        let b = Self::from_snapshot_bytes(b)?;
+
+        Ok(SnapshotDiff {
+            root_a: a.root(),
+            root_b: b.root(),
+            divergences: a.diagnose_divergence(&b),
+        })
+    }
+
+    /// Deserializes a tree serialized with [Serializable::to_bytes], e.g. a snapshot of the tree
+    /// written to disk to speed up restarts.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::InvalidSnapshotHeader] if `bytes` don't start with the header
+    /// of a serialized tree, [NullifierTreeError::TruncatedSnapshot] if they are shorter or longer
+    /// than declared by the header, and [NullifierTreeError::DeserializationError] if the entries
+    /// cannot be deserialized or don't match the serialized root.
+    pub fn from_snapshot_bytes(bytes: &[u8]) -> Result<Self, NullifierTreeError> {
+        let (len, entries) = bytes
+            .strip_prefix(&SNAPSHOT_MAGIC)
+            .and_then(<[u8]>::split_first_chunk::<8>)
+            .ok_or(NullifierTreeError::InvalidSnapshotHeader)?;
+        let expected = u64::from_le_bytes(*len);
+        if expected != entries.len() as u64 {
+            return Err(NullifierTreeError::TruncatedSnapshot {
+                expected,
+                actual: entries.len() as u64,
+            });
+        }
+
+        <Self as Deserializable>::read_from_bytes(bytes)
+            .map_err(NullifierTreeError::DeserializationError)
+    }
+
+    /// Returns a read-only copy of the tree in its current state, without copying the tree.
+    ///
+    /// The snapshot is unaffected by later mutations of the tree, which lets background tasks read
+    /// a consistent state of the tree without holding a lock on it. The first mutation of the tree
+    /// while a snapshot exists copies the tree.
+    pub fn snapshot(&self) -> NullifierTreeSnapshot {
+        NullifierTreeSnapshot(Arc::clone(&self.smt))
+    }
+
+    /// Returns the number of the latest block applied to the tree.
+    ///
+    /// This is the latest block recorded with [NullifierTree::record_applied_block], or the
+    /// latest block whose nullifiers were applied, whichever is later.
+    pub fn latest_block_num(&self) -> BlockNumber {
+        self.latest_block_num
+    }
+
+    /// Records that block `block_num` was applied to the tree.
+    ///
+    /// Blocks without nullifiers leave no trace in the SMT, so they must be recorded for
+    /// [NullifierTree::validate_block_nullifiers] to accept the block following them. Recording
+    /// a block older than the latest applied block has no effect.
+    pub fn record_applied_block(&mut self, block_num: BlockNumber) {
+        self.latest_block_num = self.latest_block_num.max(block_num);
+    }
+
+    /// Returns the number of consumed nullifiers.
+    pub fn num_nullifiers(&self) -> usize {
+        self.num_nullifiers
+    }
+
+    /// Returns the number of nullifiers consumed in each block, for blocks which consumed any.
+    ///
+    /// The counts are aggregated in one pass over the entries of the tree.
+    pub fn per_block_counts(&self) -> BTreeMap<BlockNumber, u64> {
+        let mut counts = BTreeMap::new();
+        for block_num in self
+            .smt
+            .entries()
+            .filter_map(|(_, value)| Self::leaf_value_to_block_num(*value).ok())
+        {
+            *counts.entry(block_num).or_default() += 1;
+        }
+
+        counts
+    }
+
+    /// Returns an iterator over all consumed nullifiers together with the block numbers in which
+    /// they were consumed, in the order of their leaves.
+    ///
+    /// Each nullifier is reconstructed from its key in the SMT, and each block number is decoded
+    /// with [NullifierTree::leaf_value_to_block_num]. Empty leaf values, which don't encode a
+    /// block number, are skipped.
+    ///
+    /// The entries are read from the tree as the iterator advances, so memory use doesn't grow
+    /// with the size of the tree, e.g. when reconciling the tree with a database cursor.
+    pub fn iter(&self) -> impl Iterator<Item = (Nullifier, BlockNumber)> + '_ {
+        self.smt.entries().filter_map(|(key, value)| {
+            Self::leaf_value_to_block_num(*value)
+                .ok()
+                .map(|block_num| (Nullifier::from(*key), block_num))
+        })
     }
 
     /// Returns an opening of the leaf associated with the given nullifier.
     pub fn open(&self, nullifier: &Nullifier) -> SmtProof {
-        self.0.open(&nullifier.inner())
+        self.smt.open(&nullifier.inner())
+    }
+
+    /// Same as [NullifierTree::open], but validates the nullifier first.
+    ///
+    /// This is meant for nullifiers received from untrusted sources, e.g. requests.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::InvalidNullifier] if the key of the nullifier is the zero
+    /// digest, which is not the hash of any note.
+    pub fn try_open(&self, nullifier: &Nullifier) -> Result<SmtProof, NullifierTreeError> {
+        if nullifier.inner() == RpoDigest::default() {
+            return Err(NullifierTreeError::InvalidNullifier(*nullifier));
+        }
+
+        Ok(self.open(nullifier))
+    }
+
+    /// Returns an opening of the leaf of each of `nullifiers`, in the order of `nullifiers`.
+    ///
+    /// Same as calling [NullifierTree::open] for each nullifier, but leaves room for sharing the
+    /// computation of the nodes common to the paths of the leaves.
+    pub fn open_batch(&self, nullifiers: &[Nullifier]) -> Vec<SmtProof> {
+        nullifiers.iter().map(|nullifier| self.open(nullifier)).collect()
+    }
+
+    /// Returns a multiproof of the leaves of `nullifiers` against the root of the tree, in which
+    /// the nodes shared by the paths of the leaves are included once.
+    pub fn multiproof(&self, nullifiers: &[Nullifier]) -> SmtMultiproof {
+        SmtMultiproof::from_proofs(self.open_batch(nullifiers))
     }
 
     /// Returns block number stored for the given nullifier or `None` if the nullifier wasn't
     /// consumed.
-    pub fn get_block_num(&self, nullifier: &Nullifier) -> Option<BlockNumber> {
-        let value = self.0.get_value(&nullifier.inner());
-        if value == Smt::EMPTY_VALUE {
-            return None;
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::CorruptLeaf] if the leaf value of the nullifier doesn't
+    /// encode a block number.
+    pub fn get_block_num(
+        &self,
+        nullifier: &Nullifier,
+    ) -> Result<Option<BlockNumber>, NullifierTreeError> {
+        get_block_num(&self.smt, nullifier)
+    }
+
+    /// Returns true if the given nullifier was consumed.
+    ///
+    /// This is cheaper than [NullifierTree::get_block_num] for membership checks: the leaf values
+    /// of consumed nullifiers are block numbers, which are never zero, so only the element holding
+    /// the block number is checked.
+    pub fn contains(&self, nullifier: &Nullifier) -> bool {
+        self.smt.get_value(&nullifier.inner())[0] != Felt::ZERO
+    }
+
+    /// Returns the block number in which each of `nullifiers` was consumed, or `None` for
+    /// nullifiers which weren't consumed, in the order of `nullifiers`.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::CorruptLeaf] for the first nullifier whose leaf value doesn't
+    /// encode a block number.
+    pub fn get_block_nums(
+        &self,
+        nullifiers: &[Nullifier],
+    ) -> Result<Vec<Option<BlockNumber>>, NullifierTreeError> {
+        nullifiers.iter().map(|nullifier| get_block_num(&self.smt, nullifier)).collect()
+    }
+
+    /// Same as [NullifierTree::get_block_nums], but meant for looking up many nullifiers at
+    /// once, e.g. for warming caches or benchmarking. With the `concurrent` feature, the lookups
+    /// are spread over the rayon thread pool.
+    pub fn bulk_get(
+        &self,
+        nullifiers: &[Nullifier],
+    ) -> Result<Vec<Option<BlockNumber>>, NullifierTreeError> {
+        #[cfg(feature = "concurrent")]
+        {
+            use rayon::prelude::*;
+
+            nullifiers
+                .par_iter()
+                .map(|nullifier| get_block_num(&self.smt, nullifier))
+                .collect()
         }
 
-        Some(Self::leaf_value_to_block_num(value))
+        #[cfg(not(feature = "concurrent"))]
+        self.get_block_nums(nullifiers)
+    }
+
+    /// Returns the raw `(key, value)` pairs stored in the leaves of the nullifier SMT, sorted by
+    /// leaf index.
+    ///
+    /// Unlike the decoded block numbers, this exposes the exact contents of the SMT and is meant
+    /// for leaf-by-leaf comparison against other implementations. Empty leaves are implicit in a
+    /// sparse tree and are never returned: the SMT drops pairs set to the empty value, e.g. by
+    /// pruning, so only consumed nullifiers are returned.
+    pub fn leaves(&self) -> Vec<(RpoDigest, Word)> {
+        self.smt
+            .leaves()
+            .flat_map(|(_, leaf)| leaf.entries().into_iter().copied())
+            .collect()
+    }
+
+    /// Returns a witness of marking the given nullifiers as consumed in block `block_num`, which
+    /// can be verified without access to the tree.
+    pub fn block_witness(
+        &self,
+        block_num: BlockNumber,
+        nullifiers: impl IntoIterator<Item = Nullifier>,
+    ) -> NullifierBlockWitness {
+        let nullifiers = nullifiers
+            .into_iter()
+            .map(|nullifier| (nullifier, self.open(&nullifier)))
+            .collect();
+
+        NullifierBlockWitness::new(self.root(), block_num, nullifiers)
     }
 
     /// Computes mutations for the nullifier SMT.
     pub fn compute_mutations(
         &self,
         kv_pairs: impl IntoIterator<Item = (Nullifier, BlockNumber)>,
-    ) -> MutationSet<SMT_DEPTH, RpoDigest, Word> {
-        self.0.compute_mutations(kv_pairs.into_iter().map(|(nullifier, block_num)| {
+    ) -> NullifierTreeMutations {
+        self.compute_leaf_mutations(kv_pairs.into_iter().map(|(nullifier, block_num)| {
             (nullifier.inner(), Self::block_num_to_leaf_value(block_num))
         }))
     }
 
+    /// Marks the given nullifiers as consumed at their block numbers if none of them was consumed
+    /// before, and returns the new root of the tree.
+    ///
+    /// Checking and inserting happen in one step, so no other insertion can consume one of the
+    /// nullifiers in between. Nullifiers reserved with [NullifierTree::reserve] can only be
+    /// consumed by committing their reservation.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::AlreadyConsumed] for the first nullifier which is
+    /// already consumed, or which appears twice in `updates`,
+    /// [NullifierTreeError::NullifierReserved] for the first nullifier which is reserved, and
+    /// [NullifierTreeError::CorruptLeaf] for a block number of zero. The tree is left unchanged
+    /// on error.
+    pub fn check_and_insert(
+        &mut self,
+        updates: &[(Nullifier, BlockNumber)],
+    ) -> Result<RpoDigest, NullifierTreeError> {
+        let mut inserted = BTreeMap::new();
+        for (nullifier, block_num) in updates {
+            let existing = self
+                .get_block_num(nullifier)?
+                .or_else(|| inserted.get(&nullifier.inner()).copied());
+            if let Some(existing) = existing {
+                return Err(NullifierTreeError::AlreadyConsumed {
+                    nullifier: *nullifier,
+                    block_num: existing,
+                });
+            }
+            if self.reservations.reserved.contains_key(&nullifier.inner()) {
+                return Err(NullifierTreeError::NullifierReserved(*nullifier));
+            }
+            if block_num.is_genesis() {
+                return Err(NullifierTreeError::CorruptLeaf {
+                    key: nullifier.inner(),
+                    value: Self::block_num_to_leaf_value(*block_num),
+                });
+            }
+
+            inserted.insert(nullifier.inner(), *block_num);
+        }
+
+        let mutations = self.compute_mutations(updates.iter().copied());
+        self.apply_mutations(mutations)?;
+
+        Ok(self.root())
+    }
+
+    /// Marks the given nullifiers as consumed at their block numbers, and returns the new root of
+    /// the tree.
+    ///
+    /// This computes and applies the mutations in one step, unlike
+    /// [NullifierTree::compute_mutations] and [NullifierTree::apply_mutations], so the mutations
+    /// can't be applied to a tree whose root changed in between. Consuming a nullifier twice is a
+    /// bug, so nullifiers which are already consumed are rejected instead of being overwritten.
+    ///
+    /// # Errors
+    /// Same as [NullifierTree::check_and_insert]. The tree is left unchanged on error.
+    pub fn insert_nullifiers(
+        &mut self,
+        kv_pairs: impl IntoIterator<Item = (Nullifier, BlockNumber)>,
+    ) -> Result<RpoDigest, NullifierTreeError> {
+        let updates: Vec<_> = kv_pairs.into_iter().collect();
+
+        self.check_and_insert(&updates)
+    }
+
+    /// Checks that the nullifiers of block `block_num` can be applied to the tree, before the
+    /// block is applied.
+    ///
+    /// The block must directly follow the latest block applied to the tree, see
+    /// [NullifierTree::latest_block_num], and none of its nullifiers may be consumed already.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::NonMonotonicBlock] if `block_num` is not the block after the
+    /// latest applied block, and [NullifierTreeError::AlreadyConsumed] for the first nullifier
+    /// which is already consumed, or which appears twice in `nullifiers`.
+    pub fn validate_block_nullifiers(
+        &self,
+        block_num: BlockNumber,
+        nullifiers: &[Nullifier],
+    ) -> Result<(), NullifierTreeError> {
+        let latest_block_num = self.latest_block_num;
+        if latest_block_num.next() != Some(block_num) {
+            return Err(NullifierTreeError::NonMonotonicBlock { block_num, latest_block_num });
+        }
+
+        let mut seen = BTreeSet::new();
+        for nullifier in nullifiers {
+            if let Some(consumed_at) = self.get_block_num(nullifier)? {
+                return Err(NullifierTreeError::AlreadyConsumed {
+                    nullifier: *nullifier,
+                    block_num: consumed_at,
+                });
+            }
+            if !seen.insert(nullifier.inner()) {
+                return Err(NullifierTreeError::AlreadyConsumed {
+                    nullifier: *nullifier,
+                    block_num,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reserves the given nullifiers for block `block_num`, which is being built.
+    ///
+    /// Reserved nullifiers are not consumed in the tree, and do not change its root, until the
+    /// reservation is committed with [NullifierTree::commit]. Until then, they cannot be reserved
+    /// for another block, so concurrently built blocks never consume the same nullifier. Releasing
+    /// the reservation with [NullifierTree::release] makes them available again.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::AlreadyConsumed] for the first nullifier which is already
+    /// consumed, [NullifierTreeError::NullifierReserved] for the first nullifier which is already
+    /// reserved or appears twice in `nullifiers`, and [NullifierTreeError::CorruptLeaf] for a
+    /// block number of zero. No nullifier is reserved on error.
+    pub fn reserve(
+        &mut self,
+        nullifiers: &[Nullifier],
+        block_num: BlockNumber,
+    ) -> Result<ReservationToken, NullifierTreeError> {
+        let mut seen = BTreeSet::new();
+        for nullifier in nullifiers {
+            if let Some(consumed_at) = self.get_block_num(nullifier)? {
+                return Err(NullifierTreeError::AlreadyConsumed {
+                    nullifier: *nullifier,
+                    block_num: consumed_at,
+                });
+            }
+            if self.reservations.reserved.contains_key(&nullifier.inner())
+                || !seen.insert(nullifier.inner())
+            {
+                return Err(NullifierTreeError::NullifierReserved(*nullifier));
+            }
+            if block_num.is_genesis() {
+                return Err(NullifierTreeError::CorruptLeaf {
+                    key: nullifier.inner(),
+                    value: Self::block_num_to_leaf_value(block_num),
+                });
+            }
+        }
+
+        let id = self.reservations.next_id;
+        self.reservations.next_id += 1;
+        self.reservations
+            .reserved
+            .extend(nullifiers.iter().map(|nullifier| (nullifier.inner(), id)));
+
+        Ok(ReservationToken {
+            id,
+            block_num,
+            nullifiers: nullifiers.to_vec(),
+        })
+    }
+
+    /// Marks the nullifiers of the reservation as consumed at its block number, and returns the
+    /// new root of the tree.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::UnknownReservation] if the reservation was not made on this
+    /// tree, and [NullifierTreeError::AlreadyConsumed] for the first nullifier of the reservation
+    /// which was consumed since it was reserved, e.g. by mutations applied with
+    /// [NullifierTree::apply_mutations]. The reservation can't be committed anymore in the latter
+    /// case, so it is released. The tree is left unchanged on error.
+    pub fn commit(&mut self, token: ReservationToken) -> Result<RpoDigest, NullifierTreeError> {
+        if !self.holds(&token) {
+            return Err(NullifierTreeError::UnknownReservation(token.id));
+        }
+        let mut consumed = None;
+        for nullifier in &token.nullifiers {
+            if let Some(block_num) = self.get_block_num(nullifier)? {
+                consumed = Some((*nullifier, block_num));
+                break;
+            }
+        }
+        if let Some((nullifier, block_num)) = consumed {
+            self.release(token);
+            return Err(NullifierTreeError::AlreadyConsumed { nullifier, block_num });
+        }
+
+        let mutations = self.compute_mutations(
+            token.nullifiers.iter().map(|nullifier| (*nullifier, token.block_num)),
+        );
+        self.apply_mutations(mutations)?;
+        self.release(token);
+
+        Ok(self.root())
+    }
+
+    /// Cancels the reservation, so its nullifiers can be reserved for other blocks.
+    ///
+    /// Reservations which were not made on this tree are ignored.
+    pub fn release(&mut self, token: ReservationToken) {
+        if !self.holds(&token) {
+            return;
+        }
+
+        for nullifier in &token.nullifiers {
+            self.reservations.reserved.remove(&nullifier.inner());
+        }
+    }
+
     /// Applies mutations to the nullifier SMT.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::CorruptLeaf] for the first leaf whose new value is neither
+    /// empty nor encodes a block number. The tree is left unchanged on error.
     pub fn apply_mutations(
         &mut self,
-        mutations: MutationSet<SMT_DEPTH, RpoDigest, Word>,
+        mutations: NullifierTreeMutations,
+    ) -> Result<(), NullifierTreeError> {
+        check_leaf_values(&mutations)?;
+        let applied = self.root_history.entry(&mutations);
+        let indexed = self.prefix_index.changes(&mutations);
+        let latest_block_num = latest_block_num_of(&mutations);
+        let num_nullifiers = self.num_nullifiers_after(&mutations);
+        Arc::make_mut(&mut self.smt).apply_mutations(mutations.mutations)?;
+        self.root_history.push(applied);
+        self.prefix_index.apply(indexed);
+        self.record_applied_block(latest_block_num);
+        self.num_nullifiers = num_nullifiers;
+        self.warn_if_grown();
+
+        Ok(())
+    }
+
+    /// Applies mutations to the nullifier SMT in chunks of at most `chunk_size` leaves, yielding
+    /// to the async runtime between chunks.
+    ///
+    /// This avoids stalling the runtime when applying the mutations of very large blocks. The
+    /// resulting root is the same as when applying all mutations at once.
+    pub async fn apply_mutations_batched(
+        &mut self,
+        mutations: NullifierTreeMutations,
+        chunk_size: usize,
     ) -> Result<(), NullifierTreeError> {
-        self.0.apply_mutations(mutations).map_err(Into::into)
+        if mutations.old_root() != self.root() {
+            return Err(NullifierTreeError::RootMismatch {
+                expected: mutations.old_root(),
+                actual: self.root(),
+            });
+        }
+        check_leaf_values(&mutations)?;
+
+        let applied = self.root_history.entry(&mutations);
+        let indexed = self.prefix_index.changes(&mutations);
+        let latest_block_num = latest_block_num_of(&mutations);
+        let num_nullifiers = self.num_nullifiers_after(&mutations);
+        for (i, chunk) in mutations.new_pairs.chunks(chunk_size.max(1)).enumerate() {
+            if i > 0 {
+                tokio::task::yield_now().await;
+            }
+
+            let inner = Arc::make_mut(&mut self.smt);
+            let chunk_mutations = inner.compute_mutations(chunk.iter().copied());
+            inner.apply_mutations(chunk_mutations)?;
+        }
+
+        debug_assert_eq!(self.root(), mutations.root());
+        self.root_history.push(applied);
+        self.prefix_index.apply(indexed);
+        self.record_applied_block(latest_block_num);
+        self.num_nullifiers = num_nullifiers;
+        self.warn_if_grown();
+
+        Ok(())
+    }
+
+    /// Removes the nullifiers consumed before block `block_num` from the tree, and returns the
+    /// number of removed nullifiers.
+    ///
+    /// This bounds the size of trees which only need the recent nullifiers, e.g. for checking
+    /// whether notes were spent within a retention window. Pruning changes the root of the tree,
+    /// which then no longer matches the nullifier root of any block, so it must never be used on
+    /// a tree which proves or verifies blocks, like the tree of the store. Proofs opened by a
+    /// pruned tree show pruned nullifiers as unspent.
+    ///
+    /// Only available with the `pruning` feature.
+    #[cfg(feature = "pruning")]
+    pub fn prune_before(&mut self, block_num: BlockNumber) -> usize {
+        let pruned: Vec<_> = self
+            .smt
+            .entries()
+            .filter(|(_, value)| {
+                Self::leaf_value_to_block_num(*value).is_ok_and(|consumed| consumed < block_num)
+            })
+            .map(|(key, _)| (*key, Smt::EMPTY_VALUE))
+            .collect();
+        if pruned.is_empty() {
+            return 0;
+        }
+
+        let num_pruned = pruned.len();
+        let mutations = self.compute_leaf_mutations(pruned);
+        self.apply_mutations(mutations)
+            .expect("mutations are computed against the current root of the tree");
+
+        num_pruned
     }
 
     // HELPER FUNCTIONS
     // --------------------------------------------------------------------------------------------
 
+    /// Computes mutations setting the given leaves of the SMT to raw values. For repeated keys,
+    /// the last value is used.
+    fn compute_leaf_mutations(
+        &self,
+        pairs: impl IntoIterator<Item = (RpoDigest, Word)>,
+    ) -> NullifierTreeMutations {
+        let new_pairs: Vec<_> = pairs.into_iter().collect::<BTreeMap<_, _>>().into_iter().collect();
+        let mutations = self.smt.compute_mutations(new_pairs.iter().copied());
+
+        NullifierTreeMutations {
+            old_root: self.root(),
+            new_pairs,
+            mutations,
+        }
+    }
+
+    /// Returns a tree holding `smt`, without reservations, and with the optional features
+    /// disabled.
+    ///
+    /// The latest applied block is the latest block with nullifiers in `smt`.
+    fn from_smt(smt: Smt) -> Self {
+        let latest_block_num = smt
+            .entries()
+            .filter_map(|(_, value)| Self::leaf_value_to_block_num(*value).ok())
+            .max()
+            .unwrap_or_default();
+        let num_nullifiers = smt.entries().count();
+
+        Self {
+            smt: Arc::new(smt),
+            reservations: Reservations::default(),
+            growth_warning: GrowthWarning::default(),
+            root_history: RootHistory::default(),
+            prefix_index: PrefixIndex::default(),
+            latest_block_num,
+            num_nullifiers,
+        }
+    }
+
+    /// Returns the number of consumed nullifiers once `mutations` are applied to the tree.
+    ///
+    /// Only the leaves set by the mutations are looked up, so this doesn't depend on the size of
+    /// the tree.
+    fn num_nullifiers_after(&self, mutations: &NullifierTreeMutations) -> usize {
+        mutations
+            .new_pairs
+            .iter()
+            .fold(self.num_nullifiers, |num_nullifiers, (key, value)| {
+                let was_consumed = self.smt.get_value(key) != Smt::EMPTY_VALUE;
+                let is_consumed = *value != Smt::EMPTY_VALUE;
+                match (was_consumed, is_consumed) {
+                    (false, true) => num_nullifiers + 1,
+                    (true, false) => num_nullifiers - 1,
+                    _ => num_nullifiers,
+                }
+            })
+    }
+
+    /// Logs a warning if the tree grew past the threshold set by
+    /// [NullifierTree::with_growth_warning], unless it was logged before.
+    fn warn_if_grown(&mut self) {
+        let GrowthWarning {
+            threshold: Some(threshold),
+            warned: false,
+        } = self.growth_warning
+        else {
+            return;
+        };
+
+        let num_nullifiers = self.num_nullifiers();
+        if num_nullifiers > threshold {
+            warn!(
+                target: COMPONENT,
+                num_nullifiers,
+                threshold,
+                "Nullifier tree grew past the warning threshold, consider pruning it"
+            );
+            self.growth_warning.warned = true;
+        }
+    }
+
+    /// Returns true if all nullifiers of the reservation are reserved by it in this tree.
+    fn holds(&self, token: &ReservationToken) -> bool {
+        token
+            .nullifiers
+            .iter()
+            .all(|nullifier| self.reservations.reserved.get(&nullifier.inner()) == Some(&token.id))
+    }
+
     /// Returns the nullifier's leaf value in the SMT by its block number.
     fn block_num_to_leaf_value(block: BlockNumber) -> Word {
-        [Felt::from(block), Felt::ZERO, Felt::ZERO, Felt::ZERO]
+        nullifiers::block_num_to_leaf_value(block)
     }
 
     /// Given the leaf value of the nullifier SMT, returns the nullifier's block number.
     ///
     /// There are no nullifiers in the genesis block. The value zero is instead used to signal
-    /// absence of a value.
-    fn leaf_value_to_block_num(value: Word) -> BlockNumber {
-        value[0].as_int().try_into().expect("invalid block number found in store")
+    /// absence of a value, and is not a valid block number.
+    ///
+    /// # Errors
+    /// Returns [NullifierTreeError::InvalidLeafEncoding] with the offending element if the value
+    /// is not a valid encoding of a block number, e.g. because the leaf was corrupted on disk.
+    pub fn leaf_value_to_block_num(value: Word) -> Result<BlockNumber, NullifierTreeError> {
+        nullifiers::leaf_value_to_block_num(value).map_err(|InvalidLeafEncoding(element)| {
+            NullifierTreeError::InvalidLeafEncoding(element)
+        })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use miden_objects::{Felt, ZERO};
+/// Returns the position of `nullifier` in the order of the leaves of the tree: by leaf index,
+/// which is the most significant element of the nullifier, then by the remaining elements.
+fn leaf_order(nullifier: &Nullifier) -> [u64; 4] {
+    let [a, b, c, d] = Word::from(nullifier.inner());
+    [d.as_int(), c.as_int(), b.as_int(), a.as_int()]
+}
 
-    use super::NullifierTree;
+// SERIALIZATION
+// ================================================================================================
 
-    #[test]
-    fn test_leaf_value_encoding() {
-        let block_num = 123;
-        let nullifier_value = NullifierTree::block_num_to_leaf_value(block_num);
+/// Magic bytes starting a serialized nullifier tree.
+const SNAPSHOT_MAGIC: [u8; 4] = *b"MNLT";
 
-        assert_eq!(nullifier_value, [Felt::from(block_num), ZERO, ZERO, ZERO])
+/// Number of bytes of a serialized entry, its nullifier followed by its block number.
+const SERIALIZED_ENTRY_LEN: u64 = 36;
+
+/// The tree is serialized as a header, followed by its root and its entries sorted by leaf index,
+/// so equal trees have equal serializations. The header holds [SNAPSHOT_MAGIC] and the length of
+/// the rest of the serialization, which lets [NullifierTree::from_snapshot_bytes] reject truncated
+/// snapshots early. The root is checked against the entries when deserializing.
+impl Serializable for NullifierTree {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // Leaf values are validated when inserted, so all leaves decode to block numbers.
+        let entries: Vec<_> = self.iter().collect();
+
+        let mut payload = Vec::new();
+        self.root().write_into(&mut payload);
+        payload.write_usize(entries.len());
+        for (nullifier, block_num) in entries {
+            nullifier.inner().write_into(&mut payload);
+            payload.write_u32(block_num.into());
+        }
+
+        target.write_bytes(&SNAPSHOT_MAGIC);
+        target.write_u64(payload.len() as u64);
+        target.write_bytes(&payload);
     }
+}
 
-    #[test]
-    fn test_leaf_value_decoding() {
-        let block_num = 123;
-        let nullifier_value = [Felt::from(block_num), ZERO, ZERO, ZERO];
-        let decoded_block_num = NullifierTree::leaf_value_to_block_num(nullifier_value);
+impl Deserializable for NullifierTree {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        if source.read_array::<4>()? != SNAPSHOT_MAGIC {
+            return Err(DeserializationError::InvalidValue(
+                "missing nullifier tree magic bytes".to_string(),
+            ));
+        }
+        let payload_len = source.read_u64()?;
+
+        let root = RpoDigest::read_from(source)?;
+        let num_entries = source.read_usize()?;
+        // The number of entries is untrusted, so memory is only reserved for the entries which fit
+        // into the payload.
+        let max_entries = payload_len / SERIALIZED_ENTRY_LEN;
+        if num_entries as u64 > max_entries {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{num_entries} entries don't fit into a payload of {payload_len} bytes"
+            )));
+        }
+        let mut entries = Vec::with_capacity(num_entries);
+        for _ in 0..num_entries {
+            let nullifier = Nullifier::from(RpoDigest::read_from(source)?);
+            entries.push((nullifier, source.read_u32()?.into()));
+        }
+
+        let (tree, _) = Self::with_entries(entries, CorruptionPolicy::Abort)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+        tree.assert_root(root)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+
+        Ok(tree)
+    }
+}
+
+/// The entries added to a nullifier tree since a given root, see
+/// [NullifierTree::serialize_delta].
+///
+/// The delta is serialized as the root it was taken since, the root it was taken at, and the
+/// added entries. Entries with block number zero are removed from the tree.
+struct NullifierTreeDelta {
+    since_root: RpoDigest,
+    root: RpoDigest,
+    entries: Vec<(Nullifier, BlockNumber)>,
+}
+
+impl Serializable for NullifierTreeDelta {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.since_root.write_into(target);
+        self.root.write_into(target);
+        target.write_usize(self.entries.len());
+        for (nullifier, block_num) in &self.entries {
+            nullifier.inner().write_into(target);
+            target.write_u32((*block_num).into());
+        }
+    }
+}
+
+impl Deserializable for NullifierTreeDelta {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let since_root = RpoDigest::read_from(source)?;
+        let root = RpoDigest::read_from(source)?;
+        // The delta has no header declaring its length, so the untrusted number of entries is not
+        // used to reserve memory, and a delta with fewer entries fails to deserialize.
+        let num_entries = source.read_usize()?;
+        let mut entries = Vec::new();
+        for _ in 0..num_entries {
+            let nullifier = Nullifier::from(RpoDigest::read_from(source)?);
+            entries.push((nullifier, source.read_u32()?.into()));
+        }
+
+        Ok(Self { since_root, root, entries })
+    }
+}
+
+// ROOT HISTORY
+// ================================================================================================
+
+/// The entries added by the recent applications of mutations to a nullifier tree, see
+/// [NullifierTree::with_root_history].
+#[derive(Debug, Clone, Default)]
+struct RootHistory {
+    /// Maximum number of retained applications, zero if the history is disabled
+    capacity: usize,
+    /// Root of the tree before each retained application with the entries it added, oldest first
+    applied: VecDeque<(RpoDigest, Vec<(Nullifier, BlockNumber)>)>,
+}
+
+impl RootHistory {
+    /// Returns the entry recording the application of `mutations`, or `None` if the history is
+    /// disabled.
+    fn entry(
+        &self,
+        mutations: &NullifierTreeMutations,
+    ) -> Option<(RpoDigest, Vec<(Nullifier, BlockNumber)>)> {
+        if self.capacity == 0 {
+            return None;
+        }
+
+        // Removed entries have the empty value, which decodes to block number zero.
+        let entries = mutations
+            .new_pairs
+            .iter()
+            .map(|(key, value)| {
+                let block_num = NullifierTree::leaf_value_to_block_num(*value).unwrap_or_default();
+                (Nullifier::from(*key), block_num)
+            })
+            .collect();
+
+        Some((mutations.old_root, entries))
+    }
+
+    /// Records an applied entry returned by [RootHistory::entry], dropping the oldest entry if
+    /// the history is full.
+    fn push(&mut self, entry: Option<(RpoDigest, Vec<(Nullifier, BlockNumber)>)>) {
+        let Some(entry) = entry else {
+            return;
+        };
+        if self.applied.len() == self.capacity {
+            self.applied.pop_front();
+        }
+        self.applied.push_back(entry);
+    }
+
+    /// Returns the entries added since the tree had `root`, in the order they were added, or
+    /// `None` if `root` is neither `current_root` nor retained.
+    fn entries_since(
+        &self,
+        root: RpoDigest,
+        current_root: RpoDigest,
+    ) -> Option<Vec<(Nullifier, BlockNumber)>> {
+        if root == current_root {
+            return Some(Vec::new());
+        }
+
+        let start = self.applied.iter().rposition(|(prior_root, _)| *prior_root == root)?;
+
+        Some(
+            self.applied
+                .range(start..)
+                .flat_map(|(_, entries)| entries.iter().copied())
+                .collect(),
+        )
+    }
+}
+
+// PREFIX INDEX
+// ================================================================================================
+
+/// The nullifiers of a nullifier tree by prefix, see [NullifierTree::with_prefix_index].
+#[derive(Debug, Clone, Default)]
+struct PrefixIndex {
+    /// Number of bits of the prefixes, zero if the index is disabled
+    prefix_bits: u32,
+    /// Keys of the nullifiers with each prefix
+    nullifiers: BTreeMap<u64, BTreeSet<RpoDigest>>,
+}
+
+impl PrefixIndex {
+    /// Returns the prefix of the nullifier with the given key.
+    fn prefix_of(&self, key: RpoDigest) -> u64 {
+        Nullifier::from(key).most_significant_felt().as_int() >> (64 - self.prefix_bits)
+    }
+
+    fn insert(&mut self, key: RpoDigest) {
+        self.nullifiers.entry(self.prefix_of(key)).or_default().insert(key);
+    }
+
+    fn remove(&mut self, key: RpoDigest) {
+        let prefix = self.prefix_of(key);
+        if let Some(keys) = self.nullifiers.get_mut(&prefix) {
+            keys.remove(&key);
+            if keys.is_empty() {
+                self.nullifiers.remove(&prefix);
+            }
+        }
+    }
+
+    /// Returns the leaves changed by `mutations`, which are applied to the index with
+    /// [PrefixIndex::apply] once the mutations are applied to the tree, or nothing if the index
+    /// is disabled.
+    fn changes(&self, mutations: &NullifierTreeMutations) -> Vec<(RpoDigest, Word)> {
+        if self.prefix_bits == 0 {
+            return Vec::new();
+        }
+
+        mutations.new_pairs.clone()
+    }
+
+    /// Indexes the leaves returned by [PrefixIndex::changes], removing the leaves which were
+    /// emptied.
+    fn apply(&mut self, changes: Vec<(RpoDigest, Word)>) {
+        for (key, value) in changes {
+            if value == Smt::EMPTY_VALUE {
+                self.remove(key);
+            } else {
+                self.insert(key);
+            }
+        }
+    }
+}
+
+// NULLIFIER RESERVATIONS
+// ================================================================================================
+
+/// The nullifiers reserved for blocks being built, see [NullifierTree::reserve].
+#[derive(Debug, Clone, Default)]
+struct Reservations {
+    /// Identifier of the next reservation
+    next_id: u64,
+    /// Maps each reserved nullifier to the identifier of the reservation holding it
+    reserved: BTreeMap<RpoDigest, u64>,
+}
+
+/// The state of the warning about the size of the tree, see [NullifierTree::with_growth_warning].
+#[derive(Debug, Clone, Copy, Default)]
+struct GrowthWarning {
+    /// Number of nullifiers above which the warning is logged, if enabled
+    threshold: Option<usize>,
+    /// Whether the warning was logged already
+    warned: bool,
+}
+
+/// Nullifiers reserved for a block being built, returned by [NullifierTree::reserve].
+///
+/// The nullifiers stay reserved until the token is passed to [NullifierTree::commit] or
+/// [NullifierTree::release], including when the token is dropped.
+#[must_use]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReservationToken {
+    id: u64,
+    block_num: BlockNumber,
+    nullifiers: Vec<Nullifier>,
+}
+
+impl ReservationToken {
+    /// Returns the number of the block the nullifiers are reserved for.
+    pub fn block_num(&self) -> BlockNumber {
+        self.block_num
+    }
+
+    /// Returns the reserved nullifiers.
+    pub fn nullifiers(&self) -> &[Nullifier] {
+        &self.nullifiers
+    }
+}
+
+// NULLIFIER TREE MUTATIONS
+// ================================================================================================
+
+/// Mutations of a [NullifierTree], computed by [NullifierTree::compute_mutations] and applied with
+/// [NullifierTree::apply_mutations].
+///
+/// Besides the mutations of the SMT, this keeps the leaves they set, which the tree needs to
+/// update its history and index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NullifierTreeMutations {
+    /// Root of the tree the mutations were computed for
+    old_root: RpoDigest,
+    /// Leaves set by the mutations with their new values, sorted by key
+    new_pairs: Vec<(RpoDigest, Word)>,
+    /// Mutations of the SMT
+    mutations: MutationSet<SMT_DEPTH, RpoDigest, Word>,
+}
+
+impl NullifierTreeMutations {
+    /// Returns the root of the tree after applying the mutations.
+    pub fn root(&self) -> RpoDigest {
+        self.mutations.root()
+    }
+
+    /// Returns the root of the tree the mutations were computed for.
+    pub fn old_root(&self) -> RpoDigest {
+        self.old_root
+    }
+}
+
+// NULLIFIER TREE SNAPSHOT
+// ================================================================================================
+
+/// Read-only copy of a [NullifierTree] at a point in time, created by
+/// [NullifierTree::snapshot].
+///
+/// The snapshot shares the SMT with the tree it was taken from, so taking a snapshot is cheap.
+#[derive(Debug, Clone)]
+pub struct NullifierTreeSnapshot(Arc<Smt>);
+
+impl NullifierTreeSnapshot {
+    /// Returns the root of the nullifier SMT.
+    pub fn root(&self) -> RpoDigest {
+        self.0.root()
+    }
+
+    /// Returns an opening of the leaf associated with the given nullifier.
+    pub fn open(&self, nullifier: &Nullifier) -> SmtProof {
+        self.0.open(&nullifier.inner())
+    }
+
+    /// Returns block number stored for the given nullifier or `None` if the nullifier wasn't
+    /// consumed.
+    ///
+    /// # Errors
+    /// Same as [NullifierTree::get_block_num].
+    pub fn get_block_num(
+        &self,
+        nullifier: &Nullifier,
+    ) -> Result<Option<BlockNumber>, NullifierTreeError> {
+        get_block_num(&self.0, nullifier)
+    }
+
+    /// Returns an iterator over all consumed nullifiers together with their block numbers.
+    ///
+    /// Leaves whose value doesn't encode a block number are yielded as
+    /// [NullifierTreeError::CorruptLeaf] errors.
+    pub fn entries(
+        &self,
+    ) -> impl Iterator<Item = Result<(Nullifier, BlockNumber), NullifierTreeError>> + '_ {
+        self.0.entries().map(|(key, value)| {
+            let block_num = NullifierTree::leaf_value_to_block_num(*value)
+                .map_err(|_| NullifierTreeError::CorruptLeaf { key: *key, value: *value })?;
+
+            Ok((Nullifier::from(*key), block_num))
+        })
+    }
+}
+
+/// Checks that the new values of the leaves changed by `mutations` are empty or encode block
+/// numbers, so corrupt leaves are never inserted into the nullifier SMT.
+fn check_leaf_values(mutations: &NullifierTreeMutations) -> Result<(), NullifierTreeError> {
+    let corrupt = mutations.new_pairs.iter().find(|(_, value)| {
+        *value != Smt::EMPTY_VALUE && NullifierTree::leaf_value_to_block_num(*value).is_err()
+    });
+    match corrupt {
+        Some((key, value)) => Err(NullifierTreeError::CorruptLeaf { key: *key, value: *value }),
+        None => Ok(()),
+    }
+}
+
+/// Returns the latest block number among the new values of the leaves changed by `mutations`, or
+/// the genesis block if no leaf is set to a block number.
+fn latest_block_num_of(mutations: &NullifierTreeMutations) -> BlockNumber {
+    mutations
+        .new_pairs
+        .iter()
+        .filter_map(|(_, value)| NullifierTree::leaf_value_to_block_num(*value).ok())
+        .max()
+        .unwrap_or_default()
+}
+
+/// Returns the block number stored for the given nullifier in the nullifier SMT, or `None` if the
+/// nullifier wasn't consumed.
+fn get_block_num(
+    smt: &Smt,
+    nullifier: &Nullifier,
+) -> Result<Option<BlockNumber>, NullifierTreeError> {
+    let value = smt.get_value(&nullifier.inner());
+    if value == Smt::EMPTY_VALUE {
+        return Ok(None);
+    }
+
+    NullifierTree::leaf_value_to_block_num(value)
+        .map(Some)
+        .map_err(|_| NullifierTreeError::CorruptLeaf { key: nullifier.inner(), value })
+}
+
+// NULLIFIER DIVERGENCE
+// ================================================================================================
+
+/// A nullifier whose block number differs between two nullifier trees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NullifierDivergence {
+    pub nullifier: Nullifier,
+    /// Block number of the nullifier in the local tree, or `None` if it is not consumed
+    pub local: Option<BlockNumber>,
+    /// Block number of the nullifier in the reference tree, or `None` if it is not consumed
+    pub reference: Option<BlockNumber>,
+}
+
+/// The differences between two serialized nullifier trees, as returned by
+/// [NullifierTree::diff_snapshots].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    pub root_a: RpoDigest,
+    pub root_b: RpoDigest,
+    /// Nullifiers whose block numbers differ between the trees, sorted by nullifier
+    pub divergences: Vec<NullifierDivergence>,
+}
+
+impl SnapshotDiff {
+    /// Returns true if both trees have the same root, in which case there are no divergences.
+    pub fn roots_match(&self) -> bool {
+        self.root_a == self.root_b
+    }
+}
+
+// NULLIFIER BLOCK WITNESS
+// ================================================================================================
+
+/// The nullifiers consumed by a block, together with their openings against the nullifier root
+/// prior to the block.
+///
+/// This is enough to compute the nullifier root after the block without access to the full
+/// nullifier tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NullifierBlockWitness {
+    prior_root: RpoDigest,
+    block_num: BlockNumber,
+    nullifiers: Vec<(Nullifier, SmtProof)>,
+}
+
+impl NullifierBlockWitness {
+    /// Returns a new witness of the nullifiers consumed by block `block_num`.
+    ///
+    /// Each nullifier must be accompanied by its opening against `prior_root`.
+    pub fn new(
+        prior_root: RpoDigest,
+        block_num: BlockNumber,
+        nullifiers: Vec<(Nullifier, SmtProof)>,
+    ) -> Self {
+        Self { prior_root, block_num, nullifiers }
+    }
+
+    /// Returns the nullifier root prior to the block.
+    pub fn prior_root(&self) -> RpoDigest {
+        self.prior_root
+    }
+
+    /// Returns the number of the block consuming the nullifiers.
+    pub fn block_num(&self) -> BlockNumber {
+        self.block_num
+    }
+
+    /// Returns the nullifiers consumed by the block, with their openings.
+    pub fn nullifiers(&self) -> &[(Nullifier, SmtProof)] {
+        &self.nullifiers
+    }
+
+    /// Verifies the openings against the prior root, and returns the nullifier root after
+    /// marking all nullifiers as consumed by the block.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - an opening is not for the leaf of its nullifier, or does not match the prior root.
+    /// - a nullifier was already consumed before the block, or is consumed twice by the block.
+    pub fn verify_and_compute_new_root(&self) -> Result<RpoDigest, NullifierTreeError> {
+        // Partial view of the tree, containing only the opened paths
+        let mut store = DefaultMerkleStore::new();
+        // Leaves touched by the block, used to compute the updated leaf hashes
+        let mut leaves = Smt::new();
+        // Maps leaf index -> any nullifier in that leaf, for all touched leaves
+        let mut touched = BTreeMap::new();
+
+        for (nullifier, proof) in &self.nullifiers {
+            let leaf_index = LeafIndex::<SMT_DEPTH>::from(nullifier.inner());
+            if proof.leaf().index() != leaf_index || proof.compute_root() != self.prior_root {
+                return Err(NullifierTreeError::InvalidProof {
+                    nullifier: *nullifier,
+                    root: self.prior_root,
+                });
+            }
+
+            store.add_merkle_path(leaf_index.value(), proof.leaf().hash(), proof.path().clone())?;
+            for (key, value) in proof.leaf().entries() {
+                leaves.insert(*key, *value);
+            }
+            touched.insert(leaf_index.value(), nullifier.inner());
+        }
+
+        let new_value = NullifierTree::block_num_to_leaf_value(self.block_num);
+        for (nullifier, _) in &self.nullifiers {
+            let value = leaves.get_value(&nullifier.inner());
+            if value != Smt::EMPTY_VALUE {
+                let block_num = NullifierTree::leaf_value_to_block_num(value).map_err(|_| {
+                    NullifierTreeError::InvalidProof {
+                        nullifier: *nullifier,
+                        root: self.prior_root,
+                    }
+                })?;
+                return Err(NullifierTreeError::AlreadyConsumed {
+                    nullifier: *nullifier,
+                    block_num,
+                });
+            }
+
+            leaves.insert(nullifier.inner(), new_value);
+        }
+
+        let mut root = self.prior_root;
+        for (leaf_index, key) in touched {
+            let node = NodeIndex::new(SMT_DEPTH, leaf_index)?;
+            root = store.set_node(root, node, leaves.get_leaf(&key).hash())?.root;
+        }
+
+        Ok(root)
+    }
+}
+
+// NULLIFIER MULTIPROOF
+// ================================================================================================
+
+/// An opening of several leaves of the nullifier tree against its root, returned by
+/// [NullifierTree::multiproof].
+///
+/// Instead of the full path of each leaf, the multiproof holds the siblings of the nodes on the
+/// paths which can't be computed from the leaves themselves. When the nullifiers cluster in the
+/// tree, their paths share most of their nodes, and the multiproof is much smaller than the
+/// openings of the nullifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtMultiproof {
+    /// Leaves holding the opened nullifiers, by leaf index
+    leaves: BTreeMap<u64, SmtLeaf>,
+    /// Siblings of the nodes on the paths of the leaves which are not on a path themselves
+    nodes: BTreeMap<NodeIndex, RpoDigest>,
+}
+
+impl SmtMultiproof {
+    /// Returns the multiproof combining `proofs`, which must be openings against the same root.
+    pub fn from_proofs(proofs: impl IntoIterator<Item = SmtProof>) -> Self {
+        let proofs: BTreeMap<_, _> =
+            proofs.into_iter().map(|proof| (proof.leaf().index().value(), proof)).collect();
+
+        // Nodes on the paths of the leaves, which are computed from the leaves when verifying
+        let mut on_path = BTreeSet::new();
+        for leaf_index in proofs.keys() {
+            let mut index = NodeIndex::new_unchecked(SMT_DEPTH, *leaf_index);
+            while !index.is_root() && on_path.insert(index) {
+                index.move_up();
+            }
+        }
+
+        let mut nodes = BTreeMap::new();
+        for (leaf_index, proof) in &proofs {
+            let mut index = NodeIndex::new_unchecked(SMT_DEPTH, *leaf_index);
+            for sibling in proof.path().iter() {
+                if !on_path.contains(&index.sibling()) {
+                    nodes.insert(index.sibling(), *sibling);
+                }
+                index.move_up();
+            }
+        }
+
+        let leaves = proofs
+            .into_iter()
+            .map(|(leaf_index, proof)| (leaf_index, proof.leaf().clone()))
+            .collect();
+
+        Self { leaves, nodes }
+    }
+
+    /// Returns the number of inner nodes included in the multiproof, besides the leaves.
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Verifies the multiproof against `root`, and returns the block number in which each of
+    /// `nullifiers` was consumed, or `None` for nullifiers which weren't consumed, in the order
+    /// of `nullifiers`.
+    ///
+    /// `nullifiers` may be any subset of the nullifiers the multiproof was created for.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - the multiproof does not include the leaf of a nullifier.
+    /// - the multiproof lacks a node needed to compute the root, or the computed root is not
+    ///   `root`.
+    /// - the value of a nullifier does not encode a block number.
+    pub fn verify(
+        &self,
+        root: RpoDigest,
+        nullifiers: &[Nullifier],
+    ) -> Result<Vec<Option<BlockNumber>>, NullifierTreeError> {
+        let block_nums = nullifiers
+            .iter()
+            .map(|nullifier| {
+                let invalid_proof =
+                    || NullifierTreeError::InvalidProof { nullifier: *nullifier, root };
+
+                let leaf_index = LeafIndex::<SMT_DEPTH>::from(nullifier.inner()).value();
+                let leaf = self.leaves.get(&leaf_index).ok_or_else(invalid_proof)?;
+                match leaf.entries().into_iter().find(|(key, _)| *key == nullifier.inner()) {
+                    Some((_, value)) => NullifierTree::leaf_value_to_block_num(*value)
+                        .map(Some)
+                        .map_err(|_| invalid_proof()),
+                    None => Ok(None),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !self.leaves.is_empty() {
+            let actual = self.compute_root()?;
+            if actual != root {
+                return Err(NullifierTreeError::RootMismatch { expected: root, actual });
+            }
+        }
+
+        Ok(block_nums)
+    }
+
+    /// Returns the root computed from the leaves and nodes of the multiproof.
+    ///
+    /// # Errors
+    /// Returns an error if the multiproof lacks a node needed to compute the root.
+    fn compute_root(&self) -> Result<RpoDigest, NullifierTreeError> {
+        // Nodes of the current depth, by index
+        let mut level: BTreeMap<u64, RpoDigest> =
+            self.leaves.iter().map(|(index, leaf)| (*index, leaf.hash())).collect();
+
+        for depth in (1..=SMT_DEPTH).rev() {
+            let mut parents = BTreeMap::new();
+            for (&value, &node) in &level {
+                let index = NodeIndex::new_unchecked(depth, value);
+                let sibling = index.sibling();
+                let sibling_node = match level.get(&sibling.value()) {
+                    Some(sibling_node) => *sibling_node,
+                    None => *self
+                        .nodes
+                        .get(&sibling)
+                        .ok_or(NullifierTreeError::IncompleteMultiproof(sibling))?,
+                };
+
+                let (left, right) = if index.is_value_odd() {
+                    (sibling_node, node)
+                } else {
+                    (node, sibling_node)
+                };
+                parents.insert(value >> 1, Rpo256::merge(&[left, right]));
+            }
+            level = parents;
+        }
+
+        Ok(level[&0])
+    }
+}
+
+// BATCH VERIFICATION
+// ================================================================================================
+
+/// Verifies that each proof is a valid opening of the leaf of its nullifier against `root`.
+///
+/// Inner nodes shared between the proofs are only verified once: reconstruction of a path stops
+/// as soon as it reaches a node which is already known to lead to `root`.
+///
+/// # Errors
+/// Returns [NullifierTreeError::InvalidProof] for the first nullifier whose proof is invalid.
+pub fn verify_proofs_batch(
+    root: RpoDigest,
+    proofs: &[(Nullifier, SmtProof)],
+) -> Result<(), NullifierTreeError> {
+    // Inner nodes which are known to lead to the root
+    let mut verified = BTreeMap::<NodeIndex, RpoDigest>::new();
+
+    for (nullifier, proof) in proofs {
+        let invalid_proof = || NullifierTreeError::InvalidProof { nullifier: *nullifier, root };
+
+        let leaf_index = LeafIndex::<SMT_DEPTH>::from(nullifier.inner());
+        if proof.leaf().index() != leaf_index {
+            return Err(invalid_proof());
+        }
+
+        let mut index = NodeIndex::new(SMT_DEPTH, leaf_index.value())?;
+        let mut node = proof.leaf().hash();
+        let mut computed = Vec::new();
+        for sibling in proof.path().iter() {
+            let (left, right) = if index.is_value_odd() {
+                (*sibling, node)
+            } else {
+                (node, *sibling)
+            };
+            node = Rpo256::merge(&[left, right]);
+            index.move_up();
+
+            match verified.get(&index) {
+                Some(known) if *known == node => break,
+                // Nodes at the same index of openings against the same root must be equal.
+                Some(_) => return Err(invalid_proof()),
+                None => computed.push((index, node)),
+            }
+        }
+
+        // The path is only fully reconstructed if it did not reach a verified node.
+        if verified.get(&index) != Some(&node) && node != root {
+            return Err(invalid_proof());
+        }
+        verified.extend(computed);
+    }
+
+    Ok(())
+}
+
+// SHARDING
+// ================================================================================================
+
+pub use miden_node_proto::domain::nullifiers::shard_of;
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use miden_objects::{
+        crypto::{
+            hash::rpo::RpoDigest,
+            merkle::{Smt, SmtLeaf, SmtProof},
+        },
+        notes::Nullifier,
+        utils::{ByteWriter, Serializable},
+        Felt, Word, ONE, ZERO,
+    };
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::{
+        leaf_order, verify_proofs_batch, CorruptionPolicy, NullifierBlockWitness,
+        NullifierDivergence, NullifierTree, SNAPSHOT_MAGIC,
+    };
+    use crate::{errors::NullifierTreeError, types::BlockNumber};
+
+    fn block(num: u32) -> BlockNumber {
+        BlockNumber::from(num)
+    }
+
+    /// Layer counting the logged warnings.
+    #[derive(Clone, Default)]
+    struct WarningCounter(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for WarningCounter {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if *event.metadata().level() == tracing::Level::WARN {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    #[test]
+    fn test_leaf_value_encoding() {
+        let block_num = 123;
+        let nullifier_value = NullifierTree::block_num_to_leaf_value(block(block_num));
+
+        assert_eq!(nullifier_value, [Felt::from(block_num), ZERO, ZERO, ZERO])
+    }
+
+    #[test]
+    fn test_leaf_value_decoding() {
+        let block_num = 123;
+        let nullifier_value = [Felt::from(block_num), ZERO, ZERO, ZERO];
+        let decoded_block_num = NullifierTree::leaf_value_to_block_num(nullifier_value);
+
+        assert!(matches!(decoded_block_num, Ok(decoded) if decoded == block(block_num)));
+    }
+
+    #[test]
+    fn test_leaf_value_decoding_rejects_out_of_range_block_num() {
+        let element = Felt::new(u64::from(u32::MAX) + 1);
+        let decoded_block_num = NullifierTree::leaf_value_to_block_num([element, ZERO, ZERO, ZERO]);
+
+        assert!(matches!(
+            decoded_block_num,
+            Err(NullifierTreeError::InvalidLeafEncoding(invalid)) if invalid == element
+        ));
+    }
+
+    #[test]
+    fn test_apply_mutations_rejects_corrupt_leaf_values() {
+        let nullifier = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+        let value = [Felt::new(u64::from(u32::MAX) + 1), ZERO, ZERO, ZERO];
+        let (mut tree, _) = NullifierTree::with_entries([], CorruptionPolicy::Abort).unwrap();
+        let root = tree.root();
+
+        let mutations = tree.compute_leaf_mutations([(nullifier.inner(), value)]);
+        assert!(matches!(
+            tree.apply_mutations(mutations),
+            Err(NullifierTreeError::CorruptLeaf { key, value: corrupt })
+                if key == nullifier.inner() && corrupt == value
+        ));
+
+        // The tree is unchanged, so it can be serialized and looked up without errors.
+        assert_eq!(tree.root(), root);
+        assert_eq!(tree.get_block_num(&nullifier).unwrap(), None);
+        assert_eq!(NullifierTree::from_snapshot_bytes(&tree.to_bytes()).unwrap().root(), root);
+    }
+
+    #[test]
+    fn test_leaves_match_for_identical_trees() {
+        let nullifiers: Vec<_> = (1..=10u64)
+            .map(|i| (Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]), block(i as u32)))
+            .collect();
+
+        let tree_a = NullifierTree::with_entries(nullifiers.clone(), CorruptionPolicy::Abort)
+            .unwrap()
+            .0;
+        let tree_b =
+            NullifierTree::with_entries(nullifiers.into_iter().rev(), CorruptionPolicy::Abort)
+                .unwrap()
+                .0;
+
+        let leaves = tree_a.leaves();
+        assert_eq!(leaves.len(), 10);
+        assert_eq!(leaves, tree_b.leaves());
+        assert_eq!(
+            leaves[0],
+            (
+                Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]).inner(),
+                NullifierTree::block_num_to_leaf_value(block(1))
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_mutations_batched() {
+        let nullifiers: Vec<_> = (1..=100u64)
+            .map(|i| (Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]), block(i as u32)))
+            .collect();
+
+        let mut single_shot = NullifierTree::with_entries([], CorruptionPolicy::Abort).unwrap().0;
+        let mut batched = single_shot.clone();
+        let mutations = single_shot.compute_mutations(nullifiers);
+
+        single_shot.apply_mutations(mutations.clone()).unwrap();
+
+        // The counter is only incremented when the batched apply yields to the runtime.
+        let yields = Arc::new(AtomicUsize::new(0));
+        let counter = tokio::spawn({
+            let yields = Arc::clone(&yields);
+            async move {
+                loop {
+                    yields.fetch_add(1, Ordering::Relaxed);
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+
+        batched.apply_mutations_batched(mutations, 10).await.unwrap();
+        counter.abort();
+
+        assert_eq!(batched.root(), single_shot.root());
+        assert!(yields.load(Ordering::Relaxed) > 0);
+    }
+
+    #[test]
+    fn test_block_witness_computes_new_root() {
+        // The first consumed nullifier shares its leaf with an existing one.
+        let existing = Nullifier::from([ONE, ZERO, ZERO, Felt::new(5)]);
+        let consumed = [
+            Nullifier::from([Felt::new(2), ZERO, ZERO, Felt::new(5)]),
+            Nullifier::from([ZERO, ZERO, ZERO, Felt::new(20)]),
+            Nullifier::from([ZERO, ZERO, ZERO, Felt::new(21)]),
+        ];
+        let mut tree = NullifierTree::with_entries([(existing, block(1))], CorruptionPolicy::Abort)
+            .unwrap()
+            .0;
+
+        let witness = tree.block_witness(block(2), consumed);
+        let new_root = witness.verify_and_compute_new_root().unwrap();
+
+        tree.apply_mutations(
+            tree.compute_mutations(consumed.map(|nullifier| (nullifier, block(2)))),
+        )
+        .unwrap();
+        assert_eq!(new_root, tree.root());
+
+        // A block consuming no nullifiers leaves the root unchanged.
+        let witness = tree.block_witness(block(3), []);
+        assert_eq!(witness.verify_and_compute_new_root().unwrap(), tree.root());
+    }
+
+    #[test]
+    fn test_block_witness_rejects_tampered_proof() {
+        let existing = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(5)]);
+        let consumed = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(20)]);
+        let tree = NullifierTree::with_entries([(existing, block(1))], CorruptionPolicy::Abort)
+            .unwrap()
+            .0;
+
+        // The leaf is claimed to be empty, but holds the existing nullifier.
+        let (path, _) = tree.open(&existing).into_parts();
+        let tampered = SmtProof::new(path, SmtLeaf::new_empty(existing.inner().into())).unwrap();
+        let witness = NullifierBlockWitness::new(
+            tree.root(),
+            block(2),
+            vec![(consumed, tree.open(&consumed)), (existing, tampered)],
+        );
+        assert!(matches!(
+            witness.verify_and_compute_new_root(),
+            Err(NullifierTreeError::InvalidProof { nullifier, .. }) if nullifier == existing
+        ));
+
+        // The opening is for the leaf of another nullifier.
+        let witness = NullifierBlockWitness::new(
+            tree.root(),
+            block(2),
+            vec![(consumed, tree.open(&existing))],
+        );
+        assert!(matches!(
+            witness.verify_and_compute_new_root(),
+            Err(NullifierTreeError::InvalidProof { nullifier, .. }) if nullifier == consumed
+        ));
+
+        // The nullifier was already consumed.
+        assert!(matches!(
+            tree.block_witness(block(2), [existing]).verify_and_compute_new_root(),
+            Err(NullifierTreeError::AlreadyConsumed { block_num, .. })
+                if block_num == block(1)
+        ));
+    }
+
+    #[test]
+    fn test_verify_proofs_batch_reports_failing_nullifier() {
+        let nullifiers: Vec<_> =
+            (1..=8u64).map(|i| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)])).collect();
+        let tree = NullifierTree::with_entries(
+            nullifiers[..4].iter().map(|nullifier| (*nullifier, block(1))),
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+
+        // Openings of both consumed and unconsumed nullifiers are valid.
+        let mut proofs: Vec<_> =
+            nullifiers.iter().map(|nullifier| (*nullifier, tree.open(nullifier))).collect();
+        verify_proofs_batch(tree.root(), &proofs).unwrap();
+
+        // The leaf of the sixth nullifier is claimed to hold a value it does not.
+        let (path, _) = proofs[5].1.clone().into_parts();
+        let leaf = SmtLeaf::new_single(nullifiers[5].inner(), [ONE, ZERO, ZERO, ZERO]);
+        proofs[5].1 = SmtProof::new(path, leaf).unwrap();
+
+        assert!(matches!(
+            verify_proofs_batch(tree.root(), &proofs),
+            Err(NullifierTreeError::InvalidProof { nullifier, .. }) if nullifier == nullifiers[5]
+        ));
+
+        // Proofs are also checked against the root when no nodes are shared.
+        let other_tree =
+            NullifierTree::with_entries([(nullifiers[7], block(2))], CorruptionPolicy::Abort)
+                .unwrap()
+                .0;
+        assert!(matches!(
+            verify_proofs_batch(other_tree.root(), &proofs[..1]),
+            Err(NullifierTreeError::InvalidProof { nullifier, .. }) if nullifier == nullifiers[0]
+        ));
+    }
+
+    #[test]
+    fn test_multiproof_verifies_any_subset_of_its_nullifiers() {
+        let nullifiers: Vec<_> =
+            (1..=8u64).map(|i| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)])).collect();
+        let tree = NullifierTree::with_entries(
+            nullifiers[..4]
+                .iter()
+                .enumerate()
+                .map(|(i, nullifier)| (*nullifier, block(i as u32 + 1))),
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+
+        let multiproof = tree.multiproof(&nullifiers);
+        assert_eq!(
+            multiproof.verify(tree.root(), &nullifiers).unwrap(),
+            tree.get_block_nums(&nullifiers).unwrap()
+        );
+
+        // The paths of the clustered nullifiers share most of their nodes.
+        let path_len = tree.open(&nullifiers[0]).path().len();
+        assert!(multiproof.num_nodes() < 2 * path_len);
+
+        // Nullifiers left out of the verification set don't affect the others.
+        let rest: Vec<_> = nullifiers
+            .iter()
+            .copied()
+            .filter(|nullifier| *nullifier != nullifiers[2])
+            .collect();
+        assert_eq!(
+            multiproof.verify(tree.root(), &rest).unwrap(),
+            tree.get_block_nums(&rest).unwrap()
+        );
+
+        // Nullifiers outside of the multiproof, or a wrong root, fail the verification.
+        let other = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(100)]);
+        assert!(matches!(
+            multiproof.verify(tree.root(), &[other]),
+            Err(NullifierTreeError::InvalidProof { nullifier, .. }) if nullifier == other
+        ));
+        assert!(matches!(
+            multiproof.verify(RpoDigest::default(), &nullifiers),
+            Err(NullifierTreeError::RootMismatch { .. })
+        ));
+    }
+
+    /// Returns the leaves of a tree with three nullifiers, the second of which is corrupt.
+    fn leaves_with_corrupt_leaf() -> Vec<(RpoDigest, Word)> {
+        (1..=3u64)
+            .map(|i| {
+                let key = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]).inner();
+                let value = if i == 2 {
+                    [Felt::new(u64::from(u32::MAX) + 1), ZERO, ZERO, ZERO]
+                } else {
+                    NullifierTree::block_num_to_leaf_value(block(i as u32))
+                };
+
+                (key, value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_corruption_policy_abort() {
+        let leaves = leaves_with_corrupt_leaf();
+
+        assert!(matches!(
+            NullifierTree::from_leaves(leaves.clone(), CorruptionPolicy::Abort),
+            Err(NullifierTreeError::CorruptLeaf { key, .. }) if key == leaves[1].0
+        ));
+
+        // Block number zero is not valid either.
+        let nullifier = Nullifier::from([ZERO, ZERO, ZERO, Felt::new(1)]);
+        assert!(matches!(
+            NullifierTree::with_entries(
+                [(nullifier, BlockNumber::GENESIS)],
+                CorruptionPolicy::Abort
+            ),
+            Err(NullifierTreeError::CorruptLeaf { key, .. }) if key == nullifier.inner()
+        ));
+    }
+
+    #[test]
+    fn test_corruption_policy_skip_and_warn() {
+        let leaves = leaves_with_corrupt_leaf();
+
+        let (tree, corrupt) =
+            NullifierTree::from_leaves(leaves.clone(), CorruptionPolicy::SkipAndWarn).unwrap();
+
+        assert!(corrupt.is_empty());
+        assert_eq!(tree.leaves(), [leaves[0], leaves[2]]);
+    }
+
+    #[test]
+    fn test_corruption_policy_collect() {
+        let leaves = leaves_with_corrupt_leaf();
+
+        let (tree, corrupt) =
+            NullifierTree::from_leaves(leaves.clone(), CorruptionPolicy::Collect).unwrap();
+
+        assert_eq!(corrupt, [leaves[1]]);
+        assert_eq!(tree.leaves(), [leaves[0], leaves[2]]);
+        assert_eq!(tree.get_block_num(&Nullifier::from(leaves[2].0)).unwrap(), Some(block(3)));
+    }
+
+    #[test]
+    fn test_assert_root_accepts_matching_root() {
+        let nullifiers =
+            (1..=3u64).map(|i| (Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]), block(1)));
+        let tree = NullifierTree::with_entries(nullifiers, CorruptionPolicy::Abort).unwrap().0;
+
+        assert!(tree.assert_root(tree.root()).is_ok());
+        assert!(tree.diagnose_divergence(&tree.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diverging_roots_are_diagnosed() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let local = NullifierTree::with_entries(
+            [(nullifier(1), block(1)), (nullifier(2), block(2)), (nullifier(3), block(3))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+        let reference = NullifierTree::with_entries(
+            [(nullifier(1), block(1)), (nullifier(2), block(5)), (nullifier(4), block(4))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+
+        match local.assert_root(reference.root()) {
+            Err(NullifierTreeError::RootMismatch { expected, actual }) => {
+                assert_eq!(expected, reference.root());
+                assert_eq!(actual, local.root());
+            },
+            result => panic!("expected a root mismatch, got {result:?}"),
+        }
+
+        let mut expected = vec![
+            NullifierDivergence {
+                nullifier: nullifier(2),
+                local: Some(block(2)),
+                reference: Some(block(5)),
+            },
+            NullifierDivergence {
+                nullifier: nullifier(3),
+                local: Some(block(3)),
+                reference: None,
+            },
+            NullifierDivergence {
+                nullifier: nullifier(4),
+                local: None,
+                reference: Some(block(4)),
+            },
+        ];
+        expected.sort_by_key(|divergence| divergence.nullifier.inner());
+        assert_eq!(local.diagnose_divergence(&reference), expected);
+    }
+
+    #[test]
+    fn test_snapshot_is_unaffected_by_later_mutations() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree = NullifierTree::with_entries(
+            [(nullifier(1), block(1)), (nullifier(2), block(2))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+        let old_root = tree.root();
+
+        let snapshot = tree.snapshot();
+        let mutations = tree.compute_mutations([(nullifier(3), block(3))]);
+        tree.apply_mutations(mutations).unwrap();
+
+        assert_eq!(snapshot.root(), old_root);
+        assert_ne!(snapshot.root(), tree.root());
+        assert_eq!(snapshot.get_block_num(&nullifier(3)).unwrap(), None);
+        assert_eq!(tree.get_block_num(&nullifier(3)).unwrap(), Some(block(3)));
+        assert_eq!(snapshot.open(&nullifier(1)).compute_root(), old_root);
+
+        let mut entries: Vec<_> = snapshot.entries().map(Result::unwrap).collect();
+        entries.sort_by_key(|(nullifier, _)| nullifier.inner());
+        let mut expected = vec![(nullifier(1), block(1)), (nullifier(2), block(2))];
+        expected.sort_by_key(|(nullifier, _)| nullifier.inner());
+        assert_eq!(entries, expected);
+    }
+
+    #[test]
+    fn test_diff_snapshots_reports_differing_entry() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let a = NullifierTree::with_entries(
+            [(nullifier(1), block(1)), (nullifier(2), block(2))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+        let b = NullifierTree::with_entries(
+            [(nullifier(1), block(1)), (nullifier(2), block(2)), (nullifier(3), block(3))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+
+        let same = NullifierTree::diff_snapshots(&a.to_bytes(), &a.to_bytes()).unwrap();
+        assert!(same.roots_match());
+        assert!(same.divergences.is_empty());
+
+        let diff = NullifierTree::diff_snapshots(&a.to_bytes(), &b.to_bytes()).unwrap();
+        assert!(!diff.roots_match());
+        assert_eq!(diff.root_a, a.root());
+        assert_eq!(diff.root_b, b.root());
+        assert_eq!(
+            diff.divergences,
+            [NullifierDivergence {
+                nullifier: nullifier(3),
+                local: None,
+                reference: Some(block(3)),
+            }]
+        );
+
+        // A snapshot whose entries don't match its root is rejected.
+        let mut corrupt = b.to_bytes();
+        let len = corrupt.len();
+        corrupt[len - 1] ^= 1;
+        assert!(matches!(
+            NullifierTree::diff_snapshots(&a.to_bytes(), &corrupt),
+            Err(NullifierTreeError::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_contains_agrees_with_get_block_num() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, Felt::new(i), Felt::new(i * 3)]);
+        let (tree, _) = NullifierTree::with_entries(
+            (1..=100)
+                .filter(|i| i % 3 != 0)
+                .map(|i| (nullifier(i), block((i as u32 % 7) + 1))),
+            CorruptionPolicy::Abort,
+        )
+        .unwrap();
+
+        for i in 0..=110 {
+            let nullifier = nullifier(i);
+            assert_eq!(
+                tree.contains(&nullifier),
+                tree.get_block_num(&nullifier).unwrap().is_some()
+            );
+        }
+        assert!(tree.contains(&nullifier(1)));
+        assert!(!tree.contains(&nullifier(3)));
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, Felt::new(i), Felt::new(i * 7)]);
+        let entries: Vec<_> =
+            (1..=300).map(|i| (nullifier(i), block((i as u32 % 20) + 1))).collect();
+        let (tree, _) =
+            NullifierTree::with_entries(entries.iter().copied(), CorruptionPolicy::Abort).unwrap();
+
+        let bytes = tree.to_bytes();
+        let restored = NullifierTree::from_snapshot_bytes(&bytes).unwrap();
+        assert_eq!(restored.root(), tree.root());
+        for (nullifier, block_num) in &entries {
+            assert_eq!(restored.get_block_num(nullifier).unwrap(), Some(*block_num));
+        }
+        assert_eq!(restored.get_block_num(&nullifier(301)).unwrap(), None);
+
+        // Truncated snapshots and blobs without the header are rejected.
+        let expected = (bytes.len() - 12) as u64;
+        assert!(matches!(
+            NullifierTree::from_snapshot_bytes(&bytes[..bytes.len() - 1]),
+            Err(NullifierTreeError::TruncatedSnapshot { expected: e, actual })
+                if e == expected && actual == expected - 1
+        ));
+        assert!(matches!(
+            NullifierTree::from_snapshot_bytes(&bytes[..10]),
+            Err(NullifierTreeError::InvalidSnapshotHeader)
+        ));
+        assert!(matches!(
+            NullifierTree::from_snapshot_bytes(&bytes[1..]),
+            Err(NullifierTreeError::InvalidSnapshotHeader)
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_with_huge_entry_count_is_rejected() {
+        let mut payload = Vec::new();
+        let tree = NullifierTree::with_entries([], CorruptionPolicy::Abort).unwrap().0;
+        tree.root().write_into(&mut payload);
+        payload.write_usize(usize::MAX);
+
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bytes.write_u64(payload.len() as u64);
+        bytes.extend(payload);
+
+        assert!(matches!(
+            NullifierTree::from_snapshot_bytes(&bytes),
+            Err(NullifierTreeError::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_and_insert_commits_unconsumed_nullifiers() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree =
+            NullifierTree::with_entries([(nullifier(1), block(1))], CorruptionPolicy::Abort)
+                .unwrap()
+                .0;
+
+        let root = tree
+            .check_and_insert(&[(nullifier(2), block(2)), (nullifier(3), block(2))])
+            .unwrap();
+
+        assert_eq!(root, tree.root());
+        assert_eq!(tree.get_block_num(&nullifier(2)).unwrap(), Some(block(2)));
+        assert_eq!(tree.get_block_num(&nullifier(3)).unwrap(), Some(block(2)));
+    }
+
+    #[test]
+    fn test_check_and_insert_leaves_tree_unchanged_on_conflict() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree =
+            NullifierTree::with_entries([(nullifier(1), block(1))], CorruptionPolicy::Abort)
+                .unwrap()
+                .0;
+        let root = tree.root();
+
+        assert!(matches!(
+            tree.check_and_insert(&[(nullifier(2), block(2)), (nullifier(1), block(2))]),
+            Err(NullifierTreeError::AlreadyConsumed { nullifier: conflict, block_num })
+                if conflict == nullifier(1) && block_num == block(1)
+        ));
+        assert_eq!(tree.root(), root);
+        assert_eq!(tree.get_block_num(&nullifier(2)).unwrap(), None);
+
+        // Nullifiers consumed twice by the same update conflict as well.
+        assert!(matches!(
+            tree.check_and_insert(&[(nullifier(2), block(2)), (nullifier(2), block(3))]),
+            Err(NullifierTreeError::AlreadyConsumed { block_num, .. })
+                if block_num == block(2)
+        ));
+        assert_eq!(tree.root(), root);
+    }
+
+    #[test]
+    fn test_insert_nullifiers_matches_computed_mutations() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree =
+            NullifierTree::with_entries([(nullifier(1), block(1))], CorruptionPolicy::Abort)
+                .unwrap()
+                .0;
+        let mut expected = tree.clone();
+        let updates = [(nullifier(2), block(2)), (nullifier(3), block(2))];
+        expected.apply_mutations(expected.compute_mutations(updates)).unwrap();
+
+        let root = tree.insert_nullifiers(updates).unwrap();
+
+        assert_eq!(root, expected.root());
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.get_block_num(&nullifier(3)).unwrap(), Some(block(2)));
+    }
+
+    #[test]
+    fn test_insert_nullifiers_rejects_consumed_nullifiers() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree =
+            NullifierTree::with_entries([(nullifier(1), block(1))], CorruptionPolicy::Abort)
+                .unwrap()
+                .0;
+        let root = tree.root();
+
+        assert!(matches!(
+            tree.insert_nullifiers([(nullifier(2), block(3)), (nullifier(1), block(3))]),
+            Err(NullifierTreeError::AlreadyConsumed { nullifier: conflict, block_num })
+                if conflict == nullifier(1) && block_num == block(1)
+        ));
+        assert_eq!(tree.root(), root);
+        assert_eq!(tree.get_block_num(&nullifier(1)).unwrap(), Some(block(1)));
+        assert_eq!(tree.get_block_num(&nullifier(2)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_validate_block_nullifiers_accepts_next_block() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let tree = NullifierTree::with_entries([(nullifier(1), block(1))], CorruptionPolicy::Abort)
+            .unwrap()
+            .0;
+
+        assert!(tree.validate_block_nullifiers(block(2), &[nullifier(2), nullifier(3)]).is_ok());
+        assert!(tree.validate_block_nullifiers(block(2), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_block_nullifiers_rejects_gaps() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree =
+            NullifierTree::with_entries([(nullifier(1), block(1))], CorruptionPolicy::Abort)
+                .unwrap()
+                .0;
+
+        assert!(matches!(
+            tree.validate_block_nullifiers(block(3), &[nullifier(2)]),
+            Err(NullifierTreeError::NonMonotonicBlock { block_num, latest_block_num })
+                if block_num == block(3) && latest_block_num == block(1)
+        ));
+
+        // Block 2 consumed no nullifiers, so it only shows in the tree once recorded.
+        tree.record_applied_block(block(2));
+        assert_eq!(tree.latest_block_num(), block(2));
+        assert!(tree.validate_block_nullifiers(block(3), &[nullifier(2)]).is_ok());
+
+        tree.insert_nullifiers([(nullifier(2), block(3))]).unwrap();
+        assert_eq!(tree.latest_block_num(), block(3));
+        assert!(tree.validate_block_nullifiers(block(4), &[nullifier(3)]).is_ok());
+
+        // Recording an older block doesn't move the latest block back.
+        tree.record_applied_block(block(2));
+        assert_eq!(tree.latest_block_num(), block(3));
+    }
+
+    #[test]
+    fn test_validate_block_nullifiers_rejects_out_of_order_and_duplicate_nullifiers() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let tree = NullifierTree::with_entries([(nullifier(1), block(3))], CorruptionPolicy::Abort)
+            .unwrap()
+            .0;
+
+        assert!(matches!(
+            tree.validate_block_nullifiers(block(3), &[nullifier(2)]),
+            Err(NullifierTreeError::NonMonotonicBlock { block_num, latest_block_num })
+                if block_num == block(3) && latest_block_num == block(3)
+        ));
+        assert!(matches!(
+            tree.validate_block_nullifiers(block(2), &[nullifier(2)]),
+            Err(NullifierTreeError::NonMonotonicBlock { block_num, latest_block_num })
+                if block_num == block(2) && latest_block_num == block(3)
+        ));
+
+        assert!(matches!(
+            tree.validate_block_nullifiers(block(4), &[nullifier(2), nullifier(1)]),
+            Err(NullifierTreeError::AlreadyConsumed { nullifier: consumed, block_num })
+                if consumed == nullifier(1) && block_num == block(3)
+        ));
+        assert!(matches!(
+            tree.validate_block_nullifiers(block(4), &[nullifier(2), nullifier(2)]),
+            Err(NullifierTreeError::AlreadyConsumed { nullifier: duplicate, block_num })
+                if duplicate == nullifier(2) && block_num == block(4)
+        ));
+    }
+
+    #[test]
+    fn test_reserve_rejects_conflicting_reservations() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree =
+            NullifierTree::with_entries([(nullifier(1), block(1))], CorruptionPolicy::Abort)
+                .unwrap()
+                .0;
+        let root = tree.root();
+
+        let token = tree.reserve(&[nullifier(2), nullifier(3)], block(2)).unwrap();
+        assert_eq!(tree.root(), root);
+
+        assert!(matches!(
+            tree.reserve(&[nullifier(4), nullifier(3)], block(3)),
+            Err(NullifierTreeError::NullifierReserved(reserved)) if reserved == nullifier(3)
+        ));
+        assert!(matches!(
+            tree.reserve(&[nullifier(1)], block(3)),
+            Err(NullifierTreeError::AlreadyConsumed { nullifier: consumed, block_num })
+                if consumed == nullifier(1) && block_num == block(1)
+        ));
+        assert!(matches!(
+            tree.reserve(&[nullifier(4), nullifier(4)], block(3)),
+            Err(NullifierTreeError::NullifierReserved(duplicate)) if duplicate == nullifier(4)
+        ));
+
+        // Failed reservations reserve nothing.
+        let other = tree.reserve(&[nullifier(4)], block(3)).unwrap();
+        tree.release(other);
+        tree.release(token);
+    }
+
+    #[test]
+    fn test_commit_and_release_reservations() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree = NullifierTree::with_entries([], CorruptionPolicy::Abort).unwrap().0;
+
+        let token = tree.reserve(&[nullifier(1)], block(2)).unwrap();
+        tree.release(token);
+        assert_eq!(tree.get_block_num(&nullifier(1)).unwrap(), None);
+
+        let token = tree.reserve(&[nullifier(1), nullifier(2)], block(3)).unwrap();
+        let expected = NullifierTree::with_entries(
+            [(nullifier(1), block(3)), (nullifier(2), block(3))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+        assert_eq!(tree.commit(token).unwrap(), expected.root());
+        assert_eq!(tree.get_block_num(&nullifier(2)).unwrap(), Some(block(3)));
+
+        // Committed nullifiers are consumed rather than reserved.
+        assert!(matches!(
+            tree.reserve(&[nullifier(2)], block(4)),
+            Err(NullifierTreeError::AlreadyConsumed { block_num, .. }) if block_num == block(3)
+        ));
+
+        // Reservations of another tree are not committed.
+        let mut other = NullifierTree::with_entries([], CorruptionPolicy::Abort).unwrap().0;
+        let foreign = other.reserve(&[nullifier(5)], block(4)).unwrap();
+        assert!(matches!(tree.commit(foreign), Err(NullifierTreeError::UnknownReservation(0))));
+        assert_eq!(tree.get_block_num(&nullifier(5)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_reserved_nullifiers_are_only_consumed_once() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree = NullifierTree::with_entries([], CorruptionPolicy::Abort).unwrap().0;
+
+        let token = tree.reserve(&[nullifier(1), nullifier(2)], block(2)).unwrap();
+
+        // Reserved nullifiers are not consumed by other insertions.
+        assert!(matches!(
+            tree.check_and_insert(&[(nullifier(2), block(3))]),
+            Err(NullifierTreeError::NullifierReserved(reserved)) if reserved == nullifier(2)
+        ));
+
+        // Raw mutations bypass the reservations, so the reservation can't be committed anymore.
+        let mutations = tree.compute_mutations([(nullifier(2), block(3))]);
+        tree.apply_mutations(mutations).unwrap();
+        let root = tree.root();
+        assert!(matches!(
+            tree.commit(token),
+            Err(NullifierTreeError::AlreadyConsumed { nullifier: consumed, block_num })
+                if consumed == nullifier(2) && block_num == block(3)
+        ));
+        assert_eq!(tree.root(), root);
+        assert_eq!(tree.get_block_num(&nullifier(1)).unwrap(), None);
+
+        // The failed commit released the reservation.
+        let _token = tree.reserve(&[nullifier(1)], block(4)).unwrap();
+    }
+
+    #[test]
+    fn test_per_block_counts() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let entries = [
+            (nullifier(1), block(2)),
+            (nullifier(2), block(5)),
+            (nullifier(3), block(2)),
+            (nullifier(4), block(7)),
+            (nullifier(5), block(2)),
+            (nullifier(6), block(5)),
+        ];
+        let tree = NullifierTree::with_entries(entries, CorruptionPolicy::Abort).unwrap().0;
+
+        assert_eq!(
+            tree.per_block_counts(),
+            BTreeMap::from([(block(2), 3), (block(5), 2), (block(7), 1)])
+        );
+    }
+
+    #[test]
+    fn test_iter_yields_all_entries() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, Felt::new(i), Felt::new(i * 5)]);
+        let mut entries: Vec<_> =
+            (1..=50).map(|i| (nullifier(i), block((i as u32 % 9) + 1))).collect();
+        let (tree, _) =
+            NullifierTree::with_entries(entries.iter().copied(), CorruptionPolicy::Abort).unwrap();
+
+        let mut collected: Vec<_> = tree.iter().collect();
+        collected.sort();
+        entries.sort();
+        assert_eq!(collected, entries);
+
+        let (empty, _) = NullifierTree::with_entries([], CorruptionPolicy::Abort).unwrap();
+        assert_eq!(empty.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_iter_is_lazy() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, Felt::new(i), Felt::new(i)]);
+        let mut entries: Vec<_> =
+            (1..=1_000).map(|i| (nullifier(i), block((i as u32 % 7) + 1))).collect();
+        let (tree, _) =
+            NullifierTree::with_entries(entries.iter().copied(), CorruptionPolicy::Abort).unwrap();
+        entries.sort_by_key(|(nullifier, _)| leaf_order(nullifier));
+
+        let mut stream = tree.iter();
+        let first: Vec<_> = stream.by_ref().take(3).collect();
+        assert_eq!(first, entries[..3]);
+
+        // The tree can be queried while the stream borrows it.
+        assert_eq!(tree.get_block_num(&entries[3].0).unwrap(), Some(entries[3].1));
+
+        // The stream resumes where it stopped.
+        assert_eq!(stream.next(), Some(entries[3]));
+        assert_eq!(stream.count(), entries.len() - 4);
+    }
+
+    #[test]
+    fn test_bulk_get_matches_serial_lookups() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, Felt::new(i), Felt::new(i % 97)]);
+        // Every 50th nullifier is consumed, in one of 10 blocks.
+        let entries = (0..50_000).step_by(50).map(|i| (nullifier(i), block((i % 10) as u32 + 1)));
+        let tree = NullifierTree::with_entries(entries, CorruptionPolicy::Abort).unwrap().0;
+        let lookups: Vec<_> = (0..50_000).map(nullifier).collect();
+
+        let found = tree.bulk_get(&lookups).unwrap();
+
+        assert_eq!(found, tree.get_block_nums(&lookups).unwrap());
+        assert_eq!(found.len(), lookups.len());
+        assert_eq!(found.iter().flatten().count(), 1_000);
+        assert_eq!(found[100], Some(block(1)));
+        assert_eq!(found[101], None);
+    }
+
+    #[test]
+    fn test_try_open_rejects_malformed_nullifier() {
+        let nullifier = Nullifier::from([ZERO, ZERO, ZERO, ONE]);
+        let tree = NullifierTree::with_entries([(nullifier, block(1))], CorruptionPolicy::Abort)
+            .unwrap()
+            .0;
+
+        assert_eq!(tree.try_open(&nullifier).unwrap(), tree.open(&nullifier));
+
+        let malformed = Nullifier::from(RpoDigest::default());
+        assert!(matches!(
+            tree.try_open(&malformed),
+            Err(NullifierTreeError::InvalidNullifier(invalid)) if invalid == malformed
+        ));
+    }
+
+    #[test]
+    fn test_open_batch_proofs_are_aligned_with_nullifiers() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, Felt::new(i), Felt::new(i % 13)]);
+        let entries = (1..=100).map(|i| (nullifier(i), block(i as u32)));
+        let tree = NullifierTree::with_entries(entries, CorruptionPolicy::Abort).unwrap().0;
+        // Consumed and unconsumed nullifiers, in no particular order.
+        let nullifiers: Vec<_> = [42, 7, 150, 99, 1, 7].into_iter().map(nullifier).collect();
+
+        let proofs = tree.open_batch(&nullifiers);
+
+        assert_eq!(proofs.len(), nullifiers.len());
+        for (nullifier, proof) in nullifiers.iter().zip(&proofs) {
+            assert_eq!(proof.compute_root(), tree.root());
+            assert_eq!(*proof, tree.open(nullifier));
+            let block_num =
+                proof.get(&nullifier.inner()).map(NullifierTree::leaf_value_to_block_num);
+            assert_eq!(block_num.and_then(Result::ok), tree.get_block_num(nullifier).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_get_block_nums_is_aligned_with_input() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let tree = NullifierTree::with_entries(
+            [(nullifier(1), block(3)), (nullifier(3), block(5))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap()
+        .0;
+
+        let lookups = [nullifier(2), nullifier(3), nullifier(1), nullifier(4), nullifier(3)];
+        assert_eq!(
+            tree.get_block_nums(&lookups).unwrap(),
+            vec![None, Some(block(5)), Some(block(3)), None, Some(block(5))]
+        );
+        assert_eq!(tree.get_block_nums(&[]).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_delta_round_trip() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree =
+            NullifierTree::with_entries([(nullifier(1), block(1))], CorruptionPolicy::Abort)
+                .unwrap()
+                .0
+                .with_root_history(2);
+        let mut base = tree.clone();
+        let base_root = base.root();
+
+        tree.check_and_insert(&[(nullifier(2), block(2)), (nullifier(3), block(2))])
+            .unwrap();
+        tree.check_and_insert(&[(nullifier(4), block(3))]).unwrap();
+
+        let delta = tree.serialize_delta(base_root).unwrap();
+        base.apply_delta(&delta).unwrap();
+        assert_eq!(base.root(), tree.root());
+        assert_eq!(base.get_block_num(&nullifier(4)).unwrap(), Some(block(3)));
+
+        // The delta only applies to the tree it was serialized since.
+        assert!(matches!(
+            base.apply_delta(&delta),
+            Err(NullifierTreeError::RootMismatch { actual, .. }) if actual == tree.root()
+        ));
+
+        // Roots older than the retained history are unknown.
+        tree.check_and_insert(&[(nullifier(5), block(4))]).unwrap();
+        assert!(matches!(
+            tree.serialize_delta(base_root),
+            Err(NullifierTreeError::UnknownRoot(root)) if root == base_root
+        ));
+    }
+
+    #[test]
+    fn test_delta_with_huge_entry_count_is_rejected() {
+        let mut tree = NullifierTree::with_entries([], CorruptionPolicy::Abort).unwrap().0;
+        let root = tree.root();
+
+        let mut delta = Vec::new();
+        root.write_into(&mut delta);
+        root.write_into(&mut delta);
+        delta.write_usize(usize::MAX);
+
+        assert!(matches!(
+            tree.apply_delta(&delta),
+            Err(NullifierTreeError::DeserializationError(_))
+        ));
+        assert_eq!(tree.root(), root);
+    }
+
+    #[test]
+    fn test_growth_warning_is_logged_once() {
+        let warnings = WarningCounter::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(warnings.clone()));
+
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let mut tree = NullifierTree::with_entries([], CorruptionPolicy::Abort)
+            .unwrap()
+            .0
+            .with_growth_warning(2);
+
+        // Reaching the threshold doesn't warn, crossing it does, and growing further doesn't
+        // warn again.
+        for (block_num, nullifiers) in [(1, 1..3), (2, 3..4), (3, 4..6), (4, 6..7)] {
+            tree.check_and_insert(
+                &nullifiers.map(|i| (nullifier(i), block(block_num))).collect::<Vec<_>>(),
+            )
+            .unwrap();
+            let expected = if block_num < 2 { 0 } else { 1 };
+            assert_eq!(warnings.0.load(Ordering::SeqCst), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_num_nullifiers_follows_mutations() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let (mut tree, _) = NullifierTree::with_entries(
+            [(nullifier(1), block(1)), (nullifier(2), block(1))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap();
+        assert_eq!(tree.num_nullifiers(), 2);
+
+        // Overwriting a consumed nullifier doesn't change the count, removing one decrements it.
+        let mutations = tree.compute_leaf_mutations([
+            (nullifier(1).inner(), NullifierTree::block_num_to_leaf_value(block(2))),
+            (nullifier(2).inner(), Smt::EMPTY_VALUE),
+            (nullifier(3).inner(), NullifierTree::block_num_to_leaf_value(block(2))),
+            (nullifier(4).inner(), Smt::EMPTY_VALUE),
+        ]);
+        tree.apply_mutations(mutations).unwrap();
+        assert_eq!(tree.num_nullifiers(), 2);
+
+        let mutations =
+            tree.compute_mutations([(nullifier(5), block(3)), (nullifier(6), block(3))]);
+        tree.apply_mutations_batched(mutations, 1).await.unwrap();
+        assert_eq!(tree.num_nullifiers(), 4);
+        assert_eq!(tree.num_nullifiers(), tree.smt.entries().count());
+    }
+
+    #[test]
+    fn test_prefix_index_follows_inserts() {
+        // The 4-bit prefix of a nullifier is the top nibble of its most significant element.
+        let nullifier = |prefix: u64, i: u64| {
+            Nullifier::from([ZERO, ZERO, ZERO, Felt::new((prefix << 60) | i)])
+        };
+        let with_prefix = |tree: &NullifierTree, prefix: u64| -> BTreeSet<_> {
+            tree.nullifiers_with_prefix(prefix).iter().map(Nullifier::inner).collect()
+        };
+        let expected = |nullifiers: &[Nullifier]| -> BTreeSet<_> {
+            nullifiers.iter().map(Nullifier::inner).collect()
+        };
+
+        let (tree, _) = NullifierTree::with_entries(
+            [(nullifier(1, 1), block(1)), (nullifier(2, 1), block(1))],
+            CorruptionPolicy::Abort,
+        )
+        .unwrap();
+        let mut tree = tree.with_prefix_index(4);
+        assert_eq!(with_prefix(&tree, 1), expected(&[nullifier(1, 1)]));
+
+        tree.check_and_insert(&[(nullifier(1, 2), block(2)), (nullifier(3, 1), block(2))])
+            .unwrap();
+        let mutations =
+            tree.compute_mutations([(nullifier(1, 3), block(3)), (nullifier(2, 2), block(3))]);
+        tree.apply_mutations(mutations).unwrap();
+
+        assert_eq!(
+            with_prefix(&tree, 1),
+            expected(&[nullifier(1, 1), nullifier(1, 2), nullifier(1, 3)])
+        );
+        assert_eq!(with_prefix(&tree, 2), expected(&[nullifier(2, 1), nullifier(2, 2)]));
+        assert_eq!(with_prefix(&tree, 3), expected(&[nullifier(3, 1)]));
+        assert!(with_prefix(&tree, 4).is_empty());
+
+        // Emptied leaves leave the index.
+        let mutations = tree.compute_leaf_mutations([(nullifier(1, 2).inner(), Smt::EMPTY_VALUE)]);
+        tree.apply_mutations(mutations).unwrap();
+        assert_eq!(with_prefix(&tree, 1), expected(&[nullifier(1, 1), nullifier(1, 3)]));
+
+        // Without the index, no nullifiers are listed.
+        let (tree, _) =
+            NullifierTree::with_entries([(nullifier(1, 1), block(1))], CorruptionPolicy::Abort)
+                .unwrap();
+        assert!(tree.nullifiers_with_prefix(1).is_empty());
+    }
+
+    #[cfg(feature = "pruning")]
+    #[test]
+    fn test_prune_before_removes_older_nullifiers_only() {
+        let nullifier = |i: u64| Nullifier::from([ZERO, ZERO, ZERO, Felt::new(i)]);
+        let entries: Vec<_> = (1..=9).map(|i| (nullifier(i), block((i as u32 + 2) / 3))).collect();
+        let (mut tree, _) =
+            NullifierTree::with_entries(entries.iter().copied(), CorruptionPolicy::Abort).unwrap();
+
+        // Nullifiers 1 to 3 were consumed in block 1, and 4 to 6 in block 2.
+        assert_eq!(tree.prune_before(block(3)), 6);
+        for (nullifier, block_num) in &entries {
+            let expected = (*block_num >= block(3)).then_some(*block_num);
+            assert_eq!(tree.get_block_num(nullifier).unwrap(), expected);
+        }
+        assert_eq!(tree.num_nullifiers(), 3);
+
+        // The pruned tree has the root of a tree built from the surviving nullifiers only.
+        let (survivors, _) = NullifierTree::with_entries(
+            entries.iter().copied().filter(|(_, block_num)| *block_num >= block(3)),
+            CorruptionPolicy::Abort,
+        )
+        .unwrap();
+        assert_eq!(tree.root(), survivors.root());
+
+        // Pruning again before the same block has nothing to remove.
+        let root = tree.root();
+        assert_eq!(tree.prune_before(block(3)), 0);
+        assert_eq!(tree.root(), root);
+    }
+
+    #[test]
+    fn test_with_sorted_unique_entries_matches_with_entries() {
+        // Pairs of nullifiers share a leaf, so the tree has single and multiple leaves.
+        let entries: Vec<_> = (1..=10u64)
+            .map(|i| {
+                (
+                    Nullifier::from([Felt::new(i % 2), ZERO, ZERO, Felt::new(i / 2)]),
+                    block(i as u32),
+                )
+            })
+            .collect();
+        let (expected, _) =
+            NullifierTree::with_entries(entries.iter().rev().copied(), CorruptionPolicy::Abort)
+                .unwrap();
+
+        let mut sorted: Vec<_> = expected
+            .leaves()
+            .into_iter()
+            .map(|(key, value)| {
+                (Nullifier::from(key), NullifierTree::leaf_value_to_block_num(value).unwrap())
+            })
+            .collect();
+        let tree = NullifierTree::with_sorted_unique_entries(&sorted).unwrap();
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.leaves(), expected.leaves());
+
+        sorted[0].1 = BlockNumber::GENESIS;
+        assert!(matches!(
+            NullifierTree::with_sorted_unique_entries(&sorted),
+            Err(NullifierTreeError::CorruptLeaf { .. })
+        ));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "sorted by leaf and unique")]
+    fn test_with_sorted_unique_entries_panics_on_duplicates() {
+        let nullifier = Nullifier::from([ZERO, ZERO, ZERO, ONE]);
+
+        let _ = NullifierTree::with_sorted_unique_entries(&[
+            (nullifier, block(1)),
+            (nullifier, block(2)),
+        ]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "sorted by leaf and unique")]
+    fn test_with_sorted_unique_entries_panics_on_unsorted_entries() {
+        let entries = [
+            (Nullifier::from([ZERO, ZERO, ZERO, Felt::new(2)]), block(1)),
+            (Nullifier::from([ZERO, ZERO, ZERO, ONE]), block(1)),
+        ];
 
-        assert_eq!(decoded_block_num, block_num);
+        let _ = NullifierTree::with_sorted_unique_entries(&entries);
     }
 }
@@ -0,0 +1,81 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use miden_objects::notes::Nullifier;
+
+use crate::types::BlockNumber;
+
+/// Nullifiers reserved by blocks which are being built, but were not applied yet.
+///
+/// The reservations are grouped by the number of the block which made them, so they can be
+/// released once a block with that number is applied, regardless of whether it contained them.
+///
+/// Unlike the exclusive reservations of
+/// [NullifierTree::reserve](crate::nullifier_tree::NullifierTree::reserve), these reservations are
+/// only reported to block producers, and never reject a request. They are made when block inputs
+/// are requested, which is a read that block producers retry, or repeat for competing candidates of
+/// the same block, without a way to hand back a reservation. An exclusive reservation would fail
+/// such requests, and would be held until the block is applied. The nullifiers of applied blocks
+/// are checked against the nullifier tree instead.
+#[derive(Debug, Default)]
+pub struct PendingNullifiers(BTreeMap<BlockNumber, BTreeSet<Nullifier>>);
+
+impl PendingNullifiers {
+    /// Reserves the nullifiers for the block with the given number.
+    pub fn reserve(
+        &mut self,
+        block_num: BlockNumber,
+        nullifiers: impl IntoIterator<Item = Nullifier>,
+    ) {
+        self.0.entry(block_num).or_default().extend(nullifiers);
+    }
+
+    /// Releases the reservations of all blocks up to and including `block_num`.
+    ///
+    /// This should be called once the block is applied, as the nullifiers it contains are then
+    /// committed, and no other block with the same number can be applied anymore.
+    pub fn release_up_to(&mut self, block_num: BlockNumber) {
+        match block_num.next() {
+            Some(next_block_num) => self.0 = self.0.split_off(&next_block_num),
+            None => self.0.clear(),
+        }
+    }
+
+    /// Returns the nullifiers reserved by all pending blocks.
+    pub fn nullifiers(&self) -> BTreeSet<Nullifier> {
+        self.0.values().flatten().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{Digest, Felt, ZERO};
+
+    use super::PendingNullifiers;
+    use crate::types::BlockNumber;
+
+    fn nullifier(value: u64) -> miden_objects::notes::Nullifier {
+        Digest::from([ZERO, ZERO, ZERO, Felt::new(value)]).into()
+    }
+
+    #[test]
+    fn test_reserved_nullifiers_are_pending() {
+        let mut pending = PendingNullifiers::default();
+        pending.reserve(BlockNumber::from(5), [nullifier(1), nullifier(2)]);
+        pending.reserve(BlockNumber::from(6), [nullifier(2), nullifier(3)]);
+
+        assert_eq!(pending.nullifiers(), [nullifier(1), nullifier(2), nullifier(3)].into());
+    }
+
+    #[test]
+    fn test_applied_blocks_release_reservations() {
+        let mut pending = PendingNullifiers::default();
+        pending.reserve(BlockNumber::from(5), [nullifier(1)]);
+        pending.reserve(BlockNumber::from(6), [nullifier(2)]);
+
+        pending.release_up_to(BlockNumber::from(5));
+        assert_eq!(pending.nullifiers(), [nullifier(2)].into());
+
+        pending.release_up_to(BlockNumber::from(6));
+        assert!(pending.nullifiers().is_empty());
+    }
+}
@@ -3,27 +3,36 @@ use std::{collections::BTreeSet, sync::Arc};
 use miden_node_proto::{
     convert,
     domain::notes::NoteAuthenticationInfo,
-    errors::ConversionError,
+    errors::{ConversionError, MissingFieldHelper},
     generated::{
         self,
         account::AccountSummary,
         note::NoteAuthenticationInfo as NoteAuthenticationInfoProto,
         requests::{
-            ApplyBlockRequest, CheckNullifiersByPrefixRequest, CheckNullifiersRequest,
-            GetAccountDetailsRequest, GetAccountProofsRequest, GetAccountStateDeltaRequest,
-            GetBlockByNumberRequest, GetBlockHeaderByNumberRequest, GetBlockInputsRequest,
-            GetNoteAuthenticationInfoRequest, GetNotesByIdRequest, GetTransactionInputsRequest,
-            ListAccountsRequest, ListNotesRequest, ListNullifiersRequest, SyncNoteRequest,
-            SyncStateRequest,
+            AccountVersion, ApplyBlockRequest, ApplyBlocksRequest, CheckNullifiersByPrefixRequest,
+            CheckNullifiersRequest, GetAccountCreationBlockRequest, GetAccountDetailsRequest,
+            GetAccountProofsRequest, GetAccountStateDeltaRequest, GetAccountStateForksRequest,
+            GetAccountStateRequest, GetBlockByNumberRequest, GetBlockHeaderByNumberRequest,
+            GetBlockInputsRequest, GetChainStatsRequest, GetNoteAuthenticationInfoRequest,
+            GetNotesAuthenticatedSinceRequest, GetNotesByIdRequest, GetNullifierOriginRequest,
+            GetNullifierRootHistoryRequest, GetPendingNullifiersRequest,
+            GetTransactionInputsBatchRequest, GetTransactionInputsRequest, ListAccountsRequest,
+            ListNotesRequest, ListNullifiersRequest, NullifierOrigin, SyncBlocksRequest,
+            SyncNoteRequest, SyncStateRequest,
         },
         responses::{
-            AccountTransactionInputRecord, ApplyBlockResponse, CheckNullifiersByPrefixResponse,
-            CheckNullifiersResponse, GetAccountDetailsResponse, GetAccountProofsResponse,
-            GetAccountStateDeltaResponse, GetBlockByNumberResponse, GetBlockHeaderByNumberResponse,
-            GetBlockInputsResponse, GetNoteAuthenticationInfoResponse, GetNotesByIdResponse,
-            GetTransactionInputsResponse, ListAccountsResponse, ListNotesResponse,
-            ListNullifiersResponse, NullifierTransactionInputRecord, NullifierUpdate,
-            SyncNoteResponse, SyncStateResponse,
+            AccountStateCandidate, AccountTransactionInputRecord, ApplyBlockResponse,
+            ApplyBlocksResponse, CheckNullifiersByPrefixResponse, CheckNullifiersResponse,
+            GetAccountCreationBlockResponse, GetAccountDetailsResponse, GetAccountProofsResponse,
+            GetAccountStateDeltaResponse, GetAccountStateForksResponse, GetAccountStateResponse,
+            GetBlockByNumberResponse, GetBlockHeaderByNumberResponse, GetBlockInputsResponse,
+            GetChainStatsResponse, GetNoteAuthenticationInfoResponse,
+            GetNotesAuthenticatedSinceResponse, GetNotesByIdResponse, GetNullifierOriginResponse,
+            GetNullifierRootHistoryResponse, GetPendingNullifiersResponse,
+            GetTransactionInputsBatchResponse, GetTransactionInputsResponse, ListAccountsResponse,
+            ListNotesResponse, ListNullifiersResponse, NullifierRootRecord,
+            NullifierTransactionInputRecord, NullifierUpdate, SyncBlocksResponse, SyncNoteResponse,
+            SyncStateResponse,
         },
         smt::SmtLeafEntry,
         store::api_server,
@@ -31,27 +40,109 @@ use miden_node_proto::{
     },
     try_convert,
 };
+use miden_node_utils::errors::ApiError;
 use miden_objects::{
     block::Block,
     crypto::hash::rpo::RpoDigest,
     notes::{NoteId, Nullifier},
+    transaction::TransactionId,
     utils::{Deserializable, Serializable},
     Felt, ZERO,
 };
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 use tracing::{debug, info, instrument};
 
-use crate::{state::State, types::AccountId, COMPONENT};
+use crate::{
+    blocks::BlockStore,
+    config::StoreConfig,
+    db::Db,
+    state::State,
+    types::{AccountId, BlockNumber},
+    COMPONENT,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// The number of blocks buffered by a block sync stream before waiting for the client.
+const SYNC_BLOCKS_BUFFER_SIZE: usize = 16;
 
 // STORE API
 // ================================================================================================
 
+/// Implementation of the store's gRPC API.
+///
+/// Besides being served over gRPC, the API can be called directly by components running in the
+/// same process as the store, avoiding the network round-trip.
 pub struct StoreApi {
-    pub(super) state: Arc<State>,
+    state: Arc<State>,
+}
+
+impl StoreApi {
+    /// Loads the state of the store from the database and block store given by `config`.
+    pub async fn load(config: &StoreConfig) -> Result<Self, ApiError> {
+        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await?);
+
+        let db = Db::setup(config.clone(), Arc::clone(&block_store))
+            .await
+            .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
+
+        let state = State::load(db, block_store)
+            .await
+            .map_err(|err| ApiError::DatabaseConnectionFailed(err.to_string()))?;
+
+        Ok(Self { state: Arc::new(state) })
+    }
+
+    /// Returns the inputs of the transaction described by `request`.
+    async fn transaction_inputs(
+        &self,
+        request: GetTransactionInputsRequest,
+    ) -> Result<GetTransactionInputsResponse, Status> {
+        let account_id = request.account_id.ok_or(invalid_argument("`account_id` missing"))?.id;
+        let nullifiers = validate_nullifiers(&request.nullifiers)?;
+        let unauthenticated_notes = validate_notes(&request.unauthenticated_notes)?;
+
+        let tx_inputs = self
+            .state
+            .get_transaction_inputs(account_id, &nullifiers, unauthenticated_notes)
+            .await?;
+
+        Ok(GetTransactionInputsResponse {
+            account_state: Some(AccountTransactionInputRecord {
+                account_id: Some(account_id.into()),
+                account_hash: Some(tx_inputs.account_hash.into()),
+            }),
+            nullifiers: tx_inputs
+                .nullifiers
+                .into_iter()
+                .zip(tx_inputs.nullifier_proofs)
+                .map(|(nullifier, proof)| NullifierTransactionInputRecord {
+                    nullifier: Some(nullifier.nullifier.into()),
+                    block_num: nullifier.block_num.into(),
+                    opening: Some(proof.into()),
+                })
+                .collect(),
+            missing_unauthenticated_notes: tx_inputs
+                .missing_unauthenticated_notes
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            block_height: tx_inputs.block_header.block_num(),
+            block_header: Some(tx_inputs.block_header.into()),
+            account_proof: Some(tx_inputs.account_proof.into()),
+            // The hash of an account changes with every update, so it versions the account.
+            account_version: tx_inputs.account_hash.to_bytes(),
+        })
+    }
 }
 
 #[tonic::async_trait]
 impl api_server::Api for StoreApi {
+    type SyncBlocksStream = ReceiverStream<Result<SyncBlocksResponse, Status>>;
+
     // CLIENT ENDPOINTS
     // --------------------------------------------------------------------------------------------
 
@@ -72,7 +163,7 @@ impl api_server::Api for StoreApi {
         info!(target: COMPONENT, ?request);
         let request = request.into_inner();
 
-        let block_num = request.block_num;
+        let block_num = request.block_num.map(BlockNumber::from);
         let (block_header, mmr_proof) = self
             .state
             .get_block_header(block_num, request.include_mmr_proof.unwrap_or(false))
@@ -138,7 +229,7 @@ impl api_server::Api for StoreApi {
             .into_iter()
             .map(|nullifier_info| NullifierUpdate {
                 nullifier: Some(nullifier_info.nullifier.into()),
-                block_num: nullifier_info.block_num,
+                block_num: nullifier_info.block_num.into(),
             })
             .collect();
 
@@ -164,7 +255,12 @@ impl api_server::Api for StoreApi {
 
         let (state, delta) = self
             .state
-            .sync_state(request.block_num, account_ids, request.note_tags, request.nullifiers)
+            .sync_state(
+                request.block_num.into(),
+                account_ids,
+                request.note_tags,
+                request.nullifiers,
+            )
             .await
             .map_err(internal_error)?;
 
@@ -183,7 +279,7 @@ impl api_server::Api for StoreApi {
             .into_iter()
             .map(|transaction_summary| TransactionSummary {
                 account_id: Some(transaction_summary.account_id.into()),
-                block_num: transaction_summary.block_num,
+                block_num: transaction_summary.block_num.into(),
                 transaction_id: Some(transaction_summary.transaction_id.into()),
             })
             .collect();
@@ -195,12 +291,12 @@ impl api_server::Api for StoreApi {
             .into_iter()
             .map(|nullifier_info| NullifierUpdate {
                 nullifier: Some(nullifier_info.nullifier.into()),
-                block_num: nullifier_info.block_num,
+                block_num: nullifier_info.block_num.into(),
             })
             .collect();
 
         Ok(Response::new(SyncStateResponse {
-            chain_tip: self.state.latest_block_num().await,
+            chain_tip: self.state.latest_block_num().await.into(),
             block_header: Some(state.block_header.into()),
             mmr_delta: Some(delta.into()),
             accounts,
@@ -226,20 +322,159 @@ impl api_server::Api for StoreApi {
 
         let (state, mmr_proof) = self
             .state
-            .sync_notes(request.block_num, request.note_tags)
+            .sync_notes(request.block_num.into(), request.note_tags)
             .await
             .map_err(internal_error)?;
 
         let notes = state.notes.into_iter().map(Into::into).collect();
 
         Ok(Response::new(SyncNoteResponse {
-            chain_tip: self.state.latest_block_num().await,
+            chain_tip: self.state.latest_block_num().await.into(),
             block_header: Some(state.block_header.into()),
             mmr_path: Some((&mmr_proof.merkle_path).into()),
             notes,
         }))
     }
 
+    /// Streams all blocks from the requested block number up to the chain tip, in ascending
+    /// order.
+    ///
+    /// The chain tip is read when the request is received, so blocks applied while the stream is
+    /// consumed are not included.
+    #[instrument(target = "miden-store", name = "store:sync_blocks", skip_all, err)]
+    async fn sync_blocks(
+        &self,
+        request: Request<SyncBlocksRequest>,
+    ) -> Result<Response<Self::SyncBlocksStream>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let chain_tip = self.state.latest_block_num().await;
+        let state = Arc::clone(&self.state);
+        let (sender, receiver) = mpsc::channel(SYNC_BLOCKS_BUFFER_SIZE);
+
+        tokio::spawn(async move {
+            for block_num in request.from_block_num..=chain_tip.as_u32() {
+                let response = match state.load_block(block_num.into()).await {
+                    Ok(Some(block)) => Ok(SyncBlocksResponse { block }),
+                    Ok(None) => Err(Status::not_found(format!("Block {block_num} not found"))),
+                    Err(err) => Err(err.into()),
+                };
+                let is_err = response.is_err();
+
+                // Stop once the client has gone away, or after sending an error.
+                if sender.send(response).await.is_err() || is_err {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    /// Returns the transaction which produced the specified nullifier.
+    ///
+    /// The transaction is only known if the block producer recorded it when applying the block
+    /// which contains the nullifier.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_nullifier_origin",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_nullifier_origin(
+        &self,
+        request: Request<GetNullifierOriginRequest>,
+    ) -> Result<Response<GetNullifierOriginResponse>, Status> {
+        let request = request.into_inner();
+        let nullifier = request
+            .nullifier
+            .ok_or(invalid_argument("Nullifier missing"))?
+            .try_into()
+            .map_err(|_| invalid_argument("Digest field is not in the modulus range"))?;
+
+        let transaction_id = self.state.get_nullifier_origin(nullifier).await?;
+
+        Ok(Response::new(GetNullifierOriginResponse {
+            transaction_id: transaction_id.map(Into::into),
+        }))
+    }
+
+    /// Returns the nullifier root of each block in the requested range.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_nullifier_root_history",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_nullifier_root_history(
+        &self,
+        request: Request<GetNullifierRootHistoryRequest>,
+    ) -> Result<Response<GetNullifierRootHistoryResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let roots = self
+            .state
+            .get_nullifier_root_history(request.from_block_num.into(), request.to_block_num.into())
+            .await?;
+
+        Ok(Response::new(GetNullifierRootHistoryResponse {
+            roots: roots
+                .into_iter()
+                .map(|(block_num, nullifier_root)| NullifierRootRecord {
+                    block_num: block_num.into(),
+                    nullifier_root: Some(nullifier_root.into()),
+                })
+                .collect(),
+        }))
+    }
+
+    /// Returns aggregate statistics of the chain.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_chain_stats",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_chain_stats(
+        &self,
+        _request: Request<GetChainStatsRequest>,
+    ) -> Result<Response<GetChainStatsResponse>, Status> {
+        let stats = self.state.get_chain_stats().await;
+
+        Ok(Response::new(GetChainStatsResponse {
+            num_blocks: stats.num_blocks,
+            num_accounts: stats.num_accounts,
+            num_nullifiers: stats.num_nullifiers,
+            account_root: Some(stats.account_root.into()),
+            nullifier_root: Some(stats.nullifier_root.into()),
+            chain_root: Some(stats.chain_root.into()),
+        }))
+    }
+
+    /// Returns the nullifiers reserved by blocks which are being built, but were not applied yet.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_pending_nullifiers",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_pending_nullifiers(
+        &self,
+        _request: Request<GetPendingNullifiersRequest>,
+    ) -> Result<Response<GetPendingNullifiersResponse>, Status> {
+        let nullifiers = self.state.get_pending_nullifiers().await;
+
+        Ok(Response::new(GetPendingNullifiersResponse { nullifiers: convert(nullifiers) }))
+    }
+
     /// Returns a list of Note's for the specified NoteId's.
     ///
     /// If the list is empty or no Note matched the requested NoteId and empty list is returned.
@@ -310,6 +545,35 @@ impl api_server::Api for StoreApi {
         }))
     }
 
+    /// Returns which of the given notes were included in a block after the given block.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_notes_authenticated_since",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_notes_authenticated_since(
+        &self,
+        request: Request<GetNotesAuthenticatedSinceRequest>,
+    ) -> Result<Response<GetNotesAuthenticatedSinceResponse>, Status> {
+        let request = request.into_inner();
+        let note_ids = validate_notes(&request.note_ids)?;
+
+        let note_ids = self
+            .state
+            .get_notes_authenticated_since(
+                note_ids.into_iter().collect(),
+                request.since_block_num.into(),
+            )
+            .await
+            .map_err(internal_error)?;
+
+        Ok(Response::new(GetNotesAuthenticatedSinceResponse {
+            note_ids: note_ids.into_iter().map(Into::into).collect(),
+        }))
+    }
+
     /// Returns details for public (public) account by id.
     #[instrument(
         target = "miden-store",
@@ -354,24 +618,82 @@ impl api_server::Api for StoreApi {
 
         debug!(target: COMPONENT, ?request);
 
-        let block = Block::read_from_bytes(&request.block).map_err(|err| {
-            Status::invalid_argument(format!("Block deserialization error: {err}"))
-        })?;
-
+        let BlockToApply {
+            block,
+            nullifier_origins,
+            account_versions,
+        } = read_apply_block_request(request)?;
         let block_num = block.header().block_num();
 
-        info!(
-            target: COMPONENT,
-            block_num,
-            block_hash = %block.hash(),
-            account_count = block.updated_accounts().len(),
-            note_count = block.notes().count(),
-            nullifier_count = block.nullifiers().len(),
-        );
+        let updated_accounts = block
+            .updated_accounts()
+            .iter()
+            .map(|update| AccountSummary {
+                account_id: Some(update.account_id().into()),
+                account_hash: Some(update.new_state_hash().into()),
+                block_num,
+            })
+            .collect();
 
-        self.state.apply_block(block).await?;
+        self.state.apply_block(block, nullifier_origins, account_versions).await?;
 
-        Ok(Response::new(ApplyBlockResponse {}))
+        Ok(Response::new(ApplyBlockResponse { updated_accounts }))
+    }
+
+    /// Applies a contiguous sequence of blocks, stopping at the first block which is rejected.
+    ///
+    /// Blocks applied before the rejected one stay applied, and the response reports the last
+    /// applied block together with the reason of the rejection.
+    #[instrument(
+        target = "miden-store",
+        name = "store:apply_blocks",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn apply_blocks(
+        &self,
+        request: Request<ApplyBlocksRequest>,
+    ) -> Result<Response<ApplyBlocksResponse>, Status> {
+        let blocks = request
+            .into_inner()
+            .blocks
+            .into_iter()
+            .map(read_apply_block_request)
+            .collect::<Result<Vec<_>, Status>>()?;
+
+        // The whole sequence is validated before applying any of its blocks.
+        for (previous, next) in blocks.iter().zip(blocks.iter().skip(1)) {
+            let (previous_num, next_num) = (
+                BlockNumber::from(previous.block.header().block_num()),
+                BlockNumber::from(next.block.header().block_num()),
+            );
+            if previous_num.next() != Some(next_num) {
+                return Err(invalid_argument(format!(
+                    "Blocks are not contiguous: block {next_num} follows block {previous_num}"
+                )));
+            }
+        }
+
+        let mut error = String::new();
+        for BlockToApply {
+            block,
+            nullifier_origins,
+            account_versions,
+        } in blocks
+        {
+            if let Err(err) =
+                self.state.apply_block(block, nullifier_origins, account_versions).await
+            {
+                error = err.to_string();
+                break;
+            }
+        }
+
+        Ok(Response::new(ApplyBlocksResponse {
+            last_applied_block_num: self.state.latest_block_num().await.into(),
+            error,
+        }))
     }
 
     /// Returns data needed by the block producer to construct and prove the next block.
@@ -416,37 +738,31 @@ impl api_server::Api for StoreApi {
 
         debug!(target: COMPONENT, ?request);
 
-        let account_id = request.account_id.ok_or(invalid_argument("`account_id` missing"))?.id;
-        let nullifiers = validate_nullifiers(&request.nullifiers)?;
-        let unauthenticated_notes = validate_notes(&request.unauthenticated_notes)?;
+        self.transaction_inputs(request).await.map(Response::new)
+    }
 
-        let tx_inputs = self
-            .state
-            .get_transaction_inputs(account_id, &nullifiers, unauthenticated_notes)
-            .await?;
+    /// Returns the inputs of several transactions, in the order of the request.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_transaction_inputs_batch",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_transaction_inputs_batch(
+        &self,
+        request: Request<GetTransactionInputsBatchRequest>,
+    ) -> Result<Response<GetTransactionInputsBatchResponse>, Status> {
+        let request = request.into_inner();
 
-        let block_height = self.state.latest_block_num().await;
+        debug!(target: COMPONENT, ?request);
 
-        Ok(Response::new(GetTransactionInputsResponse {
-            account_state: Some(AccountTransactionInputRecord {
-                account_id: Some(account_id.into()),
-                account_hash: Some(tx_inputs.account_hash.into()),
-            }),
-            nullifiers: tx_inputs
-                .nullifiers
-                .into_iter()
-                .map(|nullifier| NullifierTransactionInputRecord {
-                    nullifier: Some(nullifier.nullifier.into()),
-                    block_num: nullifier.block_num,
-                })
-                .collect(),
-            missing_unauthenticated_notes: tx_inputs
-                .missing_unauthenticated_notes
-                .into_iter()
-                .map(Into::into)
-                .collect(),
-            block_height,
-        }))
+        let mut transactions = Vec::with_capacity(request.transactions.len());
+        for tx_request in request.transactions {
+            transactions.push(self.transaction_inputs(tx_request).await?);
+        }
+
+        Ok(Response::new(GetTransactionInputsBatchResponse { transactions }))
     }
 
     #[instrument(
@@ -464,7 +780,7 @@ impl api_server::Api for StoreApi {
 
         debug!(target: COMPONENT, ?request);
 
-        let block = self.state.load_block(request.block_num).await?;
+        let block = self.state.load_block(request.block_num.into()).await?;
 
         Ok(Response::new(GetBlockByNumberResponse { block }))
     }
@@ -502,7 +818,7 @@ impl api_server::Api for StoreApi {
             .await?;
 
         Ok(Response::new(GetAccountProofsResponse {
-            block_num,
+            block_num: block_num.into(),
             account_proofs: infos.into_iter().map(Into::into).collect(),
         }))
     }
@@ -526,14 +842,99 @@ impl api_server::Api for StoreApi {
             .state
             .get_account_state_delta(
                 request.account_id.ok_or(invalid_argument("account_id is missing"))?.id,
-                request.from_block_num,
-                request.to_block_num,
+                request.from_block_num.into(),
+                request.to_block_num.into(),
             )
             .await?;
 
         Ok(Response::new(GetAccountStateDeltaResponse { delta: Some(delta.to_bytes()) }))
     }
 
+    /// Returns the candidate states of an account at the chain tips known to the store.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_account_state_forks",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_state_forks(
+        &self,
+        request: Request<GetAccountStateForksRequest>,
+    ) -> Result<Response<GetAccountStateForksResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let account_id = request.account_id.ok_or(invalid_argument("account_id is missing"))?.id;
+        let candidates = self.state.get_account_state_forks(account_id).await?;
+
+        Ok(Response::new(GetAccountStateForksResponse {
+            candidates: candidates
+                .into_iter()
+                .map(|(account_hash, tip)| AccountStateCandidate {
+                    account_hash: Some(account_hash.into()),
+                    tip: Some(tip.into()),
+                })
+                .collect(),
+        }))
+    }
+
+    /// Returns the number of the block in which an account was created.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_account_creation_block",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_creation_block(
+        &self,
+        request: Request<GetAccountCreationBlockRequest>,
+    ) -> Result<Response<GetAccountCreationBlockResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let account_id = request.account_id.ok_or(invalid_argument("account_id is missing"))?.id;
+        let block_num = self
+            .state
+            .get_account_creation_block(account_id)
+            .await
+            .map_err(internal_error)?;
+
+        Ok(Response::new(GetAccountCreationBlockResponse {
+            block_num: block_num.map(Into::into),
+        }))
+    }
+
+    /// Returns the current hash of an account.
+    #[instrument(
+        target = "miden-store",
+        name = "store:get_account_state",
+        skip_all,
+        ret(level = "debug"),
+        err
+    )]
+    async fn get_account_state(
+        &self,
+        request: Request<GetAccountStateRequest>,
+    ) -> Result<Response<GetAccountStateResponse>, Status> {
+        let request = request.into_inner();
+
+        debug!(target: COMPONENT, ?request);
+
+        let account_id = request.account_id.ok_or(invalid_argument("account_id is missing"))?;
+        let account_hash = self.state.get_account_hash(account_id.id).await;
+
+        Ok(Response::new(GetAccountStateResponse {
+            account_state: Some(AccountTransactionInputRecord {
+                account_id: Some(account_id),
+                account_hash: Some(account_hash.into()),
+            }),
+        }))
+    }
+
     // TESTING ENDPOINTS
     // --------------------------------------------------------------------------------------------
 
@@ -554,7 +955,7 @@ impl api_server::Api for StoreApi {
             .into_iter()
             .map(|(key, block_num)| SmtLeafEntry {
                 key: Some(key.into()),
-                value: Some([Felt::from(block_num), ZERO, ZERO, ZERO].into()),
+                value: Some([Felt::from(block_num.as_u32()), ZERO, ZERO, ZERO].into()),
             })
             .collect();
         Ok(Response::new(ListNullifiersResponse { nullifiers }))
@@ -606,6 +1007,70 @@ fn invalid_argument<E: core::fmt::Display>(err: E) -> Status {
     Status::invalid_argument(err.to_string())
 }
 
+/// A block read from an apply block request.
+struct BlockToApply {
+    block: Block,
+    /// Transactions which produced the block's nullifiers
+    nullifier_origins: Vec<(Nullifier, TransactionId)>,
+    /// Versions of the block's accounts read by its transactions
+    account_versions: Vec<(AccountId, RpoDigest)>,
+}
+
+/// Reads the block of an apply block request, together with the origins of its nullifiers and
+/// the versions of its accounts.
+fn read_apply_block_request(request: ApplyBlockRequest) -> Result<BlockToApply, Status> {
+    let block = Block::read_from_bytes(&request.block)
+        .map_err(|err| Status::invalid_argument(format!("Block deserialization error: {err}")))?;
+
+    info!(
+        target: COMPONENT,
+        block_num = block.header().block_num(),
+        block_hash = %block.hash(),
+        account_count = block.updated_accounts().len(),
+        note_count = block.notes().count(),
+        nullifier_count = block.nullifiers().len(),
+    );
+
+    let nullifier_origins = request
+        .nullifier_origins
+        .into_iter()
+        .map(|origin| {
+            let nullifier = origin
+                .nullifier
+                .ok_or(NullifierOrigin::missing_field(stringify!(nullifier)))?
+                .try_into()?;
+            let transaction_id = origin
+                .transaction_id
+                .ok_or(NullifierOrigin::missing_field(stringify!(transaction_id)))?
+                .try_into()?;
+            Ok((nullifier, transaction_id))
+        })
+        .collect::<Result<Vec<_>, ConversionError>>()
+        .map_err(invalid_argument)?;
+
+    let account_versions = request
+        .account_versions
+        .into_iter()
+        .map(|version| {
+            let account_id = version
+                .account_id
+                .ok_or(AccountVersion::missing_field(stringify!(account_id)))
+                .map_err(invalid_argument)?
+                .id;
+            let account_hash = RpoDigest::read_from_bytes(&version.version).map_err(|err| {
+                invalid_argument(format!("Invalid version of account {account_id}: {err}"))
+            })?;
+            Ok((account_id, account_hash))
+        })
+        .collect::<Result<Vec<_>, Status>>()?;
+
+    Ok(BlockToApply {
+        block,
+        nullifier_origins,
+        account_versions,
+    })
+}
+
 #[instrument(target = "miden-store", skip_all, err)]
 fn validate_nullifiers(nullifiers: &[generated::digest::Digest]) -> Result<Vec<Nullifier>, Status> {
     nullifiers
@@ -1,4 +1,4 @@
-use std::{net::ToSocketAddrs, sync::Arc};
+use std::net::ToSocketAddrs;
 
 use miden_node_proto::generated::store::api_server;
 use miden_node_utils::errors::ApiError;
@@ -6,9 +6,10 @@ use tokio::net::TcpListener;
 use tokio_stream::wrappers::TcpListenerStream;
 use tracing::info;
 
-use crate::{blocks::BlockStore, config::StoreConfig, db::Db, state::State, COMPONENT};
+use crate::{config::StoreConfig, COMPONENT};
 
 mod api;
+pub use api::StoreApi;
 
 /// Represents an initialized store component where the RPC connection is open, but not yet actively
 /// responding to requests.
@@ -17,7 +18,7 @@ mod api;
 /// components to the store without resorting to sleeps or other mechanisms to spawn dependent
 /// components.
 pub struct Store {
-    api_service: api_server::ApiServer<api::StoreApi>,
+    api_service: api_server::ApiServer<StoreApi>,
     listener: TcpListener,
 }
 
@@ -28,19 +29,7 @@ impl Store {
     pub async fn init(config: StoreConfig) -> Result<Self, ApiError> {
         info!(target: COMPONENT, %config, "Loading database");
 
-        let block_store = Arc::new(BlockStore::new(config.blockstore_dir.clone()).await?);
-
-        let db = Db::setup(config.clone(), Arc::clone(&block_store))
-            .await
-            .map_err(|err| ApiError::ApiInitialisationFailed(err.to_string()))?;
-
-        let state = Arc::new(
-            State::load(db, block_store)
-                .await
-                .map_err(|err| ApiError::DatabaseConnectionFailed(err.to_string()))?,
-        );
-
-        let api_service = api_server::ApiServer::new(api::StoreApi { state });
+        let api_service = api_server::ApiServer::new(StoreApi::load(&config).await?);
 
         let addr = config
             .endpoint
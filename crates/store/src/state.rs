@@ -22,13 +22,14 @@ use miden_objects::{
     crypto::{
         hash::rpo::RpoDigest,
         merkle::{
-            LeafIndex, Mmr, MmrDelta, MmrError, MmrPeaks, MmrProof, SimpleSmt, SmtProof, ValuePath,
+            LeafIndex, MerklePath, Mmr, MmrDelta, MmrError, MmrPeaks, MmrProof, SimpleSmt,
+            SmtProof, ValuePath,
         },
     },
     notes::{NoteId, Nullifier},
-    transaction::OutputNote,
+    transaction::{OutputNote, TransactionId},
     utils::Serializable,
-    AccountError, BlockHeader, ACCOUNT_TREE_DEPTH,
+    AccountError, BlockHeader, Word, ACCOUNT_TREE_DEPTH,
 };
 use tokio::{
     sync::{oneshot, Mutex, RwLock},
@@ -41,13 +42,21 @@ use crate::{
     db::{Db, NoteRecord, NoteSyncUpdate, NullifierInfo, StateSyncUpdate},
     errors::{
         ApplyBlockError, DatabaseError, GetBlockHeaderError, GetBlockInputsError,
-        GetNoteInclusionProofError, InvalidBlockError, NoteSyncError, StateInitializationError,
-        StateSyncError,
+        GetNoteInclusionProofError, InvalidBlockError, NoteSyncError, NullifierRootHistoryError,
+        NullifierTreeError, StateInitializationError, StateSyncError,
     },
-    nullifier_tree::NullifierTree,
+    nullifier_tree::{CorruptionPolicy, NullifierTree},
+    pending_nullifiers::PendingNullifiers,
     types::{AccountId, BlockNumber},
     COMPONENT,
 };
+
+// CONSTANTS
+// ================================================================================================
+
+/// The maximum number of blocks whose nullifier roots can be requested at once.
+pub const MAX_NULLIFIER_ROOT_HISTORY_BLOCKS: u32 = 1000;
+
 // STRUCTURES
 // ================================================================================================
 
@@ -87,6 +96,27 @@ pub struct TransactionInputs {
     pub account_hash: RpoDigest,
     pub nullifiers: Vec<NullifierInfo>,
     pub missing_unauthenticated_notes: Vec<NoteId>,
+    /// Header of the latest block, the proofs below are against its roots.
+    pub block_header: BlockHeader,
+    /// Authentication path of `account_hash` in the account tree.
+    pub account_proof: MerklePath,
+    /// Openings of `nullifiers` in the nullifier tree, in the same order.
+    pub nullifier_proofs: Vec<SmtProof>,
+}
+
+/// Aggregate statistics of the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainStats {
+    /// Number of blocks in the chain, including the genesis block.
+    pub num_blocks: u32,
+    /// Number of accounts in the account tree.
+    pub num_accounts: u64,
+    /// Number of consumed nullifiers.
+    pub num_nullifiers: u64,
+    pub account_root: RpoDigest,
+    pub nullifier_root: RpoDigest,
+    /// Root of the chain MMR, committing to all blocks of the chain.
+    pub chain_root: RpoDigest,
 }
 
 /// Container for state that needs to be updated atomically.
@@ -99,9 +129,9 @@ struct InnerState {
 impl InnerState {
     /// Returns the latest block number.
     fn latest_block_num(&self) -> BlockNumber {
-        (self.chain_mmr.forest() - 1)
-            .try_into()
+        u32::try_from(self.chain_mmr.forest() - 1)
             .expect("chain_mmr always has, at least, the genesis block")
+            .into()
     }
 }
 
@@ -122,6 +152,10 @@ pub struct State {
     /// To allow readers to access the tree data while an update in being performed, and prevent
     /// TOCTOU issues, there must be no concurrent writers. This locks to serialize the writers.
     writer: Mutex<()>,
+
+    /// Nullifiers of the blocks requested via [State::get_block_inputs], which were not applied
+    /// yet.
+    pending_nullifiers: Mutex<PendingNullifiers>,
 }
 
 impl State {
@@ -135,12 +169,23 @@ impl State {
         let chain_mmr = load_mmr(&mut db).await?;
         let account_tree = load_accounts(&mut db).await?;
 
-        let inner = RwLock::new(InnerState { nullifier_tree, chain_mmr, account_tree });
+        let mut inner = InnerState { nullifier_tree, chain_mmr, account_tree };
+        // The chain tip may be a block without nullifiers, which the nullifiers don't reveal.
+        let chain_tip = inner.latest_block_num();
+        inner.nullifier_tree.record_applied_block(chain_tip);
+        let inner = RwLock::new(inner);
 
         let writer = Mutex::new(());
         let db = Arc::new(db);
-
-        Ok(Self { db, block_store, inner, writer })
+        let pending_nullifiers = Mutex::new(PendingNullifiers::default());
+
+        Ok(Self {
+            db,
+            block_store,
+            inner,
+            writer,
+            pending_nullifiers,
+        })
     }
 
     /// Apply changes of a new block to the DB and in-memory data structures.
@@ -152,7 +197,9 @@ impl State {
     /// provide consistent results for all endpoints. In order to achieve consistency, the
     /// following steps are used:
     ///
-    /// - the request data is validated, prior to starting any modifications.
+    /// - the request data is validated, prior to starting any modifications. This includes the
+    ///   `account_versions`, the hashes of accounts read by the transactions of the block, which
+    ///   must still be the current hashes of these accounts.
     /// - block is being saved into the store in parallel with updating the DB, but before
     ///   committing. This block is considered as candidate and not yet available for reading
     ///   because the latest block pointer is not updated yet.
@@ -168,7 +215,12 @@ impl State {
     ///   released.
     // TODO: This span is logged in a root span, we should connect it to the parent span.
     #[instrument(target = "miden-store", skip_all, err)]
-    pub async fn apply_block(&self, block: Block) -> Result<(), ApplyBlockError> {
+    pub async fn apply_block(
+        &self,
+        block: Block,
+        nullifier_origins: Vec<(Nullifier, TransactionId)>,
+        account_versions: Vec<(AccountId, RpoDigest)>,
+    ) -> Result<(), ApplyBlockError> {
         let _lock = self.writer.try_lock().map_err(|_| ApplyBlockError::ConcurrentWrite)?;
 
         let header = block.header();
@@ -182,7 +234,15 @@ impl State {
             .into());
         }
 
-        let block_num = header.block_num();
+        // nullifier origins can only refer to the block's own nullifiers and transactions
+        let block_tx_ids: BTreeSet<_> = block.transactions().map(|(tx_id, _)| tx_id).collect();
+        if let Some((nullifier, _)) = nullifier_origins.iter().find(|(nullifier, tx_id)| {
+            !block.nullifiers().contains(nullifier) || !block_tx_ids.contains(tx_id)
+        }) {
+            return Err(InvalidBlockError::InvalidNullifierOrigin(*nullifier).into());
+        }
+
+        let block_num = BlockNumber::from(header.block_num());
         let block_hash = block.hash();
 
         // ensures the right block header is being processed
@@ -192,8 +252,13 @@ impl State {
             .await?
             .ok_or(ApplyBlockError::DbBlockHeaderEmpty)?;
 
-        if block_num != prev_block.block_num() + 1 {
-            return Err(InvalidBlockError::NewBlockInvalidBlockNum.into());
+        let chain_tip = BlockNumber::from(prev_block.block_num());
+        if block_num.prev() != Some(chain_tip) {
+            return Err(InvalidBlockError::NewBlockInvalidBlockNum {
+                expected: chain_tip.next().unwrap_or(BlockNumber::MAX),
+                got: block_num,
+            }
+            .into());
         }
         if header.prev_hash() != prev_block.hash() {
             return Err(InvalidBlockError::NewBlockInvalidPrevHash.into());
@@ -208,7 +273,7 @@ impl State {
         // the store.
         let store = Arc::clone(&self.block_store);
         let block_save_task =
-            tokio::spawn(async move { store.save_block(block_num, &block_data).await });
+            tokio::spawn(async move { store.save_block(block_num.as_u32(), &block_data).await });
 
         // scope to read in-memory data, compute mutations required for updating account
         // and nullifier trees, and validate the request
@@ -222,12 +287,20 @@ impl State {
 
             let _span = info_span!(target: COMPONENT, "update_in_memory_structs").entered();
 
+            // accounts can't have changed since the block's transactions read them
+            if let Some((account_id, _)) = account_versions.iter().find(|(account_id, version)| {
+                inner.account_tree.get_leaf(&LeafIndex::new_max_depth(*account_id))
+                    != Word::from(*version)
+            }) {
+                return Err(ApplyBlockError::AccountVersionConflict(*account_id));
+            }
+
             // nullifiers can be produced only once
             let duplicate_nullifiers: Vec<_> = block
                 .nullifiers()
                 .iter()
-                .filter(|&n| inner.nullifier_tree.get_block_num(n).is_some())
-                .cloned()
+                .zip(inner.nullifier_tree.get_block_nums(block.nullifiers())?)
+                .filter_map(|(nullifier, block_num)| block_num.map(|_| *nullifier))
                 .collect();
             if !duplicate_nullifiers.is_empty() {
                 return Err(InvalidBlockError::DuplicatedNullifiers(duplicate_nullifiers).into());
@@ -314,10 +387,10 @@ impl State {
         // in-memory write lock. This requires the DB update to run concurrently, so a new task is
         // spawned.
         let db = Arc::clone(&self.db);
-        let db_update_task =
-            tokio::spawn(
-                async move { db.apply_block(allow_acquire, acquire_done, block, notes).await },
-            );
+        let db_update_task = tokio::spawn(async move {
+            db.apply_block(allow_acquire, acquire_done, block, notes, nullifier_origins)
+                .await
+        });
 
         // Wait for the message from the DB update task, that we ready to commit the DB transaction
         acquired_allowed.await.map_err(ApplyBlockError::ClosedChannel)?;
@@ -361,6 +434,7 @@ impl State {
                 .nullifier_tree
                 .apply_mutations(nullifier_tree_update)
                 .expect("Unreachable: old nullifier tree root must be checked before this step");
+            inner.nullifier_tree.record_applied_block(block_num);
             inner
                 .account_tree
                 .apply_mutations(account_tree_update)
@@ -368,7 +442,9 @@ impl State {
             inner.chain_mmr.add(block_hash);
         }
 
-        info!(%block_hash, block_num, COMPONENT, "apply_block successful");
+        self.pending_nullifiers.lock().await.release_up_to(block_num);
+
+        info!(%block_hash, %block_num, COMPONENT, "apply_block successful");
 
         Ok(())
     }
@@ -416,6 +492,127 @@ impl State {
         nullifiers.iter().map(|n| inner.nullifier_tree.open(n)).collect()
     }
 
+    /// Returns the ID of the transaction which produced the given nullifier, if it was recorded.
+    pub async fn get_nullifier_origin(
+        &self,
+        nullifier: Nullifier,
+    ) -> Result<Option<TransactionId>, DatabaseError> {
+        self.db.select_nullifier_origin(nullifier).await
+    }
+
+    /// Returns the nullifier root of each block in the range `from..=to`, in ascending block
+    /// order.
+    ///
+    /// At most [MAX_NULLIFIER_ROOT_HISTORY_BLOCKS] blocks can be requested at once. Returns an
+    /// error if the header of any block in the range is missing from the database.
+    pub async fn get_nullifier_root_history(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<(BlockNumber, RpoDigest)>, NullifierRootHistoryError> {
+        let Some(distance) = to.checked_sub(from) else {
+            return Err(NullifierRootHistoryError::InvalidRange { from, to });
+        };
+        let requested = distance.saturating_add(1);
+        if requested > MAX_NULLIFIER_ROOT_HISTORY_BLOCKS {
+            return Err(NullifierRootHistoryError::RangeTooLarge {
+                requested,
+                max: MAX_NULLIFIER_ROOT_HISTORY_BLOCKS,
+            });
+        }
+        let chain_tip = self.latest_block_num().await;
+        if to > chain_tip {
+            return Err(NullifierRootHistoryError::BlockAfterChainTip { to, chain_tip });
+        }
+
+        let block_nums = (from.as_u32()..=to.as_u32()).map(BlockNumber::from);
+        let headers: BTreeMap<_, _> = self
+            .db
+            .select_block_headers(block_nums.clone().collect())
+            .await?
+            .into_iter()
+            .map(|header| (BlockNumber::from(header.block_num()), header))
+            .collect();
+
+        block_nums
+            .map(|block_num| {
+                let header = headers
+                    .get(&block_num)
+                    .ok_or(NullifierRootHistoryError::HistoryPruned(block_num))?;
+
+                Ok((block_num, header.nullifier_root()))
+            })
+            .collect()
+    }
+
+    /// Returns aggregate statistics of the chain.
+    ///
+    /// All statistics are computed from the in-memory trees, so they are consistent with each
+    /// other and do not require database access.
+    pub async fn get_chain_stats(&self) -> ChainStats {
+        let inner = self.inner.read().await;
+
+        ChainStats {
+            num_blocks: u32::try_from(inner.chain_mmr.forest())
+                .expect("Forest is a chain length so should fit into u32"),
+            num_accounts: inner.account_tree.leaves().count() as u64,
+            num_nullifiers: inner.nullifier_tree.num_nullifiers() as u64,
+            account_root: inner.account_tree.root(),
+            nullifier_root: inner.nullifier_tree.root(),
+            chain_root: inner.chain_mmr.peaks().hash_peaks(),
+        }
+    }
+
+    /// Returns the nullifiers reserved by blocks which are being built, but were not applied yet.
+    pub async fn get_pending_nullifiers(&self) -> Vec<Nullifier> {
+        self.pending_nullifiers.lock().await.nullifiers().into_iter().collect()
+    }
+
+    /// Returns the candidate states of an account as `(account hash, tip)` pairs, where `tip` is
+    /// the hash of the block header at which the account has the given hash.
+    ///
+    /// The store follows a single chain, so at most one candidate is returned. Unknown accounts
+    /// have no candidates.
+    pub async fn get_account_state_forks(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<(RpoDigest, RpoDigest)>, DatabaseError> {
+        let inner = self.inner.read().await;
+
+        let account_hash: RpoDigest =
+            inner.account_tree.get_leaf(&LeafIndex::new_max_depth(account_id)).into();
+        if account_hash == RpoDigest::default() {
+            return Ok(Vec::new());
+        }
+
+        // The in-memory trees are updated while holding the write lock, after the DB transaction
+        // is committed, so the latest header read here matches the account tree.
+        let tip = self
+            .db
+            .select_block_header_by_block_num(None)
+            .await?
+            .ok_or(DatabaseError::BlockNotFoundInDb(inner.latest_block_num()))?;
+
+        Ok(vec![(account_hash, tip.hash())])
+    }
+
+    /// Returns the current hash of the given account, which is the default digest if the account
+    /// is unknown.
+    pub async fn get_account_hash(&self, account_id: AccountId) -> RpoDigest {
+        let inner = self.inner.read().await;
+
+        inner.account_tree.get_leaf(&LeafIndex::new_max_depth(account_id)).into()
+    }
+
+    /// Returns the number of the block in which the given account was created, or `None` if the
+    /// account is unknown.
+    pub async fn get_account_creation_block(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<BlockNumber>, DatabaseError> {
+        self.db.select_account_creation_block(account_id).await
+    }
+
     /// Queries a list of [NoteRecord] from the database.
     ///
     /// If the provided list of [NoteId] given is empty or no [NoteRecord] matches the provided
@@ -440,7 +637,7 @@ impl State {
         // The set of blocks that the notes are included in.
         let blocks = note_proofs
             .values()
-            .map(|proof| proof.location().block_num())
+            .map(|proof| BlockNumber::from(proof.location().block_num()))
             .collect::<BTreeSet<_>>()
             .into_iter()
             .collect::<Vec<_>>();
@@ -458,14 +655,14 @@ impl State {
             let paths = blocks
                 .iter()
                 .map(|&block_num| {
-                    let proof = state.chain_mmr.open(block_num as usize)?.merkle_path;
+                    let proof = state.chain_mmr.open(block_num.as_u32() as usize)?.merkle_path;
 
                     Ok::<_, MmrError>((block_num, proof))
                 })
                 .collect::<Result<BTreeMap<_, _>, MmrError>>()?;
 
-            let chain_length = BlockNumber::try_from(chain_length)
-                .expect("Forest is a chain length so should fit into block number");
+            let chain_length = u32::try_from(chain_length)
+                .expect("Forest is a chain length so should fit into u32");
 
             (chain_length, paths)
         };
@@ -473,7 +670,7 @@ impl State {
         let headers = self.db.select_block_headers(blocks).await?;
         let headers = headers
             .into_iter()
-            .map(|header| (header.block_num(), header))
+            .map(|header| (BlockNumber::from(header.block_num()), header))
             .collect::<BTreeMap<BlockNumber, _>>();
 
         let mut block_proofs = Vec::with_capacity(merkle_paths.len());
@@ -487,6 +684,24 @@ impl State {
         Ok(NoteAuthenticationInfo { block_proofs, note_proofs })
     }
 
+    /// Returns which of the given notes were included in a block after block `since`, i.e. which
+    /// of them can only be authenticated since then.
+    ///
+    /// Notes which are not in the store are not returned.
+    pub async fn get_notes_authenticated_since(
+        &self,
+        note_ids: BTreeSet<NoteId>,
+        since: BlockNumber,
+    ) -> Result<Vec<NoteId>, DatabaseError> {
+        let note_proofs = self.db.select_note_inclusion_proofs(note_ids).await?;
+
+        Ok(note_proofs
+            .into_iter()
+            .filter(|(_, proof)| BlockNumber::from(proof.location().block_num()) > since)
+            .map(|(note_id, _)| note_id)
+            .collect())
+    }
+
     /// Loads data to synchronize a client.
     ///
     /// The client's request contains a list of tag prefixes, this method will return the first
@@ -517,9 +732,12 @@ impl State {
             .get_state_sync(block_num, account_ids, note_tags, nullifier_prefixes)
             .await?;
 
-        let delta = if block_num == state_sync.block_header.block_num() {
+        let delta = if block_num.as_u32() == state_sync.block_header.block_num() {
             // The client is in sync with the chain tip.
-            MmrDelta { forest: block_num as usize, data: vec![] }
+            MmrDelta {
+                forest: block_num.as_u32() as usize,
+                data: vec![],
+            }
         } else {
             // Important notes about the boundary conditions:
             //
@@ -530,7 +748,7 @@ impl State {
             // - Mmr::get_delta is inclusive, whereas the sync_state request block_num is defined to
             //   be
             // exclusive, so the from_forest has to be adjusted with a +1
-            let from_forest = (block_num + 1) as usize;
+            let from_forest = block_num.as_u32() as usize + 1;
             let to_forest = state_sync.block_header.block_num() as usize;
             inner
                 .chain_mmr
@@ -625,6 +843,19 @@ impl State {
         let found_unauthenticated_notes =
             self.get_note_authentication_info(unauthenticated_notes).await?;
 
+        // the requested nullifiers are reserved by the block being built, until it is applied.
+        // Requests for block inputs are retried and repeated for competing candidates of the same
+        // block, so these reservations are only reported, see `PendingNullifiers`.
+        let mut unconsumed = Vec::new();
+        for witness in &nullifiers {
+            if inner.nullifier_tree.get_block_num(&witness.nullifier)?.is_none() {
+                unconsumed.push(witness.nullifier);
+            }
+        }
+        if let Some(block_num) = BlockNumber::from(latest.block_num()).next() {
+            self.pending_nullifiers.lock().await.reserve(block_num, unconsumed);
+        }
+
         Ok(BlockInputs {
             block_header: latest,
             chain_peaks,
@@ -646,15 +877,30 @@ impl State {
 
         let inner = self.inner.read().await;
 
-        let account_hash = inner.account_tree.open(&LeafIndex::new_max_depth(account_id)).value;
+        // The in-memory trees are updated while holding the write lock, after the DB transaction
+        // is committed, so the latest header read here matches the trees' roots.
+        let block_header = self
+            .db
+            .select_block_header_by_block_num(None)
+            .await?
+            .ok_or(DatabaseError::BlockNotFoundInDb(inner.latest_block_num()))?;
+
+        let ValuePath { value: account_hash, path: account_proof } =
+            inner.account_tree.open(&LeafIndex::new_max_depth(account_id));
+
+        let nullifier_proofs = nullifiers.iter().map(|n| inner.nullifier_tree.open(n)).collect();
 
         let nullifiers = nullifiers
             .iter()
-            .map(|nullifier| NullifierInfo {
-                nullifier: *nullifier,
-                block_num: inner.nullifier_tree.get_block_num(nullifier).unwrap_or_default(),
+            .map(|nullifier| {
+                let block_num = inner.nullifier_tree.get_block_num(nullifier)?;
+
+                Ok(NullifierInfo {
+                    nullifier: *nullifier,
+                    block_num: block_num.unwrap_or_default(),
+                })
             })
-            .collect();
+            .collect::<Result<_, NullifierTreeError>>()?;
 
         let found_unauthenticated_notes =
             self.db.select_note_ids(unauthenticated_notes.clone()).await?;
@@ -669,11 +915,14 @@ impl State {
             account_hash,
             nullifiers,
             missing_unauthenticated_notes,
+            block_header,
+            account_proof,
+            nullifier_proofs,
         })
     }
 
     /// Lists all known nullifiers with their inclusion blocks, intended for testing.
-    pub async fn list_nullifiers(&self) -> Result<Vec<(Nullifier, u32)>, DatabaseError> {
+    pub async fn list_nullifiers(&self) -> Result<Vec<(Nullifier, BlockNumber)>, DatabaseError> {
         self.db.select_all_nullifiers().await
     }
 
@@ -784,7 +1033,7 @@ impl State {
         if block_num > self.latest_block_num().await {
             return Ok(None);
         }
-        self.block_store.load_block(block_num).await.map_err(Into::into)
+        self.block_store.load_block(block_num.as_u32()).await.map_err(Into::into)
     }
 
     /// Returns the latest block number.
@@ -802,7 +1051,7 @@ async fn load_nullifier_tree(db: &mut Db) -> Result<NullifierTree, StateInitiali
     let len = nullifiers.len();
 
     let now = Instant::now();
-    let nullifier_tree = NullifierTree::with_entries(nullifiers)
+    let (nullifier_tree, _) = NullifierTree::with_entries(nullifiers, CorruptionPolicy::Abort)
         .map_err(StateInitializationError::FailedToCreateNullifierTree)?;
     let elapsed = now.elapsed().as_secs();
 
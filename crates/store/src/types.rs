@@ -1,2 +1,3 @@
-pub type BlockNumber = u32;
+pub use miden_node_proto::domain::blocks::BlockNumber;
+
 pub type AccountId = u64;